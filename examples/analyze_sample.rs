@@ -160,7 +160,7 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
         // Print tag statistics
         println!("  📊 태그 통계 (상위 10개):");
         let mut sorted_tags: Vec<_> = tag_counts.into_iter().collect();
-        sorted_tags.sort_by(|a, b| b.1.cmp(&a.1));
+        sorted_tags.sort_by_key(|b| std::cmp::Reverse(b.1));
 
         for (tag_id, count) in sorted_tags.iter().take(10) {
             let tag_name = HwpTag::from_u16(*tag_id)
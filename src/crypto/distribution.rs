@@ -1,11 +1,27 @@
-use aes::cipher::{BlockDecrypt, KeyInit};
+use aes::cipher::{BlockDecrypt, BlockEncrypt, KeyInit};
 use aes::Aes128;
+use sha1::{Digest, Sha1};
 
 use crate::error::{HwpError, Result};
 
+/// Size of the DISTRIBUTE_DOC_DATA payload (without its 4-byte record header)
+pub const DISTRIBUTION_RECORD_SIZE: usize = 256;
+
+/// HWPTAG_DISTRIBUTE_DOC_DATA
+const DISTRIBUTE_DOC_DATA_TAG: u32 = 0x1C;
+
+/// Length of the UTF-16LE SHA-1 hex string stored in the record
+const HASH_SIZE: usize = 80;
+
+/// Option bit: copying text out of the document is not allowed
+pub const RESTRICT_COPY: u16 = 0x01;
+/// Option bit: printing the document is not allowed
+pub const RESTRICT_PRINT: u16 = 0x02;
+
 #[derive(Debug, Clone)]
 pub struct DistributionDecryptor {
     key: [u8; 16],
+    options: u16,
 }
 
 impl DistributionDecryptor {
@@ -33,7 +49,20 @@ impl DistributionDecryptor {
         let mut key = [0u8; 16];
         key.copy_from_slice(&decoded[offset..offset + 16]);
 
-        Ok(Self { key })
+        let options_at = offset + HASH_SIZE;
+        let options = u16::from_le_bytes([decoded[options_at], decoded[options_at + 1]]);
+
+        Ok(Self { key, options })
+    }
+
+    /// Whether the document forbids copying its content
+    pub fn is_copy_restricted(&self) -> bool {
+        (self.options & RESTRICT_COPY) != 0
+    }
+
+    /// Whether the document forbids printing
+    pub fn is_print_restricted(&self) -> bool {
+        (self.options & RESTRICT_PRINT) != 0
     }
 
     pub fn decrypt(&self, data: &[u8]) -> Result<Vec<u8>> {
@@ -62,6 +91,203 @@ impl DistributionDecryptor {
     }
 }
 
+/// Options for producing a distribution (배포용) document
+#[derive(Debug, Clone, Default)]
+pub struct DistributionOptions {
+    /// Password needed to lift the restrictions in Hancom Office.
+    /// Without one, a random key is generated.
+    pub password: Option<String>,
+    /// Forbid copying text out of the document
+    pub restrict_copy: bool,
+    /// Forbid printing the document
+    pub restrict_print: bool,
+}
+
+impl DistributionOptions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_password(mut self, password: &str) -> Self {
+        self.password = Some(password.to_string());
+        self
+    }
+
+    pub fn restrict_copy(mut self, restrict: bool) -> Self {
+        self.restrict_copy = restrict;
+        self
+    }
+
+    pub fn restrict_print(mut self, restrict: bool) -> Self {
+        self.restrict_print = restrict;
+        self
+    }
+
+    fn option_bits(&self) -> u16 {
+        let mut bits = 0;
+        if self.restrict_copy {
+            bits |= RESTRICT_COPY;
+        }
+        if self.restrict_print {
+            bits |= RESTRICT_PRINT;
+        }
+        bits
+    }
+}
+
+/// Encrypts ViewText streams of a distribution document.
+///
+/// The 256-byte record holds a random seed, the SHA-1 hash of the password
+/// (as a UTF-16LE hex string) and the restriction options, obfuscated with
+/// the seeded MSVC `rand()` stream. The first 16 bytes of the hash string are
+/// the AES-128 key.
+#[derive(Debug, Clone)]
+pub struct DistributionEncryptor {
+    key: [u8; 16],
+    record: [u8; DISTRIBUTION_RECORD_SIZE],
+}
+
+impl DistributionEncryptor {
+    /// Build a new distribution record from options and a random seed
+    pub fn new(options: &DistributionOptions, seed: u32) -> Self {
+        let hash = match &options.password {
+            Some(password) => password_hash(password),
+            None => random_hash(seed),
+        };
+        Self::with_hash(options, seed, hash)
+    }
+
+    /// Build a distribution record with a seed from the operating system.
+    ///
+    /// Without a password the key comes from the operating system as well,
+    /// rather than from the 32-bit seed.
+    pub fn with_random_seed(options: &DistributionOptions) -> Result<Self> {
+        let mut random = [0u8; 24];
+        getrandom::fill(&mut random)
+            .map_err(|e| HwpError::Io(std::io::Error::other(e.to_string())))?;
+        let seed = u32::from_le_bytes([random[0], random[1], random[2], random[3]]);
+        let hash = match &options.password {
+            Some(password) => password_hash(password),
+            None => hex_utf16(&random[4..]),
+        };
+        Ok(Self::with_hash(options, seed, hash))
+    }
+
+    fn with_hash(options: &DistributionOptions, seed: u32, hash: [u8; HASH_SIZE]) -> Self {
+        let mut plain = [0u8; DISTRIBUTION_RECORD_SIZE];
+        let mut rng = MsvcRng::new(seed ^ 0x5A5A_5A5A);
+        for byte in plain.iter_mut() {
+            *byte = rng.next_value();
+        }
+        plain[..4].copy_from_slice(&seed.to_le_bytes());
+
+        let offset = 4 + (plain[0] & 0x0F) as usize;
+        plain[offset..offset + HASH_SIZE].copy_from_slice(&hash);
+        plain[offset + HASH_SIZE..offset + HASH_SIZE + 2]
+            .copy_from_slice(&options.option_bits().to_le_bytes());
+
+        let mut key = [0u8; 16];
+        key.copy_from_slice(&hash[..16]);
+
+        let mut record = plain;
+        obfuscation_transform(&mut record);
+
+        Self { key, record }
+    }
+
+    /// Rebuild an encryptor from an existing record (with or without its header)
+    pub fn from_record(record: &[u8]) -> Result<Self> {
+        let body = match record.len() {
+            DISTRIBUTION_RECORD_SIZE => record,
+            n if n >= DISTRIBUTION_RECORD_SIZE + 4 => &record[4..DISTRIBUTION_RECORD_SIZE + 4],
+            n => {
+                return Err(HwpError::InvalidInput(format!(
+                    "Distribution record must be 256 bytes, got {}",
+                    n
+                )))
+            }
+        };
+
+        let mut with_header = record_header().to_vec();
+        with_header.extend_from_slice(body);
+        let decryptor = DistributionDecryptor::from_record_data(&with_header)?;
+
+        let mut stored = [0u8; DISTRIBUTION_RECORD_SIZE];
+        stored.copy_from_slice(body);
+
+        Ok(Self {
+            key: decryptor.key,
+            record: stored,
+        })
+    }
+
+    /// The obfuscated 256-byte record payload
+    pub fn record(&self) -> &[u8; DISTRIBUTION_RECORD_SIZE] {
+        &self.record
+    }
+
+    /// The record payload prefixed with its HWPTAG_DISTRIBUTE_DOC_DATA header
+    pub fn record_data(&self) -> Vec<u8> {
+        let mut data = record_header().to_vec();
+        data.extend_from_slice(&self.record);
+        data
+    }
+
+    /// AES-128 ECB encrypt, zero-padding the input to the block size
+    pub fn encrypt(&self, data: &[u8]) -> Result<Vec<u8>> {
+        let cipher = Aes128::new_from_slice(&self.key)
+            .map_err(|e| HwpError::InvalidInput(format!("AES key error: {}", e)))?;
+
+        let mut result = data.to_vec();
+        let padding_needed = (16 - (result.len() % 16)) % 16;
+        result.extend(vec![0u8; padding_needed]);
+
+        for chunk in result.chunks_exact_mut(16) {
+            let block = aes::Block::from_mut_slice(chunk);
+            cipher.encrypt_block(block);
+        }
+
+        Ok(result)
+    }
+
+    /// Produce a complete ViewText stream: record followed by the encrypted data
+    pub fn encrypt_stream(&self, data: &[u8]) -> Result<Vec<u8>> {
+        let mut stream = self.record_data();
+        stream.extend(self.encrypt(data)?);
+        Ok(stream)
+    }
+}
+
+fn record_header() -> [u8; 4] {
+    (DISTRIBUTE_DOC_DATA_TAG | ((DISTRIBUTION_RECORD_SIZE as u32) << 20)).to_le_bytes()
+}
+
+/// SHA-1 of the UTF-16LE password, stored as an uppercase hex string in UTF-16LE
+fn password_hash(password: &str) -> [u8; HASH_SIZE] {
+    let utf16 = crate::utils::encoding::string_to_utf16le(password);
+    let digest = Sha1::digest(&utf16);
+    hex_utf16(&digest)
+}
+
+fn random_hash(seed: u32) -> [u8; HASH_SIZE] {
+    let mut rng = MsvcRng::new(seed.rotate_left(13) ^ 0xA5A5_A5A5);
+    let mut bytes = [0u8; 20];
+    for byte in bytes.iter_mut() {
+        *byte = rng.next_value();
+    }
+    hex_utf16(&bytes)
+}
+
+fn hex_utf16(bytes: &[u8]) -> [u8; HASH_SIZE] {
+    const HEX: &[u8; 16] = b"0123456789ABCDEF";
+    let mut out = [0u8; HASH_SIZE];
+    for (i, byte) in bytes.iter().take(HASH_SIZE / 4).enumerate() {
+        out[i * 4] = HEX[(byte >> 4) as usize];
+        out[i * 4 + 2] = HEX[(byte & 0x0F) as usize];
+    }
+    out
+}
+
 fn obfuscation_transform(data: &mut [u8; 256]) {
     let seed = u32::from_le_bytes([data[0], data[1], data[2], data[3]]);
     let mut rng = MsvcRng::new(seed);
@@ -142,6 +368,34 @@ mod tests {
         assert_ne!(data[4..], original[4..]);
     }
 
+    #[test]
+    fn test_encrypt_roundtrip() {
+        let options = DistributionOptions::new()
+            .with_password("secret")
+            .restrict_copy(true);
+        let encryptor = DistributionEncryptor::new(&options, 0xDEADBEEF);
+
+        let plain = b"distribution document body".to_vec();
+        let stream = encryptor.encrypt_stream(&plain).unwrap();
+        assert_eq!(stream.len() % 16, 260 % 16);
+
+        let record = &stream[..260];
+        let decrypted = decrypt_distribution_stream(&stream[260..], record).unwrap();
+        assert_eq!(&decrypted[..plain.len()], &plain[..]);
+
+        let decryptor = DistributionDecryptor::from_record_data(record).unwrap();
+        assert!(decryptor.is_copy_restricted());
+        assert!(!decryptor.is_print_restricted());
+    }
+
+    #[test]
+    fn test_encryptor_from_record() {
+        let encryptor = DistributionEncryptor::new(&DistributionOptions::new(), 42);
+        let rebuilt = DistributionEncryptor::from_record(encryptor.record()).unwrap();
+        assert_eq!(encryptor.key, rebuilt.key);
+        assert_eq!(encryptor.record_data(), rebuilt.record_data());
+    }
+
     #[test]
     fn test_record_data_too_short() {
         let short_data = [0u8; 100];
@@ -1,3 +1,8 @@
 mod distribution;
+mod password;
 
-pub use distribution::{decrypt_distribution_stream, DistributionDecryptor};
+pub use distribution::{
+    decrypt_distribution_stream, DistributionDecryptor, DistributionEncryptor, DistributionOptions,
+    DISTRIBUTION_RECORD_SIZE, RESTRICT_COPY, RESTRICT_PRINT,
+};
+pub use password::{PasswordCipher, PASSWORD_HEADER_SIZE};
@@ -28,6 +28,13 @@ pub enum HwpError {
 
     #[error("Invalid input: {0}")]
     InvalidInput(String),
+
+    #[error("Reader limit exceeded: {limit} {value} is above {max}")]
+    LimitExceeded {
+        limit: &'static str,
+        value: u64,
+        max: u64,
+    },
 }
 
 pub type Result<T> = std::result::Result<T, HwpError>;
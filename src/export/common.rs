@@ -0,0 +1,404 @@
+//! Structure recovery shared by the exporters
+
+use crate::model::control::{Table, TableCell};
+use crate::model::hyperlink::Hyperlink;
+use crate::model::{CharShape, Paragraph, Section};
+use crate::HwpDocument;
+
+/// A top-level piece of section content
+pub(crate) enum Block<'a> {
+    Paragraph(&'a Paragraph),
+    Table(TableBlock<'a>),
+}
+
+pub(crate) struct TableBlock<'a> {
+    pub table: &'a Table,
+    /// Cells in storage order with the paragraphs that hold their content
+    pub cells: Vec<(&'a TableCell, Vec<&'a Paragraph>)>,
+}
+
+impl TableBlock<'_> {
+    pub fn has_merged_cells(&self) -> bool {
+        self.cells
+            .iter()
+            .any(|(cell, _)| cell.row_span > 1 || cell.col_span > 1)
+    }
+
+    /// Number of grid columns, including those only reached by spans
+    pub fn columns(&self) -> usize {
+        self.cells
+            .iter()
+            .map(|(cell, _)| cell.cell_address.1 as usize + cell.col_span.max(1) as usize)
+            .max()
+            .unwrap_or(0)
+            .max(self.table.cols as usize)
+    }
+
+    /// Column widths from unmerged cells, falling back to an even share of spans
+    pub fn column_widths(&self) -> Vec<u32> {
+        let mut widths = vec![0u32; self.columns()];
+        for (cell, _) in &self.cells {
+            let col = cell.cell_address.1 as usize;
+            if cell.col_span <= 1 && widths[col] == 0 {
+                widths[col] = cell.width;
+            }
+        }
+        for (cell, _) in &self.cells {
+            let span = cell.col_span.max(1) as usize;
+            let col = cell.cell_address.1 as usize;
+            for width in widths.iter_mut().skip(col).take(span) {
+                if *width == 0 {
+                    *width = cell.width / span as u32;
+                }
+            }
+        }
+        widths
+    }
+
+    /// Cells grouped by row, ordered by column
+    pub fn rows(&self) -> Vec<Vec<&(&TableCell, Vec<&Paragraph>)>> {
+        let mut rows: Vec<Vec<_>> = vec![Vec::new(); self.table.rows as usize];
+        for entry in &self.cells {
+            let row = entry.0.cell_address.0 as usize;
+            if row >= rows.len() {
+                rows.resize_with(row + 1, Vec::new);
+            }
+            rows[row].push(entry);
+        }
+        for row in &mut rows {
+            row.sort_by_key(|(cell, _)| cell.cell_address.1);
+        }
+        rows
+    }
+}
+
+/// Group section paragraphs into blocks.
+///
+/// Tables are stored as a control paragraph followed by one paragraph per
+/// cell, so those paragraphs are attached to the table instead of being
+/// emitted on their own.
+pub(crate) fn blocks(section: &Section) -> Vec<Block<'_>> {
+    let mut blocks = Vec::new();
+    let mut paragraphs = section.paragraphs.iter();
+
+    while let Some(paragraph) = paragraphs.next() {
+        if let Some(table) = &paragraph.table_data {
+            let cells = table
+                .cells
+                .iter()
+                .map(|cell| (cell, paragraphs.next().into_iter().collect()))
+                .collect();
+            blocks.push(Block::Table(TableBlock { table, cells }));
+        } else {
+            blocks.push(Block::Paragraph(paragraph));
+        }
+    }
+
+    blocks
+}
+
+/// Whether a link target is safe to follow from a page: web and mail links
+/// and fragments, never `javascript:`, `data:` and the like
+pub(crate) fn is_safe_href(target: &str) -> bool {
+    let lower = target.trim().to_lowercase();
+    lower.starts_with('#')
+        || ["http://", "https://", "mailto:"]
+            .iter()
+            .any(|scheme| lower.starts_with(scheme))
+}
+
+/// A run of characters sharing one character shape and link
+pub(crate) struct Span<'a> {
+    pub text: String,
+    pub char_shape_id: Option<u16>,
+    pub link: Option<&'a Hyperlink>,
+}
+
+/// Split paragraph text into spans at character shape and hyperlink boundaries
+pub(crate) fn spans<'a>(paragraph: &'a Paragraph, text: &str) -> Vec<Span<'a>> {
+    let chars: Vec<char> = text.chars().collect();
+    let links = link_ranges(paragraph, &chars);
+
+    let shape_at = |index: usize| -> Option<u16> {
+        paragraph.char_shapes.as_ref().and_then(|shapes| {
+            shapes
+                .char_positions
+                .iter()
+                .take_while(|p| p.position as usize <= index)
+                .last()
+                .map(|p| p.char_shape_id)
+        })
+    };
+    let link_at = |index: usize| {
+        links
+            .iter()
+            .find(|(start, end, _)| (*start..*end).contains(&index))
+            .map(|(_, _, link)| *link)
+    };
+
+    let mut spans: Vec<Span<'a>> = Vec::new();
+    for (index, ch) in chars.iter().enumerate() {
+        let char_shape_id = shape_at(index);
+        let link = link_at(index);
+        match spans.last_mut() {
+            Some(span) if span.char_shape_id == char_shape_id && same_link(span.link, link) => {
+                span.text.push(*ch)
+            }
+            _ => spans.push(Span {
+                text: ch.to_string(),
+                char_shape_id,
+                link,
+            }),
+        }
+    }
+    spans
+}
+
+pub(crate) fn same_link(a: Option<&Hyperlink>, b: Option<&Hyperlink>) -> bool {
+    match (a, b) {
+        (Some(a), Some(b)) => std::ptr::eq(a, b),
+        (None, None) => true,
+        _ => false,
+    }
+}
+
+/// Character ranges covered by each hyperlink.
+///
+/// The stored start position is not reliable across writers, so the display
+/// text is searched for, preferring a match at the recorded position.
+fn link_ranges<'a>(paragraph: &'a Paragraph, chars: &[char]) -> Vec<(usize, usize, &'a Hyperlink)> {
+    let mut ranges: Vec<(usize, usize, &Hyperlink)> = Vec::new();
+
+    for link in &paragraph.hyperlinks {
+        let needle: Vec<char> = link.display_text.chars().collect();
+        if needle.is_empty() || needle.len() > chars.len() {
+            continue;
+        }
+
+        let matches_at = |start: usize| {
+            chars[start..].starts_with(&needle)
+                && !ranges
+                    .iter()
+                    .any(|(s, e, _)| start < *e && *s < start + needle.len())
+        };
+        let preferred = link.start_position as usize;
+        let start = if preferred + needle.len() <= chars.len() && matches_at(preferred) {
+            Some(preferred)
+        } else {
+            (0..=chars.len() - needle.len()).find(|&start| matches_at(start))
+        };
+
+        if let Some(start) = start {
+            ranges.push((start, start + needle.len(), link));
+        }
+    }
+
+    ranges
+}
+
+/// Visible paragraph text with control characters removed
+pub(crate) fn paragraph_text(paragraph: &Paragraph) -> String {
+    let text = paragraph
+        .text
+        .as_ref()
+        .map(|t| t.content.as_str())
+        .unwrap_or_default();
+    text.chars()
+        .filter(|c| !c.is_control() || *c == '\n' || *c == '\t')
+        .collect()
+}
+
+/// Heading level 1-6 from outline numbering, style name or a large bold font
+pub(crate) fn heading_level(document: &HwpDocument, paragraph: &Paragraph) -> Option<u8> {
+    if let Some(para_shape) = document.get_para_shape(paragraph.para_shape_id as usize) {
+        // Bits 23-24: heading type (1 = outline), bits 25-27: level
+        if (para_shape.properties1 >> 23) & 0x3 == 1 {
+            return Some((((para_shape.properties1 >> 25) & 0x7) as u8 + 1).min(6));
+        }
+    }
+
+    if let Some(style) = document.get_style(paragraph.style_id as usize) {
+        for name in [&style.name, &style.english_name] {
+            let name = name.trim();
+            for prefix in ["개요", "제목", "Outline", "Heading"] {
+                if let Some(level) = name
+                    .strip_prefix(prefix)
+                    .and_then(|rest| rest.trim().parse::<u8>().ok())
+                {
+                    return Some(level.clamp(1, 6));
+                }
+            }
+        }
+    }
+
+    // Fall back to formatting: a short, entirely bold paragraph in a large font
+    let shape = uniform_char_shape(document, paragraph)?;
+    let text = paragraph_text(paragraph);
+    if !shape.is_bold() || text.trim().is_empty() || text.chars().count() > 200 {
+        return None;
+    }
+    match shape.base_size {
+        size if size >= 2000 => Some(1),
+        size if size >= 1600 => Some(2),
+        size if size >= 1400 => Some(3),
+        _ => None,
+    }
+}
+
+/// The character shape if the whole paragraph uses a single one
+pub(crate) fn uniform_char_shape<'a>(
+    document: &'a HwpDocument,
+    paragraph: &Paragraph,
+) -> Option<&'a CharShape> {
+    let positions = &paragraph.char_shapes.as_ref()?.char_positions;
+    let first = positions.first()?.char_shape_id;
+    if positions.iter().any(|p| p.char_shape_id != first) {
+        return None;
+    }
+    document.get_char_shape(first as usize)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum ListKind {
+    Bullet,
+    Ordered,
+}
+
+/// A list item recognised from paragraph numbering or a leading marker
+pub(crate) struct ListItem {
+    pub kind: ListKind,
+    pub level: usize,
+    /// Number of leading characters that form the marker (including the space)
+    pub marker_len: usize,
+}
+
+pub(crate) fn list_item(
+    document: &HwpDocument,
+    paragraph: &Paragraph,
+    text: &str,
+) -> Option<ListItem> {
+    let para_shape = document.get_para_shape(paragraph.para_shape_id as usize);
+    let level = para_shape
+        .map(|shape| ((shape.left_margin - 567).max(0) / 1000) as usize)
+        .unwrap_or(0);
+
+    if let Some(shape) = para_shape {
+        let heading_type = (shape.properties1 >> 23) & 0x3;
+        let numbered_level = ((shape.properties1 >> 25) & 0x7) as usize;
+        match heading_type {
+            2 => {
+                return Some(ListItem {
+                    kind: ListKind::Ordered,
+                    level: numbered_level,
+                    marker_len: 0,
+                })
+            }
+            3 => {
+                return Some(ListItem {
+                    kind: ListKind::Bullet,
+                    level: numbered_level,
+                    marker_len: 0,
+                })
+            }
+            _ => {}
+        }
+    }
+
+    let (marker, _) = text.split_once(' ')?;
+    let marker_len = marker.chars().count() + 1;
+    let kind = if ["•", "◦", "▪", "-", "*", "○", "●", "■", "□", "◆", "◇", "※"].contains(&marker)
+    {
+        ListKind::Bullet
+    } else if is_ordered_marker(marker) {
+        ListKind::Ordered
+    } else {
+        return None;
+    };
+
+    Some(ListItem {
+        kind,
+        level,
+        marker_len,
+    })
+}
+
+fn is_ordered_marker(marker: &str) -> bool {
+    let Some(body) = marker
+        .strip_suffix('.')
+        .or_else(|| marker.strip_suffix(')'))
+    else {
+        return false;
+    };
+    if body.is_empty() || body.chars().count() > 4 {
+        return false;
+    }
+
+    body.chars().all(|c| c.is_ascii_digit())
+        || (body.chars().count() == 1 && body.chars().all(|c| c.is_ascii_lowercase()))
+        || body.chars().all(|c| "ivxlcdm".contains(c))
+        || (body.chars().count() == 1 && "가나다라마바사아자차카타파하".contains(body))
+}
+
+/// Image payload for a picture paragraph together with a stable file name
+pub(crate) fn picture_image(
+    document: &HwpDocument,
+    paragraph: &Paragraph,
+) -> Option<super::ExportedImage> {
+    let picture = paragraph.picture_data.as_ref()?;
+    let bin_data = document.get_bin_data(picture.bin_item_id)?;
+    let data = bin_data.get_data().ok().filter(|d| !d.is_empty())?;
+    let extension = if bin_data.extension.is_empty() {
+        "bin".to_string()
+    } else {
+        bin_data.extension.to_lowercase()
+    };
+
+    Some(super::ExportedImage {
+        bin_id: bin_data.bin_id,
+        file_name: format!("image{}.{}", bin_data.bin_id, extension),
+        data,
+    })
+}
+
+/// MIME type for an image file name, or `None` if it is not an image format
+pub(crate) fn image_mime_type(file_name: &str) -> Option<&'static str> {
+    let extension = file_name.rsplit('.').next().unwrap_or_default();
+    Some(match extension.to_ascii_lowercase().as_str() {
+        "png" => "image/png",
+        "jpg" | "jpeg" => "image/jpeg",
+        "gif" => "image/gif",
+        "bmp" => "image/bmp",
+        "tif" | "tiff" => "image/tiff",
+        "svg" => "image/svg+xml",
+        "wmf" => "image/x-wmf",
+        "emf" => "image/x-emf",
+        _ => return None,
+    })
+}
+
+/// Standard base64 with padding, for data URIs
+pub(crate) fn base64(data: &[u8]) -> String {
+    const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+    let mut out = String::with_capacity(data.len().div_ceil(3) * 4);
+    for chunk in data.chunks(3) {
+        let b = [
+            chunk[0],
+            chunk.get(1).copied().unwrap_or(0),
+            chunk.get(2).copied().unwrap_or(0),
+        ];
+        let n = (b[0] as u32) << 16 | (b[1] as u32) << 8 | b[2] as u32;
+        out.push(ALPHABET[(n >> 18) as usize & 63] as char);
+        out.push(ALPHABET[(n >> 12) as usize & 63] as char);
+        out.push(if chunk.len() > 1 {
+            ALPHABET[(n >> 6) as usize & 63] as char
+        } else {
+            '='
+        });
+        out.push(if chunk.len() > 2 {
+            ALPHABET[n as usize & 63] as char
+        } else {
+            '='
+        });
+    }
+    out
+}
@@ -0,0 +1,1258 @@
+//! DOCX (Office Open XML WordprocessingML) export
+
+use std::collections::HashSet;
+use std::io::{Cursor, Seek, Write};
+use std::path::Path;
+
+use quick_xml::escape::escape;
+use zip::write::SimpleFileOptions;
+use zip::ZipWriter;
+
+use super::common::{self, Block, TableBlock};
+use super::DroppedElement;
+use crate::error::{HwpError, Result};
+use crate::model::border_fill::{BorderFill, BorderLine};
+use crate::model::ctrl_header::ControlType;
+use crate::model::footnote::{Footnote, NoteKind};
+use crate::model::header_footer::{HeaderFooter, HeaderFooterType, PageApplyType};
+use crate::model::hyperlink::{Hyperlink, HyperlinkType};
+use crate::model::{CharShape, PageDef, ParaShape, Paragraph, Section};
+use crate::HwpDocument;
+
+const NAMESPACES: &str = concat!(
+    r#"xmlns:w="http://schemas.openxmlformats.org/wordprocessingml/2006/main" "#,
+    r#"xmlns:r="http://schemas.openxmlformats.org/officeDocument/2006/relationships" "#,
+    r#"xmlns:wp="http://schemas.openxmlformats.org/drawingml/2006/wordprocessingDrawing" "#,
+    r#"xmlns:a="http://schemas.openxmlformats.org/drawingml/2006/main" "#,
+    r#"xmlns:pic="http://schemas.openxmlformats.org/drawingml/2006/picture""#
+);
+const XML_DECLARATION: &str = r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>"#;
+const RELATIONSHIP_TYPE: &str =
+    "http://schemas.openxmlformats.org/officeDocument/2006/relationships";
+
+/// A WordprocessingML package together with what could not be carried over
+#[derive(Debug, Clone, Default)]
+pub struct DocxExport {
+    /// Contents of the .docx file
+    pub bytes: Vec<u8>,
+    /// Content that has no DOCX equivalent, in document order
+    pub dropped: Vec<DroppedElement>,
+}
+
+impl DocxExport {
+    pub fn write_to<P: AsRef<Path>>(&self, path: P) -> Result<()> {
+        std::fs::write(path, &self.bytes).map_err(HwpError::Io)
+    }
+}
+
+/// Convert a document to DOCX, reporting every element that had to be dropped
+pub fn export(document: &HwpDocument) -> Result<DocxExport> {
+    let mut writer = DocxWriter {
+        document,
+        char_properties: document
+            .doc_info
+            .char_shapes
+            .iter()
+            .map(|shape| char_shape_properties(document, shape))
+            .collect(),
+        dropped: Vec::new(),
+        relationships: Vec::new(),
+        media: Vec::new(),
+        parts: Vec::new(),
+        footnotes: Vec::new(),
+        endnotes: Vec::new(),
+        reported_fills: HashSet::new(),
+        drawing_id: 0,
+        even_and_odd_headers: false,
+    };
+
+    let mut body = String::new();
+    let sections: Vec<&Section> = document.sections().collect();
+    for (section_idx, section) in sections.iter().enumerate() {
+        for block in common::blocks(section) {
+            match block {
+                Block::Paragraph(paragraph) => {
+                    let location = location(section_idx, section, paragraph);
+                    body.push_str(&writer.paragraph(paragraph, &location));
+                }
+                Block::Table(table) => body.push_str(&writer.table(section_idx, section, &table)),
+            }
+        }
+
+        let section_properties = writer.section_properties(section_idx, section);
+        if section_idx + 1 < sections.len() {
+            body.push_str(&format!("<w:p><w:pPr>{section_properties}</w:pPr></w:p>"));
+        } else {
+            body.push_str(&section_properties);
+        }
+    }
+
+    let background = sections
+        .iter()
+        .filter_map(|section| section.page_def.as_ref()?.layout.as_ref()?.background_color)
+        .next();
+    let mut xml = format!("{XML_DECLARATION}<w:document {NAMESPACES}>");
+    if let Some(background) = background {
+        xml.push_str(&format!(
+            "<w:background w:color=\"{}\"/>",
+            color(background)
+        ));
+    }
+    xml.push_str(&format!("<w:body>{body}</w:body></w:document>"));
+
+    let bytes = writer.package(&xml, background.is_some())?;
+    Ok(DocxExport {
+        bytes,
+        dropped: writer.dropped,
+    })
+}
+
+/// Convert a document to DOCX bytes
+pub fn to_docx(document: &HwpDocument) -> Result<Vec<u8>> {
+    Ok(export(document)?.bytes)
+}
+
+/// Convert a document and write the .docx file
+pub fn write_docx<P: AsRef<Path>>(document: &HwpDocument, path: P) -> Result<()> {
+    export(document)?.write_to(path)
+}
+
+/// Location string matching the one used in security reports
+fn location(section_idx: usize, section: &Section, paragraph: &Paragraph) -> String {
+    let para_idx = section
+        .paragraphs
+        .iter()
+        .position(|p| std::ptr::eq(p, paragraph))
+        .unwrap_or_default();
+    format!("section {section_idx}, paragraph {para_idx}")
+}
+
+struct Relationship {
+    id: String,
+    kind: &'static str,
+    target: String,
+    external: bool,
+}
+
+/// An image stored under word/media
+struct Media {
+    bin_id: u16,
+    relationship_id: String,
+    file_name: String,
+    data: Vec<u8>,
+}
+
+struct DocxWriter<'a> {
+    document: &'a HwpDocument,
+    /// Run property XML for each character shape
+    char_properties: Vec<String>,
+    dropped: Vec<DroppedElement>,
+    /// Relationships of word/document.xml beyond the fixed parts
+    relationships: Vec<Relationship>,
+    media: Vec<Media>,
+    /// Header and footer parts: (file name under word/, content type, xml)
+    parts: Vec<(String, &'static str, String)>,
+    /// `<w:footnote>` and `<w:endnote>` elements, numbered from 1
+    footnotes: Vec<String>,
+    endnotes: Vec<String>,
+    /// Border fills whose fill has already been reported as dropped
+    reported_fills: HashSet<u16>,
+    drawing_id: u32,
+    even_and_odd_headers: bool,
+}
+
+impl DocxWriter<'_> {
+    fn report(&mut self, element: &str, location: &str, reason: &str) {
+        self.dropped.push(DroppedElement {
+            element: element.to_string(),
+            location: location.to_string(),
+            reason: reason.to_string(),
+        });
+    }
+
+    fn add_relationship(&mut self, kind: &'static str, target: String, external: bool) -> String {
+        // rId1-rId4 are styles, settings, footnotes and endnotes
+        let id = format!("rId{}", self.relationships.len() + 5);
+        self.relationships.push(Relationship {
+            id: id.clone(),
+            kind,
+            target,
+            external,
+        });
+        id
+    }
+
+    fn paragraph(&mut self, paragraph: &Paragraph, location: &str) -> String {
+        let mut xml = String::new();
+        self.report_controls(paragraph, location);
+
+        if let Some(text_box) = &paragraph.text_box_data {
+            for line in text_box.text.lines() {
+                xml.push_str(&format!("<w:p>{}</w:p>", text_run("", line)));
+            }
+            self.report(
+                "text box",
+                location,
+                "text is kept as plain paragraphs; position, border and fill are lost",
+            );
+        }
+
+        if let Some(shape) = self
+            .document
+            .get_para_shape(paragraph.para_shape_id as usize)
+        {
+            if matches!((shape.properties1 >> 23) & 0x3, 2 | 3) {
+                self.report(
+                    "paragraph numbering",
+                    location,
+                    "automatic numbers and bullets are not exported",
+                );
+            }
+        }
+
+        let mut content = String::new();
+        if paragraph.picture_data.is_some() {
+            content.push_str(&self.image(paragraph, location));
+        }
+        content.push_str(&self.runs(paragraph, location));
+        xml.push_str(&format!(
+            "<w:p>{}{content}</w:p>",
+            self.paragraph_properties(paragraph)
+        ));
+        xml
+    }
+
+    /// Report controls that have no DOCX counterpart
+    fn report_controls(&mut self, paragraph: &Paragraph, location: &str) {
+        let Some(ctrl_header) = &paragraph.ctrl_header else {
+            return;
+        };
+        let (element, reason) = match ctrl_header.get_control_type() {
+            ControlType::Equation => ("equation", "equation scripts are not converted to OMML"),
+            ControlType::Gso
+                if paragraph.picture_data.is_none() && paragraph.text_box_data.is_none() =>
+            {
+                (
+                    "drawing object",
+                    "shapes other than pictures are not exported",
+                )
+            }
+            ControlType::HiddenComment => ("hidden comment", "comments are not exported"),
+            ControlType::OverlappingLetter => (
+                "overlapping letters",
+                "combined characters are not exported",
+            ),
+            ControlType::BookMark => ("bookmark", "bookmarks are not exported"),
+            _ => return,
+        };
+        self.report(element, location, reason);
+    }
+
+    fn paragraph_properties(&self, paragraph: &Paragraph) -> String {
+        let mut properties = String::new();
+        if let Some(style) = self.document.get_style(paragraph.style_id as usize) {
+            if !is_character_style(style) {
+                properties.push_str(&format!(
+                    "<w:pStyle w:val=\"Style{}\"/>",
+                    paragraph.style_id
+                ));
+            }
+        }
+        if let Some(shape) = self
+            .document
+            .get_para_shape(paragraph.para_shape_id as usize)
+        {
+            properties.push_str(&para_shape_properties(self.document, shape));
+        }
+        if let Some(level) = common::heading_level(self.document, paragraph) {
+            properties.push_str(&format!("<w:outlineLvl w:val=\"{}\"/>", level - 1));
+        }
+        if properties.is_empty() {
+            properties
+        } else {
+            format!("<w:pPr>{properties}</w:pPr>")
+        }
+    }
+
+    /// Runs for paragraph text with hyperlinks and note references
+    fn runs(&mut self, paragraph: &Paragraph, location: &str) -> String {
+        let text = common::paragraph_text(paragraph);
+        let spans = common::spans(paragraph, &text);
+        let mut notes: Vec<_> = paragraph.footnotes.iter().collect();
+        notes.sort_by_key(|note| note.position);
+        let mut notes = notes.into_iter().peekable();
+
+        let mut out = String::new();
+        let mut index = 0usize;
+        let mut open_link = None;
+
+        for span in &spans {
+            if !common::same_link(open_link, span.link) {
+                if open_link.is_some() {
+                    out.push_str("</w:hyperlink>");
+                }
+                if let Some(hyperlink) = span.link {
+                    out.push_str(&self.hyperlink_start(hyperlink));
+                }
+                open_link = span.link;
+            }
+
+            let properties = self.run_properties(span.char_shape_id, span.link.is_some());
+            let mut chunk = String::new();
+            for ch in span.text.chars() {
+                while let Some(note) = notes.next_if(|n| n.position as usize <= index) {
+                    out.push_str(&text_run(&properties, &std::mem::take(&mut chunk)));
+                    out.push_str(&self.note_reference(note, location));
+                }
+                chunk.push(ch);
+                index += 1;
+            }
+            out.push_str(&text_run(&properties, &chunk));
+        }
+        if open_link.is_some() {
+            out.push_str("</w:hyperlink>");
+        }
+        for note in notes {
+            out.push_str(&self.note_reference(note, location));
+        }
+        out
+    }
+
+    fn run_properties(&self, char_shape_id: Option<u16>, is_link: bool) -> String {
+        let mut properties = String::new();
+        if is_link {
+            properties.push_str("<w:rStyle w:val=\"Hyperlink\"/>");
+        }
+        if let Some(shape) = char_shape_id.and_then(|id| self.char_properties.get(id as usize)) {
+            properties.push_str(shape);
+        }
+        if properties.is_empty() {
+            properties
+        } else {
+            format!("<w:rPr>{properties}</w:rPr>")
+        }
+    }
+
+    fn hyperlink_start(&mut self, hyperlink: &Hyperlink) -> String {
+        let target = hyperlink.target_url.trim();
+        let tooltip = hyperlink
+            .tooltip
+            .as_ref()
+            .map(|tooltip| format!(" w:tooltip=\"{}\"", escape(tooltip)))
+            .unwrap_or_default();
+
+        if hyperlink.hyperlink_type == HyperlinkType::Bookmark {
+            let anchor = target.trim_start_matches('#');
+            return format!("<w:hyperlink w:anchor=\"{}\"{tooltip}>", escape(anchor));
+        }
+
+        let target = match hyperlink.hyperlink_type {
+            HyperlinkType::Email if !target.starts_with("mailto:") => format!("mailto:{target}"),
+            _ => target.to_string(),
+        };
+        let id = self.add_relationship("hyperlink", target, true);
+        format!("<w:hyperlink r:id=\"{id}\"{tooltip}>")
+    }
+
+    fn note_reference(&mut self, note: &Footnote, location: &str) -> String {
+        let (tag, reference_style) = match note.kind {
+            NoteKind::Footnote => ("footnote", "FootnoteReference"),
+            NoteKind::Endnote => ("endnote", "EndnoteReference"),
+        };
+        let id = match note.kind {
+            NoteKind::Footnote => self.footnotes.len() + 1,
+            NoteKind::Endnote => self.endnotes.len() + 1,
+        };
+        let reference_properties =
+            format!("<w:rPr><w:rStyle w:val=\"{reference_style}\"/></w:rPr>");
+
+        let mut content = String::new();
+        for (index, paragraph) in note.paragraphs.iter().enumerate() {
+            let mark = if index == 0 {
+                format!("<w:r>{reference_properties}<w:{tag}Ref/></w:r><w:r><w:t xml:space=\"preserve\"> </w:t></w:r>")
+            } else {
+                String::new()
+            };
+            content.push_str(&format!(
+                "<w:p>{}{mark}{}</w:p>",
+                self.paragraph_properties(paragraph),
+                self.runs(paragraph, location)
+            ));
+        }
+        if content.is_empty() {
+            content = format!("<w:p><w:r>{reference_properties}<w:{tag}Ref/></w:r></w:p>");
+        }
+
+        let element = format!("<w:{tag} w:id=\"{id}\">{content}</w:{tag}>");
+        match note.kind {
+            NoteKind::Footnote => self.footnotes.push(element),
+            NoteKind::Endnote => self.endnotes.push(element),
+        }
+        format!("<w:r>{reference_properties}<w:{tag}Reference w:id=\"{id}\"/></w:r>")
+    }
+
+    /// An inline drawing for a picture paragraph
+    fn image(&mut self, paragraph: &Paragraph, location: &str) -> String {
+        let Some(image) = common::picture_image(self.document, paragraph) else {
+            self.report("image", location, "the referenced BinData has no content");
+            return String::new();
+        };
+        if common::image_mime_type(&image.file_name).is_none() {
+            let reason = format!(
+                "{} is not an image format Word can display",
+                image.file_name
+            );
+            self.report("embedded object", location, &reason);
+            return String::new();
+        }
+
+        let relationship_id = match self.media.iter().find(|m| m.bin_id == image.bin_id) {
+            Some(media) => media.relationship_id.clone(),
+            None => {
+                let id =
+                    self.add_relationship("image", format!("media/{}", image.file_name), false);
+                self.media.push(Media {
+                    bin_id: image.bin_id,
+                    relationship_id: id.clone(),
+                    file_name: image.file_name.clone(),
+                    data: image.data,
+                });
+                id
+            }
+        };
+
+        let (width, height) = paragraph
+            .picture_data
+            .as_ref()
+            .map(|picture| (picture.image_width, picture.image_height))
+            .filter(|(width, height)| *width > 0 && *height > 0)
+            .unwrap_or((14173, 14173)); // 50mm
+        let (cx, cy) = (emu(width), emu(height));
+        self.drawing_id += 1;
+        let id = self.drawing_id;
+
+        format!(
+            "<w:r><w:drawing><wp:inline distT=\"0\" distB=\"0\" distL=\"0\" distR=\"0\">\
+             <wp:extent cx=\"{cx}\" cy=\"{cy}\"/><wp:docPr id=\"{id}\" name=\"Picture {id}\"/>\
+             <wp:cNvGraphicFramePr><a:graphicFrameLocks noChangeAspect=\"1\"/></wp:cNvGraphicFramePr>\
+             <a:graphic><a:graphicData uri=\"http://schemas.openxmlformats.org/drawingml/2006/picture\">\
+             <pic:pic><pic:nvPicPr><pic:cNvPr id=\"{id}\" name=\"{name}\"/><pic:cNvPicPr/></pic:nvPicPr>\
+             <pic:blipFill><a:blip r:embed=\"{relationship_id}\"/><a:stretch><a:fillRect/></a:stretch></pic:blipFill>\
+             <pic:spPr><a:xfrm><a:off x=\"0\" y=\"0\"/><a:ext cx=\"{cx}\" cy=\"{cy}\"/></a:xfrm>\
+             <a:prstGeom prst=\"rect\"><a:avLst/></a:prstGeom></pic:spPr></pic:pic>\
+             </a:graphicData></a:graphic></wp:inline></w:drawing></w:r>",
+            name = escape(&image.file_name),
+        )
+    }
+
+    fn table(&mut self, section_idx: usize, section: &Section, table: &TableBlock) -> String {
+        if table.cells.is_empty() {
+            return String::new();
+        }
+
+        let widths = table.column_widths();
+        let cols = widths.len();
+        let rows = table.rows();
+
+        let mut xml = String::from(
+            "<w:tbl><w:tblPr><w:tblW w:w=\"0\" w:type=\"auto\"/><w:tblLayout w:type=\"fixed\"/></w:tblPr><w:tblGrid>",
+        );
+        for width in &widths {
+            xml.push_str(&format!("<w:gridCol w:w=\"{}\"/>", twips(*width as i32)));
+        }
+        xml.push_str("</w:tblGrid>");
+
+        for (row_idx, row) in rows.iter().enumerate() {
+            xml.push_str("<w:tr>");
+            let mut col = 0usize;
+            while col < cols {
+                let starting = row
+                    .iter()
+                    .find(|(cell, _)| cell.cell_address.1 as usize == col);
+                if let Some((cell, paragraphs)) = starting {
+                    let span = cell.col_span.max(1) as usize;
+                    let merge = if cell.row_span > 1 {
+                        "<w:vMerge w:val=\"restart\"/>"
+                    } else {
+                        ""
+                    };
+                    let mut content: String = paragraphs
+                        .iter()
+                        .map(|paragraph| {
+                            let location = location(section_idx, section, paragraph);
+                            self.paragraph(paragraph, &location)
+                        })
+                        .collect();
+                    if content.is_empty() {
+                        content.push_str("<w:p/>");
+                    }
+                    let location = paragraphs
+                        .first()
+                        .map(|paragraph| location(section_idx, section, paragraph))
+                        .unwrap_or_else(|| format!("section {section_idx}, table"));
+                    let cell_properties = self.cell_properties(
+                        widths[col..(col + span).min(cols)].iter().sum(),
+                        span,
+                        merge,
+                        cell.border_fill_id,
+                        &location,
+                    );
+                    xml.push_str(&format!("<w:tc>{cell_properties}{content}</w:tc>"));
+                    col += span;
+                    continue;
+                }
+
+                // A cell covered by a row span from above continues the vertical merge
+                let covering = table.cells.iter().find(|(cell, _)| {
+                    let (r, c) = (cell.cell_address.0 as usize, cell.cell_address.1 as usize);
+                    r < row_idx
+                        && row_idx < r + cell.row_span.max(1) as usize
+                        && c <= col
+                        && col < c + cell.col_span.max(1) as usize
+                });
+                match covering {
+                    Some((cell, _)) => {
+                        let start = cell.cell_address.1 as usize;
+                        let span = cell.col_span.max(1) as usize;
+                        let cell_properties = self.cell_properties(
+                            widths[start..(start + span).min(cols)].iter().sum(),
+                            span,
+                            "<w:vMerge/>",
+                            cell.border_fill_id,
+                            "",
+                        );
+                        xml.push_str(&format!("<w:tc>{cell_properties}<w:p/></w:tc>"));
+                        col = start + span;
+                    }
+                    None => {
+                        xml.push_str(&format!(
+                            "<w:tc><w:tcPr><w:tcW w:w=\"{}\" w:type=\"dxa\"/></w:tcPr><w:p/></w:tc>",
+                            twips(widths[col] as i32)
+                        ));
+                        col += 1;
+                    }
+                }
+            }
+            xml.push_str("</w:tr>");
+        }
+        xml.push_str("</w:tbl>");
+        xml
+    }
+
+    fn cell_properties(
+        &mut self,
+        width: u32,
+        span: usize,
+        merge: &str,
+        border_fill_id: u16,
+        location: &str,
+    ) -> String {
+        let mut properties = format!("<w:tcW w:w=\"{}\" w:type=\"dxa\"/>", twips(width as i32));
+        if span > 1 {
+            properties.push_str(&format!("<w:gridSpan w:val=\"{span}\"/>"));
+        }
+        properties.push_str(merge);
+
+        // Border fill ids are 1-based
+        let fill = (border_fill_id > 0)
+            .then(|| self.document.get_border_fill(border_fill_id as usize - 1))
+            .flatten();
+        if let Some(fill) = fill {
+            let mut borders = String::new();
+            for (side, line) in [
+                ("top", &fill.top),
+                ("left", &fill.left),
+                ("bottom", &fill.bottom),
+                ("right", &fill.right),
+            ] {
+                borders.push_str(&border(side, line));
+            }
+            if fill.diagonal.line_type != 0 {
+                borders.push_str(&border("tl2br", &fill.diagonal));
+            }
+            properties.push_str(&format!("<w:tcBorders>{borders}</w:tcBorders>"));
+            properties.push_str(&shading(fill));
+
+            if fill.fill_info.fill_type & 0x0C != 0
+                && !location.is_empty()
+                && self.reported_fills.insert(border_fill_id)
+            {
+                let element = if fill.fill_info.fill_type & 0x08 != 0 {
+                    "gradient fill"
+                } else {
+                    "image fill"
+                };
+                self.report(element, location, "table cells only support solid shading");
+            }
+        }
+        format!("<w:tcPr>{properties}</w:tcPr>")
+    }
+
+    /// `<w:sectPr>` with page setup, columns and header/footer references
+    fn section_properties(&mut self, section_idx: usize, section: &Section) -> String {
+        let default_page_def = PageDef::new_default();
+        let page_def = section.page_def.as_ref().unwrap_or(&default_page_def);
+        let location = format!("section {section_idx}");
+        let mut references = String::new();
+        let mut used = Vec::new();
+        let mut title_page = false;
+
+        for item in &page_def.header_footer.items {
+            let (tag, part_prefix) = match item.header_footer_type {
+                HeaderFooterType::Header => ("header", "header"),
+                HeaderFooterType::Footer => ("footer", "footer"),
+            };
+            let kind = match item.apply_type {
+                PageApplyType::All | PageApplyType::OddPages => "default",
+                PageApplyType::FirstPage => "first",
+                PageApplyType::EvenPages => "even",
+            };
+            if used.contains(&(tag, kind)) {
+                self.report(
+                    tag,
+                    &location,
+                    "Word allows one header and footer per page type in a section",
+                );
+                continue;
+            }
+            used.push((tag, kind));
+            title_page |= kind == "first";
+            self.even_and_odd_headers |= kind == "even";
+
+            let file_name = format!("{part_prefix}{}.xml", self.parts.len() + 1);
+            let xml = format!(
+                "{XML_DECLARATION}<w:{tag}r {NAMESPACES}>{}</w:{tag}r>",
+                self.header_footer_paragraph(item)
+            );
+            let content_type = match item.header_footer_type {
+                HeaderFooterType::Header => "header",
+                HeaderFooterType::Footer => "footer",
+            };
+            let id = self.add_relationship(content_type, file_name.clone(), false);
+            self.parts.push((file_name, content_type, xml));
+            references.push_str(&format!(
+                "<w:{tag}Reference w:type=\"{kind}\" r:id=\"{id}\"/>"
+            ));
+        }
+
+        let layout = page_def.layout.as_ref();
+        let orientation = if page_def.is_landscape() {
+            " w:orient=\"landscape\""
+        } else {
+            ""
+        };
+        let mut xml = format!(
+            "<w:sectPr>{references}<w:pgSz w:w=\"{}\" w:h=\"{}\"{orientation}/>\
+             <w:pgMar w:top=\"{}\" w:right=\"{}\" w:bottom=\"{}\" w:left=\"{}\" \
+             w:header=\"{}\" w:footer=\"{}\" w:gutter=\"{}\"/>",
+            twips(page_def.width as i32),
+            twips(page_def.height as i32),
+            twips(page_def.top_margin as i32),
+            twips(page_def.right_margin as i32),
+            twips(page_def.bottom_margin as i32),
+            twips(page_def.left_margin as i32),
+            twips(page_def.header_margin as i32),
+            twips(page_def.footer_margin as i32),
+            twips(page_def.gutter_margin as i32),
+        );
+
+        if page_def.page_border_fill_id != 0 || layout.is_some_and(|l| l.page_border) {
+            self.report("page border", &location, "page borders are not exported");
+        }
+        if let Some(start) = layout
+            .map(|l| l.start_page_number)
+            .filter(|start| *start > 1)
+        {
+            xml.push_str(&format!("<w:pgNumType w:start=\"{start}\"/>"));
+        }
+
+        let (columns, spacing, separator) = match layout {
+            Some(layout) => (layout.columns, layout.column_spacing, layout.column_line),
+            None => section
+                .section_def
+                .as_ref()
+                .map(|def| (def.column_count(), def.column_gap as u32, false))
+                .unwrap_or((1, 0, false)),
+        };
+        if columns > 1 {
+            let separator = if separator { " w:sep=\"1\"" } else { "" };
+            xml.push_str(&format!(
+                "<w:cols w:num=\"{columns}\" w:space=\"{}\"{separator}/>",
+                twips(spacing as i32)
+            ));
+        }
+        if title_page {
+            xml.push_str("<w:titlePg/>");
+        }
+        xml.push_str("</w:sectPr>");
+        xml
+    }
+
+    fn header_footer_paragraph(&self, item: &HeaderFooter) -> String {
+        let align = match item.alignment {
+            1 => "center",
+            2 => "right",
+            _ => "left",
+        };
+        let properties = self.run_properties(Some(item.char_shape_id), false);
+        let mut runs = text_run(&properties, &item.text);
+        if item.include_page_number {
+            let format = match item.page_number_format {
+                2 => " \\* roman",
+                3 => " \\* ROMAN",
+                4 => " \\* alphabetic",
+                5 => " \\* ALPHABETIC",
+                _ => "",
+            };
+            runs.push_str(&format!(
+                "<w:fldSimple w:instr=\" PAGE{format} \"><w:r>{properties}<w:t>1</w:t></w:r></w:fldSimple>"
+            ));
+        }
+        format!("<w:p><w:pPr><w:jc w:val=\"{align}\"/></w:pPr>{runs}</w:p>")
+    }
+
+    /// Assemble the zip package around the main document part
+    fn package(&self, document_xml: &str, has_background: bool) -> Result<Vec<u8>> {
+        let mut zip = ZipWriter::new(Cursor::new(Vec::new()));
+        let options =
+            SimpleFileOptions::default().compression_method(zip::CompressionMethod::Deflated);
+
+        write_part(
+            &mut zip,
+            "[Content_Types].xml",
+            self.content_types().as_bytes(),
+            options,
+        )?;
+        write_part(
+            &mut zip,
+            "_rels/.rels",
+            package_relationships().as_bytes(),
+            options,
+        )?;
+        write_part(
+            &mut zip,
+            "docProps/core.xml",
+            self.core_properties().as_bytes(),
+            options,
+        )?;
+        write_part(
+            &mut zip,
+            "word/document.xml",
+            document_xml.as_bytes(),
+            options,
+        )?;
+        write_part(
+            &mut zip,
+            "word/_rels/document.xml.rels",
+            self.document_relationships().as_bytes(),
+            options,
+        )?;
+        write_part(
+            &mut zip,
+            "word/styles.xml",
+            styles(self.document).as_bytes(),
+            options,
+        )?;
+        write_part(
+            &mut zip,
+            "word/settings.xml",
+            self.settings(has_background).as_bytes(),
+            options,
+        )?;
+        write_part(
+            &mut zip,
+            "word/footnotes.xml",
+            notes_part("footnote", &self.footnotes).as_bytes(),
+            options,
+        )?;
+        write_part(
+            &mut zip,
+            "word/endnotes.xml",
+            notes_part("endnote", &self.endnotes).as_bytes(),
+            options,
+        )?;
+        for (file_name, _, xml) in &self.parts {
+            write_part(
+                &mut zip,
+                &format!("word/{file_name}"),
+                xml.as_bytes(),
+                options,
+            )?;
+        }
+        for media in &self.media {
+            let stored =
+                SimpleFileOptions::default().compression_method(zip::CompressionMethod::Stored);
+            write_part(
+                &mut zip,
+                &format!("word/media/{}", media.file_name),
+                &media.data,
+                stored,
+            )?;
+        }
+
+        let cursor = zip
+            .finish()
+            .map_err(|e| HwpError::Io(std::io::Error::other(e)))?;
+        Ok(cursor.into_inner())
+    }
+
+    fn content_types(&self) -> String {
+        const MAIN: &str = "application/vnd.openxmlformats-officedocument.wordprocessingml";
+        let mut xml = format!(
+            "{XML_DECLARATION}<Types xmlns=\"http://schemas.openxmlformats.org/package/2006/content-types\">\
+             <Default Extension=\"rels\" ContentType=\"application/vnd.openxmlformats-package.relationships+xml\"/>\
+             <Default Extension=\"xml\" ContentType=\"application/xml\"/>"
+        );
+        let mut extensions = Vec::new();
+        for media in &self.media {
+            let extension = media.file_name.rsplit('.').next().unwrap_or_default();
+            if !extensions.contains(&extension) {
+                extensions.push(extension);
+                xml.push_str(&format!(
+                    "<Default Extension=\"{extension}\" ContentType=\"{}\"/>",
+                    common::image_mime_type(&media.file_name).unwrap_or("application/octet-stream")
+                ));
+            }
+        }
+
+        let overrides = [
+            ("/word/document.xml", format!("{MAIN}.document.main+xml")),
+            ("/word/styles.xml", format!("{MAIN}.styles+xml")),
+            ("/word/settings.xml", format!("{MAIN}.settings+xml")),
+            ("/word/footnotes.xml", format!("{MAIN}.footnotes+xml")),
+            ("/word/endnotes.xml", format!("{MAIN}.endnotes+xml")),
+            (
+                "/docProps/core.xml",
+                "application/vnd.openxmlformats-package.core-properties+xml".to_string(),
+            ),
+        ];
+        for (part, content_type) in overrides {
+            xml.push_str(&format!(
+                "<Override PartName=\"{part}\" ContentType=\"{content_type}\"/>"
+            ));
+        }
+        for (file_name, kind, _) in &self.parts {
+            xml.push_str(&format!(
+                "<Override PartName=\"/word/{file_name}\" ContentType=\"{MAIN}.{kind}+xml\"/>"
+            ));
+        }
+        xml.push_str("</Types>");
+        xml
+    }
+
+    fn core_properties(&self) -> String {
+        let mut properties = String::new();
+        for (tag, value) in [
+            ("dc:title", self.document.title()),
+            ("dc:subject", self.document.subject()),
+            ("dc:creator", self.document.author()),
+            ("cp:keywords", self.document.keywords()),
+        ] {
+            if let Some(value) = value.filter(|v| !v.is_empty()) {
+                properties.push_str(&format!("<{tag}>{}</{tag}>", escape(value)));
+            }
+        }
+        format!(
+            "{XML_DECLARATION}<cp:coreProperties \
+             xmlns:cp=\"http://schemas.openxmlformats.org/package/2006/metadata/core-properties\" \
+             xmlns:dc=\"http://purl.org/dc/elements/1.1/\">{properties}</cp:coreProperties>"
+        )
+    }
+
+    fn document_relationships(&self) -> String {
+        let mut xml = format!(
+            "{XML_DECLARATION}<Relationships xmlns=\"http://schemas.openxmlformats.org/package/2006/relationships\">"
+        );
+        let fixed = [
+            ("rId1", "styles", "styles.xml"),
+            ("rId2", "settings", "settings.xml"),
+            ("rId3", "footnotes", "footnotes.xml"),
+            ("rId4", "endnotes", "endnotes.xml"),
+        ];
+        for (id, kind, target) in fixed {
+            xml.push_str(&format!(
+                "<Relationship Id=\"{id}\" Type=\"{RELATIONSHIP_TYPE}/{kind}\" Target=\"{target}\"/>"
+            ));
+        }
+        for relationship in &self.relationships {
+            let mode = if relationship.external {
+                " TargetMode=\"External\""
+            } else {
+                ""
+            };
+            xml.push_str(&format!(
+                "<Relationship Id=\"{}\" Type=\"{RELATIONSHIP_TYPE}/{}\" Target=\"{}\"{mode}/>",
+                relationship.id,
+                relationship.kind,
+                escape(&relationship.target)
+            ));
+        }
+        xml.push_str("</Relationships>");
+        xml
+    }
+
+    fn settings(&self, has_background: bool) -> String {
+        let mut xml = format!(
+            "{XML_DECLARATION}<w:settings xmlns:w=\"http://schemas.openxmlformats.org/wordprocessingml/2006/main\">"
+        );
+        if has_background {
+            xml.push_str("<w:displayBackgroundShape/>");
+        }
+        if self.even_and_odd_headers {
+            xml.push_str("<w:evenAndOddHeaders/>");
+        }
+        xml.push_str(
+            "<w:footnotePr><w:footnote w:id=\"-1\"/><w:footnote w:id=\"0\"/></w:footnotePr>\
+             <w:endnotePr><w:endnote w:id=\"-1\"/><w:endnote w:id=\"0\"/></w:endnotePr>\
+             <w:compat><w:compatSetting w:name=\"compatibilityMode\" \
+             w:uri=\"http://schemas.microsoft.com/office/word\" w:val=\"15\"/></w:compat>\
+             </w:settings>",
+        );
+        xml
+    }
+}
+
+fn write_part<W: Write + Seek>(
+    zip: &mut ZipWriter<W>,
+    name: &str,
+    data: &[u8],
+    options: SimpleFileOptions,
+) -> Result<()> {
+    zip.start_file(name, options)
+        .map_err(|e| HwpError::Io(std::io::Error::other(e)))?;
+    zip.write_all(data).map_err(HwpError::Io)
+}
+
+fn package_relationships() -> String {
+    format!(
+        "{XML_DECLARATION}<Relationships xmlns=\"http://schemas.openxmlformats.org/package/2006/relationships\">\
+         <Relationship Id=\"rId1\" Type=\"{RELATIONSHIP_TYPE}/officeDocument\" Target=\"word/document.xml\"/>\
+         <Relationship Id=\"rId2\" \
+         Type=\"http://schemas.openxmlformats.org/package/2006/relationships/metadata/core-properties\" \
+         Target=\"docProps/core.xml\"/></Relationships>"
+    )
+}
+
+/// footnotes.xml or endnotes.xml with the separators Word expects at ids -1 and 0
+fn notes_part(tag: &str, notes: &[String]) -> String {
+    format!(
+        "{XML_DECLARATION}<w:{tag}s {NAMESPACES}>\
+         <w:{tag} w:type=\"separator\" w:id=\"-1\"><w:p><w:r><w:separator/></w:r></w:p></w:{tag}>\
+         <w:{tag} w:type=\"continuationSeparator\" w:id=\"0\"><w:p><w:r><w:continuationSeparator/></w:r></w:p></w:{tag}>\
+         {}</w:{tag}s>",
+        notes.concat()
+    )
+}
+
+fn is_character_style(style: &crate::model::style::Style) -> bool {
+    // Bits 0-2: style kind (0 = paragraph, 1 = character)
+    style.properties & 0x7 == 1
+}
+
+/// Named styles `StyleN` for every style in DocInfo, plus the reference styles used by runs
+fn styles(document: &HwpDocument) -> String {
+    let mut xml = format!(
+        "{XML_DECLARATION}<w:styles xmlns:w=\"http://schemas.openxmlformats.org/wordprocessingml/2006/main\">\
+         <w:docDefaults><w:rPrDefault><w:rPr><w:lang w:eastAsia=\"ko-KR\"/></w:rPr></w:rPrDefault>\
+         <w:pPrDefault/></w:docDefaults>"
+    );
+
+    for (id, style) in document.doc_info.styles.iter().enumerate() {
+        let character = is_character_style(style);
+        let kind = if character { "character" } else { "paragraph" };
+        let default = if id == 0 && !character {
+            " w:default=\"1\""
+        } else {
+            ""
+        };
+        let name = if style.name.trim().is_empty() {
+            style.english_name.trim()
+        } else {
+            style.name.trim()
+        };
+        let name = if name.is_empty() {
+            format!("Style {id}")
+        } else {
+            name.to_string()
+        };
+
+        xml.push_str(&format!(
+            "<w:style w:type=\"{kind}\" w:styleId=\"Style{id}\"{default}><w:name w:val=\"{}\"/>",
+            escape(&name)
+        ));
+        if !character
+            && document
+                .get_style(style.next_style_id as usize)
+                .is_some_and(|next| !is_character_style(next))
+        {
+            xml.push_str(&format!("<w:next w:val=\"Style{}\"/>", style.next_style_id));
+        }
+        xml.push_str("<w:qFormat/>");
+        if !character {
+            if let Some(shape) = document.get_para_shape(style.para_shape_id as usize) {
+                xml.push_str(&format!(
+                    "<w:pPr>{}</w:pPr>",
+                    para_shape_properties(document, shape)
+                ));
+            }
+        }
+        if let Some(shape) = document.get_char_shape(style.char_shape_id as usize) {
+            xml.push_str(&format!(
+                "<w:rPr>{}</w:rPr>",
+                char_shape_properties(document, shape)
+            ));
+        }
+        xml.push_str("</w:style>");
+    }
+
+    xml.push_str(
+        "<w:style w:type=\"character\" w:styleId=\"Hyperlink\"><w:name w:val=\"Hyperlink\"/>\
+         <w:rPr><w:color w:val=\"0000FF\"/><w:u w:val=\"single\"/></w:rPr></w:style>\
+         <w:style w:type=\"character\" w:styleId=\"FootnoteReference\"><w:name w:val=\"footnote reference\"/>\
+         <w:rPr><w:vertAlign w:val=\"superscript\"/></w:rPr></w:style>\
+         <w:style w:type=\"character\" w:styleId=\"EndnoteReference\"><w:name w:val=\"endnote reference\"/>\
+         <w:rPr><w:vertAlign w:val=\"superscript\"/></w:rPr></w:style>\
+         </w:styles>",
+    );
+    xml
+}
+
+/// `<w:rPr>` children for a character shape, in schema order
+fn char_shape_properties(document: &HwpDocument, shape: &CharShape) -> String {
+    let mut xml = String::new();
+
+    // Slot 1 is Latin, slot 0 Hangul
+    let face = |slot: usize| {
+        document
+            .get_face_name(shape.face_name_ids[slot] as usize)
+            .map(|face| face.font_name.trim())
+            .filter(|name| !name.is_empty())
+    };
+    let mut fonts = String::new();
+    if let Some(latin) = face(1) {
+        let latin = escape(latin);
+        fonts.push_str(&format!(" w:ascii=\"{latin}\" w:hAnsi=\"{latin}\""));
+    }
+    if let Some(hangul) = face(0) {
+        fonts.push_str(&format!(" w:eastAsia=\"{}\"", escape(hangul)));
+    }
+    if !fonts.is_empty() {
+        xml.push_str(&format!("<w:rFonts{fonts}/>"));
+    }
+
+    if shape.is_bold() {
+        xml.push_str("<w:b/>");
+    }
+    if shape.is_italic() {
+        xml.push_str("<w:i/>");
+    }
+    if shape.is_strikethrough() {
+        xml.push_str("<w:strike/>");
+    }
+    if shape.get_outline_type() != 0 {
+        xml.push_str("<w:outline/>");
+    }
+    if shape.get_shadow_type() != 0 {
+        xml.push_str("<w:shadow/>");
+    }
+    xml.push_str(&format!("<w:color w:val=\"{}\"/>", color(shape.text_color)));
+    // Letter spacing is a percentage of the font size
+    if shape.char_spaces[0] != 0 && shape.base_size > 0 {
+        let spacing = shape.base_size * shape.char_spaces[0] as i32 / 500;
+        xml.push_str(&format!("<w:spacing w:val=\"{spacing}\"/>"));
+    }
+    if shape.ratios[0] != 0 && shape.ratios[0] != 100 {
+        xml.push_str(&format!("<w:w w:val=\"{}\"/>", shape.ratios[0]));
+    }
+    if shape.base_size > 0 {
+        // Half-points
+        let size = shape.base_size / 50;
+        xml.push_str(&format!(
+            "<w:sz w:val=\"{size}\"/><w:szCs w:val=\"{size}\"/>"
+        ));
+    }
+    if shape.is_underline() {
+        xml.push_str(&format!(
+            "<w:u w:val=\"single\" w:color=\"{}\"/>",
+            color(shape.underline_color)
+        ));
+    }
+    // White (or unset) shading means no highlight
+    if shape.shade_color & 0xFFFFFF != 0xFFFFFF && shape.shade_color != 0 {
+        xml.push_str(&format!(
+            "<w:shd w:val=\"clear\" w:color=\"auto\" w:fill=\"{}\"/>",
+            color(shape.shade_color)
+        ));
+    }
+    // Bits 15-16: superscript and subscript
+    if shape.properties & (1 << 15) != 0 {
+        xml.push_str("<w:vertAlign w:val=\"superscript\"/>");
+    } else if shape.properties & (1 << 16) != 0 {
+        xml.push_str("<w:vertAlign w:val=\"subscript\"/>");
+    }
+    xml
+}
+
+/// `<w:pPr>` children for a paragraph shape, in schema order
+fn para_shape_properties(document: &HwpDocument, shape: &ParaShape) -> String {
+    let mut xml = String::new();
+
+    let fill = (shape.border_fill_id > 0)
+        .then(|| document.get_border_fill(shape.border_fill_id as usize - 1))
+        .flatten();
+    if let Some(fill) = fill {
+        let lines = [
+            ("top", &fill.top),
+            ("left", &fill.left),
+            ("bottom", &fill.bottom),
+            ("right", &fill.right),
+        ];
+        if lines.iter().any(|(_, line)| line.line_type != 0) {
+            xml.push_str("<w:pBdr>");
+            for (side, line) in lines {
+                xml.push_str(&border(side, line));
+            }
+            xml.push_str("</w:pBdr>");
+        }
+        xml.push_str(&shading(fill));
+    }
+
+    let mut spacing = String::new();
+    if shape.top_para_space != 0 {
+        spacing.push_str(&format!(" w:before=\"{}\"", twips(shape.top_para_space)));
+    }
+    if shape.bottom_para_space != 0 {
+        spacing.push_str(&format!(" w:after=\"{}\"", twips(shape.bottom_para_space)));
+    }
+    match shape.line_space_type {
+        0 if shape.line_space > 0 => spacing.push_str(&format!(
+            " w:line=\"{}\" w:lineRule=\"auto\"",
+            shape.line_space * 240 / 100
+        )),
+        1 if shape.line_space > 0 => spacing.push_str(&format!(
+            " w:line=\"{}\" w:lineRule=\"exact\"",
+            twips(shape.line_space)
+        )),
+        2 if shape.line_space > 0 => spacing.push_str(&format!(
+            " w:line=\"{}\" w:lineRule=\"atLeast\"",
+            twips(shape.line_space)
+        )),
+        _ => {}
+    }
+    if !spacing.is_empty() {
+        xml.push_str(&format!("<w:spacing{spacing}/>"));
+    }
+
+    let mut indent = String::new();
+    if shape.left_margin != 0 {
+        indent.push_str(&format!(" w:left=\"{}\"", twips(shape.left_margin)));
+    }
+    if shape.right_margin != 0 {
+        indent.push_str(&format!(" w:right=\"{}\"", twips(shape.right_margin)));
+    }
+    if shape.indent > 0 {
+        indent.push_str(&format!(" w:firstLine=\"{}\"", twips(shape.indent)));
+    } else if shape.indent < 0 {
+        indent.push_str(&format!(" w:hanging=\"{}\"", twips(-shape.indent)));
+    }
+    if !indent.is_empty() {
+        xml.push_str(&format!("<w:ind{indent}/>"));
+    }
+
+    let align = match shape.get_alignment() {
+        1 => "right",
+        2 => "center",
+        3 => "both",
+        4 => "distribute",
+        _ => "left",
+    };
+    xml.push_str(&format!("<w:jc w:val=\"{align}\"/>"));
+    xml
+}
+
+fn border(side: &str, line: &BorderLine) -> String {
+    let style = match line.line_type {
+        0 => return format!("<w:{side} w:val=\"nil\"/>"),
+        2 => "dashed",
+        3 => "dotted",
+        4 => "double",
+        5 => "thick",
+        _ => "single",
+    };
+    // Thickness is an index into 0.1mm .. 5mm; w:sz is in eighths of a point
+    const THICKNESS_MM: [f64; 16] = [
+        0.1, 0.12, 0.15, 0.2, 0.25, 0.3, 0.4, 0.5, 0.6, 0.7, 1.0, 1.5, 2.0, 3.0, 4.0, 5.0,
+    ];
+    let mm = THICKNESS_MM
+        .get(line.thickness as usize)
+        .copied()
+        .unwrap_or(0.12);
+    let size = ((mm * 72.0 / 25.4 * 8.0).round() as u32).clamp(2, 96);
+    format!(
+        "<w:{side} w:val=\"{style}\" w:sz=\"{size}\" w:space=\"0\" w:color=\"{}\"/>",
+        color(line.color)
+    )
+}
+
+/// Solid shading; gradients fall back to their start colour
+fn shading(fill: &BorderFill) -> String {
+    let info = &fill.fill_info;
+    let color_value = if info.fill_type & 0x01 != 0 {
+        info.back_color
+    } else if let Some(gradient) = &info.gradient_info {
+        gradient.start_color
+    } else {
+        return String::new();
+    };
+    format!(
+        "<w:shd w:val=\"clear\" w:color=\"auto\" w:fill=\"{}\"/>",
+        color(color_value)
+    )
+}
+
+/// A run of text with tabs and line breaks, or nothing for empty text
+fn text_run(properties: &str, text: &str) -> String {
+    if text.is_empty() {
+        return String::new();
+    }
+    let mut content = String::new();
+    let mut pending = String::new();
+    let flush = |content: &mut String, pending: &mut String| {
+        if !pending.is_empty() {
+            content.push_str(&format!(
+                "<w:t xml:space=\"preserve\">{}</w:t>",
+                escape(pending.as_str())
+            ));
+            pending.clear();
+        }
+    };
+    for ch in text.chars() {
+        match ch {
+            '\n' => {
+                flush(&mut content, &mut pending);
+                content.push_str("<w:br/>");
+            }
+            '\t' => {
+                flush(&mut content, &mut pending);
+                content.push_str("<w:tab/>");
+            }
+            _ => pending.push(ch),
+        }
+    }
+    flush(&mut content, &mut pending);
+    format!("<w:r>{properties}{content}</w:r>")
+}
+
+/// HWPUNIT (1/7200 inch) to twentieths of a point
+fn twips(value: i32) -> i32 {
+    value / 5
+}
+
+/// HWPUNIT to English Metric Units
+fn emu(value: u32) -> u64 {
+    value as u64 * 127
+}
+
+fn color(value: u32) -> String {
+    format!("{:06X}", value & 0xFFFFFF)
+}
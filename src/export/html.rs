@@ -0,0 +1,599 @@
+//! HTML5 export with CSS derived from the document's shapes
+
+use std::path::Path;
+
+use super::common::{self, Block, ListKind, TableBlock};
+use super::ExportedImage;
+use crate::error::{HwpError, Result};
+use crate::model::border_fill::{BorderFill, BorderLine};
+use crate::model::footnote::NoteKind;
+use crate::model::{CharShape, ParaShape, Paragraph};
+use crate::HwpDocument;
+
+/// Options for [`export`]
+#[derive(Debug, Clone)]
+pub struct HtmlOptions {
+    /// Inline the stylesheet and embed images as data URIs
+    pub single_file: bool,
+    /// Directory for extracted images, relative to the HTML file
+    pub image_dir: String,
+    /// Stylesheet file name used when `single_file` is off
+    pub css_file: String,
+    /// Page title; defaults to the document title from summary info
+    pub title: Option<String>,
+}
+
+impl Default for HtmlOptions {
+    fn default() -> Self {
+        Self {
+            single_file: false,
+            image_dir: "images".to_string(),
+            css_file: "style.css".to_string(),
+            title: None,
+        }
+    }
+}
+
+impl HtmlOptions {
+    /// Options producing one self-contained HTML file
+    pub fn single_file() -> Self {
+        Self {
+            single_file: true,
+            ..Self::default()
+        }
+    }
+}
+
+/// HTML text together with its stylesheet and images
+#[derive(Debug, Clone, Default)]
+pub struct HtmlExport {
+    pub html: String,
+    /// Stylesheet; already inlined in `html` in single-file mode
+    pub css: String,
+    /// Images referenced by path; empty in single-file mode
+    pub images: Vec<ExportedImage>,
+    pub css_file: String,
+    pub image_dir: String,
+}
+
+impl HtmlExport {
+    /// Write the HTML file, its stylesheet and images next to it
+    pub fn write_to<P: AsRef<Path>>(&self, path: P) -> Result<()> {
+        let path = path.as_ref();
+        let dir = path.parent().unwrap_or_else(|| Path::new("."));
+        std::fs::write(path, &self.html).map_err(HwpError::Io)?;
+
+        if !self.html.contains("<style>") {
+            std::fs::write(dir.join(&self.css_file), &self.css).map_err(HwpError::Io)?;
+        }
+        if !self.images.is_empty() {
+            let image_dir = dir.join(&self.image_dir);
+            std::fs::create_dir_all(&image_dir).map_err(HwpError::Io)?;
+            for image in &self.images {
+                std::fs::write(image_dir.join(&image.file_name), &image.data)
+                    .map_err(HwpError::Io)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Convert a document to HTML5
+pub fn export(document: &HwpDocument, options: &HtmlOptions) -> HtmlExport {
+    let mut writer = HtmlWriter {
+        document,
+        options,
+        body: String::new(),
+        images: Vec::new(),
+        notes: Vec::new(),
+        open_lists: Vec::new(),
+        section: 0,
+    };
+
+    for (index, section) in document.sections().enumerate() {
+        writer.section = index;
+        writer.body.push_str("<section>\n");
+        for block in common::blocks(section) {
+            match block {
+                Block::Paragraph(paragraph) => writer.paragraph(paragraph),
+                Block::Table(table) => {
+                    writer.close_lists(0);
+                    writer.table(&table);
+                }
+            }
+        }
+        writer.close_lists(0);
+        writer.body.push_str("</section>\n");
+    }
+    writer.footnotes();
+
+    let css = stylesheet(document);
+    let title = options
+        .title
+        .clone()
+        .or_else(|| document.title().map(str::to_string))
+        .unwrap_or_default();
+
+    let mut html = String::from("<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n");
+    html.push_str(&format!("<title>{}</title>\n", escape(&title)));
+    if options.single_file {
+        html.push_str(&format!("<style>\n{css}</style>\n"));
+    } else {
+        html.push_str(&format!(
+            "<link rel=\"stylesheet\" href=\"{}\">\n",
+            escape(&options.css_file)
+        ));
+    }
+    html.push_str("</head>\n<body>\n");
+    html.push_str(&writer.body);
+    html.push_str("</body>\n</html>\n");
+
+    HtmlExport {
+        html,
+        css,
+        images: writer.images,
+        css_file: options.css_file.clone(),
+        image_dir: options.image_dir.clone(),
+    }
+}
+
+/// Convert a document to a self-contained HTML page
+pub fn to_html(document: &HwpDocument) -> String {
+    export(document, &HtmlOptions::single_file()).html
+}
+
+/// Convert a document and write the HTML file with its stylesheet and images
+pub fn write_html<P: AsRef<Path>>(
+    document: &HwpDocument,
+    path: P,
+    options: &HtmlOptions,
+) -> Result<()> {
+    export(document, options).write_to(path)
+}
+
+/// CSS classes `csN`, `psN` and `bfN` for every shape in DocInfo
+fn stylesheet(document: &HwpDocument) -> String {
+    let doc_info = &document.doc_info;
+    let mut css = String::from(
+        "body { font-family: sans-serif; }\n\
+         table { border-collapse: collapse; }\n\
+         td { vertical-align: top; }\n\
+         p { margin: 0; }\n",
+    );
+
+    for (id, shape) in doc_info.char_shapes.iter().enumerate() {
+        css.push_str(&format!(
+            ".cs{id} {{ {} }}\n",
+            char_shape_css(document, shape)
+        ));
+    }
+    for (id, shape) in doc_info.para_shapes.iter().enumerate() {
+        css.push_str(&format!(".ps{id} {{ {} }}\n", para_shape_css(shape)));
+    }
+    // Border fill ids are 1-based
+    for (index, fill) in doc_info.border_fills.iter().enumerate() {
+        css.push_str(&format!(
+            ".bf{} {{ {} }}\n",
+            index + 1,
+            border_fill_css(fill)
+        ));
+    }
+    css
+}
+
+fn char_shape_css(document: &HwpDocument, shape: &CharShape) -> String {
+    let mut rules = Vec::new();
+
+    // Slot 1 is Latin, slot 0 Hangul; list both so the browser can fall back
+    let mut families: Vec<String> = Vec::new();
+    for slot in [1, 0] {
+        if let Some(face) = document.get_face_name(shape.face_name_ids[slot] as usize) {
+            let name = face.font_name.trim();
+            if !name.is_empty() && !families.iter().any(|f| f == name) {
+                families.push(name.to_string());
+            }
+        }
+    }
+    if !families.is_empty() {
+        let quoted: Vec<String> = families
+            .iter()
+            .map(|f| format!("\"{}\"", css_string(f)))
+            .collect();
+        rules.push(format!("font-family: {}, sans-serif", quoted.join(", ")));
+    }
+
+    if shape.base_size > 0 {
+        rules.push(format!("font-size: {}pt", points(shape.base_size)));
+    }
+    rules.push(format!("color: {}", color(shape.text_color)));
+    if shape.is_bold() {
+        rules.push("font-weight: bold".to_string());
+    }
+    if shape.is_italic() {
+        rules.push("font-style: italic".to_string());
+    }
+    let mut decorations = Vec::new();
+    if shape.is_underline() {
+        decorations.push("underline");
+    }
+    if shape.is_strikethrough() {
+        decorations.push("line-through");
+    }
+    if !decorations.is_empty() {
+        rules.push(format!("text-decoration: {}", decorations.join(" ")));
+    }
+    // White (or unset) shading means no highlight
+    if shape.shade_color & 0xFFFFFF != 0xFFFFFF && shape.shade_color != 0 {
+        rules.push(format!("background-color: {}", color(shape.shade_color)));
+    }
+
+    rules.join("; ") + ";"
+}
+
+fn para_shape_css(shape: &ParaShape) -> String {
+    let align = match shape.get_alignment() {
+        1 => "right",
+        2 => "center",
+        3 | 4 => "justify",
+        _ => "left",
+    };
+    let mut rules = vec![format!("text-align: {align}")];
+
+    let lengths = [
+        ("margin-left", shape.left_margin),
+        ("margin-right", shape.right_margin),
+        ("text-indent", shape.indent),
+        ("margin-top", shape.top_para_space),
+        ("margin-bottom", shape.bottom_para_space),
+    ];
+    for (property, value) in lengths {
+        if value != 0 {
+            rules.push(format!("{property}: {}pt", points(value)));
+        }
+    }
+
+    match shape.line_space_type {
+        0 if shape.line_space > 0 => rules.push(format!("line-height: {}%", shape.line_space)),
+        1 if shape.line_space > 0 => {
+            rules.push(format!("line-height: {}pt", points(shape.line_space)))
+        }
+        // At least the given height, as in layout
+        2 if shape.line_space > 0 => rules.push(format!(
+            "line-height: max(1em, {}pt)",
+            points(shape.line_space)
+        )),
+        _ => {}
+    }
+
+    rules.join("; ") + ";"
+}
+
+fn border_fill_css(fill: &BorderFill) -> String {
+    let mut rules = Vec::new();
+    for (side, line) in [
+        ("left", &fill.left),
+        ("right", &fill.right),
+        ("top", &fill.top),
+        ("bottom", &fill.bottom),
+    ] {
+        rules.push(format!("border-{side}: {}", border_line_css(line)));
+    }
+    if fill.fill_info.fill_type != 0 {
+        rules.push(format!(
+            "background-color: {}",
+            color(fill.fill_info.back_color)
+        ));
+    }
+    rules.join("; ") + ";"
+}
+
+fn border_line_css(line: &BorderLine) -> String {
+    let style = match line.line_type {
+        0 => return "none".to_string(),
+        2 => "dashed",
+        3 => "dotted",
+        4 => "double",
+        _ => "solid",
+    };
+    // Thickness is an index into 0.1mm .. 5mm; approximate in pixels
+    let width = if line.line_type == 5 {
+        3
+    } else {
+        1 + line.thickness as u32 / 4
+    };
+    format!("{width}px {style} {}", color(line.color))
+}
+
+/// HWPUNIT (1/100 pt) as a point value without trailing zeros
+fn points(value: i32) -> String {
+    let formatted = format!("{:.2}", value as f64 / 100.0);
+    formatted
+        .trim_end_matches('0')
+        .trim_end_matches('.')
+        .to_string()
+}
+
+fn color(value: u32) -> String {
+    format!("#{:06X}", value & 0xFFFFFF)
+}
+
+struct HtmlWriter<'a> {
+    document: &'a HwpDocument,
+    options: &'a HtmlOptions,
+    body: String,
+    images: Vec<ExportedImage>,
+    /// Footnote and endnote bodies in reference order: (anchor id, label, html)
+    notes: Vec<(String, String, String)>,
+    /// Tags of the lists currently open, outermost first
+    open_lists: Vec<&'static str>,
+    /// Index of the section being written; note numbers restart in each one
+    section: usize,
+}
+
+impl HtmlWriter<'_> {
+    fn paragraph(&mut self, paragraph: &Paragraph) {
+        if paragraph.picture_data.is_some() {
+            if let Some(img) = self.image(paragraph) {
+                self.close_lists(0);
+                self.body.push_str(&format!("<figure>{img}</figure>\n"));
+            }
+        }
+        if let Some(text_box) = &paragraph.text_box_data {
+            if !text_box.text.trim().is_empty() {
+                self.close_lists(0);
+                let lines: Vec<String> = text_box.text.lines().map(escape).collect();
+                self.body
+                    .push_str(&format!("<aside>{}</aside>\n", lines.join("<br>")));
+            }
+        }
+
+        let text = common::paragraph_text(paragraph);
+        if text.trim().is_empty() && paragraph.footnotes.is_empty() {
+            if paragraph.picture_data.is_none() && paragraph.text_box_data.is_none() {
+                self.close_lists(0);
+                self.body.push_str("<p><br></p>\n");
+            }
+            return;
+        }
+
+        let class = self.paragraph_class(paragraph);
+
+        if let Some(level) = common::heading_level(self.document, paragraph) {
+            self.close_lists(0);
+            let content = self.inline(paragraph, &text, 0);
+            self.body
+                .push_str(&format!("<h{level}{class}>{content}</h{level}>\n"));
+            return;
+        }
+
+        if let Some(item) = common::list_item(self.document, paragraph, &text) {
+            let tag = match item.kind {
+                ListKind::Bullet => "ul",
+                ListKind::Ordered => "ol",
+            };
+            let depth = item.level + 1;
+            self.close_lists(depth);
+            if self.open_lists.len() == depth && self.open_lists[depth - 1] != tag {
+                self.close_lists(depth - 1);
+            }
+            while self.open_lists.len() < depth {
+                self.body.push_str(&format!("<{tag}>\n"));
+                self.open_lists.push(tag);
+            }
+            let content = self.inline(paragraph, &text, item.marker_len);
+            self.body.push_str(&format!("<li>{content}</li>\n"));
+            return;
+        }
+
+        self.close_lists(0);
+        let content = self.inline(paragraph, &text, 0);
+        self.body.push_str(&format!("<p{class}>{content}</p>\n"));
+    }
+
+    fn paragraph_class(&self, paragraph: &Paragraph) -> String {
+        if self
+            .document
+            .get_para_shape(paragraph.para_shape_id as usize)
+            .is_some()
+        {
+            format!(" class=\"ps{}\"", paragraph.para_shape_id)
+        } else {
+            String::new()
+        }
+    }
+
+    /// Close open lists until only `depth` remain
+    fn close_lists(&mut self, depth: usize) {
+        while self.open_lists.len() > depth {
+            let tag = self.open_lists.pop().unwrap_or("ul");
+            self.body.push_str(&format!("</{tag}>\n"));
+        }
+    }
+
+    fn table(&mut self, table: &TableBlock) {
+        if table.cells.is_empty() {
+            return;
+        }
+        let mut html = String::from("<table>\n");
+        for row in table.rows() {
+            html.push_str("<tr>");
+            for (cell, paragraphs) in row {
+                let mut attrs = String::new();
+                if cell.col_span > 1 {
+                    attrs.push_str(&format!(" colspan=\"{}\"", cell.col_span));
+                }
+                if cell.row_span > 1 {
+                    attrs.push_str(&format!(" rowspan=\"{}\"", cell.row_span));
+                }
+                let border_fill_id = cell.border_fill_id;
+                if border_fill_id > 0
+                    && self
+                        .document
+                        .get_border_fill(border_fill_id as usize - 1)
+                        .is_some()
+                {
+                    attrs.push_str(&format!(" class=\"bf{border_fill_id}\""));
+                }
+
+                let content: Vec<String> = paragraphs
+                    .iter()
+                    .map(|paragraph| {
+                        let text = common::paragraph_text(paragraph);
+                        let class = self.paragraph_class(paragraph);
+                        format!("<p{class}>{}</p>", self.inline(paragraph, &text, 0))
+                    })
+                    .collect();
+                html.push_str(&format!("<td{attrs}>{}</td>", content.concat()));
+            }
+            html.push_str("</tr>\n");
+        }
+        html.push_str("</table>\n");
+        self.body.push_str(&html);
+    }
+
+    /// Inline HTML for paragraph text with spans, links and note references
+    fn inline(&mut self, paragraph: &Paragraph, text: &str, skip: usize) -> String {
+        let spans = common::spans(paragraph, text);
+        let mut notes: Vec<_> = paragraph.footnotes.iter().collect();
+        notes.sort_by_key(|note| note.position);
+        let mut notes = notes.into_iter().peekable();
+
+        let mut out = String::new();
+        let mut index = 0usize;
+        let mut open_link = None;
+
+        for span in &spans {
+            // Links that could run script are kept as plain text
+            let link = span
+                .link
+                .filter(|link| common::is_safe_href(&link.target_url));
+            if !common::same_link(open_link, link) {
+                if open_link.is_some() {
+                    out.push_str("</a>");
+                }
+                if let Some(hyperlink) = link {
+                    out.push_str(&format!("<a href=\"{}\">", escape(&hyperlink.target_url)));
+                }
+                open_link = link;
+            }
+
+            let mut chunk = String::new();
+            for ch in span.text.chars() {
+                while let Some(note) = notes.next_if(|n| n.position as usize <= index) {
+                    chunk.push_str(&self.note_ref(note));
+                }
+                if index >= skip {
+                    match ch {
+                        '\n' => chunk.push_str("<br>"),
+                        '\t' => chunk.push(' '),
+                        _ => chunk.push_str(&escape(&ch.to_string())),
+                    }
+                }
+                index += 1;
+            }
+            if chunk.is_empty() {
+                continue;
+            }
+            match span
+                .char_shape_id
+                .filter(|id| self.document.get_char_shape(*id as usize).is_some())
+            {
+                Some(id) => out.push_str(&format!("<span class=\"cs{id}\">{chunk}</span>")),
+                None => out.push_str(&chunk),
+            }
+        }
+        if open_link.is_some() {
+            out.push_str("</a>");
+        }
+        for note in notes {
+            out.push_str(&self.note_ref(note));
+        }
+        out
+    }
+
+    fn note_ref(&mut self, note: &crate::model::footnote::Footnote) -> String {
+        let (prefix, label) = match note.kind {
+            NoteKind::Footnote => ("fn", note.number.to_string()),
+            NoteKind::Endnote => ("en", format!("e{}", note.number)),
+        };
+        let id = format!("{prefix}-{}-{}", self.section, note.number);
+
+        let content: Vec<String> = note
+            .paragraphs
+            .iter()
+            .map(|paragraph| escape(&common::paragraph_text(paragraph)))
+            .collect();
+        self.notes
+            .push((id.clone(), label.clone(), content.join("<br>")));
+
+        format!("<sup><a href=\"#{id}\" id=\"{id}-ref\">{label}</a></sup>")
+    }
+
+    fn footnotes(&mut self) {
+        if self.notes.is_empty() {
+            return;
+        }
+        self.body
+            .push_str("<section class=\"footnotes\">\n<hr>\n<ol>\n");
+        for (id, label, content) in &self.notes {
+            self.body.push_str(&format!(
+                "<li id=\"{id}\" value=\"{}\">{content} <a href=\"#{id}-ref\">↩</a></li>\n",
+                label.trim_start_matches('e')
+            ));
+        }
+        self.body.push_str("</ol>\n</section>\n");
+    }
+
+    fn image(&mut self, paragraph: &Paragraph) -> Option<String> {
+        let image = common::picture_image(self.document, paragraph)?;
+        let alt = format!("image {}", image.bin_id);
+
+        let src = if self.options.single_file {
+            format!(
+                "data:{};base64,{}",
+                common::image_mime_type(&image.file_name).unwrap_or("application/octet-stream"),
+                common::base64(&image.data)
+            )
+        } else {
+            let path = if self.options.image_dir.is_empty() {
+                image.file_name.clone()
+            } else {
+                format!(
+                    "{}/{}",
+                    self.options.image_dir.trim_end_matches('/'),
+                    image.file_name
+                )
+            };
+            if !self.images.iter().any(|i| i.bin_id == image.bin_id) {
+                self.images.push(image);
+            }
+            path
+        };
+        Some(format!("<img src=\"{}\" alt=\"{alt}\">", escape(&src)))
+    }
+}
+
+/// Text for a quoted CSS string, without the characters that could end the
+/// string, the rule or the `<style>` element around it
+fn css_string(text: &str) -> String {
+    text.chars()
+        .filter(|c| {
+            !matches!(c, '"' | '\'' | '<' | '>' | '\\' | ';' | '{' | '}') && !c.is_control()
+        })
+        .collect()
+}
+
+fn escape(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    for ch in text.chars() {
+        match ch {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            '\'' => out.push_str("&#39;"),
+            _ => out.push(ch),
+        }
+    }
+    out
+}
@@ -0,0 +1,390 @@
+//! Markdown (GitHub flavoured) export
+
+use std::path::Path;
+
+use super::common::{self, Block, ListKind, Span, TableBlock};
+use super::ExportedImage;
+use crate::error::{HwpError, Result};
+use crate::model::Paragraph;
+use crate::HwpDocument;
+
+/// Options for [`export`]
+#[derive(Debug, Clone)]
+pub struct MarkdownOptions {
+    /// Directory for extracted images, relative to the Markdown file
+    pub image_dir: String,
+    /// Treat short, bold, large-font paragraphs as headings
+    pub detect_headings: bool,
+}
+
+impl Default for MarkdownOptions {
+    fn default() -> Self {
+        Self {
+            image_dir: "images".to_string(),
+            detect_headings: true,
+        }
+    }
+}
+
+/// Markdown text together with the images it references
+#[derive(Debug, Clone, Default)]
+pub struct MarkdownExport {
+    pub markdown: String,
+    pub images: Vec<ExportedImage>,
+    /// Directory the image links point into
+    pub image_dir: String,
+}
+
+impl MarkdownExport {
+    /// Write the Markdown file and its images, relative to the file's directory
+    pub fn write_to<P: AsRef<Path>>(&self, path: P) -> Result<()> {
+        let path = path.as_ref();
+        std::fs::write(path, &self.markdown).map_err(HwpError::Io)?;
+
+        if !self.images.is_empty() {
+            let dir = path
+                .parent()
+                .unwrap_or_else(|| Path::new("."))
+                .join(&self.image_dir);
+            std::fs::create_dir_all(&dir).map_err(HwpError::Io)?;
+            for image in &self.images {
+                std::fs::write(dir.join(&image.file_name), &image.data).map_err(HwpError::Io)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Convert a document to Markdown, collecting images for extraction
+pub fn export(document: &HwpDocument, options: &MarkdownOptions) -> MarkdownExport {
+    let mut writer = MarkdownWriter {
+        document,
+        options,
+        out: String::new(),
+        images: Vec::new(),
+        in_list: false,
+    };
+
+    for section in document.sections() {
+        for block in common::blocks(section) {
+            match block {
+                Block::Paragraph(paragraph) => writer.paragraph(paragraph),
+                Block::Table(table) => writer.table(&table),
+            }
+        }
+    }
+
+    MarkdownExport {
+        markdown: writer.out.trim_end().to_string() + "\n",
+        images: writer.images,
+        image_dir: options.image_dir.clone(),
+    }
+}
+
+/// Convert a document to Markdown text with default options
+pub fn to_markdown(document: &HwpDocument) -> String {
+    export(document, &MarkdownOptions::default()).markdown
+}
+
+/// Convert a document and write the Markdown file and its images
+pub fn write_markdown<P: AsRef<Path>>(
+    document: &HwpDocument,
+    path: P,
+    options: &MarkdownOptions,
+) -> Result<()> {
+    export(document, options).write_to(path)
+}
+
+struct MarkdownWriter<'a> {
+    document: &'a HwpDocument,
+    options: &'a MarkdownOptions,
+    out: String,
+    images: Vec<ExportedImage>,
+    in_list: bool,
+}
+
+impl MarkdownWriter<'_> {
+    fn paragraph(&mut self, paragraph: &Paragraph) {
+        if paragraph.picture_data.is_some() {
+            if let Some(link) = self.image(paragraph) {
+                self.block(&link);
+            }
+        }
+        if let Some(text_box) = &paragraph.text_box_data {
+            if !text_box.text.trim().is_empty() {
+                let quoted: Vec<String> = text_box
+                    .text
+                    .lines()
+                    .map(|line| format!("> {}", escape(line)))
+                    .collect();
+                self.block(&quoted.join("\n"));
+            }
+        }
+
+        let text = common::paragraph_text(paragraph);
+        if text.trim().is_empty() {
+            return;
+        }
+
+        let heading = common::heading_level(self.document, paragraph).filter(|_| {
+            self.options.detect_headings || !is_formatting_heading(self.document, paragraph)
+        });
+        if let Some(level) = heading {
+            // Emphasis inside a heading is redundant
+            let content = self.inline(paragraph, &text, 0, false);
+            self.block(&format!("{} {}", "#".repeat(level as usize), content));
+            return;
+        }
+
+        if let Some(item) = common::list_item(self.document, paragraph, &text) {
+            let marker = match item.kind {
+                ListKind::Bullet => "-".to_string(),
+                ListKind::Ordered => "1.".to_string(),
+            };
+            let content = self.inline(paragraph, &text, item.marker_len, true);
+            if !self.in_list {
+                self.separate();
+            }
+            self.out.push_str(&format!(
+                "{}{} {}\n",
+                "   ".repeat(item.level),
+                marker,
+                content
+            ));
+            self.in_list = true;
+            return;
+        }
+
+        let content = self.inline(paragraph, &text, 0, true);
+        self.block(&escape_line_start(&content));
+    }
+
+    fn table(&mut self, table: &TableBlock) {
+        if table.cells.is_empty() {
+            return;
+        }
+        if table.has_merged_cells() {
+            self.block(&self.html_table(table));
+        } else {
+            self.block(&self.gfm_table(table));
+        }
+    }
+
+    fn gfm_table(&self, table: &TableBlock) -> String {
+        let rows = table.rows();
+        let cols = rows.iter().map(Vec::len).max().unwrap_or(0).max(1);
+        let mut lines = Vec::new();
+
+        for (index, row) in rows.iter().enumerate() {
+            let mut cells: Vec<String> = row
+                .iter()
+                .map(|(_, paragraphs)| {
+                    self.cell_text(paragraphs, true)
+                        .replace('|', "\\|")
+                        .replace('\n', "<br>")
+                })
+                .collect();
+            cells.resize(cols, String::new());
+            lines.push(format!("| {} |", cells.join(" | ")));
+            if index == 0 {
+                lines.push(format!("|{}", " --- |".repeat(cols)));
+            }
+        }
+        lines.join("\n")
+    }
+
+    /// GFM tables cannot express spans, so merged tables fall back to HTML
+    fn html_table(&self, table: &TableBlock) -> String {
+        let mut html = String::from("<table>\n");
+        for row in table.rows() {
+            html.push_str("  <tr>\n");
+            for (cell, paragraphs) in row {
+                let mut attrs = String::new();
+                if cell.col_span > 1 {
+                    attrs.push_str(&format!(" colspan=\"{}\"", cell.col_span));
+                }
+                if cell.row_span > 1 {
+                    attrs.push_str(&format!(" rowspan=\"{}\"", cell.row_span));
+                }
+                let text = html_escape(&self.cell_text(paragraphs, false)).replace('\n', "<br>");
+                html.push_str(&format!("    <td{attrs}>{text}</td>\n"));
+            }
+            html.push_str("  </tr>\n");
+        }
+        html.push_str("</table>");
+        html
+    }
+
+    fn cell_text(&self, paragraphs: &[&Paragraph], markdown: bool) -> String {
+        paragraphs
+            .iter()
+            .map(|paragraph| {
+                let text = common::paragraph_text(paragraph);
+                if markdown {
+                    self.inline(paragraph, &text, 0, true)
+                } else {
+                    text
+                }
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    /// Inline Markdown for paragraph text, skipping a leading list marker
+    fn inline(&self, paragraph: &Paragraph, text: &str, skip: usize, emphasis: bool) -> String {
+        let text: String = text.chars().skip(skip).collect();
+        let spans = common::spans(paragraph, &text);
+        let mut out = String::new();
+
+        let mut index = 0;
+        while index < spans.len() {
+            // Links that could run script are kept as their text
+            let link = spans[index]
+                .link
+                .filter(|link| common::is_safe_href(&link.target_url));
+            match link {
+                Some(link) => {
+                    // Consecutive spans of one link share a single [text](url)
+                    let mut label = String::new();
+                    while index < spans.len()
+                        && spans[index].link.is_some_and(|l| std::ptr::eq(l, link))
+                    {
+                        label.push_str(&self.styled(&spans[index], emphasis));
+                        index += 1;
+                    }
+                    out.push_str(&format!("[{}]({})", label, link_target(&link.target_url)));
+                }
+                None => {
+                    out.push_str(&self.styled(&spans[index], emphasis));
+                    index += 1;
+                }
+            }
+        }
+
+        out.replace('\t', " ")
+            .replace('\n', "\\\n")
+            .trim()
+            .to_string()
+    }
+
+    fn styled(&self, span: &Span, emphasis: bool) -> String {
+        let text = escape(&span.text);
+        let shape = span
+            .char_shape_id
+            .and_then(|id| self.document.get_char_shape(id as usize));
+        let Some(shape) = shape.filter(|_| emphasis) else {
+            return text;
+        };
+
+        // Markers must hug the text, so surrounding whitespace stays outside
+        let trimmed = text.trim();
+        if trimmed.is_empty() {
+            return text;
+        }
+        let leading = &text[..text.len() - text.trim_start().len()];
+        let trailing = &text[text.trim_end().len()..];
+
+        let mut marked = trimmed.to_string();
+        if shape.is_strikethrough() {
+            marked = format!("~~{marked}~~");
+        }
+        if shape.is_italic() {
+            marked = format!("*{marked}*");
+        }
+        if shape.is_bold() {
+            marked = format!("**{marked}**");
+        }
+        format!("{leading}{marked}{trailing}")
+    }
+
+    fn image(&mut self, paragraph: &Paragraph) -> Option<String> {
+        let image = common::picture_image(self.document, paragraph)?;
+        let path = if self.options.image_dir.is_empty() {
+            image.file_name.clone()
+        } else {
+            format!(
+                "{}/{}",
+                self.options.image_dir.trim_end_matches('/'),
+                image.file_name
+            )
+        };
+        let alt = format!("image {}", image.bin_id);
+        if !self.images.iter().any(|i| i.bin_id == image.bin_id) {
+            self.images.push(image);
+        }
+        Some(format!("![{alt}]({path})"))
+    }
+
+    /// Append a block separated from the previous one by a blank line
+    fn block(&mut self, content: &str) {
+        self.separate();
+        self.out.push_str(content);
+        self.out.push('\n');
+        self.in_list = false;
+    }
+
+    fn separate(&mut self) {
+        if !self.out.is_empty() && !self.out.ends_with("\n\n") {
+            self.out.push('\n');
+        }
+    }
+}
+
+fn is_formatting_heading(document: &HwpDocument, paragraph: &Paragraph) -> bool {
+    let outline = document
+        .get_para_shape(paragraph.para_shape_id as usize)
+        .is_some_and(|shape| (shape.properties1 >> 23) & 0x3 == 1);
+    let styled = document
+        .get_style(paragraph.style_id as usize)
+        .is_some_and(|style| {
+            ["개요", "제목", "Outline", "Heading"]
+                .iter()
+                .any(|p| style.name.starts_with(p) || style.english_name.starts_with(p))
+        });
+    !outline && !styled
+}
+
+/// Escape characters with inline Markdown meaning
+fn escape(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    for ch in text.chars() {
+        if matches!(ch, '\\' | '*' | '_' | '`' | '[' | ']' | '<' | '>' | '~') {
+            out.push('\\');
+        }
+        out.push(ch);
+    }
+    out
+}
+
+/// Keep ordinary paragraphs from being read as headings, lists or quotes
+fn escape_line_start(text: &str) -> String {
+    let first = text.split_whitespace().next().unwrap_or_default();
+    let is_marker = first.starts_with('#')
+        || matches!(first, "-" | "+" | "*")
+        || (first.ends_with('.')
+            && first.len() > 1
+            && first[..first.len() - 1].chars().all(|c| c.is_ascii_digit()));
+
+    if !is_marker {
+        return text.to_string();
+    }
+    if let Some(number) = first.strip_suffix('.') {
+        if !first.starts_with('#') && !number.is_empty() {
+            return text.replacen(first, &format!("{number}\\."), 1);
+        }
+    }
+    format!("\\{text}")
+}
+
+fn link_target(url: &str) -> String {
+    url.replace(' ', "%20")
+        .replace('(', "%28")
+        .replace(')', "%29")
+}
+
+fn html_escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
@@ -0,0 +1,33 @@
+//! Conversion of documents into other formats
+
+pub(crate) mod common;
+pub mod docx;
+pub mod html;
+pub mod markdown;
+pub mod odt;
+
+/// An embedded image written next to an exported document
+#[derive(Debug, Clone)]
+pub struct ExportedImage {
+    /// BinData id the image came from
+    pub bin_id: u16,
+    /// File name relative to the image directory
+    pub file_name: String,
+    pub data: Vec<u8>,
+}
+
+/// Content an exporter could not represent in the target format
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DroppedElement {
+    /// What was dropped, e.g. "text box" or "gradient fill"
+    pub element: String,
+    /// Where it was found, e.g. "section 1, paragraph 4"
+    pub location: String,
+    pub reason: String,
+}
+
+impl std::fmt::Display for DroppedElement {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} at {}: {}", self.element, self.location, self.reason)
+    }
+}
@@ -0,0 +1,1165 @@
+//! ODT (OpenDocument Text) export
+
+use std::collections::{BTreeSet, HashSet};
+use std::io::{Cursor, Seek, Write};
+use std::path::Path;
+
+use quick_xml::escape::escape;
+use zip::write::SimpleFileOptions;
+use zip::ZipWriter;
+
+use super::common::{self, Block, TableBlock};
+use super::DroppedElement;
+use crate::error::{HwpError, Result};
+use crate::model::border_fill::{BorderFill, BorderLine};
+use crate::model::ctrl_header::ControlType;
+use crate::model::footnote::{Footnote, NoteKind};
+use crate::model::header_footer::{HeaderFooter, HeaderFooterType, PageApplyType};
+use crate::model::hyperlink::{Hyperlink, HyperlinkType};
+use crate::model::page_layout::PageLayout;
+use crate::model::{CharShape, PageDef, ParaShape, Paragraph, Section};
+use crate::HwpDocument;
+
+const MIME_TYPE: &str = "application/vnd.oasis.opendocument.text";
+const NAMESPACES: &str = concat!(
+    r#"xmlns:office="urn:oasis:names:tc:opendocument:xmlns:office:1.0" "#,
+    r#"xmlns:style="urn:oasis:names:tc:opendocument:xmlns:style:1.0" "#,
+    r#"xmlns:text="urn:oasis:names:tc:opendocument:xmlns:text:1.0" "#,
+    r#"xmlns:table="urn:oasis:names:tc:opendocument:xmlns:table:1.0" "#,
+    r#"xmlns:draw="urn:oasis:names:tc:opendocument:xmlns:drawing:1.0" "#,
+    r#"xmlns:fo="urn:oasis:names:tc:opendocument:xmlns:xsl-fo-compatible:1.0" "#,
+    r#"xmlns:xlink="http://www.w3.org/1999/xlink" "#,
+    r#"xmlns:dc="http://purl.org/dc/elements/1.1/" "#,
+    r#"xmlns:meta="urn:oasis:names:tc:opendocument:xmlns:meta:1.0" "#,
+    r#"xmlns:svg="urn:oasis:names:tc:opendocument:xmlns:svg-compatible:1.0" "#,
+    r#"office:version="1.3""#
+);
+const XML_DECLARATION: &str = r#"<?xml version="1.0" encoding="UTF-8"?>"#;
+
+/// An OpenDocument text package together with what could not be carried over
+#[derive(Debug, Clone, Default)]
+pub struct OdtExport {
+    /// Contents of the .odt file
+    pub bytes: Vec<u8>,
+    /// Content that has no ODT equivalent, in document order
+    pub dropped: Vec<DroppedElement>,
+}
+
+impl OdtExport {
+    pub fn write_to<P: AsRef<Path>>(&self, path: P) -> Result<()> {
+        std::fs::write(path, &self.bytes).map_err(HwpError::Io)
+    }
+}
+
+/// Convert a document to ODT, reporting every element that had to be dropped
+pub fn export(document: &HwpDocument) -> Result<OdtExport> {
+    let mut writer = OdtWriter {
+        document,
+        dropped: Vec::new(),
+        paragraph_styles: Vec::new(),
+        table_styles: String::new(),
+        cell_styles: Vec::new(),
+        pictures: Vec::new(),
+        reported_fills: HashSet::new(),
+        tables: 0,
+        frames: 0,
+        note_counts: [0; 2],
+    };
+
+    let mut body = String::new();
+    let sections: Vec<&Section> = document.sections().collect();
+    for (section_idx, section) in sections.iter().enumerate() {
+        // The first block of a section switches to the section's master page
+        let mut master = Some(section_idx);
+        for block in common::blocks(section) {
+            match block {
+                Block::Paragraph(paragraph) => {
+                    let location = location(section_idx, section, paragraph);
+                    body.push_str(&writer.paragraph(paragraph, &location, master.take()));
+                }
+                Block::Table(table) => {
+                    body.push_str(&writer.table(section_idx, section, &table, master.take()))
+                }
+            }
+        }
+    }
+
+    let font_faces = font_faces(document);
+    let mut content = format!(
+        "{XML_DECLARATION}<office:document-content {NAMESPACES}>{font_faces}<office:automatic-styles>"
+    );
+    for (id, shape) in document.doc_info.char_shapes.iter().enumerate() {
+        content.push_str(&format!(
+            "<style:style style:name=\"T{id}\" style:family=\"text\">{}</style:style>",
+            text_properties(document, shape)
+        ));
+    }
+    for (index, (_, xml)) in writer.paragraph_styles.iter().enumerate() {
+        content.push_str(&format!("<style:style style:name=\"P{}\"{xml}", index + 1));
+    }
+    content.push_str(&writer.table_styles);
+    for (_, xml) in &writer.cell_styles {
+        content.push_str(xml);
+    }
+    content.push_str(&format!(
+        "</office:automatic-styles><office:body><office:text>{body}</office:text></office:body>\
+         </office:document-content>"
+    ));
+
+    let styles = writer.styles(&sections, &font_faces);
+    let bytes = writer.package(&content, &styles)?;
+    Ok(OdtExport {
+        bytes,
+        dropped: writer.dropped,
+    })
+}
+
+/// Convert a document to ODT bytes
+pub fn to_odt(document: &HwpDocument) -> Result<Vec<u8>> {
+    Ok(export(document)?.bytes)
+}
+
+/// Convert a document and write the .odt file
+pub fn write_odt<P: AsRef<Path>>(document: &HwpDocument, path: P) -> Result<()> {
+    export(document)?.write_to(path)
+}
+
+/// Location string matching the one used in security reports
+fn location(section_idx: usize, section: &Section, paragraph: &Paragraph) -> String {
+    let para_idx = section
+        .paragraphs
+        .iter()
+        .position(|p| std::ptr::eq(p, paragraph))
+        .unwrap_or_default();
+    format!("section {section_idx}, paragraph {para_idx}")
+}
+
+/// An image stored under Pictures/
+struct Picture {
+    bin_id: u16,
+    file_name: String,
+    data: Vec<u8>,
+}
+
+/// Key of an automatic paragraph style: style, paragraph shape and starting section
+type ParagraphStyleKey = (u16, u16, Option<usize>);
+
+struct OdtWriter<'a> {
+    document: &'a HwpDocument,
+    dropped: Vec<DroppedElement>,
+    /// Automatic paragraph styles `P1`, `P2`, ..., each without its opening name attribute
+    paragraph_styles: Vec<(ParagraphStyleKey, String)>,
+    /// Automatic table and column styles
+    table_styles: String,
+    /// Automatic cell styles by border fill id
+    cell_styles: Vec<(u16, String)>,
+    pictures: Vec<Picture>,
+    /// Border fills whose fill has already been reported as dropped
+    reported_fills: HashSet<u16>,
+    tables: usize,
+    frames: u32,
+    /// Footnotes and endnotes written so far
+    note_counts: [u32; 2],
+}
+
+impl OdtWriter<'_> {
+    fn report(&mut self, element: &str, location: &str, reason: &str) {
+        self.dropped.push(DroppedElement {
+            element: element.to_string(),
+            location: location.to_string(),
+            reason: reason.to_string(),
+        });
+    }
+
+    /// `text:p`, or `text:h` for headings; `master` marks the first paragraph of a section
+    fn paragraph(
+        &mut self,
+        paragraph: &Paragraph,
+        location: &str,
+        master: Option<usize>,
+    ) -> String {
+        let mut xml = String::new();
+        self.report_controls(paragraph, location);
+
+        if let Some(text_box) = &paragraph.text_box_data {
+            for line in text_box.text.lines() {
+                xml.push_str(&format!(
+                    "<text:p>{}</text:p>",
+                    text_content(line, &mut true)
+                ));
+            }
+            self.report(
+                "text box",
+                location,
+                "text is kept as plain paragraphs; position, border and fill are lost",
+            );
+        }
+
+        if let Some(shape) = self
+            .document
+            .get_para_shape(paragraph.para_shape_id as usize)
+        {
+            if matches!((shape.properties1 >> 23) & 0x3, 2 | 3) {
+                self.report(
+                    "paragraph numbering",
+                    location,
+                    "automatic numbers and bullets are not exported",
+                );
+            }
+        }
+
+        let style = self.paragraph_style(paragraph, master);
+        let mut content = String::new();
+        if paragraph.picture_data.is_some() {
+            content.push_str(&self.image(paragraph, location));
+        }
+        content.push_str(&self.spans(paragraph, location));
+
+        match common::heading_level(self.document, paragraph) {
+            Some(level) => xml.push_str(&format!(
+                "<text:h text:style-name=\"{style}\" text:outline-level=\"{level}\">{content}</text:h>"
+            )),
+            None => xml.push_str(&format!(
+                "<text:p text:style-name=\"{style}\">{content}</text:p>"
+            )),
+        }
+        xml
+    }
+
+    /// Report controls that have no ODT counterpart
+    fn report_controls(&mut self, paragraph: &Paragraph, location: &str) {
+        let Some(ctrl_header) = &paragraph.ctrl_header else {
+            return;
+        };
+        let (element, reason) = match ctrl_header.get_control_type() {
+            ControlType::Equation => ("equation", "equation scripts are not converted to MathML"),
+            ControlType::Gso
+                if paragraph.picture_data.is_none() && paragraph.text_box_data.is_none() =>
+            {
+                (
+                    "drawing object",
+                    "shapes other than pictures are not exported",
+                )
+            }
+            ControlType::HiddenComment => ("hidden comment", "comments are not exported"),
+            ControlType::OverlappingLetter => (
+                "overlapping letters",
+                "combined characters are not exported",
+            ),
+            ControlType::BookMark => ("bookmark", "bookmarks are not exported"),
+            _ => return,
+        };
+        self.report(element, location, reason);
+    }
+
+    /// Name of the automatic style carrying a paragraph's shape, adding it on first use
+    fn paragraph_style(&mut self, paragraph: &Paragraph, master: Option<usize>) -> String {
+        let key = (paragraph.style_id as u16, paragraph.para_shape_id, master);
+        if let Some(index) = self.paragraph_styles.iter().position(|(k, _)| *k == key) {
+            return format!("P{}", index + 1);
+        }
+
+        let mut attributes = String::new();
+        if let Some(style) = self.document.get_style(paragraph.style_id as usize) {
+            if !is_character_style(style) {
+                attributes.push_str(&format!(
+                    " style:parent-style-name=\"Style{}\"",
+                    paragraph.style_id
+                ));
+            }
+        }
+        let mut properties = String::new();
+        if let Some(section_idx) = master {
+            attributes.push_str(&format!(" style:master-page-name=\"MP{section_idx}\""));
+            if let Some(start) = self.start_page_number(section_idx) {
+                properties.push_str(&format!(" style:page-number=\"{start}\""));
+            }
+        }
+        let paragraph_properties = self
+            .document
+            .get_para_shape(paragraph.para_shape_id as usize)
+            .map(|shape| paragraph_properties(self.document, shape, &properties))
+            .unwrap_or_else(|| format!("<style:paragraph-properties{properties}/>"));
+
+        self.paragraph_styles.push((
+            key,
+            format!(
+                " style:family=\"paragraph\"{attributes}>\
+                 {paragraph_properties}</style:style>"
+            ),
+        ));
+        format!("P{}", self.paragraph_styles.len())
+    }
+
+    fn start_page_number(&self, section_idx: usize) -> Option<u16> {
+        let section = self.document.sections().nth(section_idx)?;
+        let layout = section.page_def.as_ref()?.layout.as_ref()?;
+        Some(layout.start_page_number).filter(|start| *start > 1)
+    }
+
+    /// Spans for paragraph text with hyperlinks and notes
+    fn spans(&mut self, paragraph: &Paragraph, location: &str) -> String {
+        let text = common::paragraph_text(paragraph);
+        let spans = common::spans(paragraph, &text);
+        let mut notes: Vec<_> = paragraph.footnotes.iter().collect();
+        notes.sort_by_key(|note| note.position);
+        let mut notes = notes.into_iter().peekable();
+
+        let mut out = String::new();
+        let mut index = 0usize;
+        let mut open_link = None;
+        // Leading spaces of a paragraph would be collapsed, so they are written as text:s
+        let mut after_space = true;
+
+        for span in &spans {
+            if !common::same_link(open_link, span.link) {
+                if open_link.is_some() {
+                    out.push_str("</text:a>");
+                }
+                if let Some(hyperlink) = span.link {
+                    out.push_str(&hyperlink_start(hyperlink));
+                }
+                open_link = span.link;
+            }
+
+            let mut chunk = String::new();
+            for ch in span.text.chars() {
+                while let Some(note) = notes.next_if(|n| n.position as usize <= index) {
+                    out.push_str(&text_span(
+                        span.char_shape_id,
+                        &std::mem::take(&mut chunk),
+                        &mut after_space,
+                    ));
+                    out.push_str(&self.note(note, location));
+                }
+                chunk.push(ch);
+                index += 1;
+            }
+            out.push_str(&text_span(span.char_shape_id, &chunk, &mut after_space));
+        }
+        if open_link.is_some() {
+            out.push_str("</text:a>");
+        }
+        for note in notes {
+            out.push_str(&self.note(note, location));
+        }
+        out
+    }
+
+    fn note(&mut self, note: &Footnote, location: &str) -> String {
+        let class = match note.kind {
+            NoteKind::Footnote => "footnote",
+            NoteKind::Endnote => "endnote",
+        };
+        let count = &mut self.note_counts[note.kind as usize];
+        *count += 1;
+        let id = format!("{}{}", &class[..3], *count);
+        let citation = *count;
+
+        let mut body = String::new();
+        for paragraph in &note.paragraphs {
+            body.push_str(&self.paragraph(paragraph, location, None));
+        }
+        if body.is_empty() {
+            body.push_str("<text:p/>");
+        }
+        format!(
+            "<text:note text:id=\"{id}\" text:note-class=\"{class}\">\
+             <text:note-citation>{citation}</text:note-citation>\
+             <text:note-body>{body}</text:note-body></text:note>"
+        )
+    }
+
+    /// An inline frame for a picture paragraph
+    fn image(&mut self, paragraph: &Paragraph, location: &str) -> String {
+        let Some(image) = common::picture_image(self.document, paragraph) else {
+            self.report("image", location, "the referenced BinData has no content");
+            return String::new();
+        };
+        if common::image_mime_type(&image.file_name).is_none() {
+            let reason = format!(
+                "{} is not an image format office suites can display",
+                image.file_name
+            );
+            self.report("embedded object", location, &reason);
+            return String::new();
+        }
+        if !self.pictures.iter().any(|p| p.bin_id == image.bin_id) {
+            self.pictures.push(Picture {
+                bin_id: image.bin_id,
+                file_name: image.file_name.clone(),
+                data: image.data,
+            });
+        }
+
+        let (width, height) = paragraph
+            .picture_data
+            .as_ref()
+            .map(|picture| (picture.image_width, picture.image_height))
+            .filter(|(width, height)| *width > 0 && *height > 0)
+            .unwrap_or((14173, 14173)); // 50mm
+        self.frames += 1;
+        format!(
+            "<draw:frame draw:name=\"Image{}\" text:anchor-type=\"as-char\" \
+             svg:width=\"{}\" svg:height=\"{}\"><draw:image xlink:href=\"Pictures/{}\" \
+             xlink:type=\"simple\" xlink:show=\"embed\" xlink:actuate=\"onLoad\"/></draw:frame>",
+            self.frames,
+            pt(width as i32),
+            pt(height as i32),
+            escape(&image.file_name)
+        )
+    }
+
+    fn table(
+        &mut self,
+        section_idx: usize,
+        section: &Section,
+        table: &TableBlock,
+        master: Option<usize>,
+    ) -> String {
+        if table.cells.is_empty() {
+            return String::new();
+        }
+        self.tables += 1;
+        let name = format!("Table{}", self.tables);
+        let widths = table.column_widths();
+        let cols = widths.len();
+
+        let mut attributes = String::new();
+        let mut properties = String::new();
+        if let Some(section_idx) = master {
+            attributes.push_str(&format!(" style:master-page-name=\"MP{section_idx}\""));
+            if let Some(start) = self.start_page_number(section_idx) {
+                properties.push_str(&format!(" style:page-number=\"{start}\""));
+            }
+        }
+        self.table_styles.push_str(&format!(
+            "<style:style style:name=\"{name}\" style:family=\"table\"{attributes}>\
+             <style:table-properties style:width=\"{}\" table:align=\"left\"{properties}/></style:style>",
+            pt(widths.iter().sum::<u32>() as i32)
+        ));
+
+        let mut xml = format!("<table:table table:name=\"{name}\" table:style-name=\"{name}\">");
+        for (col, width) in widths.iter().enumerate() {
+            let column = format!("{name}.C{}", col + 1);
+            self.table_styles.push_str(&format!(
+                "<style:style style:name=\"{column}\" style:family=\"table-column\">\
+                 <style:table-column-properties style:column-width=\"{}\"/></style:style>",
+                pt(*width as i32)
+            ));
+            xml.push_str(&format!(
+                "<table:table-column table:style-name=\"{column}\"/>"
+            ));
+        }
+
+        for (row_idx, row) in table.rows().iter().enumerate() {
+            xml.push_str("<table:table-row>");
+            for col in 0..cols {
+                let starting = row
+                    .iter()
+                    .find(|(cell, _)| cell.cell_address.1 as usize == col);
+                if let Some((cell, paragraphs)) = starting {
+                    let mut attributes = String::new();
+                    let cell_location = paragraphs
+                        .first()
+                        .map(|paragraph| location(section_idx, section, paragraph))
+                        .unwrap_or_else(|| format!("section {section_idx}, table"));
+                    if let Some(style) = self.cell_style(cell.border_fill_id, &cell_location) {
+                        attributes.push_str(&format!(" table:style-name=\"{style}\""));
+                    }
+                    if cell.col_span > 1 {
+                        attributes.push_str(&format!(
+                            " table:number-columns-spanned=\"{}\"",
+                            cell.col_span
+                        ));
+                    }
+                    if cell.row_span > 1 {
+                        attributes
+                            .push_str(&format!(" table:number-rows-spanned=\"{}\"", cell.row_span));
+                    }
+                    let mut content: String = paragraphs
+                        .iter()
+                        .map(|paragraph| {
+                            let location = location(section_idx, section, paragraph);
+                            self.paragraph(paragraph, &location, None)
+                        })
+                        .collect();
+                    if content.is_empty() {
+                        content.push_str("<text:p/>");
+                    }
+                    xml.push_str(&format!(
+                        "<table:table-cell office:value-type=\"string\"{attributes}>{content}</table:table-cell>"
+                    ));
+                    continue;
+                }
+
+                let covered = table.cells.iter().any(|(cell, _)| {
+                    let (r, c) = (cell.cell_address.0 as usize, cell.cell_address.1 as usize);
+                    r <= row_idx
+                        && row_idx < r + cell.row_span.max(1) as usize
+                        && c <= col
+                        && col < c + cell.col_span.max(1) as usize
+                });
+                if covered {
+                    xml.push_str("<table:covered-table-cell/>");
+                } else {
+                    xml.push_str("<table:table-cell><text:p/></table:table-cell>");
+                }
+            }
+            xml.push_str("</table:table-row>");
+        }
+        xml.push_str("</table:table>");
+        xml
+    }
+
+    /// Name of the automatic cell style for a border fill, adding it on first use
+    fn cell_style(&mut self, border_fill_id: u16, location: &str) -> Option<String> {
+        let name = format!("Cell{border_fill_id}");
+        if self.cell_styles.iter().any(|(id, _)| *id == border_fill_id) {
+            return Some(name);
+        }
+        // Border fill ids are 1-based
+        let fill = (border_fill_id > 0)
+            .then(|| self.document.get_border_fill(border_fill_id as usize - 1))
+            .flatten()?;
+
+        let mut properties = String::new();
+        for (side, line) in [
+            ("top", &fill.top),
+            ("bottom", &fill.bottom),
+            ("left", &fill.left),
+            ("right", &fill.right),
+        ] {
+            properties.push_str(&format!(" fo:border-{side}=\"{}\"", border(line)));
+        }
+        if fill.diagonal.line_type != 0 {
+            properties.push_str(&format!(
+                " style:diagonal-tl-br=\"{}\"",
+                border(&fill.diagonal)
+            ));
+        }
+        properties.push_str(&background(fill));
+
+        if fill.fill_info.fill_type & 0x0C != 0 && self.reported_fills.insert(border_fill_id) {
+            let element = if fill.fill_info.fill_type & 0x08 != 0 {
+                "gradient fill"
+            } else {
+                "image fill"
+            };
+            self.report(
+                element,
+                location,
+                "table cells only support solid backgrounds",
+            );
+        }
+
+        self.cell_styles.push((
+            border_fill_id,
+            format!(
+                "<style:style style:name=\"{name}\" style:family=\"table-cell\">\
+                 <style:table-cell-properties{properties}/></style:style>"
+            ),
+        ));
+        Some(name)
+    }
+
+    /// styles.xml: named styles, page layouts and master pages
+    fn styles(&mut self, sections: &[&Section], font_faces: &str) -> String {
+        let mut named = String::from(
+            "<style:default-style style:family=\"paragraph\"><style:text-properties \
+             fo:language=\"ko\" fo:country=\"KR\" style:language-asian=\"ko\" \
+             style:country-asian=\"KR\"/></style:default-style>",
+        );
+        for (id, style) in self.document.doc_info.styles.iter().enumerate() {
+            let character = is_character_style(style);
+            let family = if character { "text" } else { "paragraph" };
+            let name = if style.name.trim().is_empty() {
+                style.english_name.trim()
+            } else {
+                style.name.trim()
+            };
+            let name = if name.is_empty() {
+                format!("Style {id}")
+            } else {
+                name.to_string()
+            };
+
+            named.push_str(&format!(
+                "<style:style style:name=\"Style{id}\" style:display-name=\"{}\" style:family=\"{family}\"",
+                escape(&name)
+            ));
+            if !character
+                && self
+                    .document
+                    .get_style(style.next_style_id as usize)
+                    .is_some_and(|next| !is_character_style(next))
+            {
+                named.push_str(&format!(
+                    " style:next-style-name=\"Style{}\"",
+                    style.next_style_id
+                ));
+            }
+            named.push('>');
+            if !character {
+                if let Some(shape) = self.document.get_para_shape(style.para_shape_id as usize) {
+                    named.push_str(&paragraph_properties(self.document, shape, ""));
+                }
+            }
+            if let Some(shape) = self.document.get_char_shape(style.char_shape_id as usize) {
+                named.push_str(&text_properties(self.document, shape));
+            }
+            named.push_str("</style:style>");
+        }
+
+        let mut automatic = String::new();
+        let mut masters = String::new();
+        let default_page_def = PageDef::new_default();
+        for (section_idx, section) in sections.iter().enumerate() {
+            let page_def = section.page_def.as_ref().unwrap_or(&default_page_def);
+            let (header_footers, header_footer_styles) =
+                self.master_page_content(section_idx, page_def);
+            automatic.push_str(&page_layout(section_idx, section, page_def));
+            automatic.push_str(&header_footer_styles);
+            masters.push_str(&format!(
+                "<style:master-page style:name=\"MP{section_idx}\" \
+                 style:page-layout-name=\"pm{section_idx}\">{header_footers}</style:master-page>"
+            ));
+
+            if page_def.page_border_fill_id != 0
+                || page_def.layout.as_ref().is_some_and(|l| l.page_border)
+            {
+                self.report(
+                    "page border",
+                    &format!("section {section_idx}"),
+                    "page borders are not exported",
+                );
+            }
+        }
+
+        format!(
+            "{XML_DECLARATION}<office:document-styles {NAMESPACES}>{font_faces}\
+             <office:styles>{named}</office:styles>\
+             <office:automatic-styles>{automatic}</office:automatic-styles>\
+             <office:master-styles>{masters}</office:master-styles></office:document-styles>"
+        )
+    }
+
+    /// Headers and footers of a master page, with the paragraph styles they use
+    fn master_page_content(&mut self, section_idx: usize, page_def: &PageDef) -> (String, String) {
+        let location = format!("section {section_idx}");
+        // Schema order: header, header-left, header-first, footer, footer-left, footer-first
+        let mut slots: [Option<&HeaderFooter>; 6] = [None; 6];
+        for item in &page_def.header_footer.items {
+            let base = match item.header_footer_type {
+                HeaderFooterType::Header => 0,
+                HeaderFooterType::Footer => 3,
+            };
+            let offset = match item.apply_type {
+                PageApplyType::All | PageApplyType::OddPages => 0,
+                PageApplyType::EvenPages => 1,
+                PageApplyType::FirstPage => 2,
+            };
+            if slots[base + offset].is_some() {
+                let element = match item.header_footer_type {
+                    HeaderFooterType::Header => "header",
+                    HeaderFooterType::Footer => "footer",
+                };
+                self.report(
+                    element,
+                    &location,
+                    "a master page holds one header and footer per page type",
+                );
+                continue;
+            }
+            slots[base + offset] = Some(item);
+        }
+
+        let tags = [
+            "header",
+            "header-left",
+            "header-first",
+            "footer",
+            "footer-left",
+            "footer-first",
+        ];
+        let mut content = String::new();
+        let mut styles = String::new();
+        for (tag, item) in tags.iter().zip(slots) {
+            let Some(item) = item else {
+                continue;
+            };
+            let style = format!("MP{section_idx}-{tag}");
+            let align = match item.alignment {
+                1 => "center",
+                2 => "end",
+                _ => "start",
+            };
+            let text_properties = self
+                .document
+                .get_char_shape(item.char_shape_id as usize)
+                .map(|shape| text_properties(self.document, shape))
+                .unwrap_or_default();
+            styles.push_str(&format!(
+                "<style:style style:name=\"{style}\" style:family=\"paragraph\">\
+                 <style:paragraph-properties fo:text-align=\"{align}\"/>{text_properties}</style:style>"
+            ));
+
+            let mut paragraph = text_content(&item.text, &mut true);
+            if item.include_page_number {
+                let format = match item.page_number_format {
+                    2 => "i",
+                    3 => "I",
+                    4 => "a",
+                    5 => "A",
+                    _ => "1",
+                };
+                paragraph.push_str(&format!(
+                    "<text:page-number text:select-page=\"current\" style:num-format=\"{format}\">1</text:page-number>"
+                ));
+            }
+            content.push_str(&format!(
+                "<style:{tag}><text:p text:style-name=\"{style}\">{paragraph}</text:p></style:{tag}>"
+            ));
+        }
+        (content, styles)
+    }
+
+    /// Assemble the zip package; the mimetype entry comes first and uncompressed
+    fn package(&self, content_xml: &str, styles_xml: &str) -> Result<Vec<u8>> {
+        let mut zip = ZipWriter::new(Cursor::new(Vec::new()));
+        let options =
+            SimpleFileOptions::default().compression_method(zip::CompressionMethod::Deflated);
+        let stored =
+            SimpleFileOptions::default().compression_method(zip::CompressionMethod::Stored);
+
+        write_part(&mut zip, "mimetype", MIME_TYPE.as_bytes(), stored)?;
+        write_part(
+            &mut zip,
+            "META-INF/manifest.xml",
+            self.manifest().as_bytes(),
+            options,
+        )?;
+        write_part(&mut zip, "content.xml", content_xml.as_bytes(), options)?;
+        write_part(&mut zip, "styles.xml", styles_xml.as_bytes(), options)?;
+        write_part(&mut zip, "meta.xml", self.meta().as_bytes(), options)?;
+        for picture in &self.pictures {
+            write_part(
+                &mut zip,
+                &format!("Pictures/{}", picture.file_name),
+                &picture.data,
+                stored,
+            )?;
+        }
+
+        let cursor = zip
+            .finish()
+            .map_err(|e| HwpError::Io(std::io::Error::other(e)))?;
+        Ok(cursor.into_inner())
+    }
+
+    fn manifest(&self) -> String {
+        let mut xml = format!(
+            "{XML_DECLARATION}<manifest:manifest \
+             xmlns:manifest=\"urn:oasis:names:tc:opendocument:xmlns:manifest:1.0\" manifest:version=\"1.3\">\
+             <manifest:file-entry manifest:full-path=\"/\" manifest:version=\"1.3\" manifest:media-type=\"{MIME_TYPE}\"/>"
+        );
+        for part in ["content.xml", "styles.xml", "meta.xml"] {
+            xml.push_str(&format!(
+                "<manifest:file-entry manifest:full-path=\"{part}\" manifest:media-type=\"text/xml\"/>"
+            ));
+        }
+        for picture in &self.pictures {
+            xml.push_str(&format!(
+                "<manifest:file-entry manifest:full-path=\"Pictures/{}\" manifest:media-type=\"{}\"/>",
+                escape(&picture.file_name),
+                common::image_mime_type(&picture.file_name).unwrap_or("application/octet-stream")
+            ));
+        }
+        xml.push_str("</manifest:manifest>");
+        xml
+    }
+
+    fn meta(&self) -> String {
+        let mut properties = String::new();
+        for (tag, value) in [
+            ("dc:title", self.document.title()),
+            ("dc:subject", self.document.subject()),
+            ("meta:initial-creator", self.document.author()),
+            ("meta:keyword", self.document.keywords()),
+        ] {
+            if let Some(value) = value.filter(|v| !v.is_empty()) {
+                properties.push_str(&format!("<{tag}>{}</{tag}>", escape(value)));
+            }
+        }
+        format!(
+            "{XML_DECLARATION}<office:document-meta {NAMESPACES}><office:meta>{properties}</office:meta>\
+             </office:document-meta>"
+        )
+    }
+}
+
+fn write_part<W: Write + Seek>(
+    zip: &mut ZipWriter<W>,
+    name: &str,
+    data: &[u8],
+    options: SimpleFileOptions,
+) -> Result<()> {
+    zip.start_file(name, options)
+        .map_err(|e| HwpError::Io(std::io::Error::other(e)))?;
+    zip.write_all(data).map_err(HwpError::Io)
+}
+
+fn is_character_style(style: &crate::model::style::Style) -> bool {
+    // Bits 0-2: style kind (0 = paragraph, 1 = character)
+    style.properties & 0x7 == 1
+}
+
+/// `office:font-face-decls` for every face used in the Hangul and Latin slots
+fn font_faces(document: &HwpDocument) -> String {
+    let names: BTreeSet<&str> = document
+        .doc_info
+        .char_shapes
+        .iter()
+        .flat_map(|shape| [shape.face_name_ids[0], shape.face_name_ids[1]])
+        .filter_map(|id| document.get_face_name(id as usize))
+        .map(|face| face.font_name.trim())
+        .filter(|name| !name.is_empty())
+        .collect();
+    let mut xml = String::from("<office:font-face-decls>");
+    for name in names {
+        let name = escape(name);
+        xml.push_str(&format!(
+            "<style:font-face style:name=\"{name}\" svg:font-family=\"&apos;{name}&apos;\"/>"
+        ));
+    }
+    xml.push_str("</office:font-face-decls>");
+    xml
+}
+
+/// `<style:text-properties>` for a character shape
+fn text_properties(document: &HwpDocument, shape: &CharShape) -> String {
+    let mut xml = String::new();
+
+    // Slot 1 is Latin, slot 0 Hangul
+    let face = |slot: usize| {
+        document
+            .get_face_name(shape.face_name_ids[slot] as usize)
+            .map(|face| face.font_name.trim())
+            .filter(|name| !name.is_empty())
+    };
+    if let Some(latin) = face(1) {
+        xml.push_str(&format!(" style:font-name=\"{}\"", escape(latin)));
+    }
+    if let Some(hangul) = face(0) {
+        xml.push_str(&format!(" style:font-name-asian=\"{}\"", escape(hangul)));
+    }
+    if shape.base_size > 0 {
+        let size = pt(shape.base_size);
+        xml.push_str(&format!(
+            " fo:font-size=\"{size}\" style:font-size-asian=\"{size}\" style:font-size-complex=\"{size}\""
+        ));
+    }
+    if shape.is_bold() {
+        xml.push_str(
+            " fo:font-weight=\"bold\" style:font-weight-asian=\"bold\" style:font-weight-complex=\"bold\"",
+        );
+    }
+    if shape.is_italic() {
+        xml.push_str(
+            " fo:font-style=\"italic\" style:font-style-asian=\"italic\" style:font-style-complex=\"italic\"",
+        );
+    }
+    if shape.is_underline() {
+        xml.push_str(&format!(
+            " style:text-underline-style=\"solid\" style:text-underline-width=\"auto\" \
+             style:text-underline-color=\"{}\"",
+            color(shape.underline_color)
+        ));
+    }
+    if shape.is_strikethrough() {
+        xml.push_str(" style:text-line-through-style=\"solid\"");
+    }
+    if shape.get_outline_type() != 0 {
+        xml.push_str(" style:text-outline=\"true\"");
+    }
+    if shape.get_shadow_type() != 0 {
+        xml.push_str(" fo:text-shadow=\"1pt 1pt\"");
+    }
+    xml.push_str(&format!(" fo:color=\"{}\"", color(shape.text_color)));
+    // Letter spacing is a percentage of the font size
+    if shape.char_spaces[0] != 0 && shape.base_size > 0 {
+        let spacing = shape.base_size * shape.char_spaces[0] as i32 / 100;
+        xml.push_str(&format!(" fo:letter-spacing=\"{}\"", pt(spacing)));
+    }
+    if shape.ratios[0] != 0 && shape.ratios[0] != 100 {
+        xml.push_str(&format!(" style:text-scale=\"{}%\"", shape.ratios[0]));
+    }
+    // White (or unset) shading means no highlight
+    if shape.shade_color & 0xFFFFFF != 0xFFFFFF && shape.shade_color != 0 {
+        xml.push_str(&format!(
+            " fo:background-color=\"{}\"",
+            color(shape.shade_color)
+        ));
+    }
+    // Bits 15-16: superscript and subscript
+    if shape.properties & (1 << 15) != 0 {
+        xml.push_str(" style:text-position=\"super 58%\"");
+    } else if shape.properties & (1 << 16) != 0 {
+        xml.push_str(" style:text-position=\"sub 58%\"");
+    }
+    format!("<style:text-properties{xml}/>")
+}
+
+/// `<style:paragraph-properties>` for a paragraph shape, with extra attributes appended
+fn paragraph_properties(document: &HwpDocument, shape: &ParaShape, extra: &str) -> String {
+    let mut xml = String::new();
+
+    let align = match shape.get_alignment() {
+        1 => "end",
+        2 => "center",
+        3 | 4 => "justify",
+        _ => "start",
+    };
+    xml.push_str(&format!(" fo:text-align=\"{align}\""));
+    if shape.get_alignment() == 4 {
+        xml.push_str(" fo:text-align-last=\"justify\"");
+    }
+    xml.push_str(&format!(
+        " fo:margin-left=\"{}\" fo:margin-right=\"{}\" fo:text-indent=\"{}\" \
+         fo:margin-top=\"{}\" fo:margin-bottom=\"{}\"",
+        pt(shape.left_margin),
+        pt(shape.right_margin),
+        pt(shape.indent),
+        pt(shape.top_para_space),
+        pt(shape.bottom_para_space)
+    ));
+    match shape.line_space_type {
+        0 if shape.line_space > 0 => {
+            xml.push_str(&format!(" fo:line-height=\"{}%\"", shape.line_space))
+        }
+        1 if shape.line_space > 0 => {
+            xml.push_str(&format!(" fo:line-height=\"{}\"", pt(shape.line_space)))
+        }
+        2 if shape.line_space > 0 => xml.push_str(&format!(
+            " style:line-height-at-least=\"{}\"",
+            pt(shape.line_space)
+        )),
+        _ => {}
+    }
+
+    let fill = (shape.border_fill_id > 0)
+        .then(|| document.get_border_fill(shape.border_fill_id as usize - 1))
+        .flatten();
+    if let Some(fill) = fill {
+        let lines = [
+            ("top", &fill.top),
+            ("bottom", &fill.bottom),
+            ("left", &fill.left),
+            ("right", &fill.right),
+        ];
+        if lines.iter().any(|(_, line)| line.line_type != 0) {
+            for (side, line) in lines {
+                xml.push_str(&format!(" fo:border-{side}=\"{}\"", border(line)));
+            }
+        }
+        xml.push_str(&background(fill));
+    }
+    format!("<style:paragraph-properties{xml}{extra}/>")
+}
+
+/// `style:page-layout` for a section
+fn page_layout(section_idx: usize, section: &Section, page_def: &PageDef) -> String {
+    let default_layout = PageLayout::default();
+    let layout = page_def.layout.as_ref().unwrap_or(&default_layout);
+
+    let orientation = if page_def.is_landscape() {
+        "landscape"
+    } else {
+        "portrait"
+    };
+    let num_format = match layout.page_number_format as u8 {
+        2 => "i",
+        3 => "I",
+        4 => "a",
+        5 => "A",
+        _ => "1",
+    };
+    let mut properties = format!(
+        " fo:page-width=\"{}\" fo:page-height=\"{}\" style:print-orientation=\"{orientation}\" \
+         style:num-format=\"{num_format}\" fo:margin-top=\"{}\" fo:margin-bottom=\"{}\" \
+         fo:margin-left=\"{}\" fo:margin-right=\"{}\"",
+        pt(page_def.width as i32),
+        pt(page_def.height as i32),
+        pt(page_def.top_margin as i32),
+        pt(page_def.bottom_margin as i32),
+        pt((page_def.left_margin + page_def.gutter_margin) as i32),
+        pt(page_def.right_margin as i32),
+    );
+    if let Some(background) = layout.background_color {
+        properties.push_str(&format!(" fo:background-color=\"{}\"", color(background)));
+    }
+
+    let (columns, spacing, separator) = match &page_def.layout {
+        Some(layout) => (layout.columns, layout.column_spacing, layout.column_line),
+        None => section
+            .section_def
+            .as_ref()
+            .map(|def| (def.column_count(), def.column_gap as u32, false))
+            .unwrap_or((1, 0, false)),
+    };
+    let columns = if columns > 1 {
+        let separator = if separator {
+            "<style:column-sep style:width=\"0.1mm\" style:color=\"#000000\" style:height=\"100%\"/>"
+        } else {
+            ""
+        };
+        format!(
+            "<style:columns fo:column-count=\"{columns}\" fo:column-gap=\"{}\">{separator}</style:columns>",
+            pt(spacing as i32)
+        )
+    } else {
+        String::new()
+    };
+
+    let items = &page_def.header_footer.items;
+    let area = |kind: HeaderFooterType, tag: &str, height: u32| {
+        if items.iter().any(|item| item.header_footer_type == kind) {
+            format!(
+                "<style:{tag}-style><style:header-footer-properties fo:min-height=\"{}\"/></style:{tag}-style>",
+                pt(height as i32)
+            )
+        } else {
+            String::new()
+        }
+    };
+    let header = area(HeaderFooterType::Header, "header", page_def.header_margin);
+    let footer = area(HeaderFooterType::Footer, "footer", page_def.footer_margin);
+
+    format!(
+        "<style:page-layout style:name=\"pm{section_idx}\">\
+         <style:page-layout-properties{properties}>{columns}</style:page-layout-properties>{header}{footer}</style:page-layout>"
+    )
+}
+
+fn hyperlink_start(hyperlink: &Hyperlink) -> String {
+    let target = hyperlink.target_url.trim();
+    let href = match hyperlink.hyperlink_type {
+        HyperlinkType::Bookmark => format!("#{}", target.trim_start_matches('#')),
+        HyperlinkType::Email if !target.starts_with("mailto:") => format!("mailto:{target}"),
+        _ => target.to_string(),
+    };
+    let title = hyperlink
+        .tooltip
+        .as_ref()
+        .map(|tooltip| format!(" office:title=\"{}\"", escape(tooltip)))
+        .unwrap_or_default();
+    format!(
+        "<text:a xlink:type=\"simple\" xlink:href=\"{}\"{title}>",
+        escape(&href)
+    )
+}
+
+/// A span of text in a character style, or nothing for empty text
+fn text_span(char_shape_id: Option<u16>, text: &str, after_space: &mut bool) -> String {
+    if text.is_empty() {
+        return String::new();
+    }
+    let content = text_content(text, after_space);
+    match char_shape_id {
+        Some(id) => format!("<text:span text:style-name=\"T{id}\">{content}</text:span>"),
+        None => content,
+    }
+}
+
+/// Escaped text with tabs, line breaks and repeated spaces as elements.
+///
+/// `after_space` carries whether the previous character was a collapsible
+/// space, since a space following one would be lost when read back.
+fn text_content(text: &str, after_space: &mut bool) -> String {
+    let mut content = String::new();
+    let mut pending = String::new();
+    let mut spaces = 0usize;
+    let flush_spaces = |content: &mut String, spaces: &mut usize| {
+        match *spaces {
+            0 => {}
+            1 => content.push_str("<text:s/>"),
+            count => content.push_str(&format!("<text:s text:c=\"{count}\"/>")),
+        }
+        *spaces = 0;
+    };
+
+    for ch in text.chars() {
+        match ch {
+            ' ' if *after_space => {
+                content.push_str(&escape(pending.as_str()));
+                pending.clear();
+                spaces += 1;
+            }
+            ' ' => {
+                pending.push(' ');
+                *after_space = true;
+            }
+            '\n' | '\t' => {
+                content.push_str(&escape(pending.as_str()));
+                pending.clear();
+                flush_spaces(&mut content, &mut spaces);
+                content.push_str(if ch == '\n' {
+                    "<text:line-break/>"
+                } else {
+                    "<text:tab/>"
+                });
+                *after_space = ch == '\n';
+            }
+            _ => {
+                flush_spaces(&mut content, &mut spaces);
+                pending.push(ch);
+                *after_space = false;
+            }
+        }
+    }
+    content.push_str(&escape(pending.as_str()));
+    flush_spaces(&mut content, &mut spaces);
+    content
+}
+
+/// A border as `width style colour`
+fn border(line: &BorderLine) -> String {
+    let style = match line.line_type {
+        0 => return "none".to_string(),
+        2 => "dashed",
+        3 => "dotted",
+        4 => "double",
+        _ => "solid",
+    };
+    // Thickness is an index into 0.1mm .. 5mm
+    const THICKNESS_MM: [f64; 16] = [
+        0.1, 0.12, 0.15, 0.2, 0.25, 0.3, 0.4, 0.5, 0.6, 0.7, 1.0, 1.5, 2.0, 3.0, 4.0, 5.0,
+    ];
+    let mm = THICKNESS_MM
+        .get(line.thickness as usize)
+        .copied()
+        .unwrap_or(0.12);
+    format!("{mm}mm {style} {}", color(line.color))
+}
+
+/// Solid background; gradients fall back to their start colour
+fn background(fill: &BorderFill) -> String {
+    let info = &fill.fill_info;
+    let color_value = if info.fill_type & 0x01 != 0 {
+        info.back_color
+    } else if let Some(gradient) = &info.gradient_info {
+        gradient.start_color
+    } else {
+        return String::new();
+    };
+    format!(" fo:background-color=\"{}\"", color(color_value))
+}
+
+/// HWPUNIT (1/7200 inch) to points
+fn pt(value: i32) -> String {
+    format!("{}pt", value as f64 / 100.0)
+}
+
+fn color(value: u32) -> String {
+    format!("#{:06x}", value & 0xFFFFFF)
+}
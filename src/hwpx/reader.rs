@@ -6,11 +6,13 @@ use zip::ZipArchive;
 use crate::error::{HwpError, Result};
 use crate::model::{
     para_char_shape::{CharPositionShape, ParaCharShape},
-    CharShape, DocumentProperties, FaceName, HwpDocument, ParaShape, ParaText, Paragraph, Section,
+    CharShape, DocumentProperties, DocumentScripts, FaceName, HwpDocument, ParaShape, ParaText,
+    Paragraph, Section,
 };
 use crate::parser::body_text::BodyText;
 use crate::parser::doc_info::DocInfo;
 use crate::parser::header::FileHeader;
+use crate::reader::ReaderLimits;
 
 use super::xml_types::{self, HcfVersion, Head, Run, Section as XmlSection, XmlParagraph};
 
@@ -20,21 +22,38 @@ impl HwpxReader {
     pub fn from_file<P: AsRef<Path>>(path: P) -> Result<HwpDocument> {
         let file = File::open(path).map_err(HwpError::Io)?;
         let reader = BufReader::new(file);
-        Self::from_reader(reader)
+        Self::from_reader(reader, &ReaderLimits::default())
     }
 
     pub fn from_bytes(bytes: &[u8]) -> Result<HwpDocument> {
         let cursor = std::io::Cursor::new(bytes.to_vec());
-        Self::from_reader(cursor)
+        Self::from_reader(cursor, &ReaderLimits::default())
     }
 
-    fn from_reader<R: Read + std::io::Seek>(reader: R) -> Result<HwpDocument> {
+    /// Read a file with explicit resource limits, e.g. for user uploads
+    pub fn from_file_with_limits<P: AsRef<Path>>(
+        path: P,
+        limits: ReaderLimits,
+    ) -> Result<HwpDocument> {
+        let file = File::open(path).map_err(HwpError::Io)?;
+        Self::from_reader(BufReader::new(file), &limits)
+    }
+
+    /// Read bytes with explicit resource limits, e.g. for user uploads
+    pub fn from_bytes_with_limits(bytes: &[u8], limits: ReaderLimits) -> Result<HwpDocument> {
+        Self::from_reader(std::io::Cursor::new(bytes.to_vec()), &limits)
+    }
+
+    fn from_reader<R: Read + std::io::Seek>(
+        reader: R,
+        limits: &ReaderLimits,
+    ) -> Result<HwpDocument> {
         let mut archive = ZipArchive::new(reader)
             .map_err(|e| HwpError::InvalidFormat(format!("Invalid HWPX archive: {}", e)))?;
 
-        let version = Self::read_version(&mut archive)?;
-        let head = Self::read_header(&mut archive)?;
-        let sections = Self::read_sections(&mut archive)?;
+        let version = Self::read_version(&mut archive, limits)?;
+        let head = Self::read_header(&mut archive, limits)?;
+        let sections = Self::read_sections(&mut archive, limits)?;
 
         let header = Self::create_file_header(&version);
         let doc_info = Self::convert_head_to_doc_info(&head);
@@ -47,37 +66,48 @@ impl HwpxReader {
             preview_text: None,
             preview_image: None,
             summary_info: None,
+            scripts: Self::read_scripts(&mut archive, limits),
+            password: None,
         })
     }
 
-    fn read_version<R: Read + std::io::Seek>(archive: &mut ZipArchive<R>) -> Result<HcfVersion> {
-        let xml = Self::read_xml_file(archive, "version.xml")?;
+    fn read_version<R: Read + std::io::Seek>(
+        archive: &mut ZipArchive<R>,
+        limits: &ReaderLimits,
+    ) -> Result<HcfVersion> {
+        let xml = Self::read_xml_file(archive, "version.xml", limits)?;
         xml_types::parse_version(&xml)
             .map_err(|e| HwpError::ParseError(format!("Failed to parse version.xml: {}", e)))
     }
 
-    fn read_header<R: Read + std::io::Seek>(archive: &mut ZipArchive<R>) -> Result<Head> {
-        let xml = Self::read_xml_file(archive, "Contents/header.xml")?;
+    fn read_header<R: Read + std::io::Seek>(
+        archive: &mut ZipArchive<R>,
+        limits: &ReaderLimits,
+    ) -> Result<Head> {
+        let xml = Self::read_xml_file(archive, "Contents/header.xml", limits)?;
         xml_types::parse_head(&xml)
             .map_err(|e| HwpError::ParseError(format!("Failed to parse header.xml: {}", e)))
     }
 
     fn read_sections<R: Read + std::io::Seek>(
         archive: &mut ZipArchive<R>,
+        limits: &ReaderLimits,
     ) -> Result<Vec<XmlSection>> {
         let mut sections = Vec::new();
         let mut idx = 0;
 
         loop {
             let filename = format!("Contents/section{}.xml", idx);
-            match Self::read_xml_file(archive, &filename) {
+            match Self::read_xml_file(archive, &filename, limits) {
                 Ok(xml) => {
                     let section = xml_types::parse_section(&xml).map_err(|e| {
                         HwpError::ParseError(format!("Failed to parse {}: {}", filename, e))
                     })?;
+                    limits.check_paragraphs(section.paragraphs.len())?;
                     sections.push(section);
                     idx += 1;
                 }
+                Err(e @ HwpError::LimitExceeded { .. }) => return Err(e),
                 Err(_) => break,
             }
         }
@@ -91,20 +121,52 @@ impl HwpxReader {
         Ok(sections)
     }
 
-    fn read_xml_file<R: Read + std::io::Seek>(
+    fn read_scripts<R: Read + std::io::Seek>(
+        archive: &mut ZipArchive<R>,
+        limits: &ReaderLimits,
+    ) -> Option<DocumentScripts> {
+        let header = Self::read_binary_file(archive, "Scripts/headerScripts", limits).ok();
+        let source = Self::read_binary_file(archive, "Scripts/sourceScripts", limits).ok();
+        if header.is_none() && source.is_none() {
+            return None;
+        }
+
+        Some(DocumentScripts::from_hwpx_parts(
+            &header.unwrap_or_default(),
+            &source.unwrap_or_default(),
+        ))
+    }
+
+    fn read_binary_file<R: Read + std::io::Seek>(
         archive: &mut ZipArchive<R>,
         filename: &str,
-    ) -> Result<String> {
-        let mut file = archive
+        limits: &ReaderLimits,
+    ) -> Result<Vec<u8>> {
+        let file = archive
             .by_name(filename)
             .map_err(|_| HwpError::NotFound(format!("File not found in archive: {}", filename)))?;
+        limits.check_zip_entry_size(file.size())?;
 
-        let mut contents = String::new();
-        file.read_to_string(&mut contents).map_err(HwpError::Io)?;
+        // The declared size can lie, so bound the actual inflated output as well
+        let mut contents = Vec::new();
+        file.take(limits.max_zip_entry_size.saturating_add(1))
+            .read_to_end(&mut contents)
+            .map_err(HwpError::Io)?;
+        limits.check_zip_entry_size(contents.len() as u64)?;
 
         Ok(contents)
     }
 
+    fn read_xml_file<R: Read + std::io::Seek>(
+        archive: &mut ZipArchive<R>,
+        filename: &str,
+        limits: &ReaderLimits,
+    ) -> Result<String> {
+        let contents = Self::read_binary_file(archive, filename, limits)?;
+        String::from_utf8(contents)
+            .map_err(|e| HwpError::EncodingError(format!("{} is not valid UTF-8: {}", filename, e)))
+    }
+
     fn create_file_header(version: &HcfVersion) -> FileHeader {
         let mut header = FileHeader::new_default();
 
@@ -263,11 +325,25 @@ impl HwpxReader {
             control_mask: 0,
             para_shape_id: xml_para.para_pr_id_ref.unwrap_or(0) as u16,
             style_id: xml_para.style_id_ref.unwrap_or(0) as u8,
+            column_type: Self::break_flags(xml_para),
             char_shapes,
             ..Default::default()
         }
     }
 
+    /// Page and column break flags in the binary format's paragraph header layout
+    fn break_flags(xml_para: &XmlParagraph) -> u8 {
+        let set = |value: &Option<String>| matches!(value.as_deref(), Some("1" | "true"));
+        let mut flags = 0;
+        if set(&xml_para.page_break) {
+            flags |= 0x04;
+        }
+        if set(&xml_para.column_break) {
+            flags |= 0x08;
+        }
+        flags
+    }
+
     fn parse_color(color_str: &str) -> Option<u32> {
         let color_str = color_str.trim();
         if color_str.starts_with('#') && color_str.len() == 7 {
@@ -386,6 +386,8 @@ impl HwpxWriter {
                 preview_text: None,
                 preview_image: None,
                 summary_info: None,
+                scripts: None,
+                password: None,
             },
             tables: Vec::new(),
             images: Vec::new(),
@@ -133,6 +133,10 @@ pub struct XmlParagraph {
     pub para_pr_id_ref: Option<u32>,
     #[serde(rename = "@styleIDRef", default)]
     pub style_id_ref: Option<u32>,
+    #[serde(rename = "@pageBreak", default)]
+    pub page_break: Option<String>,
+    #[serde(rename = "@columnBreak", default)]
+    pub column_break: Option<String>,
     #[serde(rename = "run", default)]
     pub runs: Vec<Run>,
 }
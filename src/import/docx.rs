@@ -0,0 +1,1072 @@
+//! DOCX (Office Open XML WordprocessingML) import
+//!
+//! The document is rebuilt through [`HwpWriter`], so the result is the same
+//! kind of document the writer produces: a single section with plain-text
+//! table cells and list items.
+
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{BufReader, Read, Seek};
+use std::path::Path;
+
+use zip::ZipArchive;
+
+use super::xml::Element;
+use super::{
+    border_line_style, paper_size, read_entry, read_xml, write_images, write_paragraph, Lists,
+    ParaFormat, ParagraphContent, RunFormat, Shapes,
+};
+use crate::error::{HwpError, Result};
+use crate::model::footnote::{Footnote, NoteKind};
+use crate::model::header_footer::{HeaderFooterAlignment, PageApplyType, PageNumberFormat};
+use crate::model::hyperlink::Hyperlink;
+use crate::model::page_layout::{PageLayout, PageOrientation};
+use crate::model::style::Style;
+use crate::reader::ReaderLimits;
+use crate::writer::style::{
+    BorderLineStyle, BorderLineType, CellBorderStyle, ImageFormat, ImageOptions, ListType,
+};
+use crate::{HwpDocument, HwpWriter};
+
+/// Longest `w:basedOn` chain followed before giving up on a style
+const MAX_STYLE_DEPTH: usize = 32;
+
+pub fn from_file<P: AsRef<Path>>(path: P) -> Result<HwpDocument> {
+    from_file_with_limits(path, ReaderLimits::default())
+}
+
+pub fn from_bytes(bytes: &[u8]) -> Result<HwpDocument> {
+    from_bytes_with_limits(bytes, ReaderLimits::default())
+}
+
+/// Read a file with explicit resource limits, e.g. for user uploads
+pub fn from_file_with_limits<P: AsRef<Path>>(path: P, limits: ReaderLimits) -> Result<HwpDocument> {
+    let file = File::open(path).map_err(HwpError::Io)?;
+    import(BufReader::new(file), &limits)
+}
+
+/// Read bytes with explicit resource limits, e.g. for user uploads
+pub fn from_bytes_with_limits(bytes: &[u8], limits: ReaderLimits) -> Result<HwpDocument> {
+    import(std::io::Cursor::new(bytes), &limits)
+}
+
+fn import<R: Read + Seek>(reader: R, limits: &ReaderLimits) -> Result<HwpDocument> {
+    let mut archive = ZipArchive::new(reader)
+        .map_err(|e| HwpError::InvalidFormat(format!("Invalid DOCX archive: {}", e)))?;
+    let document = read_xml(&mut archive, "word/document.xml", limits)?
+        .ok_or_else(|| HwpError::NotFound("word/document.xml".to_string()))?;
+    let body = document
+        .child("w:body")
+        .ok_or_else(|| HwpError::InvalidFormat("word/document.xml has no body".to_string()))?;
+
+    let relationships = read_xml(&mut archive, "word/_rels/document.xml.rels", limits)?
+        .map(|root| relationships(&root))
+        .unwrap_or_default();
+    let styles = read_xml(&mut archive, "word/styles.xml", limits)?;
+    let numbering = read_xml(&mut archive, "word/numbering.xml", limits)?
+        .map(|root| numbering(&root))
+        .unwrap_or_default();
+    let footnotes = read_xml(&mut archive, "word/footnotes.xml", limits)?
+        .map(|root| notes(&root, "w:footnote"))
+        .unwrap_or_default();
+    let endnotes = read_xml(&mut archive, "word/endnotes.xml", limits)?
+        .map(|root| notes(&root, "w:endnote"))
+        .unwrap_or_default();
+
+    let mut importer = DocxImporter {
+        archive,
+        limits,
+        writer: HwpWriter::new(),
+        relationships,
+        styles: HashMap::new(),
+        default_run: RunFormat::default(),
+        default_para: ParaFormat::default(),
+        default_style: None,
+        style_ids: HashMap::new(),
+        numbering,
+        footnotes,
+        endnotes,
+        note_counts: [0; 2],
+        shapes: Shapes::default(),
+        lists: Lists::default(),
+    };
+    if let Some(styles) = &styles {
+        importer.read_styles(styles);
+    }
+
+    // Setting the layout replaces the page definition, so it goes before headers
+    if let Some(sect_pr) = body.child("w:sectPr") {
+        let background = document
+            .child("w:background")
+            .and_then(|background| background.attr("w:color"))
+            .and_then(parse_color);
+        importer
+            .writer
+            .set_page_layout(page_layout(sect_pr, background))?;
+        importer.headers_footers(sect_pr)?;
+    }
+
+    importer.blocks(body)?;
+    importer.lists.close(&mut importer.writer)?;
+
+    if let Some(core) = read_xml(&mut importer.archive, "docProps/core.xml", limits)? {
+        importer.core_properties(&core);
+    }
+    Ok(importer.writer.document().clone())
+}
+
+struct Relationship {
+    target: String,
+    external: bool,
+}
+
+/// Relationship targets by id, resolved against the `word/` folder
+fn relationships(root: &Element) -> HashMap<String, Relationship> {
+    root.children_named("Relationship")
+        .filter_map(|relationship| {
+            let id = relationship.attr("Id")?;
+            let target = relationship.attr("Target")?;
+            let external = relationship.attr("TargetMode") == Some("External");
+            let target = if external {
+                target.to_string()
+            } else if let Some(absolute) = target.strip_prefix('/') {
+                absolute.to_string()
+            } else {
+                resolve_path("word", target)
+            };
+            Some((id.to_string(), Relationship { target, external }))
+        })
+        .collect()
+}
+
+fn resolve_path(base: &str, target: &str) -> String {
+    let mut parts: Vec<&str> = base.split('/').filter(|part| !part.is_empty()).collect();
+    for part in target.split('/') {
+        match part {
+            "" | "." => {}
+            ".." => {
+                parts.pop();
+            }
+            _ => parts.push(part),
+        }
+    }
+    parts.join("/")
+}
+
+/// List type for each level of every `w:num`
+fn numbering(root: &Element) -> HashMap<String, Vec<(u8, Option<ListType>)>> {
+    let abstract_levels: HashMap<&str, Vec<(u8, Option<ListType>)>> = root
+        .children_named("w:abstractNum")
+        .filter_map(|abstract_num| {
+            let id = abstract_num.attr("w:abstractNumId")?;
+            let levels = abstract_num
+                .children_named("w:lvl")
+                .filter_map(|level| {
+                    let index = level.attr("w:ilvl")?.parse().ok()?;
+                    let format = level
+                        .child("w:numFmt")
+                        .and_then(|format| format.attr("w:val"))
+                        .unwrap_or("decimal");
+                    Some((index, list_type(format)))
+                })
+                .collect();
+            Some((id, levels))
+        })
+        .collect();
+
+    root.children_named("w:num")
+        .filter_map(|num| {
+            let id = num.attr("w:numId")?;
+            let abstract_id = num.child("w:abstractNumId")?.attr("w:val")?;
+            let levels = abstract_levels.get(abstract_id)?.clone();
+            Some((id.to_string(), levels))
+        })
+        .collect()
+}
+
+fn list_type(format: &str) -> Option<ListType> {
+    Some(match format {
+        "none" => return None,
+        "bullet" => ListType::Bullet,
+        "lowerLetter" | "upperLetter" => ListType::Alphabetic,
+        "lowerRoman" | "upperRoman" => ListType::Roman,
+        "ganada" | "chosung" | "koreanDigital" | "koreanDigital2" | "koreanCounting"
+        | "koreanLegal" => ListType::Korean,
+        _ => ListType::Numbered,
+    })
+}
+
+/// Plain text of every note, by id
+fn notes(root: &Element, tag: &str) -> HashMap<String, String> {
+    root.children_named(tag)
+        .filter_map(|note| {
+            // Notes open with the reference mark and a separating space
+            let text = plain_text(note).trim_start().to_string();
+            Some((note.attr("w:id")?.to_string(), text))
+        })
+        .collect()
+}
+
+/// Text of every paragraph below `element`, one line per paragraph
+fn plain_text(element: &Element) -> String {
+    fn collect(element: &Element, paragraphs: &mut Vec<String>) {
+        for child in element.elements() {
+            if child.name == "w:p" {
+                let mut text = String::new();
+                run_text(child, &mut text);
+                paragraphs.push(text);
+            } else {
+                collect(child, paragraphs);
+            }
+        }
+    }
+    let mut paragraphs = Vec::new();
+    collect(element, &mut paragraphs);
+    paragraphs.join("\n")
+}
+
+/// Visible text of the runs below `element`, skipping deleted text
+fn run_text(element: &Element, text: &mut String) {
+    for child in element.elements() {
+        match child.name.as_str() {
+            "w:t" => text.push_str(&child.text()),
+            "w:tab" | "w:ptab" => text.push('\t'),
+            "w:br" | "w:cr" => text.push('\n'),
+            "w:noBreakHyphen" => text.push('-'),
+            "w:del" | "w:pPr" | "w:rPr" => {}
+            _ => run_text(child, text),
+        }
+    }
+}
+
+/// `w:val` of a child element
+fn value<'a>(element: &'a Element, name: &str) -> Option<&'a str> {
+    element.child(name)?.attr("w:val")
+}
+
+/// A toggle property such as `<w:b/>` or `<w:b w:val="0"/>`
+fn toggle(element: &Element, name: &str) -> Option<bool> {
+    let child = element.child(name)?;
+    Some(!matches!(
+        child.attr("w:val"),
+        Some("0") | Some("false") | Some("off") | Some("none")
+    ))
+}
+
+fn number<T: std::str::FromStr>(element: &Element, attribute: &str) -> Option<T> {
+    element.attr(attribute)?.parse().ok()
+}
+
+/// A twips attribute in HWPUNIT
+fn twips(element: &Element, attribute: &str) -> Option<i32> {
+    number::<i32>(element, attribute).map(|value| value * 5)
+}
+
+fn parse_color(value: &str) -> Option<u32> {
+    if value == "auto" || value.len() != 6 {
+        return None;
+    }
+    u32::from_str_radix(value, 16).ok()
+}
+
+fn highlight_color(name: &str) -> Option<u32> {
+    Some(match name {
+        "black" => 0x000000,
+        "blue" => 0x0000FF,
+        "cyan" => 0x00FFFF,
+        "green" => 0x00FF00,
+        "magenta" => 0xFF00FF,
+        "red" => 0xFF0000,
+        "yellow" => 0xFFFF00,
+        "white" => 0xFFFFFF,
+        "darkBlue" => 0x000080,
+        "darkCyan" => 0x008080,
+        "darkGreen" => 0x008000,
+        "darkMagenta" => 0x800080,
+        "darkRed" => 0x800000,
+        "darkYellow" => 0x808000,
+        "darkGray" => 0x808080,
+        "lightGray" => 0xC0C0C0,
+        _ => return None,
+    })
+}
+
+/// `w:rPr` properties, in HWP units
+fn run_format(r_pr: &Element) -> RunFormat {
+    let fonts = r_pr.child("w:rFonts");
+    let font = |attribute: &str| {
+        fonts
+            .and_then(|fonts| fonts.attr(attribute))
+            .map(str::to_string)
+    };
+    let underline = r_pr.child("w:u");
+    let vert_align = value(r_pr, "w:vertAlign");
+    RunFormat {
+        latin_font: font("w:ascii").or_else(|| font("w:hAnsi")),
+        hangul_font: font("w:eastAsia"),
+        // Half-points
+        size: r_pr
+            .child("w:sz")
+            .and_then(|sz| number::<i32>(sz, "w:val"))
+            .map(|size| size * 50),
+        bold: toggle(r_pr, "w:b"),
+        italic: toggle(r_pr, "w:i"),
+        underline: underline.map(|u| u.attr("w:val") != Some("none")),
+        underline_color: underline
+            .and_then(|u| u.attr("w:color"))
+            .and_then(parse_color),
+        strike: toggle(r_pr, "w:strike").or_else(|| toggle(r_pr, "w:dstrike")),
+        superscript: vert_align.map(|align| align == "superscript"),
+        subscript: vert_align.map(|align| align == "subscript"),
+        color: value(r_pr, "w:color").and_then(parse_color),
+        shade: value(r_pr, "w:highlight")
+            .and_then(highlight_color)
+            .or_else(|| {
+                r_pr.child("w:shd")
+                    .and_then(|shd| shd.attr("w:fill"))
+                    .and_then(parse_color)
+            }),
+    }
+}
+
+/// `w:pPr` properties, in HWP units
+fn para_format(p_pr: &Element) -> ParaFormat {
+    let mut format = ParaFormat {
+        alignment: value(p_pr, "w:jc").map(|jc| match jc {
+            "right" | "end" => 1,
+            "center" => 2,
+            "both" => 3,
+            "distribute" => 4,
+            _ => 0,
+        }),
+        outline_level: p_pr
+            .child("w:outlineLvl")
+            .and_then(|level| number(level, "w:val")),
+        ..Default::default()
+    };
+
+    if let Some(ind) = p_pr.child("w:ind") {
+        format.left = twips(ind, "w:left").or_else(|| twips(ind, "w:start"));
+        format.right = twips(ind, "w:right").or_else(|| twips(ind, "w:end"));
+        format.first_line = twips(ind, "w:hanging")
+            .map(|hanging| -hanging)
+            .or_else(|| twips(ind, "w:firstLine"));
+    }
+    if let Some(spacing) = p_pr.child("w:spacing") {
+        format.before = twips(spacing, "w:before");
+        format.after = twips(spacing, "w:after");
+        format.line = number::<i32>(spacing, "w:line").map(|line| {
+            match spacing.attr("w:lineRule") {
+                Some("exact") => (line * 5, 1),
+                Some("atLeast") => (line * 5, 2),
+                // Auto spacing is in 240ths of a line
+                _ => (line * 100 / 240, 0),
+            }
+        });
+    }
+    if let Some(num_pr) = p_pr.child("w:numPr") {
+        let id = value(num_pr, "w:numId").map(str::to_string);
+        let level = value(num_pr, "w:ilvl").and_then(|level| level.parse().ok());
+        format.numbering = id.map(|id| (id, level.unwrap_or(0)));
+    }
+    format
+}
+
+struct StyleDefinition {
+    name: String,
+    based_on: Option<String>,
+    run: RunFormat,
+    para: ParaFormat,
+    /// `w:tblBorders` of a table style
+    table_borders: Option<Element>,
+}
+
+enum Link {
+    Url(String),
+    Email(String),
+    Bookmark(String),
+}
+
+struct DocxImporter<'a, R> {
+    archive: ZipArchive<R>,
+    limits: &'a ReaderLimits,
+    writer: HwpWriter,
+    relationships: HashMap<String, Relationship>,
+    styles: HashMap<String, StyleDefinition>,
+    default_run: RunFormat,
+    default_para: ParaFormat,
+    /// Paragraph style used when a paragraph names none
+    default_style: Option<String>,
+    /// HWP style index of each Word style used so far
+    style_ids: HashMap<String, u8>,
+    numbering: HashMap<String, Vec<(u8, Option<ListType>)>>,
+    footnotes: HashMap<String, String>,
+    endnotes: HashMap<String, String>,
+    /// Footnotes and endnotes numbered so far
+    note_counts: [u16; 2],
+    shapes: Shapes,
+    lists: Lists,
+}
+
+impl<R: Read + Seek> DocxImporter<'_, R> {
+    fn read_styles(&mut self, root: &Element) {
+        if let Some(defaults) = root.child("w:docDefaults") {
+            if let Some(r_pr) = defaults
+                .child("w:rPrDefault")
+                .and_then(|d| d.child("w:rPr"))
+            {
+                self.default_run = run_format(r_pr);
+            }
+            if let Some(p_pr) = defaults
+                .child("w:pPrDefault")
+                .and_then(|d| d.child("w:pPr"))
+            {
+                self.default_para = para_format(p_pr);
+            }
+        }
+
+        for style in root.children_named("w:style") {
+            let Some(id) = style.attr("w:styleId") else {
+                continue;
+            };
+            if style.attr("w:type") == Some("paragraph") && style.attr("w:default") == Some("1") {
+                self.default_style = Some(id.to_string());
+            }
+            let definition = StyleDefinition {
+                name: value(style, "w:name").unwrap_or(id).to_string(),
+                based_on: value(style, "w:basedOn").map(str::to_string),
+                run: style.child("w:rPr").map(run_format).unwrap_or_default(),
+                para: style.child("w:pPr").map(para_format).unwrap_or_default(),
+                table_borders: style
+                    .child("w:tblPr")
+                    .and_then(|tbl_pr| tbl_pr.child("w:tblBorders"))
+                    .cloned(),
+            };
+            self.styles.insert(id.to_string(), definition);
+        }
+    }
+
+    /// A style followed by the styles it is based on
+    fn style_chain(&self, id: &str) -> Vec<&StyleDefinition> {
+        let mut chain: Vec<&StyleDefinition> = Vec::new();
+        let mut next = Some(id);
+        while let Some(id) = next {
+            let Some(definition) = self.styles.get(id) else {
+                break;
+            };
+            if chain.len() >= MAX_STYLE_DEPTH || chain.iter().any(|d| std::ptr::eq(*d, definition))
+            {
+                break;
+            }
+            chain.push(definition);
+            next = definition.based_on.as_deref();
+        }
+        chain
+    }
+
+    /// Run and paragraph formats of a paragraph style, including document defaults
+    fn style_formats(&self, id: Option<&str>) -> (RunFormat, ParaFormat) {
+        let mut run = self.default_run.clone();
+        let mut para = self.default_para.clone();
+        for definition in id
+            .map(|id| self.style_chain(id))
+            .unwrap_or_default()
+            .iter()
+            .rev()
+        {
+            run.merge(&definition.run);
+            para.merge(&definition.para);
+        }
+        (run, para)
+    }
+
+    /// HWP style index for a Word paragraph style, adding it on first use
+    fn style_id(&mut self, id: &str) -> Result<u8> {
+        if let Some(&index) = self.style_ids.get(id) {
+            return Ok(index);
+        }
+        let Some(name) = self.styles.get(id).map(|style| style.name.clone()) else {
+            return Ok(0);
+        };
+        let (run, para) = self.style_formats(Some(id));
+        let char_shape_id = self.shapes.char_shape(&mut self.writer, &run)?;
+        let para_shape_id = self.shapes.para_shape(&mut self.writer, &para)?;
+        let index = match self.writer.add_style(Style {
+            name,
+            english_name: id.to_string(),
+            properties: 0,
+            next_style_id: 0,
+            lang_id: 0x0412,
+            para_shape_id,
+            char_shape_id,
+        }) {
+            Ok(index) => index,
+            // Past the style limit paragraphs keep their formatting under the default style
+            Err(HwpError::InvalidInput(_)) => 0,
+            Err(e) => return Err(e),
+        };
+        self.style_ids.insert(id.to_string(), index);
+        Ok(index)
+    }
+
+    /// Block-level content of the body or a content control
+    fn blocks(&mut self, parent: &Element) -> Result<()> {
+        for child in parent.elements() {
+            match child.name.as_str() {
+                "w:p" => self.paragraph(child)?,
+                "w:tbl" => self.table(child)?,
+                "w:sdt" => {
+                    if let Some(content) = child.child("w:sdtContent") {
+                        self.blocks(content)?;
+                    }
+                }
+                _ => {}
+            }
+        }
+        Ok(())
+    }
+
+    fn paragraph(&mut self, p: &Element) -> Result<()> {
+        let p_pr = p.child("w:pPr");
+        let style = p_pr
+            .and_then(|p_pr| value(p_pr, "w:pStyle"))
+            .filter(|id| self.styles.contains_key(*id))
+            .or(self.default_style.as_deref())
+            .map(str::to_string);
+        let (run, mut para) = self.style_formats(style.as_deref());
+        if let Some(p_pr) = p_pr {
+            para.merge(&para_format(p_pr));
+        }
+
+        let mut content = ParagraphContent::default();
+        self.inline(p, &run, &mut content)?;
+
+        let list = para
+            .numbering
+            .as_ref()
+            .filter(|(id, _)| id != "0")
+            .and_then(|(id, level)| {
+                let list_type = self
+                    .numbering
+                    .get(id)?
+                    .iter()
+                    .find(|(index, _)| index == level)?
+                    .1
+                    .clone()?;
+                Some((id.clone(), *level, list_type))
+            });
+
+        if let Some((id, level, list_type)) = list {
+            self.lists.open(&mut self.writer, &id, level, list_type)?;
+            self.writer.add_list_item(&content.text)?;
+            write_images(&mut self.writer, &content.images)
+        } else {
+            self.lists.close(&mut self.writer)?;
+            let style_id = match &style {
+                Some(id) => self.style_id(id)?,
+                None => 0,
+            };
+            write_paragraph(
+                &mut self.writer,
+                &mut self.shapes,
+                style_id,
+                &run,
+                &para,
+                content,
+            )
+        }
+    }
+
+    /// Runs of a paragraph, following links, insertions and content controls
+    fn inline(
+        &mut self,
+        parent: &Element,
+        base: &RunFormat,
+        content: &mut ParagraphContent,
+    ) -> Result<()> {
+        for child in parent.elements() {
+            match child.name.as_str() {
+                "w:r" => self.run(child, base, content)?,
+                "w:hyperlink" => {
+                    let start = content.length;
+                    let text_start = content.text.len();
+                    self.inline(child, base, content)?;
+                    let display = content.text[text_start..].to_string();
+                    if display.is_empty() {
+                        continue;
+                    }
+                    let link = if let Some(anchor) = child.attr("w:anchor") {
+                        Some(Link::Bookmark(anchor.to_string()))
+                    } else {
+                        child
+                            .attr("r:id")
+                            .and_then(|id| self.relationships.get(id))
+                            .map(
+                                |relationship| match relationship.target.strip_prefix("mailto:") {
+                                    Some(email) => Link::Email(email.to_string()),
+                                    None => Link::Url(relationship.target.clone()),
+                                },
+                            )
+                    };
+                    let hyperlink = match link {
+                        Some(Link::Url(url)) => Hyperlink::new_url(&display, &url),
+                        Some(Link::Email(email)) => Hyperlink::new_email(&display, &email),
+                        Some(Link::Bookmark(name)) => Hyperlink::new_bookmark(&display, &name),
+                        None => continue,
+                    };
+                    content.hyperlinks.push(
+                        hyperlink
+                            .with_position(start)
+                            .with_length(content.length - start),
+                    );
+                }
+                "w:ins" | "w:smartTag" | "w:fldSimple" | "w:customXml" | "w:bdo" | "w:dir" => {
+                    self.inline(child, base, content)?
+                }
+                "w:sdt" => {
+                    if let Some(sdt_content) = child.child("w:sdtContent") {
+                        self.inline(sdt_content, base, content)?;
+                    }
+                }
+                _ => {}
+            }
+        }
+        Ok(())
+    }
+
+    fn run(&mut self, r: &Element, base: &RunFormat, content: &mut ParagraphContent) -> Result<()> {
+        let mut format = base.clone();
+        if let Some(r_pr) = r.child("w:rPr") {
+            if let Some(id) = value(r_pr, "w:rStyle") {
+                for definition in self.style_chain(id).iter().rev() {
+                    format.merge(&definition.run);
+                }
+            }
+            format.merge(&run_format(r_pr));
+        }
+        let char_shape_id = self.shapes.char_shape(&mut self.writer, &format)?;
+        self.run_content(r, char_shape_id, content)
+    }
+
+    fn run_content(
+        &mut self,
+        parent: &Element,
+        char_shape_id: u16,
+        content: &mut ParagraphContent,
+    ) -> Result<()> {
+        for child in parent.elements() {
+            match child.name.as_str() {
+                "w:t" => content.push(&child.text(), char_shape_id),
+                "w:tab" | "w:ptab" => content.push("\t", char_shape_id),
+                // Page and column breaks have no place in a single flowing section
+                "w:br" if matches!(child.attr("w:type"), Some("page") | Some("column")) => {}
+                "w:br" | "w:cr" => content.push("\n", char_shape_id),
+                "w:noBreakHyphen" => content.push("-", char_shape_id),
+                "w:footnoteReference" => self.note(child, NoteKind::Footnote, content),
+                "w:endnoteReference" => self.note(child, NoteKind::Endnote, content),
+                "w:drawing" => {
+                    let size = child.find("wp:extent").map(|extent| {
+                        (
+                            number::<u64>(extent, "cx").unwrap_or(0),
+                            number::<u64>(extent, "cy").unwrap_or(0),
+                        )
+                    });
+                    if let Some(id) = child.find("a:blip").and_then(|blip| blip.attr("r:embed")) {
+                        self.image(id, size, content)?;
+                    }
+                }
+                "w:pict" | "w:object" => {
+                    if let Some(id) = child.find("v:imagedata").and_then(|data| data.attr("r:id")) {
+                        self.image(id, None, content)?;
+                    }
+                }
+                "mc:AlternateContent" => {
+                    if let Some(choice) = child.child("mc:Choice") {
+                        self.run_content(choice, char_shape_id, content)?;
+                    }
+                }
+                _ => {}
+            }
+        }
+        Ok(())
+    }
+
+    fn note(&mut self, reference: &Element, kind: NoteKind, content: &mut ParagraphContent) {
+        let notes = match kind {
+            NoteKind::Footnote => &self.footnotes,
+            NoteKind::Endnote => &self.endnotes,
+        };
+        let Some(text) = reference.attr("w:id").and_then(|id| notes.get(id)) else {
+            return;
+        };
+        let count = &mut self.note_counts[kind as usize];
+        *count += 1;
+        content
+            .footnotes
+            .push(Footnote::new(kind, *count, content.length, text));
+    }
+
+    /// Queue an embedded image; formats HWP cannot hold are skipped
+    fn image(
+        &mut self,
+        relationship_id: &str,
+        size: Option<(u64, u64)>,
+        content: &mut ParagraphContent,
+    ) -> Result<()> {
+        let Some(relationship) = self
+            .relationships
+            .get(relationship_id)
+            .filter(|relationship| !relationship.external)
+        else {
+            return Ok(());
+        };
+        let Some(data) = read_entry(&mut self.archive, &relationship.target, self.limits)? else {
+            return Ok(());
+        };
+        let Some(format) = ImageFormat::from_bytes(&data) else {
+            return Ok(());
+        };
+
+        let mut options = ImageOptions::new();
+        if let Some((width, height)) = size.filter(|(width, height)| *width > 0 && *height > 0) {
+            // 36000 EMU per millimetre
+            let mm = |emu: u64| ((emu + 18000) / 36000).max(1) as u32;
+            options = options.width(mm(width)).height(mm(height));
+        }
+        content.images.push((data, format, options));
+        Ok(())
+    }
+
+    fn table(&mut self, tbl: &Element) -> Result<()> {
+        struct Cell {
+            row: u32,
+            col: u32,
+            row_span: u16,
+            col_span: u16,
+            text: String,
+            borders: Option<Element>,
+        }
+
+        self.lists.close(&mut self.writer)?;
+
+        let mut cells: Vec<Cell> = Vec::new();
+        // Cell index that a vertical merge in each column continues
+        let mut open_merges: HashMap<u32, usize> = HashMap::new();
+        let rows: Vec<&Element> = tbl.children_named("w:tr").collect();
+        let row_count = rows.len() as u64;
+        self.limits.check_table(row_count, 0)?;
+        let mut cols = tbl
+            .child("w:tblGrid")
+            .map(|grid| grid.children_named("w:gridCol").count() as u32)
+            .unwrap_or(0);
+
+        for (row, tr) in rows.iter().enumerate() {
+            let mut col = tr
+                .child("w:trPr")
+                .and_then(|tr_pr| tr_pr.child("w:gridBefore"))
+                .and_then(|before| number(before, "w:val"))
+                .unwrap_or(0u32);
+            for tc in tr.children_named("w:tc") {
+                let tc_pr = tc.child("w:tcPr");
+                let col_span = tc_pr
+                    .and_then(|tc_pr| tc_pr.child("w:gridSpan"))
+                    .and_then(|span| number(span, "w:val"))
+                    .unwrap_or(1u16)
+                    .max(1);
+                // Spans and skipped columns come from the file, so the grid is
+                // checked before it grows
+                let end = col as u64 + col_span as u64;
+                self.limits.check_table(row_count, end)?;
+                let merge = tc_pr
+                    .and_then(|tc_pr| tc_pr.child("w:vMerge"))
+                    .map(|merge| merge.attr("w:val").unwrap_or("continue"));
+
+                match (merge, open_merges.get(&col)) {
+                    (Some("continue"), Some(&index)) => cells[index].row_span += 1,
+                    _ => {
+                        if merge == Some("restart") {
+                            open_merges.insert(col, cells.len());
+                        } else {
+                            open_merges.remove(&col);
+                        }
+                        cells.push(Cell {
+                            row: row as u32,
+                            col,
+                            row_span: 1,
+                            col_span,
+                            text: plain_text(tc),
+                            borders: tc_pr.and_then(|tc_pr| tc_pr.child("w:tcBorders")).cloned(),
+                        });
+                    }
+                }
+                col = end as u32;
+            }
+            cols = cols.max(col);
+        }
+        if rows.is_empty() || cols == 0 {
+            return Ok(());
+        }
+        self.limits.check_table(row_count, cols as u64)?;
+        let row_count = row_count as u32;
+
+        let table_borders = tbl
+            .child("w:tblPr")
+            .and_then(|tbl_pr| tbl_pr.child("w:tblBorders"))
+            .or_else(|| {
+                let style = value(tbl.child("w:tblPr")?, "w:tblStyle")?;
+                self.style_chain(style)
+                    .into_iter()
+                    .find_map(|definition| definition.table_borders.as_ref())
+            })
+            .cloned();
+
+        let mut builder = self.writer.add_table(row_count, cols);
+        for cell in &cells {
+            builder = builder.set_cell(cell.row, cell.col, &cell.text);
+            if cell.row_span > 1 || cell.col_span > 1 {
+                builder = builder.merge_cells(cell.row, cell.col, cell.row_span, cell.col_span);
+            }
+            if table_borders.is_none() && cell.borders.is_none() {
+                continue;
+            }
+
+            let last_row = cell.row + cell.row_span as u32 >= row_count;
+            let last_col = cell.col + cell.col_span as u32 >= cols;
+            let side = |outer: &str, inner: &str, is_outer: bool| {
+                let table_side = table_borders
+                    .as_ref()
+                    .and_then(|borders| borders.child(if is_outer { outer } else { inner }));
+                cell.borders
+                    .as_ref()
+                    .and_then(|borders| borders.child(outer))
+                    .or(table_side)
+                    .map(border_line)
+                    .unwrap_or_else(BorderLineStyle::none)
+            };
+            builder = builder.set_cell_border(
+                cell.row,
+                cell.col,
+                CellBorderStyle {
+                    left: side("w:left", "w:insideV", cell.col == 0),
+                    right: side("w:right", "w:insideV", last_col),
+                    top: side("w:top", "w:insideH", cell.row == 0),
+                    bottom: side("w:bottom", "w:insideH", last_row),
+                },
+            );
+        }
+        builder.finish()
+    }
+
+    fn headers_footers(&mut self, sect_pr: &Element) -> Result<()> {
+        for reference in sect_pr.elements() {
+            let is_header = match reference.name.as_str() {
+                "w:headerReference" => true,
+                "w:footerReference" => false,
+                _ => continue,
+            };
+            let Some(target) = reference
+                .attr("r:id")
+                .and_then(|id| self.relationships.get(id))
+                .map(|relationship| relationship.target.clone())
+            else {
+                continue;
+            };
+            let Some(part) = read_xml(&mut self.archive, &target, self.limits)? else {
+                continue;
+            };
+
+            let apply = match reference.attr("w:type") {
+                Some("first") => PageApplyType::FirstPage,
+                Some("even") => PageApplyType::EvenPages,
+                _ => PageApplyType::All,
+            };
+            let alignment = part
+                .find("w:jc")
+                .and_then(|jc| jc.attr("w:val"))
+                .map(|jc| match jc {
+                    "center" => HeaderFooterAlignment::Center,
+                    "right" | "end" => HeaderFooterAlignment::Right,
+                    _ => HeaderFooterAlignment::Left,
+                })
+                .unwrap_or(HeaderFooterAlignment::Left);
+
+            let mut text = HeaderFooterText::default();
+            text.collect(&part);
+            let content = text.text.trim_end_matches('\n');
+            match (is_header, text.page_number) {
+                (true, Some(format)) => self.writer.add_header_with_page_number(content, format),
+                (false, Some(format)) => self.writer.add_footer_with_page_number(content, format),
+                (true, None) if !content.is_empty() => self
+                    .writer
+                    .add_header_with_options(content, apply, alignment),
+                (false, None) if !content.is_empty() => self
+                    .writer
+                    .add_footer_with_options(content, apply, alignment),
+                _ => {}
+            }
+        }
+        Ok(())
+    }
+
+    fn core_properties(&mut self, core: &Element) {
+        let property = |name: &str| {
+            core.child(name)
+                .map(|element| element.text().trim().to_string())
+                .filter(|text| !text.is_empty())
+        };
+        if let Some(title) = property("dc:title") {
+            self.writer.set_document_title(&title);
+        }
+        if let Some(author) = property("dc:creator") {
+            self.writer.set_document_author(&author);
+        }
+        if let Some(subject) = property("dc:subject") {
+            self.writer.set_document_subject(&subject);
+        }
+        if let Some(keywords) = property("cp:keywords") {
+            self.writer.set_document_keywords(&keywords);
+        }
+    }
+}
+
+/// Header or footer text with its page number field taken out
+#[derive(Default)]
+struct HeaderFooterText {
+    text: String,
+    page_number: Option<PageNumberFormat>,
+    /// Instructions of the complex fields currently open
+    fields: Vec<String>,
+}
+
+impl HeaderFooterText {
+    fn collect(&mut self, element: &Element) {
+        for child in element.elements() {
+            match child.name.as_str() {
+                "w:p" => {
+                    self.collect(child);
+                    self.text.push('\n');
+                }
+                "w:fldChar" => match child.attr("w:fldCharType") {
+                    Some("begin") => self.fields.push(String::new()),
+                    Some("end") => {
+                        if let Some(instruction) = self.fields.pop() {
+                            self.field(&instruction);
+                        }
+                    }
+                    _ => {}
+                },
+                "w:instrText" => {
+                    if let Some(instruction) = self.fields.last_mut() {
+                        instruction.push_str(&child.text());
+                    }
+                }
+                "w:fldSimple" => {
+                    let instruction = child.attr("w:instr").unwrap_or_default();
+                    if !self.field(instruction) {
+                        self.collect(child);
+                    }
+                }
+                // The shown value of a page field is whatever page Word last rendered
+                "w:t" if self.fields.iter().any(|field| is_page_field(field)) => {}
+                "w:t" => self.text.push_str(&child.text()),
+                "w:tab" | "w:ptab" => self.text.push('\t'),
+                "w:del" | "w:pPr" | "w:rPr" => {}
+                _ => self.collect(child),
+            }
+        }
+    }
+
+    /// Record a page number field, returning whether it was one
+    fn field(&mut self, instruction: &str) -> bool {
+        if !is_page_field(instruction) {
+            return false;
+        }
+        self.page_number = Some(if instruction.contains("\\* roman") {
+            PageNumberFormat::RomanLower
+        } else if instruction.contains("\\* ROMAN") || instruction.contains("\\* Roman") {
+            PageNumberFormat::RomanUpper
+        } else if instruction.contains("\\* alphabetic") {
+            PageNumberFormat::AlphaLower
+        } else if instruction.contains("\\* ALPHABETIC") {
+            PageNumberFormat::AlphaUpper
+        } else {
+            PageNumberFormat::Numeric
+        });
+        true
+    }
+}
+
+fn is_page_field(instruction: &str) -> bool {
+    instruction.split_whitespace().next() == Some("PAGE")
+}
+
+fn border_line(border: &Element) -> BorderLineStyle {
+    let line_type = match border.attr("w:val") {
+        None | Some("nil") | Some("none") => return BorderLineStyle::none(),
+        Some("dashed") | Some("dashSmallGap") | Some("dotDash") | Some("dotDotDash") => {
+            BorderLineType::Dashed
+        }
+        Some("dotted") => BorderLineType::Dotted,
+        Some("double") => BorderLineType::Double,
+        Some("thick") => BorderLineType::Thick,
+        _ => BorderLineType::Solid,
+    };
+    // w:sz is in eighths of a point
+    let width = number::<f64>(border, "w:sz").unwrap_or(4.0) / 8.0;
+    let color = border.attr("w:color").and_then(parse_color).unwrap_or(0);
+    border_line_style(line_type, width, color)
+}
+
+/// Page size, margins, columns and numbering from a `w:sectPr`
+fn page_layout(sect_pr: &Element, background: Option<u32>) -> PageLayout {
+    let mut layout = PageLayout {
+        background_color: background,
+        ..PageLayout::default()
+    };
+    let twips = |element: &Element, attribute: &str| {
+        number::<i64>(element, attribute).map(|value| (value.unsigned_abs() * 5) as u32)
+    };
+
+    if let Some(size) = sect_pr.child("w:pgSz") {
+        layout.width = twips(size, "w:w").unwrap_or(layout.width);
+        layout.height = twips(size, "w:h").unwrap_or(layout.height);
+        layout.orientation =
+            if size.attr("w:orient") == Some("landscape") || layout.width > layout.height {
+                PageOrientation::Landscape
+            } else {
+                PageOrientation::Portrait
+            };
+        layout.paper_size = paper_size(layout.width, layout.height);
+    }
+
+    if let Some(margins) = sect_pr.child("w:pgMar") {
+        let current = layout.margins.clone();
+        layout.margins.left = twips(margins, "w:left").unwrap_or(current.left);
+        layout.margins.right = twips(margins, "w:right").unwrap_or(current.right);
+        layout.margins.top = twips(margins, "w:top").unwrap_or(current.top);
+        layout.margins.bottom = twips(margins, "w:bottom").unwrap_or(current.bottom);
+        layout.margins.header = twips(margins, "w:header").unwrap_or(current.header);
+        layout.margins.footer = twips(margins, "w:footer").unwrap_or(current.footer);
+        layout.margins.gutter = twips(margins, "w:gutter").unwrap_or(current.gutter);
+    }
+
+    if let Some(columns) = sect_pr.child("w:cols") {
+        layout.columns = number(columns, "w:num").unwrap_or(1u16).max(1);
+        layout.column_spacing = twips(columns, "w:space").unwrap_or(layout.column_spacing);
+        layout.column_line = matches!(columns.attr("w:sep"), Some("1") | Some("true") | Some("on"));
+    }
+
+    if let Some(numbering) = sect_pr.child("w:pgNumType") {
+        if let Some(start) = number(numbering, "w:start") {
+            layout.start_page_number = start;
+        }
+        layout.page_number_format = match numbering.attr("w:fmt") {
+            Some("lowerRoman") => PageNumberFormat::RomanLower,
+            Some("upperRoman") => PageNumberFormat::RomanUpper,
+            Some("lowerLetter") => PageNumberFormat::AlphaLower,
+            Some("upperLetter") => PageNumberFormat::AlphaUpper,
+            _ => PageNumberFormat::Numeric,
+        };
+    }
+    layout
+}
@@ -0,0 +1,390 @@
+//! Conversion of documents from other formats
+
+pub mod docx;
+pub mod odt;
+mod xml;
+
+use std::collections::HashMap;
+use std::io::{Read, Seek};
+
+use zip::ZipArchive;
+
+use crate::error::{HwpError, Result};
+use crate::model::footnote::Footnote;
+use crate::model::hyperlink::Hyperlink;
+use crate::model::page_layout::PaperSize;
+use crate::model::para_char_shape::{CharPositionShape, ParaCharShape};
+use crate::model::{CharShape, ParaShape, ParaText, Paragraph};
+use crate::reader::ReaderLimits;
+use crate::writer::style::{BorderLineStyle, BorderLineType, ImageFormat, ImageOptions, ListType};
+use crate::HwpWriter;
+
+/// Read an entry from a package, or `None` if it is absent
+fn read_entry<R: Read + Seek>(
+    archive: &mut ZipArchive<R>,
+    name: &str,
+    limits: &ReaderLimits,
+) -> Result<Option<Vec<u8>>> {
+    let file = match archive.by_name(name) {
+        Ok(file) => file,
+        Err(zip::result::ZipError::FileNotFound) => return Ok(None),
+        Err(e) => return Err(HwpError::Io(std::io::Error::other(e))),
+    };
+    limits.check_zip_entry_size(file.size())?;
+
+    // The declared size can lie, so bound the actual inflated output as well
+    let mut contents = Vec::new();
+    file.take(limits.max_zip_entry_size.saturating_add(1))
+        .read_to_end(&mut contents)
+        .map_err(HwpError::Io)?;
+    limits.check_zip_entry_size(contents.len() as u64)?;
+
+    Ok(Some(contents))
+}
+
+/// Read and parse an XML entry, or `None` if it is absent
+fn read_xml<R: Read + Seek>(
+    archive: &mut ZipArchive<R>,
+    name: &str,
+    limits: &ReaderLimits,
+) -> Result<Option<xml::Element>> {
+    let Some(contents) = read_entry(archive, name, limits)? else {
+        return Ok(None);
+    };
+    let text = String::from_utf8(contents)
+        .map_err(|e| HwpError::EncodingError(format!("{} is not valid UTF-8: {}", name, e)))?;
+    xml::parse(&text, limits).map(Some).map_err(|e| match e {
+        HwpError::ParseError(message) => {
+            HwpError::ParseError(format!("Failed to parse {}: {}", name, message))
+        }
+        other => other,
+    })
+}
+
+/// Run properties; unset fields inherit from the style below
+#[derive(Debug, Clone, Default)]
+struct RunFormat {
+    latin_font: Option<String>,
+    hangul_font: Option<String>,
+    /// `CharShape::base_size` units, 1/100 pt
+    size: Option<i32>,
+    bold: Option<bool>,
+    italic: Option<bool>,
+    underline: Option<bool>,
+    underline_color: Option<u32>,
+    strike: Option<bool>,
+    superscript: Option<bool>,
+    subscript: Option<bool>,
+    color: Option<u32>,
+    shade: Option<u32>,
+}
+
+impl RunFormat {
+    /// Apply `other` on top of this format
+    fn merge(&mut self, other: &RunFormat) {
+        macro_rules! take {
+            ($($field:ident),*) => {
+                $(if other.$field.is_some() {
+                    self.$field = other.$field.clone();
+                })*
+            };
+        }
+        take!(
+            latin_font,
+            hangul_font,
+            size,
+            bold,
+            italic,
+            underline,
+            underline_color,
+            strike,
+            superscript,
+            subscript,
+            color,
+            shade
+        );
+    }
+}
+
+/// Paragraph properties; unset fields inherit from the style below
+#[derive(Debug, Clone, Default)]
+struct ParaFormat {
+    /// Repo alignment order: left, right, center, justify, distribute
+    alignment: Option<u32>,
+    /// Indents and spacing in HWPUNIT
+    left: Option<i32>,
+    right: Option<i32>,
+    first_line: Option<i32>,
+    before: Option<i32>,
+    after: Option<i32>,
+    /// Line spacing value and HWP line spacing type
+    line: Option<(i32, u32)>,
+    outline_level: Option<u32>,
+    /// Source list id and level
+    numbering: Option<(String, u8)>,
+}
+
+impl ParaFormat {
+    fn merge(&mut self, other: &ParaFormat) {
+        macro_rules! take {
+            ($($field:ident),*) => {
+                $(if other.$field.is_some() {
+                    self.$field = other.$field.clone();
+                })*
+            };
+        }
+        take!(
+            alignment,
+            left,
+            right,
+            first_line,
+            before,
+            after,
+            line,
+            outline_level,
+            numbering
+        );
+    }
+}
+
+/// Text, runs, links and notes gathered for one paragraph
+#[derive(Default)]
+struct ParagraphContent {
+    text: String,
+    length: u32,
+    char_positions: Vec<CharPositionShape>,
+    hyperlinks: Vec<Hyperlink>,
+    footnotes: Vec<Footnote>,
+    images: Vec<(Vec<u8>, ImageFormat, ImageOptions)>,
+}
+
+impl ParagraphContent {
+    fn push(&mut self, text: &str, char_shape_id: u16) {
+        if text.is_empty() {
+            return;
+        }
+        if self.char_positions.last().map(|p| p.char_shape_id) != Some(char_shape_id) {
+            self.char_positions.push(CharPositionShape {
+                position: self.length,
+                char_shape_id,
+            });
+        }
+        self.text.push_str(text);
+        self.length += text.chars().count() as u32;
+    }
+}
+
+/// Add gathered paragraph content, followed by its images
+fn write_paragraph(
+    writer: &mut HwpWriter,
+    shapes: &mut Shapes,
+    style_id: u8,
+    run: &RunFormat,
+    para: &ParaFormat,
+    mut content: ParagraphContent,
+) -> Result<()> {
+    // A paragraph holding only images is replaced by the image paragraphs
+    if !content.text.is_empty() || content.images.is_empty() {
+        let para_shape_id = shapes.para_shape(writer, para)?;
+        if content.char_positions.is_empty() {
+            let char_shape_id = shapes.char_shape(writer, run)?;
+            content.char_positions.push(CharPositionShape {
+                position: 0,
+                char_shape_id,
+            });
+        }
+        let instance_id = writer.next_instance_id();
+        writer.add_formatted_paragraph(Paragraph {
+            text: Some(ParaText {
+                content: content.text,
+            }),
+            para_shape_id,
+            style_id,
+            char_shape_count: content.char_positions.len() as u16,
+            char_shapes: Some(ParaCharShape {
+                char_positions: content.char_positions,
+            }),
+            instance_id,
+            hyperlinks: content.hyperlinks,
+            footnotes: content.footnotes,
+            ..Default::default()
+        })?;
+    }
+    write_images(writer, &content.images)
+}
+
+fn write_images(
+    writer: &mut HwpWriter,
+    images: &[(Vec<u8>, ImageFormat, ImageOptions)],
+) -> Result<()> {
+    for (data, format, options) in images {
+        writer.add_image_with_options(data, *format, options)?;
+    }
+    Ok(())
+}
+
+/// Char and para shapes already added, keyed by their debug representation
+#[derive(Default)]
+struct Shapes {
+    char_shapes: HashMap<String, u16>,
+    para_shapes: HashMap<String, u16>,
+}
+
+impl Shapes {
+    fn char_shape(&mut self, writer: &mut HwpWriter, format: &RunFormat) -> Result<u16> {
+        let mut shape = CharShape::new_default();
+        // Both Word and LibreOffice fall back to 10pt
+        shape.base_size = format.size.unwrap_or(1000);
+
+        let latin = format.latin_font.as_ref().or(format.hangul_font.as_ref());
+        let hangul = format.hangul_font.as_ref().or(format.latin_font.as_ref());
+        if let (Some(latin), Some(hangul)) = (latin, hangul) {
+            let latin = writer.ensure_font(latin)?;
+            let hangul = writer.ensure_font(hangul)?;
+            // Slots: Hangul, Latin, Hanja, Japanese, other, symbol, user
+            shape.face_name_ids = [hangul, latin, hangul, hangul, latin, latin, latin];
+        }
+
+        if format.bold == Some(true) {
+            shape.properties |= 0x1;
+        }
+        if format.italic == Some(true) {
+            shape.properties |= 0x2;
+        }
+        if format.underline == Some(true) {
+            shape.properties |= 1 << 2;
+        }
+        if format.strike == Some(true) {
+            shape.properties |= 1 << 5;
+        }
+        if format.superscript == Some(true) {
+            shape.properties |= 1 << 15;
+        } else if format.subscript == Some(true) {
+            shape.properties |= 1 << 16;
+        }
+        if let Some(color) = format.color {
+            shape.text_color = color;
+        }
+        if let Some(color) = format.underline_color.or(format.color) {
+            shape.underline_color = color;
+        }
+        if let Some(shade) = format.shade {
+            shape.shade_color = shade;
+        }
+
+        let key = format!("{:?}", shape);
+        if let Some(&id) = self.char_shapes.get(&key) {
+            return Ok(id);
+        }
+        let id = writer.add_char_shape(shape)?;
+        self.char_shapes.insert(key, id);
+        Ok(id)
+    }
+
+    fn para_shape(&mut self, writer: &mut HwpWriter, format: &ParaFormat) -> Result<u16> {
+        let mut shape = ParaShape::new_default();
+        shape.properties1 = (shape.properties1 & !0x1C) | (format.alignment.unwrap_or(0) << 2);
+        // Levels past 7 do not fit in the three level bits
+        if let Some(level) = format.outline_level.filter(|level| *level < 7) {
+            shape.properties1 |= (1 << 23) | (level << 25);
+        }
+        shape.left_margin = format.left.unwrap_or(0);
+        shape.right_margin = format.right.unwrap_or(0);
+        shape.indent = format.first_line.unwrap_or(0);
+        shape.top_para_space = format.before.unwrap_or(0);
+        shape.bottom_para_space = format.after.unwrap_or(0);
+        (shape.line_space, shape.line_space_type) = format.line.unwrap_or((100, 0));
+
+        let key = format!("{:?}", shape);
+        if let Some(&id) = self.para_shapes.get(&key) {
+            return Ok(id);
+        }
+        let id = writer.add_para_shape(shape)?;
+        self.para_shapes.insert(key, id);
+        Ok(id)
+    }
+}
+
+/// Open writer lists, outermost first, with the source list id that opened them
+#[derive(Default)]
+struct Lists(Vec<(String, ListType)>);
+
+impl Lists {
+    /// Bring the writer's list nesting in line with a list item
+    fn open(
+        &mut self,
+        writer: &mut HwpWriter,
+        id: &str,
+        level: u8,
+        list_type: ListType,
+    ) -> Result<()> {
+        let lists = &mut self.0;
+        let depth = level as usize + 1;
+        while lists.len() > depth {
+            lists.pop();
+            writer.end_list()?;
+        }
+        if lists.len() == depth {
+            let (open_id, open_type) = &lists[depth - 1];
+            if open_id == id && *open_type == list_type {
+                return Ok(());
+            }
+            lists.pop();
+            writer.end_list()?;
+        }
+        while lists.len() < depth {
+            if lists.is_empty() {
+                writer.start_list(list_type.clone())?;
+            } else {
+                writer.start_nested_list(list_type.clone())?;
+            }
+            lists.push((id.to_string(), list_type.clone()));
+        }
+        Ok(())
+    }
+
+    fn close(&mut self, writer: &mut HwpWriter) -> Result<()> {
+        while self.0.pop().is_some() {
+            writer.end_list()?;
+        }
+        Ok(())
+    }
+}
+
+/// A border line of the given width in points
+fn border_line_style(line_type: BorderLineType, width: f64, color: u32) -> BorderLineStyle {
+    // HWP thickness is an index into 0.1mm .. 5mm
+    const THICKNESS_MM: [f64; 16] = [
+        0.1, 0.12, 0.15, 0.2, 0.25, 0.3, 0.4, 0.5, 0.6, 0.7, 1.0, 1.5, 2.0, 3.0, 4.0, 5.0,
+    ];
+    let mm = width * 25.4 / 72.0;
+    let thickness = THICKNESS_MM
+        .iter()
+        .enumerate()
+        .min_by(|(_, a), (_, b)| (*a - mm).abs().total_cmp(&(*b - mm).abs()))
+        .map(|(index, _)| index as u8)
+        .unwrap_or(1);
+    BorderLineStyle::new(line_type, thickness, color)
+}
+
+/// The standard paper matching a page size in HWPUNIT, in either orientation
+fn paper_size(width: u32, height: u32) -> PaperSize {
+    let (short, long) = (width.min(height), width.max(height));
+    // Source units are coarser than HWPUNIT, so allow a little slack
+    [
+        PaperSize::A4,
+        PaperSize::A3,
+        PaperSize::A5,
+        PaperSize::Letter,
+        PaperSize::Legal,
+        PaperSize::Tabloid,
+        PaperSize::B4,
+        PaperSize::B5,
+    ]
+    .into_iter()
+    .find(|paper| {
+        let (width, height) = paper.dimensions_hwp_units();
+        width.abs_diff(short) <= 100 && height.abs_diff(long) <= 100
+    })
+    .unwrap_or(PaperSize::Custom)
+}
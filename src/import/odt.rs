@@ -0,0 +1,1094 @@
+//! ODT (OpenDocument Text) import
+//!
+//! The document is rebuilt through [`HwpWriter`], so the result is the same
+//! kind of document the writer produces: a single section with plain-text
+//! table cells and list items. Page setup comes from the master page of the
+//! first paragraph.
+
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{BufReader, Read, Seek};
+use std::path::Path;
+
+use zip::ZipArchive;
+
+use super::xml::{Element, Node};
+use super::{
+    border_line_style, paper_size, read_entry, read_xml, write_images, write_paragraph, Lists,
+    ParaFormat, ParagraphContent, RunFormat, Shapes,
+};
+use crate::error::{HwpError, Result};
+use crate::model::footnote::{Footnote, NoteKind};
+use crate::model::header_footer::{HeaderFooterAlignment, PageApplyType, PageNumberFormat};
+use crate::model::hyperlink::Hyperlink;
+use crate::model::page_layout::{PageLayout, PageOrientation};
+use crate::model::style::Style;
+use crate::reader::ReaderLimits;
+use crate::writer::style::{
+    BorderLineStyle, BorderLineType, CellBorderStyle, ImageFormat, ImageOptions, ListType,
+};
+use crate::{HwpDocument, HwpWriter};
+
+/// Longest `style:parent-style-name` chain followed before giving up on a style
+const MAX_STYLE_DEPTH: usize = 32;
+/// Cap on `number-columns-repeated` and `number-rows-repeated`, which
+/// spreadsheet-minded writers set to fill a whole sheet
+const MAX_REPEAT: u32 = 1024;
+
+pub fn from_file<P: AsRef<Path>>(path: P) -> Result<HwpDocument> {
+    from_file_with_limits(path, ReaderLimits::default())
+}
+
+pub fn from_bytes(bytes: &[u8]) -> Result<HwpDocument> {
+    from_bytes_with_limits(bytes, ReaderLimits::default())
+}
+
+/// Read a file with explicit resource limits, e.g. for user uploads
+pub fn from_file_with_limits<P: AsRef<Path>>(path: P, limits: ReaderLimits) -> Result<HwpDocument> {
+    let file = File::open(path).map_err(HwpError::Io)?;
+    import(BufReader::new(file), &limits)
+}
+
+/// Read bytes with explicit resource limits, e.g. for user uploads
+pub fn from_bytes_with_limits(bytes: &[u8], limits: ReaderLimits) -> Result<HwpDocument> {
+    import(std::io::Cursor::new(bytes), &limits)
+}
+
+fn import<R: Read + Seek>(reader: R, limits: &ReaderLimits) -> Result<HwpDocument> {
+    let mut archive = ZipArchive::new(reader)
+        .map_err(|e| HwpError::InvalidFormat(format!("Invalid ODT archive: {}", e)))?;
+    let content = read_xml(&mut archive, "content.xml", limits)?
+        .ok_or_else(|| HwpError::NotFound("content.xml".to_string()))?;
+    let text = content
+        .child("office:body")
+        .and_then(|body| body.child("office:text"))
+        .ok_or_else(|| HwpError::InvalidFormat("content.xml has no text body".to_string()))?;
+    let styles = read_xml(&mut archive, "styles.xml", limits)?;
+
+    let mut importer = OdtImporter {
+        archive,
+        limits,
+        writer: HwpWriter::new(),
+        fonts: HashMap::new(),
+        styles: HashMap::new(),
+        list_styles: HashMap::new(),
+        page_layouts: HashMap::new(),
+        master_pages: Vec::new(),
+        default_run: RunFormat::default(),
+        default_para: ParaFormat::default(),
+        style_ids: HashMap::new(),
+        note_counts: [0; 2],
+        shapes: Shapes::default(),
+        lists: Lists::default(),
+    };
+    if let Some(styles) = &styles {
+        importer.read_styles(styles);
+    }
+    importer.read_styles(&content);
+
+    // Setting the layout replaces the page definition, so it goes before headers
+    if let Some(master) = importer.master_page(text).cloned() {
+        let layout = master
+            .attr("style:page-layout-name")
+            .and_then(|name| importer.page_layouts.get(name))
+            .map(|layout| page_layout(layout, importer.start_page_number(text)));
+        if let Some(layout) = layout {
+            importer.writer.set_page_layout(layout)?;
+        }
+        importer.headers_footers(&master)?;
+    }
+
+    importer.blocks(text)?;
+    importer.lists.close(&mut importer.writer)?;
+
+    if let Some(meta) = read_xml(&mut importer.archive, "meta.xml", limits)? {
+        if let Some(meta) = meta.child("office:meta") {
+            importer.meta(meta);
+        }
+    }
+    Ok(importer.writer.document().clone())
+}
+
+/// Paragraph text with ODF white-space collapsing applied
+fn paragraph_text(element: &Element, text: &mut String, after_space: &mut bool) {
+    for node in &element.children {
+        match node {
+            Node::Text(content) => push_collapsed(content, text, after_space),
+            Node::Element(child) => match child.name.as_str() {
+                "text:s" => {
+                    let count = number(child, "text:c").unwrap_or(1usize);
+                    text.push_str(&" ".repeat(count.min(MAX_REPEAT as usize)));
+                    *after_space = false;
+                }
+                "text:tab" => {
+                    text.push('\t');
+                    *after_space = false;
+                }
+                "text:line-break" => {
+                    text.push('\n');
+                    *after_space = true;
+                }
+                "text:note" | "office:annotation" | "text:tracked-changes" => {}
+                _ => paragraph_text(child, text, after_space),
+            },
+        }
+    }
+}
+
+/// Text of every paragraph below an element, one line per paragraph
+fn plain_text(element: &Element) -> String {
+    fn collect(element: &Element, paragraphs: &mut Vec<String>) {
+        for child in element.elements() {
+            match child.name.as_str() {
+                "text:p" | "text:h" => {
+                    let mut text = String::new();
+                    paragraph_text(child, &mut text, &mut true);
+                    paragraphs.push(text);
+                }
+                _ => collect(child, paragraphs),
+            }
+        }
+    }
+    let mut paragraphs = Vec::new();
+    collect(element, &mut paragraphs);
+    paragraphs.join("\n")
+}
+
+/// Append text, collapsing runs of white space into one space
+fn push_collapsed(content: &str, text: &mut String, after_space: &mut bool) {
+    for ch in content.chars() {
+        if matches!(ch, ' ' | '\t' | '\n' | '\r') {
+            if !*after_space {
+                text.push(' ');
+                *after_space = true;
+            }
+        } else {
+            text.push(ch);
+            *after_space = false;
+        }
+    }
+}
+
+fn number<T: std::str::FromStr>(element: &Element, attribute: &str) -> Option<T> {
+    element.attr(attribute)?.trim().parse().ok()
+}
+
+/// A length such as `2cm` or `12pt`, in points
+fn points(value: &str) -> Option<f64> {
+    let value = value.trim();
+    let split = value
+        .find(|c: char| c.is_ascii_alphabetic() || c == '%')
+        .unwrap_or(value.len());
+    let (number, unit) = value.split_at(split);
+    let number: f64 = number.trim().parse().ok()?;
+    let scale = match unit {
+        "pt" => 1.0,
+        "cm" => 72.0 / 2.54,
+        "mm" => 72.0 / 25.4,
+        "in" | "inch" => 72.0,
+        "pc" => 12.0,
+        "px" => 0.75,
+        _ => return None,
+    };
+    Some(number * scale)
+}
+
+/// A length attribute in HWPUNIT
+fn length(element: &Element, attribute: &str) -> Option<i32> {
+    element
+        .attr(attribute)
+        .and_then(points)
+        .map(|pt| (pt * 100.0).round() as i32)
+}
+
+fn parse_color(value: &str) -> Option<u32> {
+    let hex = value.trim().strip_prefix('#')?;
+    if hex.len() != 6 {
+        return None;
+    }
+    u32::from_str_radix(hex, 16).ok()
+}
+
+/// `style:text-properties`, in HWP units
+fn run_format(properties: &Element, fonts: &HashMap<String, String>) -> RunFormat {
+    let font = |attribute: &str| {
+        properties
+            .attr(attribute)
+            .map(|name| fonts.get(name).cloned().unwrap_or_else(|| name.to_string()))
+    };
+    let line_style = |attribute: &str| properties.attr(attribute).map(|style| style != "none");
+    let position = properties
+        .attr("style:text-position")
+        .and_then(|position| position.split_whitespace().next())
+        .map(|offset| match offset {
+            "super" => 1,
+            "sub" => -1,
+            _ => offset
+                .trim_end_matches('%')
+                .parse::<f64>()
+                .map(|offset| offset.signum() as i32)
+                .unwrap_or(0),
+        });
+
+    RunFormat {
+        latin_font: font("style:font-name").or_else(|| {
+            properties
+                .attr("fo:font-family")
+                .map(|family| family.trim_matches(['\'', '"']).to_string())
+        }),
+        hangul_font: font("style:font-name-asian"),
+        size: length(properties, "fo:font-size"),
+        bold: properties.attr("fo:font-weight").map(|weight| {
+            weight == "bold" || weight.parse::<u32>().is_ok_and(|weight| weight >= 600)
+        }),
+        italic: properties
+            .attr("fo:font-style")
+            .map(|style| style == "italic" || style == "oblique"),
+        underline: line_style("style:text-underline-style"),
+        underline_color: properties
+            .attr("style:text-underline-color")
+            .and_then(parse_color),
+        strike: line_style("style:text-line-through-style"),
+        superscript: position.map(|position| position > 0),
+        subscript: position.map(|position| position < 0),
+        color: properties.attr("fo:color").and_then(parse_color),
+        shade: properties.attr("fo:background-color").and_then(parse_color),
+    }
+}
+
+/// `style:paragraph-properties`, in HWP units
+fn para_format(properties: &Element) -> ParaFormat {
+    let justify_last = properties.attr("fo:text-align-last") == Some("justify");
+    let line = match properties.attr("fo:line-height") {
+        Some("normal") => Some((100, 0)),
+        Some(height) if height.ends_with('%') => height
+            .trim_end_matches('%')
+            .parse::<f64>()
+            .ok()
+            .map(|percent| (percent.round() as i32, 0)),
+        Some(_) => length(properties, "fo:line-height").map(|height| (height, 1)),
+        None => length(properties, "style:line-height-at-least").map(|height| (height, 2)),
+    };
+    ParaFormat {
+        alignment: properties.attr("fo:text-align").map(|align| match align {
+            "end" | "right" => 1,
+            "center" => 2,
+            "justify" if justify_last => 4,
+            "justify" => 3,
+            _ => 0,
+        }),
+        left: length(properties, "fo:margin-left"),
+        right: length(properties, "fo:margin-right"),
+        first_line: length(properties, "fo:text-indent"),
+        before: length(properties, "fo:margin-top"),
+        after: length(properties, "fo:margin-bottom"),
+        line,
+        outline_level: None,
+        numbering: None,
+    }
+}
+
+/// A border such as `0.5pt solid #000000`
+fn border_line(value: &str) -> BorderLineStyle {
+    let mut line_type = None;
+    let mut width = 0.5;
+    let mut color = 0;
+    for token in value.split_whitespace() {
+        match token {
+            "none" | "hidden" => return BorderLineStyle::none(),
+            "double" => line_type = Some(BorderLineType::Double),
+            "dotted" => line_type = Some(BorderLineType::Dotted),
+            "dashed" => line_type = Some(BorderLineType::Dashed),
+            "solid" | "groove" | "ridge" | "inset" | "outset" => {
+                line_type = Some(BorderLineType::Solid)
+            }
+            "thin" => width = 0.5,
+            "medium" => width = 1.0,
+            "thick" => width = 2.5,
+            _ => {
+                if let Some(value) = parse_color(token) {
+                    color = value;
+                } else if let Some(value) = points(token) {
+                    width = value;
+                }
+            }
+        }
+    }
+    match line_type {
+        Some(line_type) => border_line_style(line_type, width, color),
+        None => BorderLineStyle::none(),
+    }
+}
+
+/// Page size, margins, columns and numbering from a `style:page-layout`
+fn page_layout(page_layout: &Element, start_page_number: Option<u16>) -> PageLayout {
+    let mut layout = PageLayout::default();
+    if let Some(start) = start_page_number {
+        layout.start_page_number = start;
+    }
+    let unsigned = |element: &Element, attribute: &str| {
+        length(element, attribute).map(|value| value.unsigned_abs())
+    };
+
+    if let Some(properties) = page_layout.child("style:page-layout-properties") {
+        layout.width = unsigned(properties, "fo:page-width").unwrap_or(layout.width);
+        layout.height = unsigned(properties, "fo:page-height").unwrap_or(layout.height);
+        layout.orientation = if properties.attr("style:print-orientation") == Some("landscape")
+            || layout.width > layout.height
+        {
+            PageOrientation::Landscape
+        } else {
+            PageOrientation::Portrait
+        };
+        layout.paper_size = paper_size(layout.width, layout.height);
+
+        let current = layout.margins.clone();
+        layout.margins.left = unsigned(properties, "fo:margin-left").unwrap_or(current.left);
+        layout.margins.right = unsigned(properties, "fo:margin-right").unwrap_or(current.right);
+        layout.margins.top = unsigned(properties, "fo:margin-top").unwrap_or(current.top);
+        layout.margins.bottom = unsigned(properties, "fo:margin-bottom").unwrap_or(current.bottom);
+        layout.background_color = properties.attr("fo:background-color").and_then(parse_color);
+
+        if let Some(columns) = properties.child("style:columns") {
+            layout.columns = number(columns, "fo:column-count").unwrap_or(1u16).max(1);
+            layout.column_spacing =
+                unsigned(columns, "fo:column-gap").unwrap_or(layout.column_spacing);
+            layout.column_line = columns.child("style:column-sep").is_some();
+        }
+        layout.page_number_format = match properties.attr("style:num-format") {
+            Some("i") => PageNumberFormat::RomanLower,
+            Some("I") => PageNumberFormat::RomanUpper,
+            Some("a") => PageNumberFormat::AlphaLower,
+            Some("A") => PageNumberFormat::AlphaUpper,
+            _ => PageNumberFormat::Numeric,
+        };
+    }
+
+    let area_height = |tag: &str| {
+        page_layout
+            .child(tag)
+            .and_then(|style| style.child("style:header-footer-properties"))
+            .and_then(|properties| {
+                unsigned(properties, "fo:min-height").or_else(|| unsigned(properties, "svg:height"))
+            })
+    };
+    if let Some(header) = area_height("style:header-style") {
+        layout.margins.header = header;
+    }
+    if let Some(footer) = area_height("style:footer-style") {
+        layout.margins.footer = footer;
+    }
+    layout
+}
+
+fn list_type(level_style: &Element) -> Option<ListType> {
+    match level_style.name.as_str() {
+        "text:list-level-style-bullet" | "text:list-level-style-image" => Some(ListType::Bullet),
+        "text:list-level-style-number" => Some(match level_style.attr("style:num-format") {
+            None | Some("") => return None,
+            Some("a") | Some("A") => ListType::Alphabetic,
+            Some("i") | Some("I") => ListType::Roman,
+            Some("가") | Some("ㄱ") | Some("일") | Some("一") => ListType::Korean,
+            _ => ListType::Numbered,
+        }),
+        _ => None,
+    }
+}
+
+struct StyleDefinition {
+    display_name: String,
+    parent: Option<String>,
+    /// Common styles from `office:styles`; automatic styles only carry formatting
+    named: bool,
+    run: RunFormat,
+    para: ParaFormat,
+    master_page: Option<String>,
+    /// `style:page-number` of the paragraph that starts a page sequence
+    page_number: Option<u16>,
+    /// `style:table-cell-properties`
+    cell: Option<Element>,
+}
+
+struct OdtImporter<'a, R> {
+    archive: ZipArchive<R>,
+    limits: &'a ReaderLimits,
+    writer: HwpWriter,
+    /// Font family of each `style:font-face`
+    fonts: HashMap<String, String>,
+    /// Styles by family and name
+    styles: HashMap<(String, String), StyleDefinition>,
+    /// List type of each level of every `text:list-style`, indexed from 0
+    list_styles: HashMap<String, Vec<Option<ListType>>>,
+    page_layouts: HashMap<String, Element>,
+    /// `style:master-page` elements in document order
+    master_pages: Vec<Element>,
+    default_run: RunFormat,
+    default_para: ParaFormat,
+    /// HWP style index of each common paragraph style used so far
+    style_ids: HashMap<String, u8>,
+    /// Footnotes and endnotes numbered so far
+    note_counts: [u16; 2],
+    shapes: Shapes,
+    lists: Lists,
+}
+
+impl<R: Read + Seek> OdtImporter<'_, R> {
+    /// Fonts, styles, list styles, page layouts and master pages of a part
+    fn read_styles(&mut self, root: &Element) {
+        if let Some(decls) = root.child("office:font-face-decls") {
+            for face in decls.children_named("style:font-face") {
+                if let Some(name) = face.attr("style:name") {
+                    let family = face
+                        .attr("svg:font-family")
+                        .unwrap_or(name)
+                        .trim_matches(['\'', '"']);
+                    self.fonts.insert(name.to_string(), family.to_string());
+                }
+            }
+        }
+
+        for (container, named) in [("office:styles", true), ("office:automatic-styles", false)] {
+            let Some(container) = root.child(container) else {
+                continue;
+            };
+            for child in container.elements() {
+                match child.name.as_str() {
+                    "style:default-style" if child.attr("style:family") == Some("paragraph") => {
+                        if let Some(properties) = child.child("style:text-properties") {
+                            self.default_run = run_format(properties, &self.fonts);
+                        }
+                        if let Some(properties) = child.child("style:paragraph-properties") {
+                            self.default_para = para_format(properties);
+                        }
+                    }
+                    "style:style" => self.read_style(child, named),
+                    "text:list-style" => {
+                        let Some(name) = child.attr("style:name") else {
+                            continue;
+                        };
+                        let mut levels = Vec::new();
+                        for level_style in child.elements() {
+                            let level = number(level_style, "text:level").unwrap_or(1usize);
+                            if (1..=10).contains(&level) {
+                                if levels.len() < level {
+                                    levels.resize(level, None);
+                                }
+                                levels[level - 1] = list_type(level_style);
+                            }
+                        }
+                        self.list_styles.insert(name.to_string(), levels);
+                    }
+                    "style:page-layout" => {
+                        if let Some(name) = child.attr("style:name") {
+                            self.page_layouts.insert(name.to_string(), child.clone());
+                        }
+                    }
+                    _ => {}
+                }
+            }
+        }
+
+        if let Some(masters) = root.child("office:master-styles") {
+            self.master_pages
+                .extend(masters.children_named("style:master-page").cloned());
+        }
+    }
+
+    fn read_style(&mut self, style: &Element, named: bool) {
+        let (Some(name), Some(family)) = (style.attr("style:name"), style.attr("style:family"))
+        else {
+            return;
+        };
+        let paragraph_properties = style.child("style:paragraph-properties");
+        let definition = StyleDefinition {
+            display_name: style.attr("style:display-name").unwrap_or(name).to_string(),
+            parent: style.attr("style:parent-style-name").map(str::to_string),
+            named,
+            run: style
+                .child("style:text-properties")
+                .map(|properties| run_format(properties, &self.fonts))
+                .unwrap_or_default(),
+            para: paragraph_properties.map(para_format).unwrap_or_default(),
+            master_page: style
+                .attr("style:master-page-name")
+                .filter(|name| !name.is_empty())
+                .map(str::to_string),
+            page_number: paragraph_properties
+                .or_else(|| style.child("style:table-properties"))
+                .and_then(|properties| number(properties, "style:page-number")),
+            cell: style.child("style:table-cell-properties").cloned(),
+        };
+        self.styles
+            .insert((family.to_string(), name.to_string()), definition);
+    }
+
+    /// A style followed by its parents
+    fn style_chain(&self, family: &str, name: &str) -> Vec<&StyleDefinition> {
+        let mut chain: Vec<&StyleDefinition> = Vec::new();
+        let mut next = Some(name);
+        while let Some(name) = next {
+            let Some(definition) = self.styles.get(&(family.to_string(), name.to_string())) else {
+                break;
+            };
+            if chain.len() >= MAX_STYLE_DEPTH || chain.iter().any(|d| std::ptr::eq(*d, definition))
+            {
+                break;
+            }
+            chain.push(definition);
+            next = definition.parent.as_deref();
+        }
+        chain
+    }
+
+    /// Run and paragraph formats of a paragraph style, including the default style
+    fn style_formats(&self, name: Option<&str>) -> (RunFormat, ParaFormat) {
+        let mut run = self.default_run.clone();
+        let mut para = self.default_para.clone();
+        for definition in name
+            .map(|name| self.style_chain("paragraph", name))
+            .unwrap_or_default()
+            .iter()
+            .rev()
+        {
+            run.merge(&definition.run);
+            para.merge(&definition.para);
+        }
+        (run, para)
+    }
+
+    /// The nearest common style an automatic paragraph style derives from
+    fn common_style(&self, name: &str) -> Option<String> {
+        let mut next = Some(name);
+        for _ in 0..MAX_STYLE_DEPTH {
+            let name = next?;
+            let definition = self
+                .styles
+                .get(&("paragraph".to_string(), name.to_string()))?;
+            if definition.named {
+                return Some(name.to_string());
+            }
+            next = definition.parent.as_deref();
+        }
+        None
+    }
+
+    /// HWP style index for a paragraph style, adding its common style on first use
+    fn style_id(&mut self, name: Option<&str>) -> Result<u8> {
+        let Some(name) = name.and_then(|name| self.common_style(name)) else {
+            return Ok(0);
+        };
+        if let Some(&index) = self.style_ids.get(&name) {
+            return Ok(index);
+        }
+
+        let display_name = self
+            .styles
+            .get(&("paragraph".to_string(), name.clone()))
+            .map(|style| style.display_name.clone())
+            .unwrap_or_else(|| name.clone());
+        let (run, para) = self.style_formats(Some(&name));
+        let char_shape_id = self.shapes.char_shape(&mut self.writer, &run)?;
+        let para_shape_id = self.shapes.para_shape(&mut self.writer, &para)?;
+        let index = match self.writer.add_style(Style {
+            name: display_name,
+            english_name: name.clone(),
+            properties: 0,
+            next_style_id: 0,
+            lang_id: 0x0412,
+            para_shape_id,
+            char_shape_id,
+        }) {
+            Ok(index) => index,
+            // Past the style limit paragraphs keep their formatting under the default style
+            Err(HwpError::InvalidInput(_)) => 0,
+            Err(e) => return Err(e),
+        };
+        self.style_ids.insert(name, index);
+        Ok(index)
+    }
+
+    /// Master page of the first paragraph or table, falling back to the first one defined
+    fn master_page(&self, text: &Element) -> Option<&Element> {
+        let name = text
+            .elements()
+            .find_map(|child| match child.name.as_str() {
+                "text:p" | "text:h" => Some(("paragraph", child.attr("text:style-name"))),
+                "table:table" => Some(("table", child.attr("table:style-name"))),
+                _ => None,
+            })
+            .and_then(|(family, style)| {
+                self.style_chain(family, style?)
+                    .into_iter()
+                    .find_map(|definition| definition.master_page.clone())
+            });
+        name.and_then(|name| {
+            self.master_pages
+                .iter()
+                .find(|master| master.attr("style:name") == Some(name.as_str()))
+        })
+        .or_else(|| self.master_pages.first())
+    }
+
+    /// Page number the first paragraph restarts counting at
+    fn start_page_number(&self, text: &Element) -> Option<u16> {
+        let first = text
+            .elements()
+            .find(|child| matches!(child.name.as_str(), "text:p" | "text:h"))?;
+        let style = first.attr("text:style-name")?;
+        self.style_chain("paragraph", style)
+            .into_iter()
+            .find_map(|definition| definition.page_number)
+    }
+
+    /// Block-level content of the body, a section or a list item
+    fn blocks(&mut self, parent: &Element) -> Result<()> {
+        for child in parent.elements() {
+            match child.name.as_str() {
+                "text:p" => self.paragraph(child, None)?,
+                "text:h" => {
+                    let level = number(child, "text:outline-level").unwrap_or(1u32);
+                    self.paragraph(child, Some(level.saturating_sub(1)))?
+                }
+                "text:list" => self.list(child, None, 0)?,
+                "table:table" => self.table(child)?,
+                "text:section"
+                | "text:index-body"
+                | "text:table-of-content"
+                | "text:alphabetical-index"
+                | "text:illustration-index" => {
+                    self.lists.close(&mut self.writer)?;
+                    self.blocks(child)?;
+                }
+                _ => {}
+            }
+        }
+        Ok(())
+    }
+
+    fn paragraph(&mut self, p: &Element, outline_level: Option<u32>) -> Result<()> {
+        self.lists.close(&mut self.writer)?;
+        let style = p.attr("text:style-name");
+        let (run, mut para) = self.style_formats(style);
+        if outline_level.is_some() {
+            para.outline_level = outline_level;
+        }
+
+        let mut content = ParagraphContent::default();
+        self.inline(p, &run, &mut content, &mut true)?;
+
+        let style_id = self.style_id(style)?;
+        write_paragraph(
+            &mut self.writer,
+            &mut self.shapes,
+            style_id,
+            &run,
+            &para,
+            content,
+        )
+    }
+
+    /// Items of a list, with nested lists one level deeper
+    fn list(&mut self, list: &Element, inherited: Option<&str>, level: u8) -> Result<()> {
+        let style = list.attr("text:style-name").or(inherited);
+        for item in list.elements() {
+            if !matches!(item.name.as_str(), "text:list-item" | "text:list-header") {
+                continue;
+            }
+            for child in item.elements() {
+                match child.name.as_str() {
+                    "text:p" | "text:h" => {
+                        let list_type = style
+                            .and_then(|style| self.list_styles.get(style))
+                            .and_then(|levels| levels.get(level as usize).cloned())
+                            .unwrap_or(Some(ListType::Bullet));
+                        let Some(list_type) = list_type else {
+                            self.paragraph(child, None)?;
+                            continue;
+                        };
+                        self.lists.open(
+                            &mut self.writer,
+                            style.unwrap_or_default(),
+                            level,
+                            list_type,
+                        )?;
+                        let mut content = ParagraphContent::default();
+                        let (run, _) = self.style_formats(child.attr("text:style-name"));
+                        self.inline(child, &run, &mut content, &mut true)?;
+                        self.writer.add_list_item(&content.text)?;
+                        write_images(&mut self.writer, &content.images)?;
+                    }
+                    "text:list" => self.list(child, style, level.saturating_add(1))?,
+                    "table:table" => self.table(child)?,
+                    _ => {}
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Spans of a paragraph, following links and fields
+    fn inline(
+        &mut self,
+        parent: &Element,
+        format: &RunFormat,
+        content: &mut ParagraphContent,
+        after_space: &mut bool,
+    ) -> Result<()> {
+        for node in &parent.children {
+            let child = match node {
+                Node::Text(text) => {
+                    let mut collapsed = String::new();
+                    push_collapsed(text, &mut collapsed, after_space);
+                    let char_shape_id = self.shapes.char_shape(&mut self.writer, format)?;
+                    content.push(&collapsed, char_shape_id);
+                    continue;
+                }
+                Node::Element(child) => child,
+            };
+            match child.name.as_str() {
+                "text:span" => {
+                    let mut format = format.clone();
+                    if let Some(style) = child.attr("text:style-name") {
+                        for definition in self.style_chain("text", style).iter().rev() {
+                            format.merge(&definition.run);
+                        }
+                    }
+                    self.inline(child, &format, content, after_space)?;
+                }
+                "text:a" => {
+                    let start = content.length;
+                    let text_start = content.text.len();
+                    self.inline(child, format, content, after_space)?;
+                    let display = content.text[text_start..].to_string();
+                    let Some(href) = child.attr("xlink:href").filter(|_| !display.is_empty())
+                    else {
+                        continue;
+                    };
+                    let hyperlink = if let Some(bookmark) = href.strip_prefix('#') {
+                        Hyperlink::new_bookmark(&display, bookmark)
+                    } else if let Some(email) = href.strip_prefix("mailto:") {
+                        Hyperlink::new_email(&display, email)
+                    } else {
+                        Hyperlink::new_url(&display, href)
+                    };
+                    content.hyperlinks.push(
+                        hyperlink
+                            .with_position(start)
+                            .with_length(content.length - start),
+                    );
+                }
+                "text:s" | "text:tab" | "text:line-break" => {
+                    let text = match child.name.as_str() {
+                        "text:s" => {
+                            let count = number(child, "text:c").unwrap_or(1usize);
+                            " ".repeat(count.min(MAX_REPEAT as usize))
+                        }
+                        "text:tab" => "\t".to_string(),
+                        _ => "\n".to_string(),
+                    };
+                    *after_space = child.name == "text:line-break";
+                    let char_shape_id = self.shapes.char_shape(&mut self.writer, format)?;
+                    content.push(&text, char_shape_id);
+                }
+                "text:note" => self.note(child, content),
+                "draw:frame" => self.image(child, content)?,
+                "draw:a" => {
+                    for frame in child.children_named("draw:frame") {
+                        self.image(frame, content)?;
+                    }
+                }
+                "office:annotation"
+                | "office:annotation-end"
+                | "text:tracked-changes"
+                | "text:soft-page-break"
+                | "text:bookmark"
+                | "text:bookmark-start"
+                | "text:bookmark-end"
+                | "text:reference-mark"
+                | "text:reference-mark-start"
+                | "text:reference-mark-end" => {}
+                // Fields and other wrappers show their content
+                _ => self.inline(child, format, content, after_space)?,
+            }
+        }
+        Ok(())
+    }
+
+    fn note(&mut self, note: &Element, content: &mut ParagraphContent) {
+        let kind = match note.attr("text:note-class") {
+            Some("endnote") => NoteKind::Endnote,
+            _ => NoteKind::Footnote,
+        };
+        let text = note
+            .child("text:note-body")
+            .map(plain_text)
+            .unwrap_or_default();
+        let count = &mut self.note_counts[kind as usize];
+        *count += 1;
+        content
+            .footnotes
+            .push(Footnote::new(kind, *count, content.length, &text));
+    }
+
+    /// Queue an embedded image; linked images and formats HWP cannot hold are skipped
+    fn image(&mut self, frame: &Element, content: &mut ParagraphContent) -> Result<()> {
+        let Some(href) = frame
+            .child("draw:image")
+            .and_then(|image| image.attr("xlink:href"))
+            .filter(|href| !href.contains("://"))
+        else {
+            return Ok(());
+        };
+        let path = href.trim_start_matches("./");
+        let Some(data) = read_entry(&mut self.archive, path, self.limits)? else {
+            return Ok(());
+        };
+        let Some(format) = ImageFormat::from_bytes(&data) else {
+            return Ok(());
+        };
+
+        let mut options = ImageOptions::new();
+        let size = (length(frame, "svg:width"), length(frame, "svg:height"));
+        if let (Some(width), Some(height)) = size {
+            if width > 0 && height > 0 {
+                // HWPUNIT to millimetres
+                let mm = |value: i32| ((value as f64 * 25.4 / 7200.0).round() as u32).max(1);
+                options = options.width(mm(width)).height(mm(height));
+            }
+        }
+        content.images.push((data, format, options));
+        Ok(())
+    }
+
+    fn table(&mut self, table: &Element) -> Result<()> {
+        struct Cell {
+            row: u32,
+            col: u32,
+            row_span: u16,
+            col_span: u16,
+            text: String,
+            style: Option<String>,
+        }
+
+        /// Rows with repeats spelled out, stopping at the table limits
+        fn collect_rows<'e>(
+            element: &'e Element,
+            limits: &ReaderLimits,
+            rows: &mut Vec<&'e Element>,
+        ) -> Result<()> {
+            for child in element.elements() {
+                match child.name.as_str() {
+                    "table:table-row" => {
+                        let repeat = number(child, "table:number-rows-repeated")
+                            .unwrap_or(1u32)
+                            .clamp(1, MAX_REPEAT);
+                        limits.check_table(rows.len() as u64 + repeat as u64, 0)?;
+                        rows.extend(std::iter::repeat_n(child, repeat as usize));
+                    }
+                    "table:table-header-rows" | "table:table-rows" | "table:table-row-group" => {
+                        collect_rows(child, limits, rows)?
+                    }
+                    _ => {}
+                }
+            }
+            Ok(())
+        }
+
+        self.lists.close(&mut self.writer)?;
+
+        let mut rows = Vec::new();
+        collect_rows(table, self.limits, &mut rows)?;
+        let row_count = rows.len() as u64;
+        let declared_cols: u64 = table
+            .elements()
+            .flat_map(|child| match child.name.as_str() {
+                "table:table-column" => vec![child],
+                "table:table-columns"
+                | "table:table-header-columns"
+                | "table:table-column-group" => {
+                    child.children_named("table:table-column").collect()
+                }
+                _ => Vec::new(),
+            })
+            .map(|column| {
+                number(column, "table:number-columns-repeated")
+                    .unwrap_or(1u32)
+                    .clamp(1, MAX_REPEAT) as u64
+            })
+            .sum();
+        // Each repeat is capped, but their totals are checked as the grid grows
+        self.limits.check_table(row_count, declared_cols)?;
+        let mut cols = declared_cols as u32;
+
+        let mut cells = Vec::new();
+        for (row, tr) in rows.iter().enumerate() {
+            let mut col = 0u32;
+            for tc in tr.elements() {
+                let repeat = number(tc, "table:number-columns-repeated")
+                    .unwrap_or(1u32)
+                    .clamp(1, MAX_REPEAT);
+                if matches!(
+                    tc.name.as_str(),
+                    "table:table-cell" | "table:covered-table-cell"
+                ) {
+                    self.limits
+                        .check_table(row_count, col as u64 + repeat as u64)?;
+                }
+                match tc.name.as_str() {
+                    "table:table-cell" => {
+                        let text = plain_text(tc);
+                        for _ in 0..repeat {
+                            cells.push(Cell {
+                                row: row as u32,
+                                col,
+                                row_span: number(tc, "table:number-rows-spanned")
+                                    .unwrap_or(1u16)
+                                    .max(1),
+                                col_span: number(tc, "table:number-columns-spanned")
+                                    .unwrap_or(1u16)
+                                    .max(1),
+                                text: text.clone(),
+                                style: tc.attr("table:style-name").map(str::to_string),
+                            });
+                            col += 1;
+                        }
+                    }
+                    "table:covered-table-cell" => col += repeat,
+                    _ => {}
+                }
+            }
+            cols = cols.max(col);
+        }
+        if rows.is_empty() || cols == 0 {
+            return Ok(());
+        }
+        let row_count = row_count as u32;
+
+        let borders: Vec<Option<CellBorderStyle>> = cells
+            .iter()
+            .map(|cell| {
+                let properties = self
+                    .style_chain("table-cell", cell.style.as_deref()?)
+                    .into_iter()
+                    .find_map(|definition| definition.cell.as_ref())?;
+                let side = |name: &str| {
+                    properties
+                        .attr(name)
+                        .or_else(|| properties.attr("fo:border"))
+                        .map(border_line)
+                        .unwrap_or_else(BorderLineStyle::none)
+                };
+                Some(CellBorderStyle {
+                    left: side("fo:border-left"),
+                    right: side("fo:border-right"),
+                    top: side("fo:border-top"),
+                    bottom: side("fo:border-bottom"),
+                })
+            })
+            .collect();
+
+        let mut builder = self.writer.add_table(row_count, cols);
+        for (cell, border) in cells.iter().zip(borders) {
+            builder = builder.set_cell(cell.row, cell.col, &cell.text);
+            if cell.row_span > 1 || cell.col_span > 1 {
+                builder = builder.merge_cells(cell.row, cell.col, cell.row_span, cell.col_span);
+            }
+            if let Some(border) = border {
+                builder = builder.set_cell_border(cell.row, cell.col, border);
+            }
+        }
+        builder.finish()
+    }
+
+    fn headers_footers(&mut self, master: &Element) -> Result<()> {
+        for area in master.elements() {
+            let (is_header, apply) = match area.name.as_str() {
+                "style:header" => (true, PageApplyType::All),
+                "style:header-left" => (true, PageApplyType::EvenPages),
+                "style:header-first" => (true, PageApplyType::FirstPage),
+                "style:footer" => (false, PageApplyType::All),
+                "style:footer-left" => (false, PageApplyType::EvenPages),
+                "style:footer-first" => (false, PageApplyType::FirstPage),
+                _ => continue,
+            };
+            if area.attr("style:display") == Some("false") {
+                continue;
+            }
+
+            let alignment = area
+                .find("text:p")
+                .and_then(|p| p.attr("text:style-name"))
+                .map(|style| self.style_formats(Some(style)).1)
+                .and_then(|para| para.alignment)
+                .map(|alignment| match alignment {
+                    1 => HeaderFooterAlignment::Right,
+                    2 => HeaderFooterAlignment::Center,
+                    _ => HeaderFooterAlignment::Left,
+                })
+                .unwrap_or(HeaderFooterAlignment::Left);
+
+            let page_number =
+                area.find("text:page-number")
+                    .map(|field| match field.attr("style:num-format") {
+                        Some("i") => PageNumberFormat::RomanLower,
+                        Some("I") => PageNumberFormat::RomanUpper,
+                        Some("a") => PageNumberFormat::AlphaLower,
+                        Some("A") => PageNumberFormat::AlphaUpper,
+                        _ => PageNumberFormat::Numeric,
+                    });
+            // The shown value of a page number field is whatever page was last rendered
+            let mut area = area.clone();
+            remove_elements(&mut area, "text:page-number");
+            let text = plain_text(&area);
+            let content = text.trim_end_matches('\n');
+
+            match (is_header, page_number) {
+                (true, Some(format)) => self.writer.add_header_with_page_number(content, format),
+                (false, Some(format)) => self.writer.add_footer_with_page_number(content, format),
+                (true, None) if !content.is_empty() => self
+                    .writer
+                    .add_header_with_options(content, apply, alignment),
+                (false, None) if !content.is_empty() => self
+                    .writer
+                    .add_footer_with_options(content, apply, alignment),
+                _ => {}
+            }
+        }
+        Ok(())
+    }
+
+    fn meta(&mut self, meta: &Element) {
+        let property = |name: &str| {
+            let values: Vec<String> = meta
+                .children_named(name)
+                .map(|element| element.text().trim().to_string())
+                .filter(|text| !text.is_empty())
+                .collect();
+            (!values.is_empty()).then(|| values.join(", "))
+        };
+        if let Some(title) = property("dc:title") {
+            self.writer.set_document_title(&title);
+        }
+        if let Some(author) = property("meta:initial-creator").or_else(|| property("dc:creator")) {
+            self.writer.set_document_author(&author);
+        }
+        if let Some(subject) = property("dc:subject") {
+            self.writer.set_document_subject(&subject);
+        }
+        if let Some(keywords) = property("meta:keyword") {
+            self.writer.set_document_keywords(&keywords);
+        }
+    }
+}
+
+fn remove_elements(element: &mut Element, name: &str) {
+    element.children.retain(|node| match node {
+        Node::Element(child) => child.name != name,
+        Node::Text(_) => true,
+    });
+    for node in &mut element.children {
+        if let Node::Element(child) = node {
+            remove_elements(child, name);
+        }
+    }
+}
@@ -0,0 +1,143 @@
+//! A small element tree for reading package parts
+//!
+//! Names are kept as written (`w:p`, `text:span`); the prefixes used by
+//! word processors are fixed in practice, so no namespace resolution is done.
+
+use quick_xml::events::{BytesStart, Event};
+use quick_xml::Reader;
+
+use crate::error::{HwpError, Result};
+use crate::reader::ReaderLimits;
+
+#[derive(Debug, Clone, Default)]
+pub(crate) struct Element {
+    pub name: String,
+    pub attributes: Vec<(String, String)>,
+    pub children: Vec<Node>,
+}
+
+#[derive(Debug, Clone)]
+pub(crate) enum Node {
+    Element(Element),
+    Text(String),
+}
+
+impl Element {
+    pub fn attr(&self, name: &str) -> Option<&str> {
+        self.attributes
+            .iter()
+            .find(|(key, _)| key == name)
+            .map(|(_, value)| value.as_str())
+    }
+
+    /// Child elements, skipping text
+    pub fn elements(&self) -> impl Iterator<Item = &Element> {
+        self.children.iter().filter_map(|node| match node {
+            Node::Element(element) => Some(element),
+            Node::Text(_) => None,
+        })
+    }
+
+    pub fn child(&self, name: &str) -> Option<&Element> {
+        self.elements().find(|element| element.name == name)
+    }
+
+    pub fn children_named<'a>(&'a self, name: &'a str) -> impl Iterator<Item = &'a Element> {
+        self.elements().filter(move |element| element.name == name)
+    }
+
+    /// First descendant with the given name, depth first
+    pub fn find(&self, name: &str) -> Option<&Element> {
+        self.elements().find_map(|element| {
+            (element.name == name)
+                .then_some(element)
+                .or_else(|| element.find(name))
+        })
+    }
+
+    /// All text content, concatenated in document order
+    pub fn text(&self) -> String {
+        let mut text = String::new();
+        self.collect_text(&mut text);
+        text
+    }
+
+    fn collect_text(&self, text: &mut String) {
+        for node in &self.children {
+            match node {
+                Node::Element(element) => element.collect_text(text),
+                Node::Text(content) => text.push_str(content),
+            }
+        }
+    }
+}
+
+/// Parse a document into its root element
+pub(crate) fn parse(xml: &str, limits: &ReaderLimits) -> Result<Element> {
+    let mut reader = Reader::from_str(xml);
+    let mut stack: Vec<Element> = Vec::new();
+
+    loop {
+        let event = reader
+            .read_event()
+            .map_err(|e| HwpError::ParseError(e.to_string()))?;
+        match event {
+            Event::Start(start) => {
+                limits.check_nesting_depth(stack.len() as u32 + 1)?;
+                stack.push(element(&start)?);
+            }
+            Event::Empty(start) => {
+                let element = element(&start)?;
+                match stack.last_mut() {
+                    Some(parent) => parent.children.push(Node::Element(element)),
+                    None => return Ok(element),
+                }
+            }
+            Event::End(_) => {
+                let element = stack
+                    .pop()
+                    .ok_or_else(|| HwpError::ParseError("unbalanced end tag".to_string()))?;
+                match stack.last_mut() {
+                    Some(parent) => parent.children.push(Node::Element(element)),
+                    None => return Ok(element),
+                }
+            }
+            Event::Text(text) => {
+                if let Some(parent) = stack.last_mut() {
+                    let text = text
+                        .unescape()
+                        .map_err(|e| HwpError::ParseError(e.to_string()))?;
+                    parent.children.push(Node::Text(text.into_owned()));
+                }
+            }
+            Event::CData(data) => {
+                if let Some(parent) = stack.last_mut() {
+                    let text = String::from_utf8_lossy(&data).into_owned();
+                    parent.children.push(Node::Text(text));
+                }
+            }
+            Event::Eof => {
+                return Err(HwpError::ParseError("no root element".to_string()));
+            }
+            _ => {}
+        }
+    }
+}
+
+fn element(start: &BytesStart) -> Result<Element> {
+    let name = String::from_utf8_lossy(start.name().as_ref()).into_owned();
+    let mut attributes = Vec::new();
+    for attribute in start.attributes() {
+        let attribute = attribute.map_err(|e| HwpError::ParseError(e.to_string()))?;
+        let key = String::from_utf8_lossy(attribute.key.as_ref()).into_owned();
+        let value = attribute
+            .unescape_value()
+            .map_err(|e| HwpError::ParseError(e.to_string()))?;
+        attributes.push((key, value.into_owned()));
+    }
+    Ok(Element {
+        name,
+        attributes,
+        children: Vec::new(),
+    })
+}
@@ -1,24 +1,29 @@
 pub mod crypto;
 pub mod error;
+pub mod export;
 pub mod hwpx;
+pub mod import;
 pub mod model;
 pub mod parser;
 pub mod preview;
 pub mod reader;
 pub mod render;
+pub mod security;
 pub mod utils;
 pub mod writer;
 
 use std::io::{Read, Seek};
 use std::path::Path;
 
-pub use crate::crypto::decrypt_distribution_stream;
+use crate::crypto::PasswordCipher;
+pub use crate::crypto::{decrypt_distribution_stream, DistributionOptions};
 pub use crate::error::{HwpError, Result};
 pub use crate::hwpx::{HwpxReader, HwpxWriter};
-pub use crate::model::HwpDocument;
+pub use crate::model::{DocumentScripts, HwpDocument};
 use crate::parser::{body_text::BodyTextParser, doc_info::DocInfoParser, header::FileHeader};
 pub use crate::preview::{PreviewImage, PreviewText, SummaryInfo};
 use crate::reader::CfbReader;
+pub use crate::reader::ReaderLimits;
 pub use crate::writer::style;
 pub use crate::writer::HwpWriter;
 
@@ -27,38 +32,73 @@ pub struct HwpReader;
 impl HwpReader {
     pub fn from_file<P: AsRef<Path>>(path: P) -> Result<HwpDocument> {
         let reader = CfbReader::from_file(path)?;
-        Self::parse_document(reader)
+        Self::parse_document(reader, None)
     }
 
     pub fn from_bytes(bytes: &[u8]) -> Result<HwpDocument> {
         let cursor = std::io::Cursor::new(bytes.to_vec());
         let reader = CfbReader::new(cursor)?;
-        Self::parse_document(reader)
+        Self::parse_document(reader, None)
     }
 
-    fn parse_document<F: Read + Seek>(mut reader: CfbReader<F>) -> Result<HwpDocument> {
+    /// Read a password-protected file written with [`HwpWriter::set_password`]
+    pub fn from_file_with_password<P: AsRef<Path>>(path: P, password: &str) -> Result<HwpDocument> {
+        let reader = CfbReader::from_file(path)?;
+        Self::parse_document(reader, Some(password))
+    }
+
+    /// Read password-protected bytes written with [`HwpWriter::set_password`]
+    pub fn from_bytes_with_password(bytes: &[u8], password: &str) -> Result<HwpDocument> {
+        let cursor = std::io::Cursor::new(bytes.to_vec());
+        let reader = CfbReader::new(cursor)?;
+        Self::parse_document(reader, Some(password))
+    }
+
+    /// Read a file with explicit resource limits, e.g. for user uploads
+    pub fn from_file_with_limits<P: AsRef<Path>>(
+        path: P,
+        limits: ReaderLimits,
+    ) -> Result<HwpDocument> {
+        let reader = CfbReader::from_file(path)?.with_limits(limits);
+        Self::parse_document(reader, None)
+    }
+
+    /// Read bytes with explicit resource limits, e.g. for user uploads
+    pub fn from_bytes_with_limits(bytes: &[u8], limits: ReaderLimits) -> Result<HwpDocument> {
+        let cursor = std::io::Cursor::new(bytes.to_vec());
+        let reader = CfbReader::new(cursor)?.with_limits(limits);
+        Self::parse_document(reader, None)
+    }
+
+    fn parse_document<F: Read + Seek>(
+        mut reader: CfbReader<F>,
+        password: Option<&str>,
+    ) -> Result<HwpDocument> {
         let header_data = reader.read_stream("FileHeader")?;
         let header = FileHeader::parse(header_data)?;
+        let limits = *reader.limits();
 
-        if header.is_encrypted() {
-            return Err(HwpError::UnsupportedVersion(
-                "Password-encrypted documents are not supported".to_string(),
-            ));
-        }
-
-        let distribution_record = if header.is_distribute() {
-            Some(Self::read_distribution_record(
-                &mut reader,
-                header.is_compressed(),
-            )?)
+        let mut doc_info_data = reader.read_stream("DocInfo")?;
+        let cipher = if header.is_encrypted() {
+            let password = password.ok_or_else(|| {
+                HwpError::InvalidInput(
+                    "Document is password-protected; read it with a password".to_string(),
+                )
+            })?;
+            let cipher = PasswordCipher::from_stream(password, &doc_info_data)?;
+            doc_info_data = cipher.decrypt_stream(&doc_info_data)?;
+            Some(cipher)
         } else {
             None
         };
-
-        let doc_info_data = reader.read_stream("DocInfo")?;
-        let doc_info_decrypted =
-            Self::decrypt_stream(doc_info_data, &header, distribution_record.as_deref())?;
-        let doc_info = DocInfoParser::parse(doc_info_decrypted, header.is_compressed())?;
+        let mut doc_info =
+            DocInfoParser::parse_with_limits(doc_info_data, header.is_compressed(), &limits)?;
+        Self::read_bin_data(
+            &mut reader,
+            &mut doc_info.bin_data,
+            header.is_compressed(),
+            cipher.as_ref(),
+        )?;
 
         let mut body_texts = Vec::new();
         let mut section_idx = 0;
@@ -75,10 +115,17 @@ impl HwpReader {
                 break;
             }
 
-            let section_data = reader.read_stream(&section_name)?;
-            let section_decrypted =
-                Self::decrypt_stream(section_data, &header, distribution_record.as_deref())?;
-            let body_text = BodyTextParser::parse(section_decrypted, header.is_compressed())?;
+            let mut section_data = reader.read_stream(&section_name)?;
+            if let Some(cipher) = &cipher {
+                section_data = cipher.decrypt_stream(&section_data)?;
+            }
+            if header.is_distribute() {
+                let (record, decrypted) = Self::decrypt_view_text(&section_data)?;
+                doc_info.distribution_record.get_or_insert(record);
+                section_data = decrypted;
+            }
+            let body_text =
+                BodyTextParser::parse_with_limits(section_data, header.is_compressed(), &limits)?;
             body_texts.push(body_text);
 
             section_idx += 1;
@@ -93,6 +140,7 @@ impl HwpReader {
         let preview_text = Self::read_preview_text(&mut reader).ok();
         let preview_image = Self::read_preview_image(&mut reader).ok();
         let summary_info = Self::read_summary_info(&mut reader).ok();
+        let scripts = Self::read_scripts(&mut reader, header.is_compressed()).ok();
 
         Ok(HwpDocument {
             header,
@@ -101,9 +149,39 @@ impl HwpReader {
             preview_text,
             preview_image,
             summary_info,
+            scripts,
+            password: cipher,
         })
     }
 
+    /// Load the contents of embedded binaries from the BinData storage
+    fn read_bin_data<F: Read + Seek>(
+        reader: &mut CfbReader<F>,
+        bin_data: &mut [crate::model::bin_data::BinData],
+        is_compressed: bool,
+        cipher: Option<&PasswordCipher>,
+    ) -> Result<()> {
+        let limits = *reader.limits();
+        for item in bin_data.iter_mut() {
+            let name = format!("BinData/BIN{:04X}.{}", item.bin_id, item.extension);
+            let data = match reader.read_stream(&name) {
+                Ok(data) => data,
+                Err(e @ HwpError::LimitExceeded { .. }) => return Err(e),
+                Err(_) => continue,
+            };
+            let data = match cipher {
+                Some(cipher) => cipher.decrypt_stream(&data)?,
+                None => data,
+            };
+            item.data = if item.is_stored_compressed(is_compressed) {
+                crate::utils::compression::decompress_stream_with_limits(&data, &limits)?
+            } else {
+                data
+            };
+        }
+        Ok(())
+    }
+
     fn read_preview_text<F: Read + Seek>(reader: &mut CfbReader<F>) -> Result<PreviewText> {
         let data = reader.read_stream("PrvText")?;
         PreviewText::from_bytes(&data)
@@ -119,41 +197,36 @@ impl HwpReader {
         SummaryInfo::from_bytes(&data)
     }
 
-    fn read_distribution_record<F: Read + Seek>(
+    fn read_scripts<F: Read + Seek>(
         reader: &mut CfbReader<F>,
         is_compressed: bool,
-    ) -> Result<Vec<u8>> {
-        let doc_info_data = reader.read_stream("DocInfo")?;
-
-        let decompressed = if is_compressed {
-            crate::utils::decompress(&doc_info_data)?
-        } else {
-            doc_info_data
+    ) -> Result<DocumentScripts> {
+        let limits = *reader.limits();
+        let mut read = |name: &str| -> Result<Vec<u8>> {
+            let data = reader.read_stream(name)?;
+            if is_compressed {
+                crate::utils::compression::decompress_stream_with_limits(&data, &limits)
+            } else {
+                Ok(data)
+            }
         };
 
-        if decompressed.len() < 260 {
+        let script = read("Scripts/DefaultJScript")?;
+        let version = read("Scripts/JScriptVersion").unwrap_or_default();
+        DocumentScripts::from_streams(&version, &script)
+    }
+
+    /// Split a ViewText stream into its distribution record and decrypted payload
+    fn decrypt_view_text(data: &[u8]) -> Result<(Vec<u8>, Vec<u8>)> {
+        if data.len() < 260 {
             return Err(HwpError::ParseError(
-                "DocInfo too short to contain distribution data".to_string(),
+                "ViewText stream too short to contain distribution data".to_string(),
             ));
         }
 
-        Ok(decompressed[..260].to_vec())
-    }
-
-    fn decrypt_stream(
-        data: Vec<u8>,
-        _header: &FileHeader,
-        distribution_record: Option<&[u8]>,
-    ) -> Result<Vec<u8>> {
-        if let Some(dist_record) = distribution_record {
-            if data.len() < 260 {
-                return Ok(data);
-            }
-            let encrypted_data = &data[260..];
-            decrypt_distribution_stream(encrypted_data, dist_record)
-        } else {
-            Ok(data)
-        }
+        let (record, encrypted_data) = data.split_at(260);
+        let decrypted = decrypt_distribution_stream(encrypted_data, record)?;
+        Ok((record[4..].to_vec(), decrypted))
     }
 }
 
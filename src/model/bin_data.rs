@@ -1,5 +1,6 @@
 use crate::error::Result;
 use crate::parser::record::Record;
+use crate::reader::StreamReader;
 
 #[derive(Debug, Clone)]
 pub struct BinData {
@@ -18,11 +19,20 @@ pub enum BinDataType {
     Storage = 2,
 }
 
+/// Compression of a BinData stream
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BinDataCompression {
+    /// Compressed when the document is
+    Default,
+    Compress,
+    NoCompress,
+}
+
 impl BinData {
     pub fn from_record(record: &Record) -> Result<Self> {
         let mut reader = record.data_reader();
 
-        if reader.remaining() < 6 {
+        if reader.remaining() < 2 {
             return Err(crate::error::HwpError::ParseError(format!(
                 "BinData record too small: {} bytes",
                 reader.remaining()
@@ -30,53 +40,32 @@ impl BinData {
         }
 
         let properties = reader.read_u16()?;
-
-        // Read absolute file name
-        let abs_name_len = reader.read_u16()? as usize;
-        let abs_name = if reader.remaining() >= abs_name_len * 2 {
-            reader.read_string(abs_name_len * 2)?
-        } else {
-            "bindata.dat".to_string()
-        };
-
-        // Read relative file name
-        let rel_name_len = reader.read_u16()? as usize;
-        let rel_name = if reader.remaining() >= rel_name_len * 2 {
-            reader.read_string(rel_name_len * 2)?
-        } else {
-            "bindata.dat".to_string()
-        };
-
-        if reader.remaining() < 2 {
-            return Err(crate::error::HwpError::ParseError(
-                "Insufficient data for BinData ID".to_string(),
-            ));
-        }
-
-        let bin_id = reader.read_u16()?;
-
-        // Read extension
-        let extension_len = reader.read_u16()? as usize;
-        let extension = if reader.remaining() >= extension_len * 2 {
-            reader.read_string(extension_len * 2)?
-        } else {
-            "dat".to_string()
+        // Links carry their paths, embedded files an id and an extension,
+        // and OLE storages only an id
+        let mut bin_data = Self {
+            properties,
+            abs_name: String::new(),
+            rel_name: String::new(),
+            bin_id: 0,
+            extension: String::new(),
+            data: Vec::new(),
         };
-
-        // Read remaining data as binary content
-        let mut data = Vec::new();
-        while reader.remaining() > 0 {
-            data.push(reader.read_u8()?);
+        match bin_data.get_type() {
+            BinDataType::Link => {
+                bin_data.abs_name = read_name(&mut reader)?;
+                bin_data.rel_name = read_name(&mut reader)?;
+            }
+            BinDataType::Embedding => {
+                bin_data.bin_id = reader.read_u16()?;
+                bin_data.extension = read_name(&mut reader)?;
+            }
+            BinDataType::Storage => {
+                bin_data.bin_id = reader.read_u16()?;
+                bin_data.extension = "ole".to_string();
+            }
         }
 
-        Ok(Self {
-            properties,
-            abs_name,
-            rel_name,
-            bin_id,
-            extension,
-            data,
-        })
+        Ok(bin_data)
     }
 
     pub fn get_type(&self) -> BinDataType {
@@ -88,6 +77,25 @@ impl BinData {
         }
     }
 
+    /// How the item's stream is stored, from bits 4-5 of the properties
+    pub fn compression(&self) -> BinDataCompression {
+        match (self.properties >> 4) & 0x03 {
+            1 => BinDataCompression::Compress,
+            2 => BinDataCompression::NoCompress,
+            _ => BinDataCompression::Default,
+        }
+    }
+
+    /// Whether the item's stream is deflated in a document that is or is
+    /// not compressed
+    pub fn is_stored_compressed(&self, document_compressed: bool) -> bool {
+        match self.compression() {
+            BinDataCompression::Default => document_compressed,
+            BinDataCompression::Compress => true,
+            BinDataCompression::NoCompress => false,
+        }
+    }
+
     pub fn is_compressed(&self) -> bool {
         (self.properties & 0x04) != 0
     }
@@ -116,3 +124,9 @@ impl BinData {
         }
     }
 }
+
+/// A length-prefixed UTF-16LE string
+fn read_name(reader: &mut StreamReader) -> Result<String> {
+    let len = reader.read_u16()? as usize;
+    reader.read_string(len * 2)
+}
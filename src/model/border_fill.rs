@@ -22,9 +22,12 @@ pub struct BorderLine {
 
 #[derive(Debug, Clone)]
 pub struct FillInfo {
+    /// Bit flags: 0x01 solid colour, 0x04 picture, 0x08 gradient
     pub fill_type: u32,
     pub back_color: u32,
     pub pattern_color: u32,
+    /// Hatching drawn over a solid colour: 0 horizontal, 1 vertical,
+    /// 2 backslash, 3 slash, 4 cross, 5 diagonal cross; -1 for none
     pub pattern_type: u32,
     pub image_info: Option<ImageInfo>,
     pub gradient_info: Option<GradientInfo>,
@@ -181,7 +184,7 @@ impl BorderFill {
                 fill_type: 1,           // Solid fill
                 back_color: 0xFFFFFFF0, // Light cream background
                 pattern_color: 0,
-                pattern_type: 0,
+                pattern_type: u32::MAX, // No hatching
                 image_info: None,
                 gradient_info: None,
             },
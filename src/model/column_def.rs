@@ -0,0 +1,154 @@
+use crate::error::Result;
+use crate::model::border_fill::BorderLine;
+use crate::parser::record::Record;
+
+/// How text fills the columns (단 종류)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ColumnKind {
+    /// Each column fills before the next (일반 다단)
+    Normal,
+    /// Columns are kept level (배분 다단)
+    Distributed,
+    /// Columns hold separate text side by side (평행 다단)
+    Parallel,
+}
+
+/// Column layout started by a paragraph (단 정의)
+#[derive(Debug, Clone, PartialEq)]
+pub struct ColumnDef {
+    pub properties: u16,
+    /// Space between columns (HWP units)
+    pub gap: u16,
+    /// Width and following gap of each column in 1/32768ths of the body
+    /// width, used when the columns are not all the same width
+    pub widths: Vec<(u16, u16)>,
+    /// Upper half of the attributes
+    pub properties2: u16,
+    /// Divider line between columns; type 0 draws none
+    pub line_type: u8,
+    pub line_thickness: u8,
+    pub line_color: u32,
+}
+
+impl ColumnDef {
+    /// `count` columns of the same width, `gap` apart, without a divider
+    pub fn new(count: u16, gap: u16) -> Self {
+        Self {
+            properties: (count.clamp(1, 255) << 2) | 0x1000,
+            gap,
+            widths: Vec::new(),
+            properties2: 0,
+            line_type: 0,
+            line_thickness: 0,
+            line_color: 0,
+        }
+    }
+
+    /// Parse from the `CTRL_HEADER` record of a `cold` control
+    pub fn from_record(record: &Record) -> Result<Self> {
+        let mut reader = record.data_reader();
+        let _ctrl_id = reader.read_u32()?;
+        let properties = reader.read_u16()?;
+        let gap = reader.read_u16()?;
+        let mut column_def = Self::new(1, gap);
+        column_def.properties = properties;
+        if !column_def.is_same_width() {
+            for _ in 0..column_def.count() {
+                column_def
+                    .widths
+                    .push((reader.read_u16()?, reader.read_u16()?));
+            }
+        }
+        column_def.properties2 = reader.read_u16()?;
+        column_def.line_type = reader.read_u8()?;
+        column_def.line_thickness = reader.read_u8()?;
+        column_def.line_color = reader.read_u32()?;
+        Ok(column_def)
+    }
+
+    /// Columns of the given widths and following gaps, relative to each other
+    pub fn with_widths(mut self, widths: &[(u32, u32)]) -> Self {
+        let total: u32 = widths.iter().map(|(width, gap)| width + gap).sum();
+        let scale = |value: u32| (value as u64 * 32768 / total.max(1) as u64) as u16;
+        self.widths = widths
+            .iter()
+            .map(|&(width, gap)| (scale(width), scale(gap)))
+            .collect();
+        self.properties = (self.properties & !(0xFF << 2 | 0x1000)) | (widths.len() as u16) << 2;
+        self
+    }
+
+    /// Draw a divider line between columns
+    pub fn with_line(mut self, line_type: u8, thickness: u8, color: u32) -> Self {
+        self.line_type = line_type;
+        self.line_thickness = thickness;
+        self.line_color = color;
+        self
+    }
+
+    pub fn with_kind(mut self, kind: ColumnKind) -> Self {
+        let kind = match kind {
+            ColumnKind::Normal => 0,
+            ColumnKind::Distributed => 1,
+            ColumnKind::Parallel => 2,
+        };
+        self.properties = (self.properties & !0x03) | kind;
+        self
+    }
+
+    pub fn kind(&self) -> ColumnKind {
+        match self.properties & 0x03 {
+            1 => ColumnKind::Distributed,
+            2 => ColumnKind::Parallel,
+            _ => ColumnKind::Normal,
+        }
+    }
+
+    pub fn count(&self) -> u16 {
+        ((self.properties >> 2) & 0xFF).max(1)
+    }
+
+    /// Whether the first column is on the right (단 방향)
+    pub fn is_right_to_left(&self) -> bool {
+        (self.properties >> 10) & 0x03 == 1
+    }
+
+    pub fn is_same_width(&self) -> bool {
+        (self.properties & 0x1000) != 0
+    }
+
+    pub fn line(&self) -> Option<BorderLine> {
+        (self.line_type != 0).then_some(BorderLine {
+            line_type: self.line_type,
+            thickness: self.line_thickness,
+            color: self.line_color,
+        })
+    }
+
+    /// Offset from the left of the body and width of each column, in
+    /// reading order, for a body `width` wide
+    pub fn columns(&self, width: i32) -> Vec<(i32, i32)> {
+        let count = self.count() as i32;
+        let mut columns = Vec::new();
+        if self.is_same_width() || self.widths.len() != count as usize {
+            let gap = self.gap as i32;
+            let column_width = (width - gap * (count - 1)) / count;
+            for index in 0..count {
+                columns.push((index * (column_width + gap), column_width));
+            }
+        } else {
+            let scale = |value: u16| (value as i64 * width as i64 / 32768) as i32;
+            let mut offset = 0;
+            for &(column_width, gap) in &self.widths {
+                columns.push((offset, scale(column_width)));
+                offset += scale(column_width) + scale(gap);
+            }
+        }
+        if self.is_right_to_left() {
+            for column in &mut columns {
+                column.0 = width - column.0 - column.1;
+            }
+        }
+        columns
+    }
+}
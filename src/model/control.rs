@@ -62,6 +62,19 @@ impl Picture {
         }
     }
 
+    /// Anchor, alignment and text wrap of the picture
+    pub fn placement(&self) -> crate::model::drawing::ObjectProperties {
+        crate::model::drawing::ObjectProperties(self.properties)
+    }
+
+    pub fn width(&self) -> i32 {
+        self.right - self.left
+    }
+
+    pub fn height(&self) -> i32 {
+        self.bottom - self.top
+    }
+
     /// Serialize picture to bytes for HWP format
     pub fn to_bytes(&self) -> Vec<u8> {
         let mut data = Vec::new();
@@ -135,6 +148,16 @@ impl Table {
         }
     }
 
+    /// How the table splits at a page boundary: 0 never, 1 at cell boundaries, 2 anywhere
+    pub fn page_break(&self) -> u8 {
+        (self.properties & 0x03) as u8
+    }
+
+    /// Whether the header row repeats on every page the table continues on
+    pub fn repeats_header(&self) -> bool {
+        (self.properties & 0x04) != 0
+    }
+
     /// Get cell at specific row and column
     pub fn get_cell(&self, row: u16, col: u16) -> Option<&TableCell> {
         self.cells
@@ -280,7 +303,7 @@ impl Table {
 
         // Read cells if available
         let mut cells = Vec::new();
-        let total_cells = (rows * cols) as usize;
+        let total_cells = rows as usize * cols as usize;
 
         for i in 0..total_cells {
             if reader.remaining() < 34 {
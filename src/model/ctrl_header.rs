@@ -87,14 +87,18 @@ impl ControlType {
             0x7165 => Self::Equation,              // 'eq'
             0x636573 => Self::SectionDefinition,   // 'sec'
             0x6C6F63 => Self::ColumnDefinition,    // 'col'
+            0x636F6C64 => Self::ColumnDefinition,  // 'cold'
             0x646E65 => Self::Endnote,             // 'end'
             0x746F66 => Self::Footnote,            // 'fot'
             0x676170 => Self::PageNumberPosition,  // 'pag'
             0x6B6D62 => Self::BookMark,            // 'bmk'
             0x6F6961 => Self::AutoNumber,          // 'aio'
             0x6E756E => Self::NewNumber,           // 'nun'
+            0x6E776E6F => Self::NewNumber,         // 'nwno'
             0x65646968 => Self::PageHide,          // 'hide'
-            0x74636573 => Self::OverlappingLetter, // 'tcmt'
+            0x70676864 => Self::PageHide,          // 'pghd'
+            0x74636573 => Self::OverlappingLetter, // 'tces'
+            0x74636D74 => Self::HiddenComment,     // 'tcmt'
             0x6B6469 => Self::IndexMark,           // 'idx'
             0x646C66 => Self::Field,               // 'fld'
             _ => Self::Unknown,
@@ -5,7 +5,7 @@ use crate::parser::header::FileHeader;
 use crate::parser::record::Record;
 use crate::preview::{PreviewImage, PreviewText, SummaryInfo};
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct HwpDocument {
     pub header: FileHeader,
     pub doc_info: DocInfo,
@@ -13,6 +13,9 @@ pub struct HwpDocument {
     pub preview_text: Option<PreviewText>,
     pub preview_image: Option<PreviewImage>,
     pub summary_info: Option<SummaryInfo>,
+    pub scripts: Option<crate::model::DocumentScripts>,
+    /// Key the streams are encrypted with, for password-protected documents
+    pub password: Option<crate::crypto::PasswordCipher>,
 }
 
 impl HwpDocument {
@@ -142,6 +145,11 @@ impl HwpDocument {
         self.summary_info.as_ref()
     }
 
+    /// Embedded JScript macros, if the document carries a Scripts storage
+    pub fn scripts(&self) -> Option<&crate::model::DocumentScripts> {
+        self.scripts.as_ref()
+    }
+
     pub fn title(&self) -> Option<&str> {
         self.summary_info.as_ref().and_then(|s| s.title.as_deref())
     }
@@ -169,6 +177,25 @@ impl HwpDocument {
     pub fn is_encrypted(&self) -> bool {
         self.header.is_encrypted()
     }
+
+    /// Whether a distribution document forbids copying its content
+    pub fn is_copy_restricted(&self) -> bool {
+        self.distribution_decryptor()
+            .is_some_and(|d| d.is_copy_restricted())
+    }
+
+    /// Whether a distribution document forbids printing
+    pub fn is_print_restricted(&self) -> bool {
+        self.distribution_decryptor()
+            .is_some_and(|d| d.is_print_restricted())
+    }
+
+    fn distribution_decryptor(&self) -> Option<crate::crypto::DistributionDecryptor> {
+        let record = self.doc_info.distribution_record.as_ref()?;
+        let mut data = vec![0u8; 4];
+        data.extend_from_slice(record);
+        crate::crypto::DistributionDecryptor::from_record_data(&data).ok()
+    }
 }
 
 #[derive(Debug, Default, Clone)]
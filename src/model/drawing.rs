@@ -0,0 +1,237 @@
+/// Area a floating object's offset is measured from
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RelativeTo {
+    Paper,
+    Page,
+    Column,
+    Paragraph,
+}
+
+/// Alignment of a floating object within its area
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ObjectAlign {
+    /// Left or top
+    Start,
+    Center,
+    /// Right or bottom
+    End,
+}
+
+/// How body text flows around an object (본문과의 배치)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TextWrap {
+    /// 어울림
+    Square,
+    /// 자리 차지
+    TopAndBottom,
+    /// 글 뒤로
+    BehindText,
+    /// 글 앞으로
+    InFrontOfText,
+}
+
+/// Common object attributes (개체 공통 속성) held in a control's `properties`
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ObjectProperties(pub u32);
+
+impl ObjectProperties {
+    /// Treated as a character of the paragraph (글자처럼 취급)
+    pub fn inline() -> Self {
+        Self(0x01)
+    }
+
+    /// Floating object measured from the given areas
+    pub fn floating(vertical: RelativeTo, horizontal: RelativeTo, wrap: TextWrap) -> Self {
+        let vertical = match vertical {
+            RelativeTo::Paper => 0,
+            RelativeTo::Page => 1,
+            RelativeTo::Column | RelativeTo::Paragraph => 2,
+        };
+        let horizontal = match horizontal {
+            RelativeTo::Paper => 0,
+            RelativeTo::Page => 1,
+            RelativeTo::Column => 2,
+            RelativeTo::Paragraph => 3,
+        };
+        let wrap = match wrap {
+            TextWrap::Square => 0,
+            TextWrap::TopAndBottom => 1,
+            TextWrap::BehindText => 2,
+            TextWrap::InFrontOfText => 3,
+        };
+        Self(vertical << 3 | horizontal << 8 | wrap << 21)
+    }
+
+    pub fn with_horizontal_align(self, align: ObjectAlign) -> Self {
+        let align = match align {
+            ObjectAlign::Start => 0,
+            ObjectAlign::Center => 1,
+            ObjectAlign::End => 2,
+        };
+        Self(self.0 & !(0x07 << 10) | align << 10)
+    }
+
+    pub fn is_inline(&self) -> bool {
+        (self.0 & 0x01) != 0
+    }
+
+    pub fn vertical_relative_to(&self) -> RelativeTo {
+        match (self.0 >> 3) & 0x03 {
+            0 => RelativeTo::Paper,
+            1 => RelativeTo::Page,
+            _ => RelativeTo::Paragraph,
+        }
+    }
+
+    pub fn vertical_align(&self) -> ObjectAlign {
+        align((self.0 >> 5) & 0x07)
+    }
+
+    pub fn horizontal_relative_to(&self) -> RelativeTo {
+        match (self.0 >> 8) & 0x03 {
+            0 => RelativeTo::Paper,
+            1 => RelativeTo::Page,
+            2 => RelativeTo::Column,
+            _ => RelativeTo::Paragraph,
+        }
+    }
+
+    pub fn horizontal_align(&self) -> ObjectAlign {
+        align((self.0 >> 10) & 0x07)
+    }
+
+    pub fn text_wrap(&self) -> TextWrap {
+        match (self.0 >> 21) & 0x07 {
+            1 => TextWrap::TopAndBottom,
+            2 => TextWrap::BehindText,
+            3 => TextWrap::InFrontOfText,
+            _ => TextWrap::Square,
+        }
+    }
+}
+
+/// Inside and outside count as the start and end of a page
+fn align(value: u32) -> ObjectAlign {
+    match value {
+        1 => ObjectAlign::Center,
+        2 | 4 => ObjectAlign::End,
+        _ => ObjectAlign::Start,
+    }
+}
+
+/// Outline of a drawing object, in HWP units from its top-left corner
+#[derive(Debug, Clone, PartialEq)]
+pub enum ShapeKind {
+    Line {
+        start: (i32, i32),
+        end: (i32, i32),
+    },
+    /// `round` is the corner radius as a percentage of half the shorter side
+    Rectangle {
+        round: u8,
+    },
+    Ellipse,
+    Polygon(Vec<(i32, i32)>),
+    /// A start point followed by cubic Bézier control, control and end points
+    Curve(Vec<(i32, i32)>),
+}
+
+/// Drawing object (그리기 개체)
+#[derive(Debug, Clone)]
+pub struct Shape {
+    pub properties: ObjectProperties,
+    /// Horizontal offset (HWP units)
+    pub x: i32,
+    /// Vertical offset (HWP units)
+    pub y: i32,
+    pub width: u32,
+    pub height: u32,
+    pub z_order: i32,
+    /// Clockwise rotation about the centre in degrees
+    pub rotation: i16,
+    pub kind: ShapeKind,
+    /// 0 none, 1 solid, 2 dashed, 3 dotted, as for `BorderLine`
+    pub line_type: u8,
+    /// Outline width (HWP units)
+    pub line_width: u32,
+    pub line_color: u32,
+    pub fill_color: Option<u32>,
+}
+
+impl Shape {
+    /// Thin black outline without fill, placed below the paragraph it is anchored to
+    pub fn new(kind: ShapeKind, width: u32, height: u32) -> Self {
+        Self {
+            properties: ObjectProperties::floating(
+                RelativeTo::Paragraph,
+                RelativeTo::Column,
+                TextWrap::TopAndBottom,
+            ),
+            x: 0,
+            y: 0,
+            width,
+            height,
+            z_order: 0,
+            rotation: 0,
+            kind,
+            line_type: 1,
+            line_width: 34, // 0.12mm
+            line_color: 0x000000,
+            fill_color: None,
+        }
+    }
+
+    pub fn rectangle(width: u32, height: u32) -> Self {
+        Self::new(ShapeKind::Rectangle { round: 0 }, width, height)
+    }
+
+    pub fn ellipse(width: u32, height: u32) -> Self {
+        Self::new(ShapeKind::Ellipse, width, height)
+    }
+
+    /// Line from the top-left to the bottom-right corner
+    pub fn line(width: u32, height: u32) -> Self {
+        Self::new(
+            ShapeKind::Line {
+                start: (0, 0),
+                end: (width as i32, height as i32),
+            },
+            width,
+            height,
+        )
+    }
+
+    /// Offset from the anchor area (HWP units)
+    pub fn with_position(mut self, x: i32, y: i32) -> Self {
+        self.x = x;
+        self.y = y;
+        self
+    }
+
+    pub fn with_properties(mut self, properties: ObjectProperties) -> Self {
+        self.properties = properties;
+        self
+    }
+
+    pub fn with_line(mut self, line_type: u8, width: u32, color: u32) -> Self {
+        self.line_type = line_type;
+        self.line_width = width;
+        self.line_color = color;
+        self
+    }
+
+    pub fn with_fill(mut self, color: u32) -> Self {
+        self.fill_color = Some(color);
+        self
+    }
+
+    pub fn with_rotation(mut self, degrees: i16) -> Self {
+        self.rotation = degrees;
+        self
+    }
+
+    pub fn with_z_order(mut self, z_order: i32) -> Self {
+        self.z_order = z_order;
+        self
+    }
+}
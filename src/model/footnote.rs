@@ -0,0 +1,50 @@
+use crate::model::Paragraph;
+
+/// Whether a note is printed at the bottom of the page or the end of the document
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NoteKind {
+    Footnote,
+    Endnote,
+}
+
+/// A footnote or endnote attached to a paragraph
+#[derive(Debug, Clone)]
+pub struct Footnote {
+    pub kind: NoteKind,
+    /// Number shown at the reference mark
+    pub number: u16,
+    /// Character offset of the reference mark in the owning paragraph's text
+    pub position: u32,
+    /// Note content
+    pub paragraphs: Vec<Paragraph>,
+}
+
+impl Footnote {
+    pub fn new(kind: NoteKind, number: u16, position: u32, text: &str) -> Self {
+        let paragraphs = text
+            .split('\n')
+            .map(|line| Paragraph {
+                text: Some(crate::model::ParaText {
+                    content: line.to_string(),
+                }),
+                ..Default::default()
+            })
+            .collect();
+
+        Self {
+            kind,
+            number,
+            position,
+            paragraphs,
+        }
+    }
+
+    /// Note content as plain text, one line per paragraph
+    pub fn text(&self) -> String {
+        self.paragraphs
+            .iter()
+            .filter_map(|p| p.text.as_ref().map(|t| t.content.as_str()))
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+}
@@ -115,6 +115,29 @@ impl HeaderFooter {
         self
     }
 
+    /// 페이지 번호 형식
+    pub fn number_format(&self) -> PageNumberFormat {
+        match self.page_number_format {
+            2 => PageNumberFormat::RomanLower,
+            3 => PageNumberFormat::RomanUpper,
+            4 => PageNumberFormat::AlphaLower,
+            5 => PageNumberFormat::AlphaUpper,
+            _ => PageNumberFormat::Numeric,
+        }
+    }
+
+    /// 이 Header/Footer가 `page_number` 쪽에 쓰이는지 여부
+    ///
+    /// `first_page`는 구역의 첫 쪽인지를 뜻합니다.
+    pub fn applies_to(&self, page_number: u32, first_page: bool) -> bool {
+        match self.apply_type {
+            PageApplyType::All => true,
+            PageApplyType::FirstPage => first_page,
+            PageApplyType::EvenPages => page_number.is_multiple_of(2),
+            PageApplyType::OddPages => !page_number.is_multiple_of(2),
+        }
+    }
+
     /// HWP 형식으로 직렬화
     pub fn to_bytes(&self) -> Vec<u8> {
         use crate::utils::encoding::string_to_utf16le;
@@ -210,6 +233,58 @@ pub enum PageNumberFormat {
     AlphaUpper = 5,
 }
 
+impl PageNumberFormat {
+    /// 페이지 번호를 이 형식의 문자열로 변환
+    pub fn format(&self, number: u32) -> String {
+        match self {
+            PageNumberFormat::Numeric => number.to_string(),
+            PageNumberFormat::RomanLower => roman(number).to_lowercase(),
+            PageNumberFormat::RomanUpper => roman(number),
+            PageNumberFormat::AlphaLower => alpha(number).to_lowercase(),
+            PageNumberFormat::AlphaUpper => alpha(number),
+        }
+    }
+}
+
+/// 로마 숫자 (0은 숫자 그대로)
+fn roman(mut number: u32) -> String {
+    if number == 0 {
+        return number.to_string();
+    }
+    const NUMERALS: [(u32, &str); 13] = [
+        (1000, "M"),
+        (900, "CM"),
+        (500, "D"),
+        (400, "CD"),
+        (100, "C"),
+        (90, "XC"),
+        (50, "L"),
+        (40, "XL"),
+        (10, "X"),
+        (9, "IX"),
+        (5, "V"),
+        (4, "IV"),
+        (1, "I"),
+    ];
+    let mut out = String::new();
+    for (value, numeral) in NUMERALS {
+        while number >= value {
+            out.push_str(numeral);
+            number -= value;
+        }
+    }
+    out
+}
+
+/// A, B, ... Z 다음 AA, BB, ... 처럼 글자를 반복
+fn alpha(number: u32) -> String {
+    if number == 0 {
+        return number.to_string();
+    }
+    let letter = (b'A' + ((number - 1) % 26) as u8) as char;
+    letter.to_string().repeat(((number - 1) / 26 + 1) as usize)
+}
+
 /// Header/Footer 정렬 방식
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub enum HeaderFooterAlignment {
@@ -1,4 +1,5 @@
 use crate::error::Result;
+use crate::model::TextDirection;
 use crate::parser::record::Record;
 
 #[derive(Debug, Clone)]
@@ -53,4 +54,14 @@ impl ListHeader {
     pub fn is_editable_at_form_mode(&self) -> bool {
         (self.properties & 0x04) != 0
     }
+
+    /// Direction of the list's text
+    pub fn text_direction(&self) -> TextDirection {
+        TextDirection::from_bits(self.properties)
+    }
+
+    /// Vertical alignment of a cell's content: 0 top, 1 center, 2 bottom
+    pub fn vertical_alignment(&self) -> u8 {
+        ((self.properties >> 5) & 0x03) as u8
+    }
 }
@@ -1,35 +1,44 @@
 pub mod bin_data;
 pub mod border_fill;
 pub mod char_shape;
+pub mod column_def;
 pub mod control;
 pub mod ctrl_header;
 pub mod document;
+pub mod drawing;
+pub mod footnote;
 pub mod header_footer;
 pub mod hyperlink;
 pub mod list_header;
 pub mod numbering;
+pub mod page_control;
 pub mod page_def;
 pub mod page_layout;
 pub mod para_char_shape;
 pub mod para_line_seg;
 pub mod para_shape;
 pub mod paragraph;
+pub mod scripts;
 pub mod section_def;
 pub mod style;
 pub mod tab_def;
 pub mod text_box;
 
 pub use self::char_shape::{CharShape, FaceName};
+pub use self::column_def::{ColumnDef, ColumnKind};
 pub use self::control::{Control, Table, TableCell};
 pub use self::ctrl_header::{ControlType, CtrlHeader};
 pub use self::document::{DocumentProperties, FormattedText, HwpDocument};
+pub use self::drawing::{ObjectAlign, ObjectProperties, RelativeTo, Shape, ShapeKind, TextWrap};
+pub use self::footnote::{Footnote, NoteKind};
 pub use self::header_footer::{
     HeaderFooter, HeaderFooterAlignment, HeaderFooterCollection, HeaderFooterType, PageApplyType,
     PageNumberFormat,
 };
 pub use self::hyperlink::{Hyperlink, HyperlinkDisplay, HyperlinkType};
 pub use self::list_header::ListHeader;
-pub use self::page_def::PageDef;
+pub use self::page_control::{NewNumber, NumberKind, PageHide};
+pub use self::page_def::{PageBorderFill, PageDef, PageFillArea};
 pub use self::page_layout::{
     hwp_units_to_inches, hwp_units_to_mm, inches_to_hwp_units, mm_to_hwp_units, MarginUnit,
     PageLayout, PageMargins, PageOrientation, PaperSize,
@@ -38,5 +47,6 @@ pub use self::para_char_shape::{CharPositionShape, ParaCharShape};
 pub use self::para_line_seg::{LineSegment, ParaLineSeg};
 pub use self::para_shape::ParaShape;
 pub use self::paragraph::{ParaText, Paragraph, Section};
-pub use self::section_def::SectionDef;
+pub use self::scripts::DocumentScripts;
+pub use self::section_def::{SectionDef, TextDirection};
 pub use self::text_box::{TextBox, TextBoxAlignment, TextBoxBorderStyle, TextBoxFillType};
@@ -0,0 +1,93 @@
+use crate::error::Result;
+use crate::parser::record::Record;
+
+/// Hides header, footer and other page decorations on one page (감추기)
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct PageHide {
+    pub properties: u32,
+}
+
+impl PageHide {
+    /// Parse from the `CTRL_HEADER` record of a `pghd` control
+    pub fn from_record(record: &Record) -> Result<Self> {
+        let mut reader = record.data_reader();
+        let _ctrl_id = reader.read_u32()?;
+        Ok(Self {
+            properties: reader.read_u32()?,
+        })
+    }
+
+    pub fn hides_header(&self) -> bool {
+        (self.properties & 0x01) != 0
+    }
+
+    pub fn hides_footer(&self) -> bool {
+        (self.properties & 0x02) != 0
+    }
+
+    pub fn hides_master_page(&self) -> bool {
+        (self.properties & 0x04) != 0
+    }
+
+    pub fn hides_border(&self) -> bool {
+        (self.properties & 0x08) != 0
+    }
+
+    pub fn hides_fill(&self) -> bool {
+        (self.properties & 0x10) != 0
+    }
+
+    pub fn hides_page_number(&self) -> bool {
+        (self.properties & 0x20) != 0
+    }
+}
+
+/// Sequence restarted by a new number control
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NumberKind {
+    Page,
+    Footnote,
+    Endnote,
+    Picture,
+    Table,
+    Equation,
+}
+
+/// Restarts page, note, picture, table or equation numbering (새 번호로 시작)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct NewNumber {
+    pub properties: u32,
+    pub number: u16,
+}
+
+impl NewNumber {
+    /// Restart page numbering at `number` on the page holding the control
+    pub fn page(number: u16) -> Self {
+        Self {
+            properties: 0,
+            number,
+        }
+    }
+
+    /// Parse from the `CTRL_HEADER` record of a `nwno` control
+    pub fn from_record(record: &Record) -> Result<Self> {
+        let mut reader = record.data_reader();
+        let _ctrl_id = reader.read_u32()?;
+        Ok(Self {
+            properties: reader.read_u32()?,
+            number: reader.read_u16()?,
+        })
+    }
+
+    pub fn kind(&self) -> Option<NumberKind> {
+        match self.properties & 0x0F {
+            0 => Some(NumberKind::Page),
+            1 => Some(NumberKind::Footnote),
+            2 => Some(NumberKind::Endnote),
+            3 => Some(NumberKind::Picture),
+            4 => Some(NumberKind::Table),
+            5 => Some(NumberKind::Equation),
+            _ => None,
+        }
+    }
+}
@@ -17,6 +17,8 @@ pub struct PageDef {
     pub properties: u32,
     pub footnote_shape_id: u16,
     pub page_border_fill_id: u16,
+    /// Page border and background (쪽 테두리/배경)
+    pub border_fill: Option<PageBorderFill>,
     /// Header/Footer 컬렉션
     pub header_footer: HeaderFooterCollection,
     /// 고급 페이지 레이아웃 설정
@@ -94,6 +96,7 @@ impl PageDef {
             properties,
             footnote_shape_id: 0,
             page_border_fill_id: 0,
+            border_fill: None,
             header_footer: HeaderFooterCollection::new(),
             layout: None,
         })
@@ -126,6 +129,7 @@ impl PageDef {
             properties: 0,
             footnote_shape_id: 0,
             page_border_fill_id: 0,
+            border_fill: None,
             header_footer: HeaderFooterCollection::new(),
             layout: None,
         }
@@ -146,6 +150,7 @@ impl PageDef {
             properties: 0, // Will be set based on layout properties
             footnote_shape_id: 0,
             page_border_fill_id: 0,
+            border_fill: None,
             header_footer: HeaderFooterCollection::new(),
             layout: Some(layout),
         }
@@ -225,3 +230,73 @@ impl PageDef {
         data
     }
 }
+
+/// Where a page border sits and what its background covers (`PAGE_BORDER_FILL`)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PageBorderFill {
+    pub properties: u32,
+    /// Space between the border and the body or paper edge (HWP units)
+    pub left_gap: u16,
+    pub right_gap: u16,
+    pub top_gap: u16,
+    pub bottom_gap: u16,
+    /// 1-based `BorderFill` drawn
+    pub border_fill_id: u16,
+}
+
+/// Area painted by a page background
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PageFillArea {
+    Paper,
+    Page,
+    Border,
+}
+
+impl PageBorderFill {
+    /// Border around the body text, background over the whole paper
+    pub fn new(border_fill_id: u16) -> Self {
+        Self {
+            properties: 0,
+            left_gap: 1417, // 5mm
+            right_gap: 1417,
+            top_gap: 1417,
+            bottom_gap: 1417,
+            border_fill_id,
+        }
+    }
+
+    pub fn from_record(record: &Record) -> Result<Self> {
+        let mut reader = record.data_reader();
+        Ok(Self {
+            properties: reader.read_u32()?,
+            left_gap: reader.read_u16()?,
+            right_gap: reader.read_u16()?,
+            top_gap: reader.read_u16()?,
+            bottom_gap: reader.read_u16()?,
+            border_fill_id: reader.read_u16()?,
+        })
+    }
+
+    /// Whether the gaps are measured from the paper edge rather than the body
+    pub fn is_relative_to_paper(&self) -> bool {
+        (self.properties & 0x01) != 0
+    }
+
+    /// Whether a border around the body also takes in the header
+    pub fn includes_header(&self) -> bool {
+        (self.properties & 0x02) != 0
+    }
+
+    /// Whether a border around the body also takes in the footer
+    pub fn includes_footer(&self) -> bool {
+        (self.properties & 0x04) != 0
+    }
+
+    pub fn fill_area(&self) -> PageFillArea {
+        match (self.properties >> 3) & 0x03 {
+            1 => PageFillArea::Page,
+            2 => PageFillArea::Border,
+            _ => PageFillArea::Paper,
+        }
+    }
+}
@@ -28,6 +28,46 @@ impl ParaShape {
         ((self.properties1 >> 2) & 0x7) as u8
     }
 
+    /// Latin line break unit: 0 word, 1 hyphenation, 2 character
+    pub fn get_latin_break_unit(&self) -> u8 {
+        ((self.properties1 >> 5) & 0x3) as u8
+    }
+
+    /// Korean line break unit: 0 word (어절), 1 character (글자)
+    pub fn get_korean_break_unit(&self) -> u8 {
+        ((self.properties1 >> 7) & 0x1) as u8
+    }
+
+    /// Whether a single first or last line may not be left alone on a page
+    pub fn is_widow_orphan_protected(&self) -> bool {
+        (self.properties1 & (1 << 16)) != 0
+    }
+
+    /// Whether the paragraph stays on the same page as the next one
+    pub fn is_keep_with_next(&self) -> bool {
+        (self.properties1 & (1 << 17)) != 0
+    }
+
+    /// Whether the paragraph's lines may not be split across pages
+    pub fn is_keep_lines(&self) -> bool {
+        (self.properties1 & (1 << 18)) != 0
+    }
+
+    /// Whether the paragraph always starts a new page
+    pub fn is_page_break_before(&self) -> bool {
+        (self.properties1 & (1 << 19)) != 0
+    }
+
+    /// Whether the border joins up with the next paragraph's when they share one
+    pub fn is_border_connected(&self) -> bool {
+        (self.properties1 & (1 << 28)) != 0
+    }
+
+    /// Whether the border reaches out over the left and right margins
+    pub fn is_border_ignoring_margins(&self) -> bool {
+        (self.properties1 & (1 << 29)) != 0
+    }
+
     pub fn get_line_spacing_percent(&self) -> i32 {
         // Line spacing depends on line_space_type
         match self.line_space_type {
@@ -1,14 +1,14 @@
 use crate::error::Result;
 use crate::parser::record::Record;
 
-#[derive(Debug, Default)]
+#[derive(Debug, Default, Clone)]
 pub struct Section {
     pub paragraphs: Vec<Paragraph>,
     pub section_def: Option<crate::model::SectionDef>,
     pub page_def: Option<crate::model::PageDef>,
 }
 
-#[derive(Debug, Default)]
+#[derive(Debug, Default, Clone)]
 pub struct Paragraph {
     pub text: Option<ParaText>,
     pub control_mask: u32,
@@ -27,8 +27,17 @@ pub struct Paragraph {
     pub table_data: Option<crate::model::control::Table>,
     pub picture_data: Option<crate::model::control::Picture>,
     pub text_box_data: Option<crate::model::text_box::TextBox>,
+    pub shape_data: Option<crate::model::drawing::Shape>,
     // Store hyperlinks for this paragraph
     pub hyperlinks: Vec<crate::model::hyperlink::Hyperlink>,
+    // Footnotes and endnotes referenced from this paragraph
+    pub footnotes: Vec<crate::model::footnote::Footnote>,
+    // Page decorations hidden on the page holding this paragraph
+    pub page_hide: Option<crate::model::PageHide>,
+    // Numbering sequences restarted at this paragraph
+    pub new_numbers: Vec<crate::model::NewNumber>,
+    // Column layout starting at this paragraph
+    pub column_def: Option<crate::model::ColumnDef>,
 }
 
 impl Paragraph {
@@ -58,10 +67,21 @@ impl Paragraph {
             line_align_count: reader.read_u16()?,
             instance_id: reader.read_u32()?,
             hyperlinks: Vec::new(),
+            footnotes: Vec::new(),
             ..Default::default()
         })
     }
 
+    /// Whether the paragraph starts a new page (쪽 나누기)
+    pub fn is_page_break(&self) -> bool {
+        (self.column_type & 0x04) != 0
+    }
+
+    /// Whether the paragraph starts a new column (단 나누기)
+    pub fn is_column_break(&self) -> bool {
+        (self.column_type & 0x08) != 0
+    }
+
     pub fn parse_char_shapes(&mut self, _record: &Record) -> Result<()> {
         // Character shape parsing logic would go here
         // For now, we'll skip the implementation
@@ -69,7 +89,7 @@ impl Paragraph {
     }
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct ParaText {
     pub content: String,
 }
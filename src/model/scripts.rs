@@ -0,0 +1,203 @@
+use crate::error::{HwpError, Result};
+use crate::reader::StreamReader;
+
+/// End-of-script marker that closes the DefaultJScript stream
+const SCRIPT_END_MARKER: u32 = 0xFFFF_FFFF;
+
+/// JScript macros stored in the `Scripts` storage
+#[derive(Debug, Clone, Default)]
+pub struct DocumentScripts {
+    /// JScript engine version from `JScriptVersion`
+    pub version: u32,
+    /// Global declarations evaluated before the document functions
+    pub header: String,
+    /// Document event handlers and user functions
+    pub source: String,
+    /// Script run before `source` (usually empty)
+    pub pre_source: String,
+    /// Script run after `source` (usually empty)
+    pub post_source: String,
+}
+
+impl DocumentScripts {
+    /// Parse the decompressed `JScriptVersion` and `DefaultJScript` streams
+    pub fn from_streams(version_data: &[u8], script_data: &[u8]) -> Result<Self> {
+        let version = if version_data.len() >= 4 {
+            u32::from_le_bytes([
+                version_data[0],
+                version_data[1],
+                version_data[2],
+                version_data[3],
+            ])
+        } else {
+            0
+        };
+
+        let mut reader = StreamReader::new(script_data.to_vec());
+        let header = read_script_string(&mut reader)?;
+        let source = read_script_string(&mut reader)?;
+        let pre_source = read_script_string(&mut reader)?;
+        let post_source = read_script_string(&mut reader)?;
+
+        Ok(Self {
+            version,
+            header,
+            source,
+            pre_source,
+            post_source,
+        })
+    }
+
+    /// Parse the HWPX `Scripts/headerScripts` and `Scripts/sourceScripts` parts
+    pub fn from_hwpx_parts(header_data: &[u8], source_data: &[u8]) -> Self {
+        Self {
+            version: 0,
+            header: decode_utf16_part(header_data),
+            source: decode_utf16_part(source_data),
+            pre_source: String::new(),
+            post_source: String::new(),
+        }
+    }
+
+    /// All script text concatenated in evaluation order
+    pub fn full_text(&self) -> String {
+        [
+            self.header.as_str(),
+            self.pre_source.as_str(),
+            self.source.as_str(),
+            self.post_source.as_str(),
+        ]
+        .iter()
+        .filter(|part| !part.is_empty())
+        .copied()
+        .collect::<Vec<_>>()
+        .join("\n")
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.header.trim().is_empty()
+            && self.source.trim().is_empty()
+            && self.pre_source.trim().is_empty()
+            && self.post_source.trim().is_empty()
+    }
+
+    /// Whether any script contains code beyond the empty stubs and global
+    /// bindings Hancom Office writes into every new document.
+    pub fn has_user_code(&self) -> bool {
+        let header = strip_default_bindings(strip_empty_functions(&strip_comments(&self.header)));
+        !header.is_empty()
+            || [&self.source, &self.pre_source, &self.post_source]
+                .iter()
+                .any(|script| !strip_empty_functions(&strip_comments(script)).is_empty())
+    }
+}
+
+/// Global bindings of the default header script, without whitespace
+const DEFAULT_BINDINGS: [&str; 2] = [
+    "varDocuments=XHwpDocuments;",
+    "varDocument=Documents.Active_XHwpDocument;",
+];
+
+/// Remove the default header bindings from a compacted script
+fn strip_default_bindings(mut compact: String) -> String {
+    for binding in DEFAULT_BINDINGS {
+        compact = compact.replacen(binding, "", 1);
+    }
+    compact
+}
+
+/// Read a length-prefixed (u32 character count) UTF-16LE string
+fn read_script_string(reader: &mut StreamReader) -> Result<String> {
+    if reader.remaining() < 4 {
+        return Ok(String::new());
+    }
+
+    let len = reader.read_u32()?;
+    if len == SCRIPT_END_MARKER {
+        return Ok(String::new());
+    }
+
+    let byte_len = len as usize * 2;
+    if byte_len > reader.remaining() {
+        return Err(HwpError::ParseError(format!(
+            "Script length {} exceeds remaining data {}",
+            len,
+            reader.remaining()
+        )));
+    }
+
+    let bytes = reader.read_bytes(byte_len)?;
+    let units: Vec<u16> = bytes
+        .chunks_exact(2)
+        .map(|c| u16::from_le_bytes([c[0], c[1]]))
+        .collect();
+    Ok(String::from_utf16_lossy(&units))
+}
+
+fn decode_utf16_part(data: &[u8]) -> String {
+    let data = data.strip_prefix(&[0xFF, 0xFE]).unwrap_or(data);
+    let units: Vec<u16> = data
+        .chunks_exact(2)
+        .map(|c| u16::from_le_bytes([c[0], c[1]]))
+        .collect();
+    String::from_utf16_lossy(&units)
+        .trim_end_matches('\0')
+        .to_string()
+}
+
+fn strip_comments(script: &str) -> String {
+    let mut result = String::new();
+    let mut chars = script.chars().peekable();
+
+    while let Some(ch) = chars.next() {
+        if ch == '/' && chars.peek() == Some(&'/') {
+            for c in chars.by_ref() {
+                if c == '\n' {
+                    break;
+                }
+            }
+        } else if ch == '/' && chars.peek() == Some(&'*') {
+            chars.next();
+            let mut prev = ' ';
+            for c in chars.by_ref() {
+                if prev == '*' && c == '/' {
+                    break;
+                }
+                prev = c;
+            }
+        } else if matches!(ch, '"' | '\'' | '`') {
+            // Comment markers inside string literals, such as URLs, are text
+            result.push(ch);
+            while let Some(c) = chars.next() {
+                result.push(c);
+                if c == '\\' {
+                    result.extend(chars.next());
+                } else if c == ch {
+                    break;
+                }
+            }
+        } else {
+            result.push(ch);
+        }
+    }
+
+    result
+}
+
+/// Remove `function name(...) {}` declarations with empty bodies and all whitespace
+fn strip_empty_functions(script: &str) -> String {
+    let mut compact: String = script.chars().filter(|c| !c.is_whitespace()).collect();
+
+    while let Some(start) = compact.find("function") {
+        let rest = &compact[start..];
+        let Some(body) = rest.find("){}") else {
+            break;
+        };
+        if rest[..body].contains('{') {
+            break;
+        }
+        compact.replace_range(start..start + body + 3, "");
+    }
+
+    compact
+}
@@ -1,6 +1,33 @@
 use crate::error::Result;
 use crate::parser::record::Record;
 
+/// Direction lines of text run in (글자 방향)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum TextDirection {
+    /// Left to right, lines stacked top to bottom
+    #[default]
+    Horizontal,
+    /// Top to bottom, lines stacked right to left (세로쓰기)
+    Vertical,
+}
+
+impl TextDirection {
+    /// Direction stored in a three-bit field; values other than 1 read as horizontal
+    pub(crate) fn from_bits(bits: u32) -> Self {
+        match bits & 0x07 {
+            1 => TextDirection::Vertical,
+            _ => TextDirection::Horizontal,
+        }
+    }
+
+    pub(crate) fn bits(self) -> u32 {
+        match self {
+            TextDirection::Horizontal => 0,
+            TextDirection::Vertical => 1,
+        }
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct SectionDef {
     pub properties: u32,
@@ -50,18 +77,30 @@ impl SectionDef {
         ((self.properties >> 20) & 0xFF) as u16 + 1
     }
 
+    /// Whether the header is hidden on the section's first page
     pub fn is_hide_header(&self) -> bool {
         (self.properties & 0x01) != 0
     }
 
+    /// Whether the footer is hidden on the section's first page
     pub fn is_hide_footer(&self) -> bool {
         (self.properties & 0x02) != 0
     }
 
+    /// Whether the page number is hidden on the section's first page
     pub fn is_hide_page_number(&self) -> bool {
         (self.properties & 0x04) != 0
     }
 
+    /// Direction of the section's body text
+    pub fn text_direction(&self) -> TextDirection {
+        TextDirection::from_bits(self.properties >> 16)
+    }
+
+    pub fn set_text_direction(&mut self, direction: TextDirection) {
+        self.properties = (self.properties & !(0x07 << 16)) | (direction.bits() << 16);
+    }
+
     /// Create a new default SectionDef for writing
     pub fn new_default() -> Self {
         Self {
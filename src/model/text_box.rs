@@ -1,4 +1,5 @@
 use crate::error::{HwpError, Result};
+use crate::model::TextDirection;
 use crate::parser::record::Record;
 
 /// 텍스트 박스 정렬 방식
@@ -81,6 +82,8 @@ pub struct TextBox {
     pub opacity: u8,
     /// 회전 각도 (도 단위)
     pub rotation: i16,
+    /// 글자 방향 (세로쓰기 여부)
+    pub text_direction: TextDirection,
 }
 
 impl Default for TextBox {
@@ -103,6 +106,7 @@ impl Default for TextBox {
             z_order: 0,
             opacity: 255, // Fully opaque
             rotation: 0,
+            text_direction: TextDirection::Horizontal,
         }
     }
 }
@@ -175,6 +179,12 @@ impl TextBox {
         self
     }
 
+    /// 글자 방향 설정
+    pub fn with_text_direction(mut self, direction: TextDirection) -> Self {
+        self.text_direction = direction;
+        self
+    }
+
     /// Z-order 설정
     pub fn with_z_order(mut self, z_order: u16) -> Self {
         self.z_order = z_order;
@@ -306,6 +316,7 @@ impl TextBox {
             z_order,
             opacity,
             rotation,
+            text_direction: TextDirection::Horizontal,
         })
     }
 }
@@ -1,18 +1,26 @@
 use crate::error::Result;
 use crate::model::{
-    CtrlHeader, ListHeader, PageDef, ParaCharShape, ParaLineSeg, ParaText, Paragraph, Section,
-    SectionDef,
+    ColumnDef, ControlType, CtrlHeader, ListHeader, NewNumber, PageBorderFill, PageDef, PageHide,
+    ParaCharShape, ParaLineSeg, ParaText, Paragraph, Section, SectionDef,
 };
 use crate::parser::record::{HwpTag, Record};
-use crate::reader::StreamReader;
-use crate::utils::compression::decompress_stream;
+use crate::reader::{ReaderLimits, StreamReader};
+use crate::utils::compression::decompress_stream_with_limits;
 
 pub struct BodyTextParser;
 
 impl BodyTextParser {
     pub fn parse(data: Vec<u8>, is_compressed: bool) -> Result<BodyText> {
+        Self::parse_with_limits(data, is_compressed, &ReaderLimits::default())
+    }
+
+    pub fn parse_with_limits(
+        data: Vec<u8>,
+        is_compressed: bool,
+        limits: &ReaderLimits,
+    ) -> Result<BodyText> {
         let data = if is_compressed {
-            decompress_stream(&data)?
+            decompress_stream_with_limits(&data, limits)?
         } else {
             data
         };
@@ -23,6 +31,7 @@ impl BodyTextParser {
         let mut current_paragraph: Option<Paragraph> = None;
 
         let mut first_section = true;
+        let mut record_count = 0;
 
         while reader.remaining() >= 4 {
             // Need at least 4 bytes for record header
@@ -30,6 +39,9 @@ impl BodyTextParser {
                 Ok(r) => r,
                 Err(_) => break, // Stop parsing on error
             };
+            record_count += 1;
+            limits.check_records(record_count)?;
+            limits.check_nesting_depth(record.header.level as u32)?;
 
             match HwpTag::from_u16(record.tag_id()) {
                 // Page Definition - only appears once at the beginning
@@ -37,6 +49,17 @@ impl BodyTextParser {
                     current_section.page_def = PageDef::from_record(&record).ok();
                 }
 
+                // Page border and fill for both, even and odd pages; the first applies
+                Some(HwpTag::PageBorderFill) => {
+                    if let Some(page_def) = current_section
+                        .page_def
+                        .as_mut()
+                        .filter(|page_def| page_def.border_fill.is_none())
+                    {
+                        page_def.border_fill = PageBorderFill::from_record(&record).ok();
+                    }
+                }
+
                 // SectionDefine (0x42) - Actually marks paragraph start in this document
                 Some(HwpTag::SectionDefine) => {
                     // First one is the actual section definition
@@ -47,6 +70,7 @@ impl BodyTextParser {
                         // Subsequent ones mark new paragraphs
                         if let Some(para) = current_paragraph.take() {
                             current_section.paragraphs.push(para);
+                            limits.check_paragraphs(current_section.paragraphs.len())?;
                         }
                         current_paragraph = Some(Paragraph::default());
                     }
@@ -83,6 +107,7 @@ impl BodyTextParser {
                 Some(HwpTag::ParaHeader) => {
                     if let Some(para) = current_paragraph.take() {
                         current_section.paragraphs.push(para);
+                        limits.check_paragraphs(current_section.paragraphs.len())?;
                     }
                     if let Ok(para) = Paragraph::from_header_record(&record) {
                         current_paragraph = Some(para);
@@ -114,6 +139,20 @@ impl BodyTextParser {
                 Some(HwpTag::CtrlHeader) => {
                     if let Some(ref mut para) = current_paragraph {
                         para.ctrl_header = CtrlHeader::from_record(&record).ok();
+                        match para.ctrl_header.as_ref().map(|h| h.get_control_type()) {
+                            Some(ControlType::PageHide) => {
+                                para.page_hide = PageHide::from_record(&record).ok();
+                            }
+                            Some(ControlType::ColumnDefinition) => {
+                                para.column_def = ColumnDef::from_record(&record).ok();
+                            }
+                            Some(ControlType::NewNumber) => {
+                                if let Ok(new_number) = NewNumber::from_record(&record) {
+                                    para.new_numbers.push(new_number);
+                                }
+                            }
+                            _ => {}
+                        }
                     }
                 }
 
@@ -138,6 +177,7 @@ impl BodyTextParser {
         // Add last paragraph and section
         if let Some(para) = current_paragraph {
             current_section.paragraphs.push(para);
+            limits.check_paragraphs(current_section.paragraphs.len())?;
         }
         // Always add the section even if empty - there's at least one section
         sections.push(current_section);
@@ -146,7 +186,7 @@ impl BodyTextParser {
     }
 }
 
-#[derive(Debug, Default)]
+#[derive(Debug, Default, Clone)]
 pub struct BodyText {
     pub sections: Vec<Section>,
 }
@@ -6,21 +6,30 @@ use crate::model::style::Style;
 use crate::model::tab_def::TabDef;
 use crate::model::{CharShape, DocumentProperties, FaceName, ParaShape};
 use crate::parser::record::{HwpTag, Record};
-use crate::reader::StreamReader;
-use crate::utils::compression::decompress_stream;
+use crate::reader::{ReaderLimits, StreamReader};
+use crate::utils::compression::decompress_stream_with_limits;
 
 pub struct DocInfoParser;
 
 impl DocInfoParser {
     pub fn parse(data: Vec<u8>, is_compressed: bool) -> Result<DocInfo> {
+        Self::parse_with_limits(data, is_compressed, &ReaderLimits::default())
+    }
+
+    pub fn parse_with_limits(
+        data: Vec<u8>,
+        is_compressed: bool,
+        limits: &ReaderLimits,
+    ) -> Result<DocInfo> {
         let data = if is_compressed {
-            decompress_stream(&data)?
+            decompress_stream_with_limits(&data, limits)?
         } else {
             data
         };
 
         let mut reader = StreamReader::new(data);
         let mut doc_info = DocInfo::default();
+        let mut record_count = 0;
 
         while reader.remaining() >= 4 {
             // Need at least 4 bytes for record header
@@ -28,6 +37,9 @@ impl DocInfoParser {
                 Ok(r) => r,
                 Err(_) => break, // Stop parsing on error
             };
+            record_count += 1;
+            limits.check_records(record_count)?;
+            limits.check_nesting_depth(record.header.level as u32)?;
 
             match HwpTag::from_u16(record.tag_id()) {
                 Some(HwpTag::DocumentProperties) => {
@@ -62,6 +74,9 @@ impl DocInfoParser {
                 Some(HwpTag::BinData) => {
                     doc_info.bin_data.push(BinData::from_record(&record)?);
                 }
+                Some(HwpTag::DistributeDocData) => {
+                    doc_info.distribution_record = Some(record.data.clone());
+                }
                 _ => {
                     // Skip unknown or unimplemented tags
                 }
@@ -72,7 +87,7 @@ impl DocInfoParser {
     }
 }
 
-#[derive(Debug, Default)]
+#[derive(Debug, Default, Clone)]
 pub struct DocInfo {
     pub properties: Option<DocumentProperties>,
     pub face_names: Vec<FaceName>,
@@ -84,4 +99,6 @@ pub struct DocInfo {
     pub numberings: Vec<Numbering>,
     pub bullets: Vec<Bullet>,
     pub bin_data: Vec<BinData>,
+    /// Raw 256-byte DISTRIBUTE_DOC_DATA payload of a distribution document
+    pub distribution_record: Option<Vec<u8>>,
 }
@@ -151,6 +151,16 @@ impl FileHeader {
         self
     }
 
+    /// Set distribution (배포용) document flag
+    pub fn set_distribute(&mut self, distribute: bool) -> &mut Self {
+        if distribute {
+            self.flags |= 0x04;
+        } else {
+            self.flags &= !0x04;
+        }
+        self
+    }
+
     /// Set document version
     pub fn set_version(&mut self, major: u8, minor: u8, build: u8, revision: u8) -> &mut Self {
         self.version = ((major as u32) << 24)
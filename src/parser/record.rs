@@ -4,7 +4,7 @@ use crate::reader::StreamReader;
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub struct RecordHeader {
     pub tag_id: u16,
-    pub level: u8,
+    pub level: u16,
     pub size: u32,
 }
 
@@ -18,7 +18,7 @@ impl RecordHeader {
         // Bits 10-19: level (10 bits)
         // Bits 20-31: size (12 bits)
         let tag_id = (header & 0x3FF) as u16;
-        let level = ((header >> 10) & 0x3FF) as u8;
+        let level = ((header >> 10) & 0x3FF) as u16;
         let mut size = header >> 20;
 
         // If size is 0xFFF (4095), read extended size
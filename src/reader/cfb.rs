@@ -1,10 +1,12 @@
 use crate::error::{HwpError, Result};
+use crate::reader::ReaderLimits;
 use cfb::CompoundFile;
 use std::io::{Read, Seek};
 use std::path::Path;
 
 pub struct CfbReader<F> {
     cfb: CompoundFile<F>,
+    limits: ReaderLimits,
 }
 
 impl CfbReader<std::fs::File> {
@@ -12,7 +14,10 @@ impl CfbReader<std::fs::File> {
         let file = std::fs::File::open(path)?;
         let cfb = CompoundFile::open(file)
             .map_err(|e| HwpError::Cfb(format!("Failed to open CFB: {e}")))?;
-        Ok(Self { cfb })
+        Ok(Self {
+            cfb,
+            limits: ReaderLimits::default(),
+        })
     }
 }
 
@@ -20,20 +25,44 @@ impl<F: Read + Seek> CfbReader<F> {
     pub fn new(reader: F) -> Result<Self> {
         let cfb = CompoundFile::open(reader)
             .map_err(|e| HwpError::Cfb(format!("Failed to open CFB: {e}")))?;
-        Ok(Self { cfb })
+        Ok(Self {
+            cfb,
+            limits: ReaderLimits::default(),
+        })
+    }
+
+    /// Replace the resource limits applied to streams read from this file
+    pub fn with_limits(mut self, limits: ReaderLimits) -> Self {
+        self.limits = limits;
+        self
+    }
+
+    pub fn limits(&self) -> &ReaderLimits {
+        &self.limits
     }
 
     pub fn read_stream(&mut self, path: &str) -> Result<Vec<u8>> {
-        let mut stream = self
+        let stream = self
             .cfb
             .open_stream(path)
             .map_err(|e| HwpError::NotFound(format!("Stream '{path}' not found: {e}")))?;
+        self.limits.check_stream_size(stream.len())?;
 
         let mut buffer = Vec::new();
-        stream.read_to_end(&mut buffer)?;
+        stream
+            .take(self.limits.max_stream_size)
+            .read_to_end(&mut buffer)?;
         Ok(buffer)
     }
 
+    pub fn stream_size(&self, path: &str) -> Result<u64> {
+        let entry = self
+            .cfb
+            .entry(path)
+            .map_err(|e| HwpError::NotFound(format!("Stream '{path}' not found: {e}")))?;
+        Ok(entry.len())
+    }
+
     pub fn stream_exists(&self, path: &str) -> bool {
         self.cfb.exists(path)
     }
@@ -0,0 +1,117 @@
+use crate::error::{HwpError, Result};
+
+/// Resource bounds applied while reading untrusted files.
+///
+/// The defaults are far above anything a real document needs but stop
+/// decompression bombs and record floods from exhausting memory.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ReaderLimits {
+    /// Largest stream after decompression, in bytes
+    pub max_stream_size: u64,
+    /// Most records in a single DocInfo or section stream
+    pub max_records: usize,
+    /// Most paragraphs in a single section
+    pub max_paragraphs: usize,
+    /// Deepest record level (nested controls, cells, text boxes)
+    pub max_nesting_depth: u32,
+    /// Largest uncompressed entry in an HWPX archive, in bytes
+    pub max_zip_entry_size: u64,
+    /// Most cells in one imported table, counting every grid position
+    pub max_table_cells: u64,
+}
+
+impl Default for ReaderLimits {
+    fn default() -> Self {
+        Self {
+            max_stream_size: 512 * 1024 * 1024,
+            max_records: 10_000_000,
+            max_paragraphs: 1_000_000,
+            max_nesting_depth: 256,
+            max_zip_entry_size: 512 * 1024 * 1024,
+            max_table_cells: 1_000_000,
+        }
+    }
+}
+
+impl ReaderLimits {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// No bounds at all; only use with trusted input
+    pub fn unlimited() -> Self {
+        Self {
+            max_stream_size: u64::MAX,
+            max_records: usize::MAX,
+            max_paragraphs: usize::MAX,
+            max_nesting_depth: u32::MAX,
+            max_zip_entry_size: u64::MAX,
+            max_table_cells: u64::MAX,
+        }
+    }
+
+    pub fn with_max_stream_size(mut self, bytes: u64) -> Self {
+        self.max_stream_size = bytes;
+        self
+    }
+
+    pub fn with_max_records(mut self, count: usize) -> Self {
+        self.max_records = count;
+        self
+    }
+
+    pub fn with_max_paragraphs(mut self, count: usize) -> Self {
+        self.max_paragraphs = count;
+        self
+    }
+
+    pub fn with_max_nesting_depth(mut self, depth: u32) -> Self {
+        self.max_nesting_depth = depth;
+        self
+    }
+
+    pub fn with_max_zip_entry_size(mut self, bytes: u64) -> Self {
+        self.max_zip_entry_size = bytes;
+        self
+    }
+
+    pub fn with_max_table_cells(mut self, count: u64) -> Self {
+        self.max_table_cells = count;
+        self
+    }
+
+    pub(crate) fn check_stream_size(&self, size: u64) -> Result<()> {
+        check("stream size", size, self.max_stream_size)
+    }
+
+    pub(crate) fn check_records(&self, count: usize) -> Result<()> {
+        check("record count", count as u64, self.max_records as u64)
+    }
+
+    pub(crate) fn check_paragraphs(&self, count: usize) -> Result<()> {
+        check("paragraph count", count as u64, self.max_paragraphs as u64)
+    }
+
+    pub(crate) fn check_nesting_depth(&self, depth: u32) -> Result<()> {
+        check("nesting depth", depth as u64, self.max_nesting_depth as u64)
+    }
+
+    /// Check a table's grid; HWP tables also hold at most `u16::MAX` rows and columns
+    pub(crate) fn check_table(&self, rows: u64, cols: u64) -> Result<()> {
+        check("table rows", rows, u16::MAX as u64)?;
+        check("table columns", cols, u16::MAX as u64)?;
+        check("table cells", rows * cols, self.max_table_cells)
+    }
+
+    pub(crate) fn check_zip_entry_size(&self, size: u64) -> Result<()> {
+        check("ZIP entry size", size, self.max_zip_entry_size)
+    }
+}
+
+fn check(limit: &'static str, value: u64, max: u64) -> Result<()> {
+    if value > max {
+        Err(HwpError::LimitExceeded { limit, value, max })
+    } else {
+        Ok(())
+    }
+}
@@ -1,5 +1,7 @@
 pub mod cfb;
+pub mod limits;
 pub mod stream;
 
 pub use self::cfb::CfbReader;
+pub use self::limits::ReaderLimits;
 pub use self::stream::StreamReader;
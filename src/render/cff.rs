@@ -0,0 +1,656 @@
+//! Subsetting of CFF (`CFF ` table) font programs
+//!
+//! Glyph ids are kept, as in the TrueType subset: the charstrings of glyphs
+//! that are not needed are replaced with a bare `endchar`, and subroutines no
+//! kept glyph calls with a bare `return`. Everything else is copied, with the
+//! offsets between the structures rewritten.
+
+use std::collections::BTreeSet;
+use std::ops::Range;
+
+/// Top DICT and Font DICT operators whose operands are offsets
+const CHARSET: u16 = 15;
+const ENCODING: u16 = 16;
+const CHAR_STRINGS: u16 = 17;
+const PRIVATE: u16 = 18;
+const FD_ARRAY: u16 = 0x0C00 | 36;
+const FD_SELECT: u16 = 0x0C00 | 37;
+/// Private DICT operator: local subroutines, relative to the Private DICT
+const SUBRS: u16 = 19;
+
+/// Type 2 charstring operators the walk has to follow
+const HSTEM: u8 = 1;
+const VSTEM: u8 = 3;
+const CALLSUBR: u8 = 10;
+const RETURN: u8 = 11;
+const ESCAPE: u8 = 12;
+const ENDCHAR: u8 = 14;
+const HSTEMHM: u8 = 18;
+const HINTMASK: u8 = 19;
+const CNTRMASK: u8 = 20;
+const VSTEMHM: u8 = 23;
+const SHORTINT: u8 = 28;
+const CALLGSUBR: u8 = 29;
+
+/// Deepest subroutine nesting the Type 2 format allows
+const MAX_CALL_DEPTH: usize = 10;
+
+/// Reduce a CFF table to `glyphs` (plus `.notdef`)
+///
+/// `None` when the program uses something the subsetter does not follow,
+/// such as `seac` accents or computed subroutine numbers; the caller keeps
+/// the whole table then.
+pub(crate) fn subset(data: &[u8], glyphs: &BTreeSet<u16>) -> Option<Vec<u8>> {
+    let cff = Cff::parse(data)?;
+    let num_glyphs = cff.char_strings.len();
+
+    let mut keep: BTreeSet<usize> = glyphs
+        .iter()
+        .map(|&g| g as usize)
+        .filter(|&g| g < num_glyphs)
+        .collect();
+    keep.insert(0);
+
+    let mut global_used = vec![false; cff.global_subrs.len()];
+    let mut local_used: Vec<Vec<bool>> = cff
+        .privates
+        .iter()
+        .map(|private| vec![false; private.subrs.len()])
+        .collect();
+    for &glyph in &keep {
+        let fd = cff.fd(glyph)?;
+        let mut walk = Walk {
+            data,
+            global: &cff.global_subrs,
+            local: &cff.privates.get(fd)?.subrs,
+            global_used: &mut global_used,
+            local_used: local_used.get_mut(fd)?,
+            stack: Vec::new(),
+            stems: 0,
+        };
+        walk.run(cff.char_strings[glyph].clone(), 0)?;
+    }
+
+    let char_strings: Vec<&[u8]> = (0..num_glyphs)
+        .map(|glyph| match keep.contains(&glyph) {
+            true => &data[cff.char_strings[glyph].clone()],
+            false => &[ENDCHAR][..],
+        })
+        .collect();
+    let char_strings = index(&char_strings);
+    let global_subrs = index(&subroutines(data, &cff.global_subrs, &global_used));
+
+    // Blocks after the global subroutines, in order, with the Top DICT
+    // operator that points at each
+    let mut blocks: Vec<(u16, Vec<u8>)> = Vec::new();
+    for (op, range) in [
+        (CHARSET, &cff.charset),
+        (ENCODING, &cff.encoding),
+        (FD_SELECT, &cff.fd_select),
+    ] {
+        if let Some(range) = range {
+            blocks.push((op, data[range.clone()].to_vec()));
+        }
+    }
+    blocks.push((CHAR_STRINGS, char_strings));
+
+    let privates: Vec<(Vec<u8>, Option<Vec<u8>>)> = cff
+        .privates
+        .iter()
+        .zip(&local_used)
+        .map(|(private, used)| {
+            if private.dict.operands(SUBRS).is_none() {
+                return (private.dict.write(&[]), None);
+            }
+            // The subroutines follow the DICT, so their offset is its length
+            let len = private.dict.write(&[(SUBRS, vec![0])]).len();
+            let subrs = index(&subroutines(data, &private.subrs, used));
+            (
+                private.dict.write(&[(SUBRS, vec![len as i32])]),
+                Some(subrs),
+            )
+        })
+        .collect();
+
+    // Offsets are written five bytes wide, so sizes are known before the values
+    let top_ops: Vec<(u16, Vec<i32>)> = blocks
+        .iter()
+        .map(|(op, _)| (*op, vec![0]))
+        .chain(std::iter::once(match cff.font_dicts {
+            Some(_) => (FD_ARRAY, vec![0]),
+            None => (PRIVATE, vec![0, 0]),
+        }))
+        .collect();
+    let mut at = cff.header.len()
+        + cff.names.len()
+        + index(&[cff.top.write(&top_ops)]).len()
+        + cff.strings.len()
+        + global_subrs.len();
+    let mut top_ops = Vec::new();
+    let mut tail = Vec::new();
+    for (op, block) in blocks {
+        top_ops.push((op, vec![at as i32]));
+        at += block.len();
+        tail.extend(block);
+    }
+
+    let font_dicts_len = cff.font_dicts.as_ref().map_or(0, |dicts| {
+        let dicts: Vec<Vec<u8>> = dicts
+            .iter()
+            .map(|dict| dict.write(&[(PRIVATE, vec![0, 0])]))
+            .collect();
+        index(&dicts).len()
+    });
+    let mut private_at = at + font_dicts_len;
+    let mut private_refs = Vec::new();
+    for (dict, subrs) in &privates {
+        private_refs.push(vec![dict.len() as i32, private_at as i32]);
+        private_at += dict.len() + subrs.as_ref().map_or(0, Vec::len);
+    }
+    match &cff.font_dicts {
+        Some(dicts) => {
+            let dicts: Vec<Vec<u8>> = dicts
+                .iter()
+                .zip(&private_refs)
+                .map(|(dict, private)| dict.write(&[(PRIVATE, private.clone())]))
+                .collect();
+            top_ops.push((FD_ARRAY, vec![at as i32]));
+            tail.extend(index(&dicts));
+        }
+        None => top_ops.push((PRIVATE, private_refs.first()?.clone())),
+    }
+    for (dict, subrs) in privates {
+        tail.extend(dict);
+        tail.extend(subrs.unwrap_or_default());
+    }
+
+    Some(
+        [
+            &data[cff.header.clone()],
+            &data[cff.names.clone()],
+            &index(&[cff.top.write(&top_ops)]),
+            &data[cff.strings.clone()],
+            &global_subrs,
+            &tail,
+        ]
+        .concat(),
+    )
+}
+
+/// The structures of a CFF table, as ranges into its data
+struct Cff {
+    header: Range<usize>,
+    names: Range<usize>,
+    top: Dict,
+    strings: Range<usize>,
+    global_subrs: Vec<Range<usize>>,
+    char_strings: Vec<Range<usize>>,
+    charset: Option<Range<usize>>,
+    encoding: Option<Range<usize>>,
+    fd_select: Option<Range<usize>>,
+    /// Font DICTs of a CID-keyed font
+    font_dicts: Option<Vec<Dict>>,
+    /// The Private DICT of each Font DICT, or the single one of a name-keyed font
+    privates: Vec<Private>,
+    /// Font DICT of each glyph, for CID-keyed fonts
+    fd_of_glyph: Option<Vec<u8>>,
+}
+
+struct Private {
+    dict: Dict,
+    subrs: Vec<Range<usize>>,
+}
+
+impl Cff {
+    fn parse(data: &[u8]) -> Option<Self> {
+        if *data.first()? != 1 {
+            return None;
+        }
+        let header = 0..*data.get(2)? as usize;
+        let names = header.end..read_index(data, header.end)?.1;
+        let names_end = names.end;
+        let (tops, top_end) = read_index(data, names_end)?;
+        let top = Dict::parse(data, tops.first()?.clone())?;
+        let (_, strings_end) = read_index(data, top_end)?;
+        let (global_subrs, _) = read_index(data, strings_end)?;
+
+        let char_strings_at = top.offset(CHAR_STRINGS)?;
+        let (char_strings, _) = read_index(data, char_strings_at)?;
+        let num_glyphs = char_strings.len();
+        if num_glyphs == 0 {
+            return None;
+        }
+
+        // Offsets 0 to 2 name predefined charsets and 0 and 1 predefined encodings
+        let charset = match top.offset(CHARSET) {
+            Some(at) if at > 2 => Some(at..at + charset_len(data, at, num_glyphs)?),
+            _ => None,
+        };
+        let encoding = match top.offset(ENCODING) {
+            Some(at) if at > 1 => Some(at..at + encoding_len(data, at)?),
+            _ => None,
+        };
+
+        let (font_dicts, fd_select, fd_of_glyph, privates) = match top.offset(FD_ARRAY) {
+            Some(fd_array_at) => {
+                let (dicts, _) = read_index(data, fd_array_at)?;
+                let dicts = dicts
+                    .into_iter()
+                    .map(|range| Dict::parse(data, range))
+                    .collect::<Option<Vec<_>>>()?;
+                let privates = dicts
+                    .iter()
+                    .map(|dict| Private::parse(data, dict))
+                    .collect::<Option<Vec<_>>>()?;
+                let fd_select_at = top.offset(FD_SELECT)?;
+                let (fd_of_glyph, len) = read_fd_select(data, fd_select_at, num_glyphs)?;
+                let fd_select = fd_select_at..fd_select_at + len;
+                (Some(dicts), Some(fd_select), Some(fd_of_glyph), privates)
+            }
+            None => (None, None, None, vec![Private::parse(data, &top)?]),
+        };
+
+        Some(Self {
+            header,
+            names,
+            top,
+            strings: top_end..strings_end,
+            global_subrs,
+            char_strings,
+            charset,
+            encoding,
+            fd_select,
+            font_dicts,
+            privates,
+            fd_of_glyph,
+        })
+    }
+
+    /// Index into `privates` for `glyph`
+    fn fd(&self, glyph: usize) -> Option<usize> {
+        match &self.fd_of_glyph {
+            Some(fds) => fds.get(glyph).map(|&fd| fd as usize),
+            None => Some(0),
+        }
+    }
+}
+
+impl Private {
+    /// The Private DICT a Top or Font DICT points at, with its subroutines
+    fn parse(data: &[u8], owner: &Dict) -> Option<Self> {
+        let Some(operands) = owner.operands(PRIVATE) else {
+            // A Private DICT is required, but an empty one means the same
+            return Some(Self {
+                dict: Dict::default(),
+                subrs: Vec::new(),
+            });
+        };
+        let [size, offset] = operands[..] else {
+            return None;
+        };
+        let start = usize::try_from(offset).ok()?;
+        let end = start.checked_add(usize::try_from(size).ok()?)?;
+        let dict = Dict::parse(data, start..end)?;
+        let subrs = match dict.offset(SUBRS) {
+            Some(relative) => read_index(data, start.checked_add(relative)?)?.0,
+            None => Vec::new(),
+        };
+        Some(Self { dict, subrs })
+    }
+}
+
+/// A DICT as its entries, each kept as the bytes it was read from
+#[derive(Default)]
+struct Dict {
+    entries: Vec<DictEntry>,
+}
+
+struct DictEntry {
+    op: u16,
+    /// Integer operands; reals read as zero, as no offset is ever a real
+    operands: Vec<i32>,
+    bytes: Vec<u8>,
+}
+
+impl Dict {
+    fn parse(data: &[u8], range: Range<usize>) -> Option<Self> {
+        let bytes = data.get(range)?;
+        let mut entries = Vec::new();
+        let mut operands = Vec::new();
+        let mut start = 0;
+        let mut i = 0;
+        while i < bytes.len() {
+            let b0 = bytes[i];
+            match b0 {
+                0..=21 => {
+                    let op = if b0 == ESCAPE {
+                        i += 1;
+                        0x0C00 | *bytes.get(i)? as u16
+                    } else {
+                        b0 as u16
+                    };
+                    i += 1;
+                    entries.push(DictEntry {
+                        op,
+                        operands: std::mem::take(&mut operands),
+                        bytes: bytes[start..i].to_vec(),
+                    });
+                    start = i;
+                }
+                28 => {
+                    operands
+                        .push(i16::from_be_bytes(bytes.get(i + 1..i + 3)?.try_into().ok()?) as i32);
+                    i += 3;
+                }
+                29 => {
+                    operands.push(i32::from_be_bytes(
+                        bytes.get(i + 1..i + 5)?.try_into().ok()?,
+                    ));
+                    i += 5;
+                }
+                30 => {
+                    // Real number: nibbles up to an 0xF terminator
+                    i += 1;
+                    loop {
+                        let byte = *bytes.get(i)?;
+                        i += 1;
+                        if byte >> 4 == 0x0F || byte & 0x0F == 0x0F {
+                            break;
+                        }
+                    }
+                    operands.push(0);
+                }
+                32..=246 => {
+                    operands.push(b0 as i32 - 139);
+                    i += 1;
+                }
+                247..=250 => {
+                    operands.push((b0 as i32 - 247) * 256 + *bytes.get(i + 1)? as i32 + 108);
+                    i += 2;
+                }
+                251..=254 => {
+                    operands.push(-(b0 as i32 - 251) * 256 - *bytes.get(i + 1)? as i32 - 108);
+                    i += 2;
+                }
+                _ => return None,
+            }
+        }
+        Some(Self { entries })
+    }
+
+    fn operands(&self, op: u16) -> Option<&[i32]> {
+        self.entries
+            .iter()
+            .find(|entry| entry.op == op)
+            .map(|entry| entry.operands.as_slice())
+    }
+
+    /// The single offset operand of `op`
+    fn offset(&self, op: u16) -> Option<usize> {
+        match self.operands(op)? {
+            [offset] => usize::try_from(*offset).ok(),
+            _ => None,
+        }
+    }
+
+    /// Encode the DICT, giving the operators in `replace` new operands
+    ///
+    /// Replaced operators are dropped from where they stood and written last,
+    /// five bytes per operand, so the size does not depend on their values.
+    fn write(&self, replace: &[(u16, Vec<i32>)]) -> Vec<u8> {
+        let mut out = Vec::new();
+        for entry in &self.entries {
+            if !replace.iter().any(|(op, _)| *op == entry.op) {
+                out.extend_from_slice(&entry.bytes);
+            }
+        }
+        for (op, operands) in replace {
+            for operand in operands {
+                out.push(29);
+                out.extend_from_slice(&operand.to_be_bytes());
+            }
+            if op >> 8 == ESCAPE as u16 {
+                out.push(ESCAPE);
+            }
+            out.push(*op as u8);
+        }
+        out
+    }
+}
+
+/// Walks the charstrings of kept glyphs, marking the subroutines they call
+struct Walk<'a> {
+    data: &'a [u8],
+    global: &'a [Range<usize>],
+    local: &'a [Range<usize>],
+    global_used: &'a mut [bool],
+    local_used: &'a mut [bool],
+    stack: Vec<i32>,
+    stems: usize,
+}
+
+impl Walk<'_> {
+    /// Run a charstring; `Some(true)` once the glyph has ended
+    fn run(&mut self, range: Range<usize>, depth: usize) -> Option<bool> {
+        if depth > MAX_CALL_DEPTH {
+            return None;
+        }
+        let code = self.data.get(range)?;
+        let mut i = 0;
+        while i < code.len() {
+            let b0 = code[i];
+            i += 1;
+            match b0 {
+                SHORTINT => {
+                    self.stack
+                        .push(i16::from_be_bytes(code.get(i..i + 2)?.try_into().ok()?) as i32);
+                    i += 2;
+                }
+                32..=246 => self.stack.push(b0 as i32 - 139),
+                247..=250 => {
+                    self.stack
+                        .push((b0 as i32 - 247) * 256 + *code.get(i)? as i32 + 108);
+                    i += 1;
+                }
+                251..=254 => {
+                    self.stack
+                        .push(-(b0 as i32 - 251) * 256 - *code.get(i)? as i32 - 108);
+                    i += 1;
+                }
+                255 => {
+                    // 16.16 fixed point; only its integer part could index a subroutine
+                    let fixed = i32::from_be_bytes(code.get(i..i + 4)?.try_into().ok()?);
+                    self.stack.push(fixed >> 16);
+                    i += 4;
+                }
+                HSTEM | VSTEM | HSTEMHM | VSTEMHM => {
+                    self.stems += self.stack.len() / 2;
+                    self.stack.clear();
+                }
+                HINTMASK | CNTRMASK => {
+                    // Operands left here are an implied vstemhm
+                    self.stems += self.stack.len() / 2;
+                    self.stack.clear();
+                    i += self.stems.div_ceil(8);
+                }
+                CALLSUBR | CALLGSUBR => {
+                    let operand = self.stack.pop()?;
+                    let (subrs, used) = match b0 {
+                        CALLSUBR => (self.local, &mut *self.local_used),
+                        _ => (self.global, &mut *self.global_used),
+                    };
+                    let number = operand + bias(subrs.len());
+                    let number = usize::try_from(number).ok()?;
+                    let subr = subrs.get(number)?.clone();
+                    used[number] = true;
+                    if self.run(subr, depth + 1)? {
+                        return Some(true);
+                    }
+                }
+                RETURN => return Some(false),
+                ENDCHAR => {
+                    // Four operands (five with a width) make it a seac accent,
+                    // which draws two other glyphs by their standard codes
+                    if self.stack.len() >= 4 {
+                        return None;
+                    }
+                    return Some(true);
+                }
+                ESCAPE => {
+                    // Only the flex operators; arithmetic could compute a subroutine number
+                    match code.get(i)? {
+                        0 | 34..=37 => self.stack.clear(),
+                        _ => return None,
+                    }
+                    i += 1;
+                }
+                _ => self.stack.clear(),
+            }
+        }
+        Some(false)
+    }
+}
+
+/// Subroutines with the unused ones reduced to `return`, keeping their numbers
+fn subroutines<'a>(data: &'a [u8], subrs: &[Range<usize>], used: &[bool]) -> Vec<&'a [u8]> {
+    subrs
+        .iter()
+        .zip(used)
+        .map(|(range, &used)| match used {
+            true => &data[range.clone()],
+            false => &[RETURN][..],
+        })
+        .collect()
+}
+
+/// Number added to a subroutine operand, which depends on how many there are
+fn bias(count: usize) -> i32 {
+    match count {
+        0..1240 => 107,
+        1240..33900 => 1131,
+        _ => 32768,
+    }
+}
+
+/// Items of the INDEX at `at`, and where it ends
+fn read_index(data: &[u8], at: usize) -> Option<(Vec<Range<usize>>, usize)> {
+    let count = u16::from_be_bytes(data.get(at..at + 2)?.try_into().ok()?) as usize;
+    if count == 0 {
+        return Some((Vec::new(), at + 2));
+    }
+    let off_size = *data.get(at + 2)? as usize;
+    if !(1..=4).contains(&off_size) {
+        return None;
+    }
+    let offsets_at = at + 3;
+    let offset = |i: usize| -> Option<usize> {
+        let start = offsets_at + i * off_size;
+        let bytes = data.get(start..start + off_size)?;
+        Some(
+            bytes
+                .iter()
+                .fold(0usize, |value, &b| value << 8 | b as usize),
+        )
+    };
+    // Offsets count from the byte before the item data
+    let base = offsets_at + (count + 1) * off_size - 1;
+    let mut items = Vec::with_capacity(count);
+    let mut start = offset(0)?;
+    for i in 1..=count {
+        let end = offset(i)?;
+        if end < start || base + end > data.len() {
+            return None;
+        }
+        items.push(base + start..base + end);
+        start = end;
+    }
+    Some((items, base + start))
+}
+
+fn charset_len(data: &[u8], at: usize, num_glyphs: usize) -> Option<usize> {
+    match *data.get(at)? {
+        0 => Some(1 + 2 * (num_glyphs - 1)),
+        format @ (1 | 2) => {
+            let left_size = format as usize;
+            let mut covered = 1;
+            let mut len = 1;
+            while covered < num_glyphs {
+                let left = data.get(at + len + 2..at + len + 2 + left_size)?;
+                let left = left
+                    .iter()
+                    .fold(0usize, |value, &b| value << 8 | b as usize);
+                covered += left + 1;
+                len += 2 + left_size;
+            }
+            Some(len)
+        }
+        _ => None,
+    }
+}
+
+fn encoding_len(data: &[u8], at: usize) -> Option<usize> {
+    let format = *data.get(at)?;
+    let count = *data.get(at + 1)? as usize;
+    let mut len = match format & 0x7F {
+        0 => 2 + count,
+        1 => 2 + 2 * count,
+        _ => return None,
+    };
+    if format & 0x80 != 0 {
+        len += 1 + 3 * *data.get(at + len)? as usize;
+    }
+    Some(len)
+}
+
+/// Font DICT of each glyph, and the length of the FDSelect
+fn read_fd_select(data: &[u8], at: usize, num_glyphs: usize) -> Option<(Vec<u8>, usize)> {
+    match *data.get(at)? {
+        0 => Some((
+            data.get(at + 1..at + 1 + num_glyphs)?.to_vec(),
+            1 + num_glyphs,
+        )),
+        3 => {
+            let ranges = u16::from_be_bytes(data.get(at + 1..at + 3)?.try_into().ok()?) as usize;
+            let mut fds = vec![0; num_glyphs];
+            for range in 0..ranges {
+                let record = at + 3 + range * 3;
+                let first = u16::from_be_bytes(data.get(record..record + 2)?.try_into().ok()?);
+                let fd = *data.get(record + 2)?;
+                let next = u16::from_be_bytes(data.get(record + 3..record + 5)?.try_into().ok()?);
+                let end = (next as usize).min(num_glyphs);
+                fds.get_mut(first as usize..end)?.fill(fd);
+            }
+            Some((fds, 3 + ranges * 3 + 2))
+        }
+        _ => None,
+    }
+}
+
+/// Build an INDEX with the smallest offset size that fits
+fn index(items: &[impl AsRef<[u8]>]) -> Vec<u8> {
+    let mut out = (items.len() as u16).to_be_bytes().to_vec();
+    if items.is_empty() {
+        return out;
+    }
+    let total: usize = items.iter().map(|item| item.as_ref().len()).sum::<usize>() + 1;
+    let off_size = match total {
+        0..=0xFF => 1,
+        0x100..=0xFFFF => 2,
+        0x1_0000..=0xFF_FFFF => 3,
+        _ => 4,
+    };
+    out.push(off_size as u8);
+    let mut offset = 1usize;
+    let push = |offset: usize, out: &mut Vec<u8>| {
+        out.extend_from_slice(&(offset as u32).to_be_bytes()[4 - off_size..]);
+    };
+    push(offset, &mut out);
+    for item in items {
+        offset += item.as_ref().len();
+        push(offset, &mut out);
+    }
+    for item in items {
+        out.extend_from_slice(item.as_ref());
+    }
+    out
+}
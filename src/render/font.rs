@@ -0,0 +1,929 @@
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt;
+use std::ops::Range;
+use std::path::Path;
+use std::sync::Arc;
+
+use crate::error::{HwpError, Result};
+use crate::render::cff;
+
+/// Tables copied into a subset; everything else (layout, colour, bitmaps) is dropped
+const SUBSET_TABLES: [&[u8; 4]; 13] = [
+    b"OS/2", b"cmap", b"cvt ", b"fpgm", b"glyf", b"head", b"hhea", b"hmtx", b"loca", b"maxp",
+    b"name", b"post", b"prep",
+];
+
+const FONT_EXTENSIONS: [&str; 4] = ["ttf", "otf", "ttc", "otc"];
+
+/// A single face loaded from a TrueType/OpenType file
+#[derive(Clone)]
+pub struct FontFace {
+    /// Parsed once on load. It borrows from `data`, which every clone of the
+    /// face shares and never changes; declared first so it is dropped first.
+    parsed: ttf_parser::Face<'static>,
+    data: Arc<[u8]>,
+    index: u32,
+    families: Vec<String>,
+    postscript_name: String,
+    bold: bool,
+    italic: bool,
+    /// GPOS lookups of the `kern` feature
+    kern_lookups: Vec<u16>,
+}
+
+impl FontFace {
+    fn load(data: Arc<[u8]>, index: u32) -> Result<Self> {
+        // SAFETY: the bytes are a heap allocation owned by `data`, which is
+        // stored beside the parsed face and outlives it. `face()` only lends
+        // the face out for as long as `self` is borrowed.
+        let bytes: &'static [u8] = unsafe { &*Arc::as_ptr(&data) };
+        let face = ttf_parser::Face::parse(bytes, index)
+            .map_err(|e| HwpError::InvalidFormat(format!("font face {index}: {e}")))?;
+
+        let mut families: Vec<String> = Vec::new();
+        let mut postscript_name = None;
+        for name in face.names() {
+            match name.name_id {
+                ttf_parser::name_id::FAMILY | ttf_parser::name_id::TYPOGRAPHIC_FAMILY => {
+                    if let Some(family) = name.to_string() {
+                        if !families.iter().any(|f| f.eq_ignore_ascii_case(&family)) {
+                            families.push(family);
+                        }
+                    }
+                }
+                ttf_parser::name_id::POST_SCRIPT_NAME if postscript_name.is_none() => {
+                    postscript_name = name.to_string();
+                }
+                _ => {}
+            }
+        }
+
+        let postscript_name = postscript_name
+            .or_else(|| families.first().cloned())
+            .unwrap_or_else(|| "Font".to_string())
+            .chars()
+            .filter(|c| c.is_ascii_graphic() && !"[](){}<>/%#".contains(*c))
+            .collect();
+        let bold = face.is_bold() || face.weight().to_number() >= 600;
+        let italic = face.is_italic() || face.is_oblique();
+
+        let mut kern_lookups: Vec<u16> = face
+            .tables()
+            .gpos
+            .iter()
+            .flat_map(|gpos| gpos.features)
+            .filter(|feature| feature.tag == ttf_parser::Tag::from_bytes(b"kern"))
+            .flat_map(|feature| feature.lookup_indices)
+            .collect();
+        kern_lookups.sort_unstable();
+        kern_lookups.dedup();
+
+        Ok(Self {
+            parsed: face,
+            data,
+            index,
+            families,
+            postscript_name,
+            bold,
+            italic,
+            kern_lookups,
+        })
+    }
+
+    /// Parsed view of the face for glyph and metric queries
+    pub fn face(&self) -> &ttf_parser::Face<'_> {
+        &self.parsed
+    }
+
+    /// Kerning between two glyphs in font units
+    ///
+    /// Pair adjustments of the GPOS `kern` feature are used when the face has
+    /// them, and the legacy `kern` table otherwise.
+    pub fn kerning(&self, left: ttf_parser::GlyphId, right: ttf_parser::GlyphId) -> i16 {
+        use ttf_parser::gpos::{PairAdjustment, PositioningSubtable};
+
+        let tables = self.parsed.tables();
+        let Some(gpos) = tables.gpos.filter(|_| !self.kern_lookups.is_empty()) else {
+            return tables
+                .kern
+                .into_iter()
+                .flat_map(|kern| kern.subtables)
+                .filter(|table| table.horizontal && !table.variable)
+                .find_map(|table| table.glyphs_kerning(left, right))
+                .unwrap_or(0);
+        };
+
+        let mut kerning = 0i16;
+        for &index in &self.kern_lookups {
+            let Some(lookup) = gpos.lookups.get(index) else {
+                continue;
+            };
+            // The first subtable covering the pair applies
+            let adjustment = lookup
+                .subtables
+                .into_iter::<PositioningSubtable>()
+                .find_map(|subtable| {
+                    let PositioningSubtable::Pair(pair) = subtable else {
+                        return None;
+                    };
+                    let coverage = pair.coverage().get(left)?;
+                    let (first, _) = match pair {
+                        PairAdjustment::Format1 { sets, .. } => sets.get(coverage)?.get(right)?,
+                        PairAdjustment::Format2 {
+                            classes, matrix, ..
+                        } => matrix.get((classes.0.get(left), classes.1.get(right)))?,
+                    };
+                    Some(first.x_advance)
+                });
+            kerning = kerning.saturating_add(adjustment.unwrap_or(0));
+        }
+        kerning
+    }
+
+    /// Family names from the name table, in every language the font provides
+    pub fn families(&self) -> &[String] {
+        &self.families
+    }
+
+    pub fn postscript_name(&self) -> &str {
+        &self.postscript_name
+    }
+
+    pub fn is_bold(&self) -> bool {
+        self.bold
+    }
+
+    pub fn is_italic(&self) -> bool {
+        self.italic
+    }
+
+    /// True when glyphs are TrueType outlines (a `glyf` table) rather than CFF
+    pub fn has_glyf_outlines(&self) -> bool {
+        self.parsed.tables().glyf.is_some()
+    }
+
+    /// True when glyphs are CFF outlines (a `CFF ` table)
+    pub fn has_cff_outlines(&self) -> bool {
+        self.parsed.tables().cff.is_some()
+    }
+
+    pub fn has_family(&self, family: &str) -> bool {
+        let family = family.trim();
+        self.families.iter().any(|f| {
+            f.eq_ignore_ascii_case(family) || f.replace(' ', "") == family.replace(' ', "")
+        })
+    }
+
+    /// Build a standalone font holding only `glyphs` (plus `.notdef` and composite parts)
+    ///
+    /// Glyph ids are preserved, so the subset can be addressed with the original ids.
+    /// CFF charstrings and the subroutines they call are reduced the same way;
+    /// a CFF program the subsetter cannot follow is kept whole. Faces with other
+    /// outlines are extracted from their collection but not reduced.
+    pub fn subset(&self, glyphs: &BTreeSet<u16>) -> Result<Vec<u8>> {
+        let tables = self.table_records()?;
+        let table = |tag: &[u8; 4]| {
+            tables
+                .iter()
+                .find(|(t, _)| t == tag)
+                .map(|(_, range)| &self.data[range.clone()])
+        };
+
+        let (Some(glyf), Some(loca), Some(head), Some(maxp)) = (
+            table(b"glyf"),
+            table(b"loca"),
+            table(b"head"),
+            table(b"maxp"),
+        ) else {
+            let cff = table(b"CFF ");
+            let parts = tables
+                .iter()
+                .filter(|(tag, _)| cff.is_none() || tag == b"CFF " || SUBSET_TABLES.contains(&tag))
+                .map(|(tag, range)| {
+                    let data = &self.data[range.clone()];
+                    match tag {
+                        b"CFF " => (
+                            *tag,
+                            cff::subset(data, glyphs).unwrap_or_else(|| data.to_vec()),
+                        ),
+                        _ => (*tag, data.to_vec()),
+                    }
+                })
+                .collect();
+            return Ok(sfnt(parts));
+        };
+
+        let invalid =
+            || HwpError::InvalidFormat(format!("{}: bad glyph table", self.postscript_name));
+        let num_glyphs = read_u16(maxp, 4).ok_or_else(invalid)? as usize;
+        let long_loca = read_u16(head, 50).ok_or_else(invalid)? != 0;
+        let glyph_range = |gid: usize| -> Option<Range<usize>> {
+            let (start, end) = if long_loca {
+                (
+                    read_u32(loca, gid * 4)? as usize,
+                    read_u32(loca, gid * 4 + 4)? as usize,
+                )
+            } else {
+                (
+                    read_u16(loca, gid * 2)? as usize * 2,
+                    read_u16(loca, gid * 2 + 2)? as usize * 2,
+                )
+            };
+            (start <= end && end <= glyf.len()).then_some(start..end)
+        };
+
+        // Composite glyphs pull in their components
+        let mut keep: BTreeSet<u16> = glyphs
+            .iter()
+            .copied()
+            .filter(|&g| (g as usize) < num_glyphs)
+            .collect();
+        keep.insert(0);
+        let mut pending: Vec<u16> = keep.iter().copied().collect();
+        while let Some(gid) = pending.pop() {
+            let Some(range) = glyph_range(gid as usize) else {
+                continue;
+            };
+            for component in composite_components(&glyf[range]) {
+                if (component as usize) < num_glyphs && keep.insert(component) {
+                    pending.push(component);
+                }
+            }
+        }
+
+        let mut new_glyf = Vec::new();
+        let mut new_loca = Vec::with_capacity((num_glyphs + 1) * 4);
+        for gid in 0..num_glyphs {
+            new_loca.extend_from_slice(&(new_glyf.len() as u32).to_be_bytes());
+            if keep.contains(&(gid as u16)) {
+                if let Some(range) = glyph_range(gid) {
+                    new_glyf.extend_from_slice(&glyf[range]);
+                    while new_glyf.len() % 4 != 0 {
+                        new_glyf.push(0);
+                    }
+                }
+            }
+        }
+        new_loca.extend_from_slice(&(new_glyf.len() as u32).to_be_bytes());
+
+        let mut parts = Vec::new();
+        for (tag, range) in &tables {
+            if !SUBSET_TABLES.contains(&tag) {
+                continue;
+            }
+            let data = match tag {
+                b"glyf" => std::mem::take(&mut new_glyf),
+                b"loca" => std::mem::take(&mut new_loca),
+                b"head" => {
+                    let mut head = self.data[range.clone()].to_vec();
+                    if head.len() < 54 {
+                        return Err(invalid());
+                    }
+                    head[8..12].fill(0);
+                    head[50..52].copy_from_slice(&1u16.to_be_bytes());
+                    head
+                }
+                _ => self.data[range.clone()].to_vec(),
+            };
+            parts.push((*tag, data));
+        }
+        Ok(sfnt(parts))
+    }
+
+    /// Table directory of this face as (tag, byte range in the file)
+    fn table_records(&self) -> Result<Vec<([u8; 4], Range<usize>)>> {
+        let data = &self.data[..];
+        let invalid =
+            || HwpError::InvalidFormat(format!("{}: bad table directory", self.postscript_name));
+        let offset = if data.starts_with(b"ttcf") {
+            read_u32(data, 12 + self.index as usize * 4).ok_or_else(invalid)? as usize
+        } else {
+            0
+        };
+        let count = read_u16(data, offset + 4).ok_or_else(invalid)? as usize;
+        let mut tables = Vec::with_capacity(count);
+        for i in 0..count {
+            let record = offset + 12 + i * 16;
+            let tag = data.get(record..record + 4).ok_or_else(invalid)?;
+            let start = read_u32(data, record + 8).ok_or_else(invalid)? as usize;
+            let length = read_u32(data, record + 12).ok_or_else(invalid)? as usize;
+            if start.checked_add(length).is_none_or(|end| end > data.len()) {
+                return Err(invalid());
+            }
+            tables.push(([tag[0], tag[1], tag[2], tag[3]], start..start + length));
+        }
+        Ok(tables)
+    }
+}
+
+impl fmt::Debug for FontFace {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("FontFace")
+            .field("families", &self.families)
+            .field("postscript_name", &self.postscript_name)
+            .field("bold", &self.bold)
+            .field("italic", &self.italic)
+            .finish()
+    }
+}
+
+/// Vertical font metrics as fractions of the em size
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct VerticalMetrics {
+    /// Height above the baseline
+    pub ascent: f32,
+    /// Depth below the baseline, as a positive number
+    pub descent: f32,
+    pub line_gap: f32,
+}
+
+/// Source of font metrics for text measurement in [`LayoutEngine`](super::LayoutEngine)
+pub trait FontProvider {
+    /// Advance of each character of `text` as a fraction of the em size
+    ///
+    /// Kerning with the following character is folded into each advance.
+    /// `None` when no face is available, so the caller can estimate instead.
+    fn advances(&self, family: &str, bold: bool, italic: bool, text: &str) -> Option<Vec<f32>>;
+
+    /// Ascent, descent and line gap of the face that would draw `family`
+    fn vertical_metrics(&self, family: &str, bold: bool, italic: bool) -> Option<VerticalMetrics>;
+}
+
+/// Open fonts standing in for Myeongjo (serif) faces
+const SERIF: &[&str] = &[
+    "Noto Serif CJK KR",
+    "Noto Serif KR",
+    "NanumMyeongjo",
+    "UnBatang",
+    "serif",
+];
+
+/// Open fonts standing in for Gothic and Dotum (sans-serif) faces
+const SANS_SERIF: &[&str] = &[
+    "Noto Sans CJK KR",
+    "Noto Sans KR",
+    "NanumGothic",
+    "UnDotum",
+    "sans-serif",
+];
+
+/// Open fonts standing in for heavy headline faces
+const HEADLINE: &[&str] = &[
+    "Noto Sans CJK KR Black",
+    "NanumGothic ExtraBold",
+    "Noto Sans CJK KR",
+    "NanumGothic",
+    "sans-serif",
+];
+
+/// Open fonts standing in for fixed-width faces
+const MONOSPACE: &[&str] = &[
+    "D2Coding",
+    "NanumGothicCoding",
+    "Noto Sans Mono CJK KR",
+    "Liberation Mono",
+    "monospace",
+];
+
+/// Fonts Hancom Office and Windows install, with the substitutes for each
+const HANCOM_FONTS: &[(&[&str], &[&str])] = &[
+    (
+        &[
+            "함초롬바탕",
+            "HCR Batang",
+            "한컴바탕",
+            "한컴 바탕",
+            "바탕",
+            "Batang",
+            "휴먼명조",
+            "신명조",
+            "HY신명조",
+            "한양신명조",
+            "HY견명조",
+            "견명조",
+            "새바탕",
+            "궁서",
+            "Gungsuh",
+            "명조",
+        ],
+        SERIF,
+    ),
+    (
+        &[
+            "함초롬돋움",
+            "HCR Dotum",
+            "한컴돋움",
+            "한컴 돋움",
+            "한컴 윤고딕",
+            "한컴윤고딕",
+            "윤고딕",
+            "돋움",
+            "Dotum",
+            "굴림",
+            "Gulim",
+            "새돋움",
+            "새굴림",
+            "맑은 고딕",
+            "Malgun Gothic",
+            "휴먼고딕",
+            "HY중고딕",
+            "한양중고딕",
+            "중고딕",
+            "고딕",
+        ],
+        SANS_SERIF,
+    ),
+    (
+        &[
+            "HY헤드라인M",
+            "헤드라인",
+            "휴먼둥근헤드라인",
+            "HY견고딕",
+            "견고딕",
+            "휴먼엑스포",
+        ],
+        HEADLINE,
+    ),
+    (
+        &[
+            "바탕체",
+            "BatangChe",
+            "돋움체",
+            "DotumChe",
+            "굴림체",
+            "GulimChe",
+            "궁서체",
+        ],
+        MONOSPACE,
+    ),
+    (
+        &["Times New Roman", "Times"],
+        &["Liberation Serif", "DejaVu Serif", "serif"],
+    ),
+    (
+        &["Arial", "Helvetica"],
+        &["Liberation Sans", "DejaVu Sans", "sans-serif"],
+    ),
+    (
+        &["Courier New", "Courier"],
+        &["Liberation Mono", "DejaVu Sans Mono", "monospace"],
+    ),
+];
+
+/// Substitutes for font families that are not installed
+///
+/// Documents name the fonts of the machine they were written on, often
+/// Hancom's own. The default map sends common Hancom and Windows fonts to
+/// open equivalents (Noto CJK, Nanum, Liberation), ending each list with a
+/// CSS generic family. Names match ignoring case and spaces, and a trailing
+/// weight number such as the 240 of `한컴 윤고딕 240`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FontMap {
+    substitutes: BTreeMap<String, Vec<String>>,
+}
+
+impl Default for FontMap {
+    fn default() -> Self {
+        let mut map = Self::empty();
+        for (families, substitutes) in HANCOM_FONTS {
+            for family in *families {
+                map.insert(family, substitutes.iter().copied());
+            }
+        }
+        map
+    }
+}
+
+impl FontMap {
+    /// Map with the built-in table of Hancom fonts
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Map without any substitutes
+    pub fn empty() -> Self {
+        Self {
+            substitutes: BTreeMap::new(),
+        }
+    }
+
+    /// Set the substitutes for `family`, most preferred first, replacing any it had
+    pub fn insert<S: Into<String>>(
+        &mut self,
+        family: &str,
+        substitutes: impl IntoIterator<Item = S>,
+    ) {
+        self.substitutes.insert(
+            normalize_family(family),
+            substitutes.into_iter().map(Into::into).collect(),
+        );
+    }
+
+    /// Builder form of [`insert`](Self::insert)
+    pub fn with<S: Into<String>>(
+        mut self,
+        family: &str,
+        substitutes: impl IntoIterator<Item = S>,
+    ) -> Self {
+        self.insert(family, substitutes);
+        self
+    }
+
+    pub fn remove(&mut self, family: &str) {
+        self.substitutes.remove(&normalize_family(family));
+    }
+
+    /// Substitutes for `family`, empty when it has none
+    pub fn get(&self, family: &str) -> &[String] {
+        let key = normalize_family(family);
+        self.substitutes
+            .get(&key)
+            .or_else(|| {
+                let base = key.trim_end_matches(|c: char| c.is_ascii_digit());
+                (base.len() < key.len())
+                    .then(|| self.substitutes.get(base))
+                    .flatten()
+            })
+            .map_or(&[], Vec::as_slice)
+    }
+
+    /// `family` followed by its substitutes
+    pub fn candidates<'a>(&'a self, family: &'a str) -> impl Iterator<Item = &'a str> {
+        std::iter::once(family).chain(self.get(family).iter().map(String::as_str))
+    }
+}
+
+/// Key a family name is matched by
+fn normalize_family(family: &str) -> String {
+    family
+        .chars()
+        .filter(|c| !c.is_whitespace())
+        .flat_map(char::to_lowercase)
+        .collect()
+}
+
+/// A font family the document uses that no installed face carries
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MissingFont {
+    pub family: String,
+    /// Installed family from the [`FontMap`] used instead; `None` when text
+    /// falls back to whichever face is closest in style
+    pub substitute: Option<String>,
+}
+
+/// A run of text drawn with one face of a [`FontSet`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FontSegment {
+    /// Index into [`FontSet::faces`]
+    pub face: usize,
+    /// Byte range of the run in the source text
+    pub range: Range<usize>,
+}
+
+/// Fonts available to the renderer, loaded from local font files
+///
+/// Faces are looked up by family name and style, then by the family's
+/// substitutes in the [`FontMap`]; characters the chosen face lacks fall
+/// back to the first other face that has them.
+#[derive(Debug, Clone, Default)]
+pub struct FontSet {
+    faces: Vec<FontFace>,
+    font_map: FontMap,
+}
+
+impl FontSet {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Load every `.ttf`, `.otf`, `.ttc` and `.otc` file below `dir`
+    pub fn from_dir<P: AsRef<Path>>(dir: P) -> Result<Self> {
+        let mut fonts = Self::new();
+        let mut pending = vec![dir.as_ref().to_path_buf()];
+        while let Some(dir) = pending.pop() {
+            let mut entries = std::fs::read_dir(&dir)?
+                .map(|entry| entry.map(|e| e.path()))
+                .collect::<std::io::Result<Vec<_>>>()?;
+            entries.sort();
+            for path in entries {
+                if path.is_dir() {
+                    pending.push(path);
+                } else if path
+                    .extension()
+                    .and_then(|e| e.to_str())
+                    .is_some_and(|e| FONT_EXTENSIONS.contains(&e.to_ascii_lowercase().as_str()))
+                {
+                    // One unreadable file should not hide the rest of the directory
+                    let _ = fonts.add_file(&path);
+                }
+            }
+        }
+        Ok(fonts)
+    }
+
+    /// Load all faces of a font file, returning how many were added
+    pub fn add_file<P: AsRef<Path>>(&mut self, path: P) -> Result<usize> {
+        self.add_bytes(std::fs::read(path)?)
+    }
+
+    /// Load all faces of an in-memory font file, returning how many were added
+    pub fn add_bytes(&mut self, data: Vec<u8>) -> Result<usize> {
+        let count = ttf_parser::fonts_in_collection(&data).unwrap_or(1);
+        let data: Arc<[u8]> = data.into();
+        let faces = (0..count)
+            .map(|index| FontFace::load(data.clone(), index))
+            .collect::<Result<Vec<_>>>()?;
+        self.faces.extend(faces);
+        Ok(count as usize)
+    }
+
+    pub fn faces(&self) -> &[FontFace] {
+        &self.faces
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.faces.is_empty()
+    }
+
+    pub fn font_map(&self) -> &FontMap {
+        &self.font_map
+    }
+
+    pub fn font_map_mut(&mut self) -> &mut FontMap {
+        &mut self.font_map
+    }
+
+    /// Replace the substitutes used for families that are not installed
+    pub fn with_font_map(mut self, font_map: FontMap) -> Self {
+        self.font_map = font_map;
+        self
+    }
+
+    /// Whether some face carries `family` itself
+    pub fn has_family(&self, family: &str) -> bool {
+        self.faces.iter().any(|face| face.has_family(family))
+    }
+
+    /// Installed family that draws `family`: itself, or else its first
+    /// installed substitute
+    pub fn resolve<'a>(&'a self, family: &'a str) -> Option<&'a str> {
+        self.font_map
+            .candidates(family)
+            .find(|&candidate| self.has_family(candidate))
+    }
+
+    /// Face of `family`, or of its first installed substitute, closest to the
+    /// requested style
+    pub fn find(&self, family: &str, bold: bool, italic: bool) -> Option<usize> {
+        self.find_matching(family, bold, italic, |_| true)
+    }
+
+    /// Like [`find`](Self::find), restricted to faces accepted by `usable`
+    fn find_matching(
+        &self,
+        family: &str,
+        bold: bool,
+        italic: bool,
+        usable: impl Fn(&FontFace) -> bool,
+    ) -> Option<usize> {
+        self.font_map.candidates(family).find_map(|candidate| {
+            self.best_match(
+                |face| usable(face) && face.has_family(candidate),
+                bold,
+                italic,
+            )
+        })
+    }
+
+    /// Report how each family in `families` is drawn when it is not installed
+    pub fn missing<'a>(&self, families: impl IntoIterator<Item = &'a str>) -> Vec<MissingFont> {
+        let families: BTreeSet<&str> = families
+            .into_iter()
+            .map(str::trim)
+            .filter(|family| !family.is_empty())
+            .collect();
+        families
+            .into_iter()
+            .filter(|family| !self.has_family(family))
+            .map(|family| MissingFont {
+                family: family.to_string(),
+                substitute: self.resolve(family).map(str::to_string),
+            })
+            .collect()
+    }
+
+    /// Split `text` into runs by the face that should draw each character
+    ///
+    /// `family` is preferred; when it is missing the closest style of any face is used.
+    /// Empty when the set has no faces.
+    pub fn segments(&self, text: &str, family: &str, bold: bool, italic: bool) -> Vec<FontSegment> {
+        self.segments_matching(text, family, bold, italic, |_| true)
+    }
+
+    /// Like [`segments`](Self::segments), restricted to faces accepted by `usable`
+    pub(crate) fn segments_matching(
+        &self,
+        text: &str,
+        family: &str,
+        bold: bool,
+        italic: bool,
+        usable: impl Fn(&FontFace) -> bool,
+    ) -> Vec<FontSegment> {
+        let Some(primary) = self
+            .find_matching(family, bold, italic, &usable)
+            .or_else(|| self.best_match(&usable, bold, italic))
+        else {
+            return Vec::new();
+        };
+
+        let mut fallbacks: Vec<usize> = (0..self.faces.len())
+            .filter(|&i| i != primary && usable(&self.faces[i]))
+            .collect();
+        fallbacks.sort_by_key(|&i| std::cmp::Reverse(self.style_score(i, bold, italic)));
+
+        let covers = |i: usize, c: char| self.faces[i].face().glyph_index(c).is_some();
+        let mut segments: Vec<FontSegment> = Vec::new();
+        for (offset, c) in text.char_indices() {
+            let face = match segments.last() {
+                // Spaces stay with the surrounding run
+                Some(last) if c.is_whitespace() && covers(last.face, c) => last.face,
+                _ if covers(primary, c) => primary,
+                _ => fallbacks
+                    .iter()
+                    .copied()
+                    .find(|&i| covers(i, c))
+                    .unwrap_or(primary),
+            };
+            match segments.last_mut() {
+                Some(last) if last.face == face => last.range.end = offset + c.len_utf8(),
+                _ => segments.push(FontSegment {
+                    face,
+                    range: offset..offset + c.len_utf8(),
+                }),
+            }
+        }
+        segments
+    }
+
+    fn best_match(
+        &self,
+        filter: impl Fn(&FontFace) -> bool,
+        bold: bool,
+        italic: bool,
+    ) -> Option<usize> {
+        let mut best: Option<(usize, u8)> = None;
+        for (i, face) in self.faces.iter().enumerate() {
+            if !filter(face) {
+                continue;
+            }
+            let score = self.style_score(i, bold, italic);
+            if best.is_none_or(|(_, s)| score > s) {
+                best = Some((i, score));
+            }
+        }
+        best.map(|(i, _)| i)
+    }
+
+    fn style_score(&self, index: usize, bold: bool, italic: bool) -> u8 {
+        let face = &self.faces[index];
+        u8::from(face.bold == bold) * 2 + u8::from(face.italic == italic)
+    }
+}
+
+impl FontProvider for FontSet {
+    fn advances(&self, family: &str, bold: bool, italic: bool, text: &str) -> Option<Vec<f32>> {
+        let segments = self.segments(text, family, bold, italic);
+        if segments.is_empty() {
+            return None;
+        }
+
+        let mut advances = Vec::with_capacity(text.len());
+        for segment in segments {
+            let font = &self.faces[segment.face];
+            let face = font.face();
+            let units_per_em = face.units_per_em().max(1) as f32;
+            let glyphs: Vec<ttf_parser::GlyphId> = text[segment.range]
+                .chars()
+                .map(|c| face.glyph_index(c).unwrap_or(ttf_parser::GlyphId(0)))
+                .collect();
+            for (i, &glyph) in glyphs.iter().enumerate() {
+                let mut advance = face.glyph_hor_advance(glyph).unwrap_or(0) as f32;
+                if let Some(&next) = glyphs.get(i + 1) {
+                    advance += font.kerning(glyph, next) as f32;
+                }
+                advances.push(advance / units_per_em);
+            }
+        }
+        Some(advances)
+    }
+
+    fn vertical_metrics(&self, family: &str, bold: bool, italic: bool) -> Option<VerticalMetrics> {
+        let index = self
+            .find(family, bold, italic)
+            .or_else(|| self.best_match(|_| true, bold, italic))?;
+        let face = self.faces[index].face();
+        let units_per_em = face.units_per_em().max(1) as f32;
+        Some(VerticalMetrics {
+            ascent: face.ascender() as f32 / units_per_em,
+            descent: -(face.descender() as f32) / units_per_em,
+            line_gap: face.line_gap() as f32 / units_per_em,
+        })
+    }
+}
+
+/// Glyph ids referenced by a composite glyph
+fn composite_components(glyph: &[u8]) -> Vec<u16> {
+    const ARG_1_AND_2_ARE_WORDS: u16 = 0x0001;
+    const WE_HAVE_A_SCALE: u16 = 0x0008;
+    const MORE_COMPONENTS: u16 = 0x0020;
+    const WE_HAVE_AN_X_AND_Y_SCALE: u16 = 0x0040;
+    const WE_HAVE_A_TWO_BY_TWO: u16 = 0x0080;
+
+    let mut components = Vec::new();
+    if glyph.len() < 10 || i16::from_be_bytes([glyph[0], glyph[1]]) >= 0 {
+        return components;
+    }
+    let mut offset = 10;
+    while let (Some(flags), Some(gid)) = (read_u16(glyph, offset), read_u16(glyph, offset + 2)) {
+        components.push(gid);
+        offset += 4;
+        offset += if flags & ARG_1_AND_2_ARE_WORDS != 0 {
+            4
+        } else {
+            2
+        };
+        if flags & WE_HAVE_A_SCALE != 0 {
+            offset += 2;
+        } else if flags & WE_HAVE_AN_X_AND_Y_SCALE != 0 {
+            offset += 4;
+        } else if flags & WE_HAVE_A_TWO_BY_TWO != 0 {
+            offset += 8;
+        }
+        if flags & MORE_COMPONENTS == 0 {
+            break;
+        }
+    }
+    components
+}
+
+/// Assemble an sfnt file from tables, fixing up checksums
+fn sfnt(mut tables: Vec<([u8; 4], Vec<u8>)>) -> Vec<u8> {
+    tables.sort_by_key(|(tag, _)| *tag);
+    let count = tables.len() as u16;
+    let entry_selector = 15 - count.max(1).leading_zeros() as u16;
+    let search_range = (1u16 << entry_selector) * 16;
+    let cff = tables
+        .iter()
+        .any(|(tag, _)| tag == b"CFF " || tag == b"CFF2");
+
+    let mut out = Vec::new();
+    out.extend_from_slice(if cff { b"OTTO" } else { &[0, 1, 0, 0] });
+    out.extend_from_slice(&count.to_be_bytes());
+    out.extend_from_slice(&search_range.to_be_bytes());
+    out.extend_from_slice(&entry_selector.to_be_bytes());
+    out.extend_from_slice(&(count * 16 - search_range).to_be_bytes());
+
+    let mut offset = 12 + tables.len() * 16;
+    let mut head_offset = None;
+    for (tag, data) in &tables {
+        if tag == b"head" {
+            head_offset = Some(offset);
+        }
+        out.extend_from_slice(tag);
+        out.extend_from_slice(&checksum(data).to_be_bytes());
+        out.extend_from_slice(&(offset as u32).to_be_bytes());
+        out.extend_from_slice(&(data.len() as u32).to_be_bytes());
+        offset += data.len().div_ceil(4) * 4;
+    }
+    for (_, data) in &tables {
+        out.extend_from_slice(data);
+        out.resize(out.len().div_ceil(4) * 4, 0);
+    }
+
+    if let Some(head) = head_offset.filter(|&h| h + 12 <= out.len()) {
+        out[head + 8..head + 12].fill(0);
+        let adjustment = 0xB1B0_AFBAu32.wrapping_sub(checksum(&out));
+        out[head + 8..head + 12].copy_from_slice(&adjustment.to_be_bytes());
+    }
+    out
+}
+
+fn checksum(data: &[u8]) -> u32 {
+    data.chunks(4).fold(0u32, |sum, chunk| {
+        let mut word = [0u8; 4];
+        word[..chunk.len()].copy_from_slice(chunk);
+        sum.wrapping_add(u32::from_be_bytes(word))
+    })
+}
+
+fn read_u16(data: &[u8], offset: usize) -> Option<u16> {
+    Some(u16::from_be_bytes(
+        data.get(offset..offset + 2)?.try_into().ok()?,
+    ))
+}
+
+fn read_u32(data: &[u8], offset: usize) -> Option<u32> {
+    Some(u32::from_be_bytes(
+        data.get(offset..offset + 4)?.try_into().ok()?,
+    ))
+}
@@ -0,0 +1,238 @@
+use std::ops::Range;
+
+use crate::render::layout::{LayoutResult, RenderedLine, RenderedParagraph, VerticalFrame};
+
+/// Place in the body text, before the character at `char_offset`
+///
+/// Positions order as they read: by section, then paragraph, then character.
+/// Paragraphs in table cells are addressed like any other paragraph of the section.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct TextPosition {
+    pub section: usize,
+    pub paragraph: usize,
+    pub char_offset: usize,
+}
+
+impl TextPosition {
+    pub fn new(section: usize, paragraph: usize, char_offset: usize) -> Self {
+        Self {
+            section,
+            paragraph,
+            char_offset,
+        }
+    }
+}
+
+/// Area on a page, in HWP units
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PageRect {
+    /// Index into [`LayoutResult::pages`]
+    pub page: usize,
+    pub x: i32,
+    pub y: i32,
+    pub width: i32,
+    pub height: i32,
+}
+
+/// A laid out line of body text and where it sits
+struct LineRef<'a> {
+    page: usize,
+    /// Frame turning the line onto the page in a vertical section
+    frame: Option<VerticalFrame>,
+    paragraph: &'a RenderedParagraph,
+    line: &'a RenderedLine,
+}
+
+impl LineRef<'_> {
+    /// Page area of a layout area on the line's page
+    fn rect(&self, area: (i32, i32, i32, i32)) -> PageRect {
+        let (x, y, width, height) = self.frame.map_or(area, |frame| frame.to_page_rect(area));
+        PageRect {
+            page: self.page,
+            x,
+            y,
+            width,
+            height,
+        }
+    }
+
+    fn position(&self, char_offset: usize) -> TextPosition {
+        TextPosition::new(
+            self.paragraph.section_index,
+            self.paragraph.paragraph_index,
+            char_offset,
+        )
+    }
+
+    fn start(&self) -> TextPosition {
+        self.position(self.line.text_range.start)
+    }
+
+    fn end(&self) -> TextPosition {
+        self.position(self.line.text_range.end)
+    }
+
+    /// Left edge of the character at `char_offset`, or the end of the text
+    /// drawn on the line past its last character
+    fn x_of(&self, char_offset: usize) -> i32 {
+        let mut end = None;
+        for run in &self.line.runs {
+            let mut x = run.x;
+            for (k, advance) in run.advances.iter().enumerate() {
+                if run.char_offset + k >= char_offset {
+                    return x;
+                }
+                x += advance;
+            }
+            end = Some(x);
+        }
+        end.unwrap_or(self.paragraph.x)
+    }
+
+    /// Character boundary nearest to `x`
+    fn offset_at(&self, x: i32) -> usize {
+        let range = &self.line.text_range;
+        let mut offset = range.start;
+        for run in &self.line.runs {
+            let mut left = run.x;
+            for (k, advance) in run.advances.iter().enumerate() {
+                let index = run.char_offset + k;
+                // A hyphen ending the line is not part of the paragraph
+                if index >= range.end {
+                    return range.end;
+                }
+                if x < left + advance / 2 {
+                    return index;
+                }
+                left += advance;
+                offset = index + 1;
+            }
+        }
+        offset.clamp(range.start, range.end)
+    }
+}
+
+impl LayoutResult {
+    /// Position in the body text nearest to a point on a page, in HWP units
+    ///
+    /// A point inside a table cell finds the cell's text; anywhere else the
+    /// closest line is used, preferring lines of the column under the point.
+    /// Headers, footers, footnotes and text boxes are not hit.
+    pub fn hit_test(&self, page: usize, x: i32, y: i32) -> Option<TextPosition> {
+        let rendered = self.pages.get(page)?;
+        let frame = rendered.frame();
+        // Vertical lines are measured along and across as if horizontal
+        let (x, y) = frame.map_or((x, y), |frame| frame.to_layout(x, y));
+        let mut lines = Vec::new();
+        collect_lines(
+            (page, frame),
+            &rendered.paragraphs,
+            Some((x, y)),
+            &mut lines,
+        );
+
+        let distance = |from: i32, (start, length): (i32, i32)| {
+            if from < start {
+                start - from
+            } else {
+                (from - (start + length)).max(0)
+            }
+        };
+        let nearest = lines.iter().min_by_key(|line| {
+            (
+                distance(x, (line.paragraph.x, line.paragraph.width)),
+                distance(y, (line.line.y, line.line.height - 1)),
+            )
+        })?;
+        Some(nearest.position(nearest.offset_at(x)))
+    }
+
+    /// Zero-width caret box at a text position, as tall as its line
+    ///
+    /// A position where a line wraps is shown at the start of the next line.
+    /// In vertical text the caret is a zero-height box as wide as the line.
+    pub fn caret_rect(&self, position: TextPosition) -> Option<PageRect> {
+        let lines = self.body_lines();
+        let mut in_paragraph = lines
+            .iter()
+            .filter(|line| line.start() <= position && position <= line.end());
+        let first = in_paragraph.next()?;
+        let line = std::iter::once(first)
+            .chain(in_paragraph)
+            .find(|line| position < line.end())
+            .unwrap_or(first);
+        Some(line.rect((
+            line.x_of(position.char_offset),
+            line.line.y,
+            0,
+            line.line.height,
+        )))
+    }
+
+    /// Boxes covering the text between two positions, one per line
+    pub fn selection_rects(&self, range: Range<TextPosition>) -> Vec<PageRect> {
+        let (start, end) = if range.start <= range.end {
+            (range.start, range.end)
+        } else {
+            (range.end, range.start)
+        };
+        self.body_lines()
+            .iter()
+            .filter_map(|line| {
+                let from = start.max(line.start());
+                let to = end.min(line.end());
+                if from >= to {
+                    return None;
+                }
+                let left = line.x_of(from.char_offset);
+                let right = line.x_of(to.char_offset);
+                Some(line.rect((left, line.line.y, right - left, line.line.height)))
+            })
+            .collect()
+    }
+
+    /// Every line of body text, table cells included, in page order
+    fn body_lines(&self) -> Vec<LineRef<'_>> {
+        let mut lines = Vec::new();
+        for (index, page) in self.pages.iter().enumerate() {
+            collect_lines((index, page.frame()), &page.paragraphs, None, &mut lines);
+        }
+        lines
+    }
+}
+
+/// Lines of `paragraphs` and the cells of their tables
+///
+/// With a `point` inside a table cell, in layout coordinates, only the
+/// lines of that cell are kept and `true` returned.
+fn collect_lines<'a>(
+    (page, frame): (usize, Option<VerticalFrame>),
+    paragraphs: &'a [RenderedParagraph],
+    point: Option<(i32, i32)>,
+    lines: &mut Vec<LineRef<'a>>,
+) -> bool {
+    for paragraph in paragraphs {
+        lines.extend(paragraph.lines.iter().map(|line| LineRef {
+            page,
+            frame,
+            paragraph,
+            line,
+        }));
+        let Some(table) = &paragraph.table else {
+            continue;
+        };
+        for cell in &table.cells {
+            let inside = point.is_some_and(|(x, y)| {
+                (cell.x..cell.x + cell.width).contains(&x)
+                    && (cell.y..cell.y + cell.height).contains(&y)
+            });
+            if inside {
+                lines.clear();
+                collect_lines((page, frame), &cell.paragraphs, point, lines);
+                return true;
+            }
+            collect_lines((page, frame), &cell.paragraphs, None, lines);
+        }
+    }
+    false
+}
@@ -1,140 +1,1979 @@
-use crate::model::{CharShape, ParaLineSeg, ParaShape, Paragraph, Section};
+use std::ops::Range;
+
+use crate::export::common::TableBlock;
+use crate::model::border_fill::BorderLine;
+use crate::model::control::Table;
+use crate::model::footnote::{Footnote, NoteKind};
+use crate::model::header_footer::{HeaderFooter, HeaderFooterType};
+use crate::model::text_box::{TextBox, TextBoxAlignment};
+use crate::model::{
+    CharPositionShape, CharShape, ColumnDef, ColumnKind, NumberKind, ObjectAlign, ObjectProperties,
+    PageApplyType, PageBorderFill, PageDef, PageFillArea, PageHide, ParaCharShape, ParaLineSeg,
+    ParaShape, ParaText, Paragraph, RelativeTo, Section, SectionDef, TextDirection, TextWrap,
+};
+use crate::render::font::{FontProvider, VerticalMetrics};
 use crate::HwpDocument;
 
-/// Represents a single rendered line of text
-#[derive(Debug, Clone)]
-pub struct RenderedLine {
-    pub y: i32,          // Vertical position in HWP units
-    pub height: i32,     // Line height in HWP units
-    pub baseline_y: i32, // Baseline position
-    pub runs: Vec<TextRun>,
-}
+/// Metrics assumed when no font is available, close to Hancom's bundled fonts
+pub(crate) const DEFAULT_METRICS: VerticalMetrics = VerticalMetrics {
+    ascent: 0.85,
+    descent: 0.15,
+    line_gap: 0.0,
+};
+
+/// Language slot of CharShape's per-language font, ratio, spacing and size arrays
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Script {
+    Hangul = 0,
+    Latin = 1,
+    Hanja = 2,
+    Japanese = 3,
+    Other = 4,
+    Symbol = 5,
+    User = 6,
+}
+
+impl Script {
+    /// Slot used for `c`; whitespace counts as Latin
+    pub fn of(c: char) -> Self {
+        match c as u32 {
+            0x1100..=0x11FF
+            | 0x3000..=0x303F
+            | 0x3130..=0x318F
+            | 0xA960..=0xA97F
+            | 0xAC00..=0xD7FF
+            | 0xFF00..=0xFFEF => Script::Hangul,
+            0x3040..=0x30FF | 0x31F0..=0x31FF => Script::Japanese,
+            0x2E80..=0x2FDF
+            | 0x3400..=0x4DBF
+            | 0x4E00..=0x9FFF
+            | 0xF900..=0xFAFF
+            | 0x20000..=0x3FFFF => Script::Hanja,
+            0x0000..=0x024F | 0x1E00..=0x1EFF | 0x2000..=0x206F => Script::Latin,
+            0x2100..=0x2BFF => Script::Symbol,
+            0xE000..=0xF8FF => Script::User,
+            _ => Script::Other,
+        }
+    }
+
+    pub fn index(self) -> usize {
+        self as usize
+    }
+
+    /// Whether characters of this script are full width
+    fn is_wide(self) -> bool {
+        matches!(self, Script::Hangul | Script::Hanja | Script::Japanese)
+    }
+
+    /// Whether characters of this script stand upright in vertical text,
+    /// where the others are turned on their side
+    fn is_upright(self) -> bool {
+        self.is_wide() || self == Script::Symbol
+    }
+}
+
+/// Punctuation and brackets with a vertical presentation form
+const VERTICAL_FORMS: [(char, char); 31] = [
+    ('，', '︐'),
+    ('、', '︑'),
+    ('。', '︒'),
+    ('：', '︓'),
+    ('；', '︔'),
+    ('！', '︕'),
+    ('？', '︖'),
+    ('〖', '︗'),
+    ('〗', '︘'),
+    ('…', '︙'),
+    ('‥', '︰'),
+    ('—', '︱'),
+    ('–', '︲'),
+    ('（', '︵'),
+    ('）', '︶'),
+    ('｛', '︷'),
+    ('｝', '︸'),
+    ('〔', '︹'),
+    ('〕', '︺'),
+    ('【', '︻'),
+    ('】', '︼'),
+    ('《', '︽'),
+    ('》', '︾'),
+    ('〈', '︿'),
+    ('〉', '﹀'),
+    ('「', '﹁'),
+    ('」', '﹂'),
+    ('『', '﹃'),
+    ('』', '﹄'),
+    ('［', '﹇'),
+    ('］', '﹈'),
+];
+
+/// Form of `c` drawn upright in vertical text
+fn vertical_form(c: char) -> char {
+    VERTICAL_FORMS
+        .iter()
+        .find(|(horizontal, _)| *horizontal == c)
+        .map_or(c, |(_, vertical)| *vertical)
+}
+
+/// Split text into runs of one script; whitespace joins the run before it
+fn script_runs(text: &str) -> Vec<(Script, Range<usize>)> {
+    let mut runs: Vec<(Script, Range<usize>)> = Vec::new();
+    for (offset, c) in text.char_indices() {
+        let end = offset + c.len_utf8();
+        match runs.last_mut() {
+            Some((_, range)) if c.is_whitespace() => range.end = end,
+            Some((script, range)) if *script == Script::of(c) => range.end = end,
+            // Leading whitespace takes the script of what follows
+            Some((script, range)) if text[range.clone()].chars().all(char::is_whitespace) => {
+                *script = Script::of(c);
+                range.end = end;
+            }
+            _ => runs.push((Script::of(c), offset..end)),
+        }
+    }
+    runs
+}
+
+/// Byte range of characters `start..end` of `text`, clamped to its length
+fn char_range(text: &str, start: usize, end: usize) -> Range<usize> {
+    let byte = |index: usize| {
+        text.char_indices()
+            .nth(index)
+            .map_or(text.len(), |(offset, _)| offset)
+    };
+    byte(start)..byte(end.max(start))
+}
+
+/// Characters that may not begin a line (행두 금칙)
+const NO_LINE_START: &str = "!%),.:;?]}¢°·’”‰′″℃、。々〉》」』】〕〗〙〛ぁぃぅぇぉっゃゅょゎゕゖ゛゜ゝゞァィゥェォッャュョヮヵヶ・ーヽヾ！％），．：；？］｝｡｣､･ｰ";
+
+/// Characters that may not end a line (행말 금칙)
+const NO_LINE_END: &str = "$([{£¥‘“〈《「『【〔〖〘〚＄（［｛｢￡￥";
+
+/// Horizontal alignment stored in bits 2-4 of `ParaShape::properties1`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Alignment {
+    Justify,
+    Left,
+    Right,
+    Center,
+    Distribute,
+    Divide,
+}
+
+impl Alignment {
+    fn of(para_shape: &ParaShape) -> Self {
+        match para_shape.get_alignment() {
+            0 => Alignment::Justify,
+            2 => Alignment::Right,
+            3 => Alignment::Center,
+            4 => Alignment::Distribute,
+            5 => Alignment::Divide,
+            _ => Alignment::Left,
+        }
+    }
+}
+
+/// Whether a line may break between `prev` and `next`
+fn may_break(prev: char, next: char, para_shape: &ParaShape) -> bool {
+    if NO_LINE_START.contains(next) || NO_LINE_END.contains(prev) {
+        return false;
+    }
+    // Spaces stay at the end of the line they follow
+    if next.is_whitespace() {
+        return false;
+    }
+    if prev.is_whitespace() || prev == '-' {
+        return true;
+    }
+
+    let scripts = [Script::of(prev), Script::of(next)];
+    if scripts
+        .iter()
+        .any(|s| matches!(s, Script::Hanja | Script::Japanese))
+    {
+        return true;
+    }
+    if scripts.contains(&Script::Hangul) {
+        return para_shape.get_korean_break_unit() == 1;
+    }
+    para_shape.get_latin_break_unit() == 2
+}
+
+fn is_latin_letter(c: char) -> bool {
+    c.is_alphabetic() && Script::of(c) == Script::Latin
+}
+
+/// One line chosen by the line breaker
+struct LineBreak {
+    /// Characters of the paragraph on this line, without a trailing line feed
+    range: Range<usize>,
+    /// The line ends inside a hyphenated word
+    hyphen: bool,
+    /// Last line of the paragraph or before a forced break; never justified
+    last: bool,
+}
+
+/// Horizontal offset of a line and the extra space after each of its characters
+fn arrange(
+    alignment: Alignment,
+    chars: &[char],
+    advances: &[i32],
+    available: i32,
+    last: bool,
+) -> (i32, Vec<i32>) {
+    // Trailing spaces hang past the margin and are not aligned
+    let content = chars
+        .iter()
+        .rposition(|c| !c.is_whitespace())
+        .map_or(0, |end| end + 1);
+    let extra = available - advances[..content].iter().sum::<i32>();
+    if extra <= 0 {
+        return (0, Vec::new());
+    }
+
+    let gaps: Vec<usize> = match alignment {
+        Alignment::Left => return (0, Vec::new()),
+        Alignment::Right => return (extra, Vec::new()),
+        Alignment::Center => return (extra / 2, Vec::new()),
+        Alignment::Justify if last => return (0, Vec::new()),
+        Alignment::Justify => {
+            // Widen word spaces, or every character gap when there are none
+            let spaces: Vec<usize> = (0..content.saturating_sub(1))
+                .filter(|&k| chars[k].is_whitespace() && !chars[k + 1].is_whitespace())
+                .collect();
+            if spaces.is_empty() {
+                (0..content.saturating_sub(1)).collect()
+            } else {
+                spaces
+            }
+        }
+        Alignment::Distribute | Alignment::Divide => (0..content.saturating_sub(1)).collect(),
+    };
+    if gaps.is_empty() {
+        return (0, Vec::new());
+    }
+
+    let mut spacing = vec![0; chars.len()];
+    let share = extra / gaps.len() as i32;
+    let remainder = extra as usize % gaps.len();
+    for (n, &k) in gaps.iter().enumerate() {
+        spacing[k] = share + i32::from(n < remainder);
+    }
+    (0, spacing)
+}
+
+/// Represents a single rendered line of text
+#[derive(Debug, Clone)]
+pub struct RenderedLine {
+    pub y: i32,          // Vertical position in HWP units
+    pub height: i32,     // Line height in HWP units
+    pub baseline_y: i32, // Baseline position
+    pub runs: Vec<TextRun>,
+    pub text_range: Range<usize>, // Characters of the paragraph text on the line
+}
+
+/// Represents a run of text with consistent formatting
+///
+/// A hyphen added where a word is split across lines is drawn as part of
+/// the text, but falls outside its line's `text_range`.
+#[derive(Debug, Clone)]
+pub struct TextRun {
+    pub x: i32,                 // Horizontal position in HWP units
+    pub width: i32,             // Width of the run
+    pub text: String,           // Text content
+    pub char_shape_id: u16,     // Character shape ID
+    pub font_size: i32,         // Actual font size in HWP units
+    pub script: Script,         // Language slot for the face and size
+    pub paragraph_index: usize, // Paragraph the text comes from, as in `RenderedParagraph`
+    pub char_offset: usize,     // Position of the first character in the paragraph text
+    pub advances: Vec<i32>,     // Width of each character, adding up to `width`
+    pub sideways: bool,         // Turned a quarter clockwise in vertical text, as Latin is
+}
+
+/// Placement of vertical text on the page
+///
+/// Vertical text is laid out as if horizontal and turned a quarter
+/// clockwise: layout x runs down the page from `top`, and layout y runs
+/// leftwards from `right`, so lines stack from right to left.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct VerticalFrame {
+    pub right: i32,
+    pub top: i32,
+}
+
+impl VerticalFrame {
+    /// Page position of a layout point
+    pub fn to_page(&self, x: i32, y: i32) -> (i32, i32) {
+        (self.right - y, self.top + x)
+    }
+
+    /// Layout point at a page position
+    pub fn to_layout(&self, x: i32, y: i32) -> (i32, i32) {
+        (y - self.top, self.right - x)
+    }
+
+    /// Page area, as left, top, width and height, covered by a layout area
+    pub fn to_page_rect(
+        &self,
+        (x, y, width, height): (i32, i32, i32, i32),
+    ) -> (i32, i32, i32, i32) {
+        (self.right - y - height, self.top + x, height, width)
+    }
+}
+
+/// Represents a rendered paragraph
+///
+/// A paragraph split across pages appears once on each page it touches.
+#[derive(Debug, Clone)]
+pub struct RenderedParagraph {
+    pub x: i32,                       // Left position
+    pub y: i32,                       // Top position
+    pub width: i32,                   // Paragraph width
+    pub height: i32,                  // Total height
+    pub lines: Vec<RenderedLine>,     // Rendered lines
+    pub para_shape_id: u16,           // Paragraph shape ID
+    pub section_index: usize,         // Section holding the paragraph
+    pub paragraph_index: usize,       // Position of the paragraph in its section
+    pub first_line: usize,            // Index of the first line placed here
+    pub table: Option<RenderedTable>, // Table held by the paragraph
+    pub objects: Vec<RenderedObject>, // Objects placed with the paragraph's lines
+}
+
+impl RenderedParagraph {
+    /// Record the section and paragraph the laid out text comes from
+    fn set_source(&mut self, section_index: usize, paragraph_index: usize) {
+        self.section_index = section_index;
+        self.paragraph_index = paragraph_index;
+        for run in self.lines.iter_mut().flat_map(|line| &mut line.runs) {
+            run.paragraph_index = paragraph_index;
+        }
+    }
+
+    /// Turn the text for vertical writing: runs of upright scripts take
+    /// their vertical punctuation forms and the rest are set on their side
+    fn write_vertically(&mut self) {
+        for run in self.lines.iter_mut().flat_map(|line| &mut line.runs) {
+            run.sideways = !run.script.is_upright();
+            if !run.sideways {
+                run.text = run.text.chars().map(vertical_form).collect();
+            }
+        }
+        for cell in self.table.iter_mut().flat_map(|table| &mut table.cells) {
+            for paragraph in &mut cell.paragraphs {
+                paragraph.write_vertically();
+            }
+        }
+    }
+
+    /// Set objects placed with the paragraph, laid out turned in `frame`,
+    /// upright on the page
+    fn turn_objects_onto_page(&mut self, frame: VerticalFrame) {
+        for object in &mut self.objects {
+            object.turn_onto_page(frame);
+        }
+        for cell in self.table.iter_mut().flat_map(|table| &mut table.cells) {
+            for paragraph in &mut cell.paragraphs {
+                paragraph.turn_objects_onto_page(frame);
+            }
+        }
+    }
+
+    /// Move the paragraph and everything in it down by `dy`
+    fn shift(&mut self, dy: i32) {
+        self.translate(0, dy);
+    }
+
+    /// Move the paragraph and everything in it right by `dx` and down by `dy`
+    fn translate(&mut self, dx: i32, dy: i32) {
+        self.x += dx;
+        self.y += dy;
+        for line in &mut self.lines {
+            line.y += dy;
+            line.baseline_y += dy;
+            for run in &mut line.runs {
+                run.x += dx;
+            }
+        }
+        for object in &mut self.objects {
+            object.translate(dx, dy);
+        }
+        if let Some(table) = &mut self.table {
+            table.x += dx;
+            table.y += dy;
+            for cell in &mut table.cells {
+                cell.x += dx;
+                cell.y += dy;
+                for paragraph in &mut cell.paragraphs {
+                    paragraph.translate(dx, dy);
+                }
+            }
+        }
+    }
+}
+
+/// Represents a rendered table
+///
+/// A table split across pages appears once on each page it touches, with
+/// the rows placed there and a repeated header row first.
+#[derive(Debug, Clone)]
+pub struct RenderedTable {
+    pub x: i32,                   // Left position
+    pub y: i32,                   // Top position
+    pub width: i32,               // Total width
+    pub height: i32,              // Total height
+    pub row_heights: Vec<i32>,    // Height of each row placed here
+    pub first_row: usize,         // Index of the first body row placed here
+    pub cells: Vec<RenderedCell>, // Cells in storage order
+}
+
+/// Represents a rendered table cell, spans included
+#[derive(Debug, Clone)]
+pub struct RenderedCell {
+    pub x: i32,                             // Left position
+    pub y: i32,                             // Top position
+    pub width: i32,                         // Width across spanned columns
+    pub height: i32,                        // Height across spanned rows
+    pub row: usize,                         // First row of the cell
+    pub col: usize,                         // First column of the cell
+    pub row_span: usize,                    // Rows covered
+    pub col_span: usize,                    // Columns covered
+    pub border_fill_id: u16,                // 1-based border fill, 0 for none
+    pub paragraphs: Vec<RenderedParagraph>, // Cell content
+}
+
+/// Represents a placed picture, text box or drawing object
+///
+/// The frame is given before rotation, which turns it about its centre.
+#[derive(Debug, Clone)]
+pub struct RenderedObject {
+    pub x: i32,                             // Left position
+    pub y: i32,                             // Top position
+    pub width: i32,                         // Frame width
+    pub height: i32,                        // Frame height
+    pub rotation: f32,                      // Clockwise rotation in degrees
+    pub z_order: i32,                       // Stacking order, lowest first
+    pub wrap: TextWrap,                     // How body text flows around it
+    pub section_index: usize,               // Section holding the anchor paragraph
+    pub paragraph_index: usize,             // Paragraph holding the object
+    pub paragraphs: Vec<RenderedParagraph>, // Text box content
+    pub text_direction: TextDirection,      // Direction of the text box content
+}
+
+impl RenderedObject {
+    /// Frame of vertical text box content, whose paragraphs are laid out
+    /// from the frame rather than placed on the page
+    pub fn frame(&self) -> Option<VerticalFrame> {
+        (self.text_direction == TextDirection::Vertical).then_some(VerticalFrame {
+            right: self.x + self.width,
+            top: self.y,
+        })
+    }
+
+    fn shift(&mut self, dy: i32) {
+        self.translate(0, dy);
+    }
+
+    fn translate(&mut self, dx: i32, dy: i32) {
+        self.x += dx;
+        self.y += dy;
+        // Vertical content moves with the frame
+        if self.text_direction == TextDirection::Horizontal {
+            for paragraph in &mut self.paragraphs {
+                paragraph.translate(dx, dy);
+            }
+        }
+    }
+
+    /// Move an object laid out in a vertical frame onto the page, upright
+    fn turn_onto_page(&mut self, frame: VerticalFrame) {
+        let (x, y, width, height) = frame.to_page_rect((self.x, self.y, self.width, self.height));
+        self.translate(x - self.x, y - self.y);
+        self.width = width;
+        self.height = height;
+    }
+}
+
+/// Size, offsets and placement of a picture, text box or drawing object
+struct ObjectFrame {
+    inline: bool,
+    vertical: (RelativeTo, ObjectAlign),
+    horizontal: (RelativeTo, ObjectAlign),
+    wrap: TextWrap,
+    x: i32,
+    y: i32,
+    width: i32,
+    height: i32,
+    z_order: i32,
+    rotation: f32,
+    /// Placed in vertical text, with the page's axes swapped
+    turned: bool,
+}
+
+impl ObjectFrame {
+    fn of(paragraph: &Paragraph) -> Option<Self> {
+        if let Some(picture) = &paragraph.picture_data {
+            let bounds = (picture.left, picture.top, picture.width(), picture.height());
+            return Some(Self::new(picture.placement(), bounds, picture.z_order, 0.0));
+        }
+        if let Some(shape) = &paragraph.shape_data {
+            let bounds = (shape.x, shape.y, shape.width as i32, shape.height as i32);
+            return Some(Self::new(
+                shape.properties,
+                bounds,
+                shape.z_order,
+                shape.rotation as f32,
+            ));
+        }
+
+        // Text boxes carry an alignment instead of the common object attributes
+        let text_box = paragraph.text_box_data.as_ref()?;
+        let in_column = |align| {
+            ObjectProperties::floating(
+                RelativeTo::Paragraph,
+                RelativeTo::Column,
+                TextWrap::TopAndBottom,
+            )
+            .with_horizontal_align(align)
+        };
+        let properties = match text_box.alignment {
+            TextBoxAlignment::Inline => ObjectProperties::inline(),
+            TextBoxAlignment::Left => in_column(ObjectAlign::Start),
+            TextBoxAlignment::Center => in_column(ObjectAlign::Center),
+            TextBoxAlignment::Right => in_column(ObjectAlign::End),
+            TextBoxAlignment::Absolute => ObjectProperties::floating(
+                RelativeTo::Page,
+                RelativeTo::Page,
+                TextWrap::InFrontOfText,
+            ),
+        };
+        let bounds = (
+            text_box.x,
+            text_box.y,
+            text_box.width as i32,
+            text_box.height as i32,
+        );
+        Some(Self::new(
+            properties,
+            bounds,
+            text_box.z_order as i32,
+            text_box.rotation as f32,
+        ))
+    }
+
+    fn new(
+        properties: ObjectProperties,
+        (x, y, width, height): (i32, i32, i32, i32),
+        z_order: i32,
+        rotation: f32,
+    ) -> Self {
+        Self {
+            inline: properties.is_inline(),
+            vertical: (
+                properties.vertical_relative_to(),
+                properties.vertical_align(),
+            ),
+            horizontal: (
+                properties.horizontal_relative_to(),
+                properties.horizontal_align(),
+            ),
+            wrap: properties.text_wrap(),
+            x,
+            y,
+            width,
+            height,
+            z_order,
+            rotation,
+            turned: false,
+        }
+    }
+
+    /// The frame as placed in text of `direction`; vertical text runs down
+    /// the page, so offsets, sizes and alignments swap axes
+    fn turn(self, direction: TextDirection) -> Self {
+        if direction == TextDirection::Horizontal {
+            return self;
+        }
+        Self {
+            vertical: self.horizontal,
+            horizontal: self.vertical,
+            x: self.y,
+            y: self.x,
+            width: self.height,
+            height: self.width,
+            turned: true,
+            ..self
+        }
+    }
+
+    /// Floating objects measured from the page or paper rather than their paragraph
+    fn is_page_anchored(&self) -> bool {
+        !self.inline && self.vertical.0 != RelativeTo::Paragraph
+    }
+
+    /// Whether body text moves out of the object's way
+    fn takes_space(&self) -> bool {
+        matches!(self.wrap, TextWrap::Square | TextWrap::TopAndBottom)
+    }
+}
+
+/// Start of an object `size` long, `offset` in from the aligned edge of an area
+fn align_in(offset: i32, size: i32, (start, length): (i32, i32), align: ObjectAlign) -> i32 {
+    match align {
+        ObjectAlign::Start => start + offset,
+        ObjectAlign::Center => start + (length - size) / 2 + offset,
+        ObjectAlign::End => start + length - size - offset,
+    }
+}
+
+/// Represents a rendered page
+#[derive(Debug, Clone)]
+pub struct RenderedPage {
+    pub width: u32,                           // Page width
+    pub height: u32,                          // Page height
+    pub paragraphs: Vec<RenderedParagraph>,   // Rendered paragraphs
+    pub page_number: u32,                     // Page number
+    pub section_index: usize,                 // Section the page belongs to
+    pub page_hide: Option<PageHide>,          // Decorations hidden on this page
+    pub objects: Vec<RenderedObject>,         // Objects anchored to the page or paper
+    pub header: Option<RenderedParagraph>,    // Header in the top margin
+    pub footer: Option<RenderedParagraph>,    // Footer in the bottom margin
+    pub footnotes: Option<RenderedFootnotes>, // Notes at the foot of the body
+    pub column_lines: Vec<ColumnLine>,        // Dividers between columns
+    pub border: Option<RenderedPageBorder>,   // Page border and its background
+    pub background_color: Option<u32>,        // Paper colour from the page layout
+    pub text_direction: TextDirection,        // Direction of the section's body text
+}
+
+impl RenderedPage {
+    /// Frame of a vertical section's body, footnotes and column lines, which
+    /// are given in layout coordinates; headers, footers and objects are not
+    pub fn frame(&self) -> Option<VerticalFrame> {
+        (self.text_direction == TextDirection::Vertical).then_some(VerticalFrame {
+            right: self.width as i32,
+            top: 0,
+        })
+    }
+}
+
+/// Page border and the area its background covers
+#[derive(Debug, Clone)]
+pub struct RenderedPageBorder {
+    pub x: i32,                     // Left of the border
+    pub y: i32,                     // Top of the border
+    pub width: i32,                 // Border width
+    pub height: i32,                // Border height
+    pub fill: (i32, i32, i32, i32), // Left, top, width and height of the background
+    pub border_fill_id: u16,        // 1-based border fill
+}
+
+/// Divider drawn down the middle of the gap between two columns
+#[derive(Debug, Clone)]
+pub struct ColumnLine {
+    pub x: i32,           // Horizontal position
+    pub y: i32,           // Top of the line
+    pub height: i32,      // Length of the line
+    pub line: BorderLine, // Type, thickness and colour
+}
+
+/// Footnotes of one page, stacked up from the bottom of the body area
+#[derive(Debug, Clone)]
+pub struct RenderedFootnotes {
+    pub x: i32,                             // Start of the separator line
+    pub y: i32,                             // Height of the separator line
+    pub separator_width: i32,               // Length of the separator line
+    pub paragraphs: Vec<RenderedParagraph>, // Notes, numbered, top to bottom
+}
+
+/// Space between the body text and the first footnote, the separator line in its middle
+const FOOTNOTE_SEPARATOR_SPACE: i32 = 850; // 3mm
+
+/// Layout calculation result
+#[derive(Debug)]
+pub struct LayoutResult {
+    pub pages: Vec<RenderedPage>,
+    pub total_height: i32,
+}
+
+impl LayoutResult {
+    pub fn page_count(&self) -> usize {
+        self.pages.len()
+    }
+
+    /// Index of the page where a paragraph starts
+    pub fn page_of_paragraph(&self, section_index: usize, paragraph_index: usize) -> Option<usize> {
+        self.pages.iter().position(|page| {
+            page.paragraphs.iter().any(|para| {
+                para.section_index == section_index && para.paragraph_index == paragraph_index
+            })
+        })
+    }
+}
+
+/// Pages of one section being filled top to bottom
+struct PageFlow {
+    pages: Vec<RenderedPage>,
+    page: RenderedPage,
+    /// Top of the next line or paragraph
+    y: i32,
+    top: i32,
+    /// Bottom of the body text, above any footnotes
+    bottom: i32,
+    /// Bottom of the body area, footnotes included
+    limit: i32,
+    /// Left edge and width of each column of the current region, in reading order
+    columns: Vec<(i32, i32)>,
+    /// Column being filled
+    column: usize,
+    /// Divider drawn between the region's columns
+    column_line: Option<BorderLine>,
+    /// Whether the region's columns are levelled where it ends mid-page
+    balanced: bool,
+    /// Top of the current region on this page
+    region_top: i32,
+    /// Index of the region's first piece on this page
+    region_start: usize,
+    /// Index of the first piece in the current column
+    column_start: usize,
+    /// Column of each piece on the page
+    piece_columns: Vec<usize>,
+    /// Pieces on the page that follow a column break
+    column_breaks: Vec<usize>,
+}
+
+impl PageFlow {
+    fn room(&self) -> i32 {
+        self.bottom - self.y
+    }
+
+    fn capacity(&self) -> i32 {
+        self.bottom - self.top
+    }
+
+    fn is_empty(&self) -> bool {
+        self.page.paragraphs.is_empty()
+    }
+
+    /// Whether nothing is placed in the current column and it starts at the
+    /// top of the page, so moving on would not give more room
+    fn column_is_empty(&self) -> bool {
+        self.page.paragraphs.len() == self.column_start && self.region_top == self.top
+    }
+
+    /// Put a piece laid out for the first column into the current one
+    fn push(&mut self, mut piece: RenderedParagraph) {
+        piece.translate(self.columns[self.column].0 - self.columns[0].0, 0);
+        self.page.paragraphs.push(piece);
+        self.piece_columns.push(self.column);
+    }
+
+    /// Continue at the top of the next column, or on a new page after the last
+    fn advance(&mut self, page_num: &mut u32) {
+        if self.column + 1 < self.columns.len() {
+            self.column += 1;
+            self.y = self.region_top;
+            self.column_start = self.page.paragraphs.len();
+        } else {
+            self.break_page(page_num);
+        }
+    }
+
+    /// Move on to the next column, keeping what follows there when balancing
+    fn break_column(&mut self, page_num: &mut u32) {
+        self.advance(page_num);
+        self.column_breaks.push(self.page.paragraphs.len());
+    }
+
+    /// Continue on a fresh page unless the current one is still empty
+    fn break_page(&mut self, page_num: &mut u32) {
+        if self.is_empty() {
+            return;
+        }
+        self.draw_column_lines();
+        *page_num += 1;
+        let next = RenderedPage {
+            paragraphs: Vec::new(),
+            page_number: *page_num,
+            page_hide: None,
+            objects: Vec::new(),
+            footnotes: None,
+            column_lines: Vec::new(),
+            ..self.page.clone()
+        };
+        self.pages.push(std::mem::replace(&mut self.page, next));
+        self.y = self.top;
+        self.bottom = self.limit;
+        self.column = 0;
+        self.region_top = self.top;
+        self.region_start = 0;
+        self.column_start = 0;
+        self.piece_columns.clear();
+        self.column_breaks.clear();
+    }
+
+    /// End the current column region and start the columns of `column_def`
+    /// below it, across the body area `(x, width)`
+    fn start_region(&mut self, column_def: &ColumnDef, (x, width): (i32, i32)) {
+        self.end_region();
+        self.columns = column_boxes(column_def, (x, width));
+        self.column_line = column_def.line();
+        self.balanced = column_def.kind() != ColumnKind::Parallel;
+        self.column = 0;
+        self.region_top = self.y;
+        self.region_start = self.page.paragraphs.len();
+        self.column_start = self.region_start;
+    }
+
+    /// Level the region's columns on this page and draw their dividers
+    fn end_region(&mut self) {
+        if self.balanced {
+            self.balance();
+        }
+        self.draw_column_lines();
+    }
+
+    /// Add dividers between the region's columns, as tall as the longest
+    /// column on this page
+    fn draw_column_lines(&mut self) {
+        let Some(line) = self.column_line.clone() else {
+            return;
+        };
+        let Some(bottom) = self.page.paragraphs[self.region_start..]
+            .iter()
+            .map(|piece| piece.y + piece.height)
+            .max()
+        else {
+            return;
+        };
+        let mut columns = self.columns.clone();
+        columns.sort_unstable();
+        for pair in columns.windows(2) {
+            self.page.column_lines.push(ColumnLine {
+                x: (pair[0].0 + pair[0].1 + pair[1].0) / 2,
+                y: self.region_top,
+                height: bottom - self.region_top,
+                line: line.clone(),
+            });
+        }
+    }
+
+    /// Spread the region's lines on this page over its columns as evenly as
+    /// their order allows, leaving `y` below the longest column
+    ///
+    /// Lines of a paragraph may move between columns; a table moves whole.
+    fn balance(&mut self) {
+        if self.columns.len() < 2 || self.page.paragraphs.len() == self.region_start {
+            return;
+        }
+        let pieces = self.page.paragraphs.split_off(self.region_start);
+        let piece_columns = self.piece_columns.split_off(self.region_start);
+
+        // Units stacked as if in a single column, each with the space above it
+        struct Unit {
+            piece: usize,
+            line: Option<usize>,
+            top: i32,
+            bottom: i32,
+            /// Starts a column after a column break
+            forced: bool,
+        }
+        let mut units = Vec::new();
+        let mut previous: Option<(usize, i32)> = None;
+        for (index, piece) in pieces.iter().enumerate() {
+            let column = piece_columns[index];
+            let mut top = match previous {
+                Some((previous_column, bottom)) if previous_column == column => bottom,
+                _ => piece.y,
+            };
+            let bottom = piece.y + piece.height;
+            let forced = self.column_breaks.contains(&(self.region_start + index));
+            if piece.table.is_some() || piece.lines.is_empty() {
+                units.push(Unit {
+                    piece: index,
+                    line: None,
+                    top,
+                    bottom,
+                    forced,
+                });
+            } else {
+                let last = piece.lines.len() - 1;
+                for (line_index, line) in piece.lines.iter().enumerate() {
+                    let line_bottom = if line_index == last {
+                        bottom
+                    } else {
+                        line.y + line.height
+                    };
+                    units.push(Unit {
+                        piece: index,
+                        line: Some(line_index),
+                        top,
+                        bottom: line_bottom,
+                        forced: forced && line_index == 0,
+                    });
+                    top = line_bottom;
+                }
+            }
+            previous = Some((column, bottom));
+        }
+
+        // Fill columns no taller than `limit`, each taking at least one unit
+        let fill = |limit: i32| {
+            let mut assigned = Vec::with_capacity(units.len());
+            let (mut column, mut used, mut empty) = (0, 0, true);
+            for unit in &units {
+                let height = unit.bottom - unit.top;
+                if !empty && (unit.forced || used + height > limit) {
+                    column += 1;
+                    used = 0;
+                }
+                used += height;
+                empty = false;
+                assigned.push(column);
+            }
+            assigned
+        };
+        let fits = |limit: i32| fill(limit).last().is_some_and(|&c| c < self.columns.len());
+        let mut low = units.iter().map(|u| u.bottom - u.top).max().unwrap_or(0);
+        let mut high = units.iter().map(|u| u.bottom - u.top).sum::<i32>();
+        while low < high {
+            let middle = low + (high - low) / 2;
+            if fits(middle) {
+                high = middle;
+            } else {
+                low = middle + 1;
+            }
+        }
+        let assigned = fill(low);
+
+        // Rebuild the pieces, splitting paragraphs where a column ends
+        let mut column_tops = vec![self.region_top; self.columns.len()];
+        let mut start = 0;
+        while start < units.len() {
+            let (piece_index, column) = (units[start].piece, assigned[start]);
+            let end = (start..units.len())
+                .find(|&k| units[k].piece != piece_index || assigned[k] != column)
+                .unwrap_or(units.len());
+            let (first, last) = (&units[start], &units[end - 1]);
+            let piece = &pieces[piece_index];
+            let dx = self.columns[column].0 - self.columns[piece_columns[piece_index]].0;
+            // Space between paragraphs is dropped at the top of a column
+            let dy = match first.line {
+                Some(0) | None if column_tops[column] == self.region_top => {
+                    self.region_top - piece.y
+                }
+                _ => column_tops[column] - first.top,
+            };
+
+            let mut part = piece.clone();
+            if let (Some(from), Some(to)) = (first.line, last.line) {
+                part.lines = piece.lines[from..=to].to_vec();
+                part.first_line += from;
+                if from > 0 {
+                    part.y = first.top;
+                    part.objects.clear();
+                }
+                part.height = last.bottom - part.y;
+            }
+            part.translate(dx, dy);
+            column_tops[column] = last.bottom + dy;
+            self.page.paragraphs.push(part);
+            self.piece_columns.push(column);
+            start = end;
+        }
+        self.y = column_tops.into_iter().max().unwrap_or(self.y);
+        self.column = 0;
+        let region_start = self.region_start;
+        self.column_breaks.retain(|&index| index < region_start);
+    }
+
+    /// Put notes at the foot of the current page, below those already there
+    ///
+    /// Moves on to a new page first when the notes and `first_line` of the
+    /// paragraph referring to them would not fit.
+    fn add_footnotes(
+        &mut self,
+        notes: Vec<RenderedParagraph>,
+        (x, width): (i32, i32),
+        first_line: i32,
+        page_num: &mut u32,
+    ) {
+        let total: i32 = notes.iter().map(|note| note.height).sum();
+        let needed = |flow: &Self| {
+            let separator = match flow.page.footnotes {
+                Some(_) => 0,
+                None => FOOTNOTE_SEPARATOR_SPACE,
+            };
+            total + separator
+        };
+        if needed(self) + first_line > self.room() {
+            self.break_page(page_num);
+        }
+        self.bottom -= needed(self);
+
+        let area = self
+            .page
+            .footnotes
+            .get_or_insert_with(|| RenderedFootnotes {
+                x,
+                y: 0,
+                separator_width: width / 3,
+                paragraphs: Vec::new(),
+            });
+        for note in &mut area.paragraphs {
+            note.shift(-total);
+        }
+        let mut y = self.limit - total;
+        for mut note in notes {
+            note.shift(y - note.y);
+            y += note.height;
+            area.paragraphs.push(note);
+        }
+        area.y = self.bottom + FOOTNOTE_SEPARATOR_SPACE / 2;
+    }
+
+    /// Left edge of the first column and width of the narrowest
+    fn column_area(&self) -> (i32, i32) {
+        column_area_of(&self.columns)
+    }
+
+    /// The page where a paragraph's first line was placed
+    fn page_starting(&mut self, paragraph_index: usize) -> &mut RenderedPage {
+        let starts_here = |page: &RenderedPage| {
+            page.paragraphs
+                .iter()
+                .any(|para| para.paragraph_index == paragraph_index && para.first_line == 0)
+        };
+        if !starts_here(&self.page) {
+            if let Some(index) = self.pages.iter().rposition(starts_here) {
+                return &mut self.pages[index];
+            }
+        }
+        &mut self.page
+    }
+
+    fn finish(mut self, page_num: &mut u32) -> Vec<RenderedPage> {
+        self.end_region();
+        if !self.is_empty() {
+            self.pages.push(self.page);
+            *page_num += 1;
+        }
+        self.pages
+    }
+}
+
+/// Layout engine for HWP documents
+pub struct LayoutEngine<'a> {
+    document: &'a HwpDocument,
+    fonts: Option<&'a dyn FontProvider>,
+}
+
+impl<'a> LayoutEngine<'a> {
+    pub fn new(document: &'a HwpDocument) -> Self {
+        Self {
+            document,
+            fonts: None,
+        }
+    }
+
+    /// Measure text with real font metrics instead of estimates
+    pub fn with_font_provider(mut self, fonts: &'a dyn FontProvider) -> Self {
+        self.fonts = Some(fonts);
+        self
+    }
+
+    /// Perform full layout calculation for the document
+    pub fn calculate_layout(&self) -> LayoutResult {
+        let mut pages = Vec::new();
+        let mut current_page_num = self
+            .document
+            .doc_info
+            .properties
+            .as_ref()
+            .map_or(1, |props| props.page_start_number.max(1) as u32);
+
+        for (section_index, section) in self.document.sections().enumerate() {
+            // A section may restart page numbering; zero continues from the previous one
+            if let Some(start) = section
+                .section_def
+                .as_ref()
+                .map(|def| def.page_starting_number)
+                .filter(|&start| start > 0)
+            {
+                current_page_num = start as u32;
+            }
+            let section_pages = self.layout_section(section, section_index, &mut current_page_num);
+            pages.extend(section_pages);
+        }
+
+        let total_height = pages.iter().map(|p| p.height as i32).sum();
+
+        LayoutResult {
+            pages,
+            total_height,
+        }
+    }
+
+    /// Flow a section's paragraphs onto pages
+    fn layout_section(
+        &self,
+        section: &Section,
+        section_index: usize,
+        page_num: &mut u32,
+    ) -> Vec<RenderedPage> {
+        // Documents built with HwpWriter may not carry a page definition
+        let page_def = section
+            .page_def
+            .clone()
+            .unwrap_or_else(PageDef::new_default);
+
+        // Calculate content area
+        let content_x = page_def.left_margin as i32;
+        let content_y = page_def.top_margin as i32;
+        let content_width = page_def.effective_width() as i32;
+        let content_height = page_def.effective_height() as i32;
+
+        // Headers start at the header margin and footers end at the footer
+        // margin; the body gives way where they reach into it
+        let tallest = |kind| {
+            page_def
+                .header_footer
+                .items
+                .iter()
+                .filter(|item| item.header_footer_type == kind)
+                .filter_map(|item| {
+                    self.layout_header_footer(item, Some(*page_num), (content_x, content_width))
+                })
+                .map(|laid| laid.height)
+                .max()
+                .unwrap_or(0)
+        };
+        let body_top =
+            content_y.max(page_def.header_margin as i32 + tallest(HeaderFooterType::Header));
+        let body_bottom = (content_y + content_height).min(
+            page_def.height as i32
+                - page_def.footer_margin as i32
+                - tallest(HeaderFooterType::Footer),
+        );
+
+        // A vertical body is laid out as if turned a quarter anticlockwise:
+        // lines run across its height and stack from the right margin leftwards
+        let direction = section
+            .section_def
+            .as_ref()
+            .map_or(TextDirection::Horizontal, SectionDef::text_direction);
+        let (body, body_top, body_bottom) = match direction {
+            TextDirection::Horizontal => ((content_x, content_width), body_top, body_bottom),
+            TextDirection::Vertical => (
+                (body_top, body_bottom - body_top),
+                page_def.right_margin as i32,
+                (page_def.width - page_def.left_margin) as i32,
+            ),
+        };
+
+        let mut flow = PageFlow {
+            pages: Vec::new(),
+            page: RenderedPage {
+                width: page_def.width,
+                height: page_def.height,
+                paragraphs: Vec::new(),
+                page_number: *page_num,
+                section_index,
+                page_hide: None,
+                objects: Vec::new(),
+                header: None,
+                footer: None,
+                footnotes: None,
+                column_lines: Vec::new(),
+                border: None,
+                background_color: page_def.layout.as_ref().and_then(|l| l.background_color),
+                text_direction: direction,
+            },
+            y: body_top,
+            top: body_top,
+            bottom: body_bottom,
+            limit: body_bottom,
+            columns: vec![body],
+            column: 0,
+            column_line: None,
+            balanced: false,
+            region_top: body_top,
+            region_start: 0,
+            column_start: 0,
+            piece_columns: Vec::new(),
+            column_breaks: Vec::new(),
+        };
+        flow.start_region(
+            &section_columns(&page_def, section.section_def.as_ref()),
+            body,
+        );
+
+        // Lines are laid out relative to each paragraph's top, then placed.
+        // Cell paragraphs are laid out inside their table rather than here.
+        // Columns of unequal width all take the narrowest one's lines.
+        let mut laid = Vec::new();
+        let mut column_area = flow.column_area();
+        let mut index = 0;
+        while index < section.paragraphs.len() {
+            if let Some(column_def) = &section.paragraphs[index].column_def {
+                column_area = column_area_of(&column_boxes(column_def, body));
+            }
+            let (block, next) = self.layout_block(
+                &section.paragraphs,
+                index,
+                section_index,
+                column_area,
+                (false, direction),
+            );
+            laid.extend(block);
+            index = next;
+        }
+
+        for (block, laid_para) in laid.iter().enumerate() {
+            let paragraph = &section.paragraphs[laid_para.paragraph_index];
+            let para_shape = self
+                .document
+                .get_para_shape(paragraph.para_shape_id as usize);
+            let has = |flag: fn(&ParaShape) -> bool| para_shape.is_some_and(flag);
+
+            if paragraph.is_page_break() || has(ParaShape::is_page_break_before) {
+                flow.break_page(page_num);
+            }
+            if let Some(column_def) = &paragraph.column_def {
+                flow.start_region(column_def, body);
+            } else if paragraph.is_column_break() {
+                flow.break_column(page_num);
+            }
+
+            // The whole paragraph and the next one's first line share a column
+            if has(ParaShape::is_keep_with_next) && !flow.column_is_empty() {
+                let next_line = laid.get(block + 1).map_or(0, |next| match &next.table {
+                    Some(table) => table.row_heights.first().copied().unwrap_or(0),
+                    None => next.lines.first().map_or(0, |line| line.y + line.height),
+                });
+                let needed = laid_para.height + next_line;
+                if needed > flow.room() && needed <= flow.capacity() {
+                    flow.advance(page_num);
+                }
+            }
+
+            // Footnotes go to the foot of the page the paragraph starts on
+            let notes: Vec<RenderedParagraph> = paragraph
+                .footnotes
+                .iter()
+                .filter(|note| note.kind == NoteKind::Footnote)
+                .flat_map(|note| {
+                    self.layout_footnote(note, (section_index, laid_para.paragraph_index), body)
+                })
+                .collect();
+            if !notes.is_empty() {
+                let first_line = laid_para
+                    .lines
+                    .first()
+                    .map_or(0, |line| line.y + line.height);
+                flow.add_footnotes(notes, body, first_line - laid_para.y, page_num);
+            }
+
+            match (&laid_para.table, &paragraph.table_data) {
+                (Some(table), Some(model)) => {
+                    self.place_table(&mut flow, laid_para, table, model, paragraph, page_num)
+                }
+                _ => self.place_paragraph(&mut flow, laid_para, paragraph, para_shape, page_num),
+            }
+
+            // Objects on the page or paper stay put wherever their paragraph lands
+            if let Some(frame) = ObjectFrame::of(paragraph).filter(ObjectFrame::is_page_anchored) {
+                let area = |relative, paper, body| {
+                    if relative == RelativeTo::Paper {
+                        (0, paper as i32)
+                    } else {
+                        body
+                    }
+                };
+                let horizontal = area(
+                    frame.horizontal.0,
+                    page_def.width,
+                    (content_x, content_width),
+                );
+                let vertical = area(
+                    frame.vertical.0,
+                    page_def.height,
+                    (content_y, content_height),
+                );
+                let x = align_in(frame.x, frame.width, horizontal, frame.horizontal.1);
+                let y = align_in(frame.y, frame.height, vertical, frame.vertical.1);
+                let object = self.layout_object(laid_para, paragraph, &frame, x, y);
+                flow.page_starting(laid_para.paragraph_index)
+                    .objects
+                    .push(object);
+            }
+        }
+
+        let mut pages = flow.finish(page_num);
+        if direction == TextDirection::Vertical {
+            for page in &mut pages {
+                write_page_vertically(page);
+            }
+        }
+        let section_def = section.section_def.as_ref();
+        for (index, page) in pages.iter_mut().enumerate() {
+            self.decorate_page(page, &page_def, section_def, index == 0);
+        }
+        pages
+    }
+
+    /// Add the header and footer that apply to a page, with its number filled in
+    fn decorate_page(
+        &self,
+        page: &mut RenderedPage,
+        page_def: &PageDef,
+        section_def: Option<&SectionDef>,
+        first_page: bool,
+    ) {
+        let page_hide = page.page_hide.unwrap_or_default();
+        let first_hides =
+            |flag: fn(&SectionDef) -> bool| first_page && section_def.is_some_and(flag);
+        let hide_number =
+            page_hide.hides_page_number() || first_hides(SectionDef::is_hide_page_number);
+        let area = (
+            page_def.left_margin as i32,
+            page_def.effective_width() as i32,
+        );
+
+        page.border = page_def
+            .border_fill
+            .filter(|placement| placement.border_fill_id != 0)
+            .map(|placement| page_border(page_def, &placement));
+
+        // A first page or odd/even header wins over one for all pages
+        let page_number = page.page_number;
+        let pick = |kind| {
+            let mut items = page_def
+                .header_footer
+                .items
+                .iter()
+                .filter(move |item| item.header_footer_type == kind)
+                .filter(move |item| item.applies_to(page_number, first_page));
+            items
+                .clone()
+                .find(|item| item.apply_type != PageApplyType::All)
+                .or_else(|| items.next())
+        };
+        let number = (!hide_number).then_some(page_number);
+
+        if !(page_hide.hides_header() || first_hides(SectionDef::is_hide_header)) {
+            page.header = pick(HeaderFooterType::Header)
+                .and_then(|item| self.layout_header_footer(item, number, area))
+                .map(|mut header| {
+                    header.shift(page_def.header_margin as i32);
+                    header
+                });
+        }
+        if !(page_hide.hides_footer() || first_hides(SectionDef::is_hide_footer)) {
+            page.footer = pick(HeaderFooterType::Footer)
+                .and_then(|item| self.layout_header_footer(item, number, area))
+                .map(|mut footer| {
+                    let bottom = page_def.height as i32 - page_def.footer_margin as i32;
+                    footer.shift(bottom - footer.height);
+                    footer
+                });
+        }
+    }
+
+    /// Lay out a header or footer from the top of its area, followed by the
+    /// page number when it carries one and `page_number` is given
+    fn layout_header_footer(
+        &self,
+        item: &HeaderFooter,
+        page_number: Option<u32>,
+        (x, width): (i32, i32),
+    ) -> Option<RenderedParagraph> {
+        let mut text = item.text.clone();
+        if let Some(number) = page_number.filter(|_| item.include_page_number) {
+            text.push_str(&item.number_format().format(number));
+        }
+        let content = Paragraph {
+            text: Some(ParaText { content: text }),
+            para_shape_id: item.para_shape_id,
+            char_shapes: Some(ParaCharShape {
+                char_positions: vec![CharPositionShape {
+                    position: 0,
+                    char_shape_id: item.char_shape_id,
+                }],
+            }),
+            ..Default::default()
+        };
+        let mut laid = self.layout_paragraph(&content, x, 0, width)?;
+
+        // The item's own alignment overrides its paragraph shape's
+        let factor = match item.alignment {
+            1 => 2,
+            2 => 1,
+            _ => 0,
+        };
+        if factor != 0 {
+            for line in &mut laid.lines {
+                let end = line.runs.iter().map(|run| run.x + run.width).max();
+                let free = end.map_or(0, |end| laid.x + laid.width - end);
+                for run in &mut line.runs {
+                    run.x += free / factor;
+                }
+            }
+        }
+        Some(laid)
+    }
+
+    /// Lay out a footnote's paragraphs from y 0, the first led by its number
+    fn layout_footnote(
+        &self,
+        note: &Footnote,
+        (section_index, paragraph_index): (usize, usize),
+        (x, width): (i32, i32),
+    ) -> Vec<RenderedParagraph> {
+        let mut laid = Vec::new();
+        for (index, paragraph) in note.paragraphs.iter().enumerate() {
+            let mut paragraph = paragraph.clone();
+            if index == 0 {
+                let mark = format!("{}) ", note.number);
+                let shift = mark.chars().count() as u32;
+                let text = paragraph.text.get_or_insert_with(|| ParaText {
+                    content: String::new(),
+                });
+                text.content.insert_str(0, &mark);
+                for shape in paragraph
+                    .char_shapes
+                    .iter_mut()
+                    .flat_map(|shapes| &mut shapes.char_positions)
+                    .filter(|shape| shape.position > 0)
+                {
+                    shape.position += shift;
+                }
+                // Stored line breaks no longer match the text
+                paragraph.line_segments = None;
+            }
+            let y = laid
+                .last()
+                .map_or(0, |last: &RenderedParagraph| last.y + last.height);
+            if let Some(mut note_para) = self.layout_paragraph(&paragraph, x, y, width) {
+                note_para.set_source(section_index, paragraph_index);
+                laid.push(note_para);
+            }
+        }
+        laid
+    }
+
+    /// Place a laid out paragraph, splitting it where the page fills up
+    fn place_paragraph(
+        &self,
+        flow: &mut PageFlow,
+        laid: &RenderedParagraph,
+        paragraph: &Paragraph,
+        para_shape: Option<&ParaShape>,
+        page_num: &mut u32,
+    ) {
+        let has = |flag: fn(&ParaShape) -> bool| para_shape.is_some_and(flag);
+        let lines = &laid.lines;
+
+        let mut start = 0;
+        while start < lines.len() {
+            // Space above the paragraph is dropped where it continues on a new page
+            let base = if start == 0 { 0 } else { lines[start].y };
+            let mut fit = lines[start..]
+                .iter()
+                .take_while(|line| line.y + line.height - base <= flow.room())
+                .count();
+
+            if start + fit < lines.len() {
+                if flow.column_is_empty() {
+                    // Always make progress, even with a line taller than the page
+                    fit = fit.max(1);
+                } else {
+                    if start == 0 && has(ParaShape::is_keep_lines) && laid.height <= flow.capacity()
+                    {
+                        fit = 0;
+                    }
+                    if has(ParaShape::is_widow_orphan_protected) {
+                        // Carry a line over rather than leave one alone at the top of the next page
+                        if lines.len() - start - fit == 1 {
+                            fit = fit.saturating_sub(1);
+                        }
+                        // Nor a single first line at the bottom of this one
+                        if start == 0 && fit == 1 {
+                            fit = 0;
+                        }
+                    }
+                }
+            }
+
+            if fit > 0 {
+                if start == 0 {
+                    apply_page_controls(flow, paragraph, page_num);
+                }
+
+                let dy = flow.y - base;
+                let placed: Vec<RenderedLine> = lines[start..start + fit]
+                    .iter()
+                    .map(|line| RenderedLine {
+                        y: line.y + dy,
+                        baseline_y: line.baseline_y + dy,
+                        ..line.clone()
+                    })
+                    .collect();
+                let end = lines[start + fit - 1].y + lines[start + fit - 1].height;
+
+                // Objects go with the first piece of the paragraph
+                let mut objects = Vec::new();
+                if start == 0 {
+                    objects = laid.objects.clone();
+                    for object in &mut objects {
+                        object.shift(dy);
+                    }
+                }
+
+                flow.push(RenderedParagraph {
+                    y: flow.y,
+                    height: end - base,
+                    lines: placed,
+                    first_line: start,
+                    objects,
+                    ..laid.clone()
+                });
+                flow.y += end - base;
+            }
+
+            start += fit;
+            if start < lines.len() {
+                flow.advance(page_num);
+            }
+        }
+
+        flow.y += laid.height - lines.last().map_or(0, |line| line.y + line.height);
+    }
+
+    /// Place a laid out table, splitting it between rows where the page fills up
+    fn place_table(
+        &self,
+        flow: &mut PageFlow,
+        laid: &RenderedParagraph,
+        table: &RenderedTable,
+        model: &Table,
+        paragraph: &Paragraph,
+        page_num: &mut u32,
+    ) {
+        let rows = &table.row_heights;
+        let tops = row_tops(rows);
+        let header = if model.repeats_header() && rows.len() > 1 {
+            rows[0]
+        } else {
+            0
+        };
+
+        let mut start = 0;
+        while start < rows.len() {
+            // Continued pieces start with the repeated header row
+            let repeat = if start > 0 { header } else { 0 };
+            let base = tops[start];
+            let mut fit = (start..rows.len())
+                .take_while(|&row| tops[row + 1] - base + repeat <= flow.room())
+                .count();
 
-/// Represents a run of text with consistent formatting
-#[derive(Debug, Clone)]
-pub struct TextRun {
-    pub x: i32,             // Horizontal position in HWP units
-    pub width: i32,         // Width of the run
-    pub text: String,       // Text content
-    pub char_shape_id: u16, // Character shape ID
-    pub font_size: i32,     // Actual font size in HWP units
-}
+            if start + fit < rows.len() {
+                if flow.column_is_empty() {
+                    // Always make progress, even with a row taller than the page
+                    fit = fit.max(1);
+                } else if start == 0 && model.page_break() == 0 && table.height <= flow.capacity() {
+                    fit = 0;
+                }
+            }
 
-/// Represents a rendered paragraph
-#[derive(Debug, Clone)]
-pub struct RenderedParagraph {
-    pub x: i32,                   // Left position
-    pub y: i32,                   // Top position
-    pub width: i32,               // Paragraph width
-    pub height: i32,              // Total height
-    pub lines: Vec<RenderedLine>, // Rendered lines
-    pub para_shape_id: u16,       // Paragraph shape ID
-}
+            if fit > 0 {
+                if start == 0 {
+                    apply_page_controls(flow, paragraph, page_num);
+                }
 
-/// Represents a rendered page
-#[derive(Debug, Clone)]
-pub struct RenderedPage {
-    pub width: u32,                         // Page width
-    pub height: u32,                        // Page height
-    pub paragraphs: Vec<RenderedParagraph>, // Rendered paragraphs
-    pub page_number: u32,                   // Page number
-}
+                let end = start + fit;
+                let mut cells = Vec::new();
+                let mut row_heights = Vec::new();
+                if repeat > 0 {
+                    cells.extend(clip_cells(table, &tops, 0..1, flow.y - table.y));
+                    row_heights.push(header);
+                }
+                let dy = flow.y + repeat - base - table.y;
+                cells.extend(clip_cells(table, &tops, start..end, dy));
+                row_heights.extend_from_slice(&rows[start..end]);
+                let height = tops[end] - base + repeat;
 
-/// Layout calculation result
-#[derive(Debug)]
-pub struct LayoutResult {
-    pub pages: Vec<RenderedPage>,
-    pub total_height: i32,
-}
+                flow.push(RenderedParagraph {
+                    y: flow.y,
+                    height,
+                    first_line: start,
+                    table: Some(RenderedTable {
+                        y: flow.y,
+                        height,
+                        row_heights,
+                        first_row: start,
+                        cells,
+                        ..table.clone()
+                    }),
+                    ..laid.clone()
+                });
+                flow.y += height;
+            }
 
-/// Layout engine for HWP documents
-pub struct LayoutEngine<'a> {
-    document: &'a HwpDocument,
-}
+            start += fit;
+            if start < rows.len() {
+                flow.advance(page_num);
+            }
+        }
+    }
 
-impl<'a> LayoutEngine<'a> {
-    pub fn new(document: &'a HwpDocument) -> Self {
-        Self { document }
+    /// Lay out the paragraph at `index`, and for a table the cell paragraphs
+    /// stored after it
+    ///
+    /// Returns the index of the paragraph that follows the block.
+    ///
+    /// Objects placed on the page are left to the section, except in table
+    /// cells (`nested`), where they stay with their paragraph. In vertical
+    /// text, objects are placed turned along with the lines.
+    fn layout_block(
+        &self,
+        paragraphs: &[Paragraph],
+        index: usize,
+        section_index: usize,
+        (x, width): (i32, i32),
+        (nested, direction): (bool, TextDirection),
+    ) -> (Option<RenderedParagraph>, usize) {
+        let paragraph = &paragraphs[index];
+        let (laid, next) = match &paragraph.table_data {
+            Some(table) => {
+                self.layout_table(table, paragraphs, (index, section_index), x, direction)
+            }
+            None => (self.layout_paragraph(paragraph, x, 0, width), index + 1),
+        };
+        let laid = laid.map(|mut laid| {
+            laid.set_source(section_index, index);
+            match ObjectFrame::of(paragraph) {
+                Some(frame) if frame.inline => {
+                    self.place_inline_object(&mut laid, paragraph, frame.turn(direction))
+                }
+                Some(frame) if nested || !frame.is_page_anchored() => self.place_floating_object(
+                    &mut laid,
+                    paragraph,
+                    frame.turn(direction),
+                    (x, width),
+                ),
+                _ => {}
+            }
+            laid
+        });
+        (laid, next)
     }
 
-    /// Perform full layout calculation for the document
-    pub fn calculate_layout(&self) -> LayoutResult {
-        let mut pages = Vec::new();
-        let mut current_page_num = 1;
+    /// Put an inline object on a line of its own ahead of the paragraph's text
+    fn place_inline_object(
+        &self,
+        laid: &mut RenderedParagraph,
+        paragraph: &Paragraph,
+        frame: ObjectFrame,
+    ) {
+        let bottom = |laid: &RenderedParagraph| laid.lines.last().map_or(0, |l| l.y + l.height);
+        let old_bottom = bottom(laid);
+        let top = laid.lines.first().map_or(laid.y, |line| line.y);
+        let start = laid.lines.first().map_or(0, |line| line.text_range.start);
 
-        for section in self.document.sections() {
-            let section_pages = self.layout_section(section, &mut current_page_num);
-            pages.extend(section_pages);
+        // A paragraph holding only the object gives up its empty line
+        if laid.lines.iter().all(|line| line.runs.is_empty()) {
+            laid.lines.clear();
         }
+        for line in &mut laid.lines {
+            line.y += frame.height;
+            line.baseline_y += frame.height;
+        }
+        laid.lines.insert(
+            0,
+            RenderedLine {
+                y: top,
+                height: frame.height,
+                baseline_y: top + frame.height,
+                runs: Vec::new(),
+                text_range: start..start,
+            },
+        );
+        laid.height += bottom(laid) - old_bottom;
 
-        let total_height = pages.iter().map(|p| p.height as i32).sum();
+        let free = (laid.width - frame.width).max(0);
+        let offset = match self
+            .document
+            .get_para_shape(paragraph.para_shape_id as usize)
+            .map(Alignment::of)
+        {
+            Some(Alignment::Center) => free / 2,
+            Some(Alignment::Right) => free,
+            _ => 0,
+        };
+        let object = self.layout_object(laid, paragraph, &frame, laid.x + offset, top);
+        laid.objects.push(object);
+    }
 
-        LayoutResult {
-            pages,
-            total_height,
+    /// Place a floating object from the top of its paragraph
+    ///
+    /// The page and paper count as the column here. Objects that take space
+    /// make the paragraph tall enough to clear them.
+    fn place_floating_object(
+        &self,
+        laid: &mut RenderedParagraph,
+        paragraph: &Paragraph,
+        frame: ObjectFrame,
+        column: (i32, i32),
+    ) {
+        let area = match frame.horizontal.0 {
+            RelativeTo::Paragraph => (laid.x, laid.width),
+            _ => column,
+        };
+        let x = align_in(frame.x, frame.width, area, frame.horizontal.1);
+        let y = laid.y + frame.y;
+        if frame.takes_space() {
+            laid.height = laid.height.max(y + frame.height - laid.y);
         }
+        let object = self.layout_object(laid, paragraph, &frame, x, y);
+        laid.objects.push(object);
     }
 
-    /// Layout a single section
-    fn layout_section(&self, section: &Section, page_num: &mut u32) -> Vec<RenderedPage> {
-        let mut pages = Vec::new();
+    /// An object of `paragraph` at `x`/`y`, with a text box's content laid out inside
+    fn layout_object(
+        &self,
+        laid: &RenderedParagraph,
+        paragraph: &Paragraph,
+        frame: &ObjectFrame,
+        x: i32,
+        y: i32,
+    ) -> RenderedObject {
+        // Text box content is laid out upright whichever way the frame was placed
+        let size = if frame.turned {
+            (frame.height, frame.width)
+        } else {
+            (frame.width, frame.height)
+        };
+        let text_box = paragraph.text_box_data.as_ref();
+        let text_direction = match paragraph.list_header.as_ref() {
+            Some(header) if header.text_direction() == TextDirection::Vertical => {
+                TextDirection::Vertical
+            }
+            _ => text_box.map_or(TextDirection::Horizontal, |text_box| {
+                text_box.text_direction
+            }),
+        };
+        let paragraphs = text_box
+            .and_then(|text_box| self.layout_text_box(text_box, text_direction, (x, y), size))
+            .map(|mut content| {
+                content.set_source(laid.section_index, laid.paragraph_index);
+                content
+            })
+            .into_iter()
+            .collect();
 
-        // Get page definition
-        let page_def = section
-            .page_def
-            .as_ref()
-            .expect("Section must have page definition");
+        RenderedObject {
+            x,
+            y,
+            width: frame.width,
+            height: frame.height,
+            rotation: frame.rotation,
+            z_order: frame.z_order,
+            wrap: if frame.inline {
+                TextWrap::TopAndBottom
+            } else {
+                frame.wrap
+            },
+            section_index: laid.section_index,
+            paragraph_index: laid.paragraph_index,
+            paragraphs,
+            text_direction,
+        }
+    }
 
-        let mut current_page = RenderedPage {
-            width: page_def.width,
-            height: page_def.height,
-            paragraphs: Vec::new(),
-            page_number: *page_num,
+    /// Lay out a text box's text within its padding
+    ///
+    /// Vertical text is laid out from the box's top right corner, as its
+    /// `RenderedObject::frame` places it.
+    fn layout_text_box(
+        &self,
+        text_box: &TextBox,
+        direction: TextDirection,
+        (x, y): (i32, i32),
+        (width, height): (i32, i32),
+    ) -> Option<RenderedParagraph> {
+        let content = Paragraph {
+            text: Some(ParaText {
+                content: text_box.text.clone(),
+            }),
+            para_shape_id: text_box.para_shape_id,
+            char_shapes: Some(ParaCharShape {
+                char_positions: vec![CharPositionShape {
+                    position: 0,
+                    char_shape_id: text_box.char_shape_id,
+                }],
+            }),
+            ..Default::default()
         };
+        let padding = text_box.padding as i32;
+        if direction == TextDirection::Vertical {
+            let mut laid = self.layout_paragraph(&content, padding, 0, height - 2 * padding)?;
+            laid.shift(padding);
+            laid.write_vertically();
+            return Some(laid);
+        }
+        let mut laid = self.layout_paragraph(&content, x + padding, 0, width - 2 * padding)?;
+        laid.shift(y + padding);
+        Some(laid)
+    }
 
-        // Calculate content area
-        let content_x = page_def.left_margin as i32;
-        let content_y = page_def.top_margin as i32;
-        let content_width = page_def.effective_width() as i32;
-        let content_height = page_def.effective_height() as i32;
+    /// Lay out a table from the top of its paragraph, one cell paragraph per cell
+    fn layout_table(
+        &self,
+        table: &Table,
+        paragraphs: &[Paragraph],
+        (index, section_index): (usize, usize),
+        x: i32,
+        direction: TextDirection,
+    ) -> (Option<RenderedParagraph>, usize) {
+        let paragraph = &paragraphs[index];
+        let table_x = x + self
+            .document
+            .get_para_shape(paragraph.para_shape_id as usize)
+            .map_or(0, |shape| shape.left_margin);
 
-        let mut current_y = content_y;
+        let block = TableBlock {
+            table,
+            cells: table.cells.iter().map(|cell| (cell, Vec::new())).collect(),
+        };
+        let mut lefts = vec![table_x];
+        for width in block.column_widths() {
+            lefts.push(lefts[lefts.len() - 1] + width as i32);
+        }
+        let column = |col: usize| lefts[col.min(lefts.len() - 1)];
 
-        // Layout each paragraph
-        for paragraph in &section.paragraphs {
-            if let Some(rendered_para) =
-                self.layout_paragraph(paragraph, content_x, current_y, content_width)
-            {
-                // Check if paragraph fits on current page
-                if current_y + rendered_para.height > content_y + content_height {
-                    // Start new page
-                    pages.push(current_page);
-                    *page_num += 1;
-
-                    current_page = RenderedPage {
-                        width: page_def.width,
-                        height: page_def.height,
-                        paragraphs: Vec::new(),
-                        page_number: *page_num,
-                    };
+        // Content first, laid out within each cell's margins
+        let mut next = index + 1;
+        let mut cells = Vec::new();
+        let mut needed = Vec::new();
+        for cell in &table.cells {
+            let (row, col) = (cell.cell_address.0 as usize, cell.cell_address.1 as usize);
+            let row_span = cell.row_span.max(1) as usize;
+            let col_span = cell.col_span.max(1) as usize;
+            let cell_x = column(col);
+            let cell_width = column(col + col_span) - cell_x;
 
-                    current_y = content_y;
-                }
+            let mut content = Vec::new();
+            if next < paragraphs.len() {
+                let inner_x = cell_x + cell.left_margin as i32;
+                let inner_width = cell_width - cell.left_margin as i32 - cell.right_margin as i32;
+                let (laid, after) = self.layout_block(
+                    paragraphs,
+                    next,
+                    section_index,
+                    (inner_x, inner_width),
+                    (true, direction),
+                );
+                content.extend(laid);
+                next = after;
+            }
+            let content_height: i32 = content.iter().map(|para| para.height).sum();
+            let margins = cell.top_margin as i32 + cell.bottom_margin as i32;
+            needed.push((cell.height as i32).max(content_height + margins));
+
+            cells.push(RenderedCell {
+                x: cell_x,
+                y: 0,
+                width: cell_width,
+                height: 0,
+                row,
+                col,
+                row_span,
+                col_span,
+                border_fill_id: cell.border_fill_id,
+                paragraphs: content,
+            });
+        }
 
-                current_y += rendered_para.height;
-                current_page.paragraphs.push(rendered_para);
+        // Rows fit their tallest single-row cell; a taller merged cell grows its last row
+        let row_count = cells
+            .iter()
+            .map(|cell| cell.row + cell.row_span)
+            .max()
+            .unwrap_or(0)
+            .max(table.rows as usize);
+        let mut row_heights = vec![0; row_count];
+        for (cell, &needed) in cells.iter().zip(&needed) {
+            if cell.row_span == 1 {
+                row_heights[cell.row] = row_heights[cell.row].max(needed);
+            }
+        }
+        let mut spanning: Vec<usize> = (0..cells.len())
+            .filter(|&i| cells[i].row_span > 1)
+            .collect();
+        spanning.sort_by_key(|&i| cells[i].row_span);
+        for i in spanning {
+            let rows = cells[i].row..cells[i].row + cells[i].row_span;
+            let spanned: i32 = row_heights[rows.clone()].iter().sum();
+            if spanned < needed[i] {
+                row_heights[rows.end - 1] += needed[i] - spanned;
             }
         }
 
-        if !current_page.paragraphs.is_empty() {
-            pages.push(current_page);
-            *page_num += 1;
+        let tops = row_tops(&row_heights);
+        for (cell, model) in cells.iter_mut().zip(&table.cells) {
+            cell.y = tops[cell.row];
+            cell.height = tops[cell.row + cell.row_span] - cell.y;
+
+            let content_height: i32 = cell.paragraphs.iter().map(|para| para.height).sum();
+            let free =
+                cell.height - model.top_margin as i32 - model.bottom_margin as i32 - content_height;
+            let vertical_alignment = cell
+                .paragraphs
+                .first()
+                .and_then(|para| paragraphs[para.paragraph_index].list_header.as_ref())
+                .map_or(0, |header| header.vertical_alignment());
+            let offset = match vertical_alignment {
+                1 => free.max(0) / 2,
+                2 => free.max(0),
+                _ => 0,
+            };
+
+            let mut y = cell.y + model.top_margin as i32 + offset;
+            for para in &mut cell.paragraphs {
+                para.shift(y);
+                y += para.height;
+            }
         }
 
-        pages
+        let width = column(usize::MAX) - table_x;
+        let height = tops[row_count];
+        let laid = RenderedParagraph {
+            x: table_x,
+            y: 0,
+            width,
+            height,
+            lines: Vec::new(),
+            para_shape_id: paragraph.para_shape_id,
+            section_index,
+            paragraph_index: index,
+            first_line: 0,
+            objects: Vec::new(),
+            table: Some(RenderedTable {
+                x: table_x,
+                y: 0,
+                width,
+                height,
+                row_heights,
+                first_row: 0,
+                cells,
+            }),
+        };
+        (Some(laid), next)
     }
 
-    /// Layout a single paragraph
+    /// Layout a single paragraph with its lines relative to `y`
+    ///
+    /// The height includes the space above and below the paragraph.
     fn layout_paragraph(
         &self,
         paragraph: &Paragraph,
@@ -142,10 +1981,8 @@ impl<'a> LayoutEngine<'a> {
         y: i32,
         width: i32,
     ) -> Option<RenderedParagraph> {
-        let text = paragraph.text.as_ref()?.content.as_str();
-        if text.is_empty() {
-            return None;
-        }
+        // Paragraphs without text still take up an empty line
+        let text = paragraph.text.as_ref().map_or("", |t| t.content.as_str());
 
         // Get paragraph shape
         let para_shape = self
@@ -156,27 +1993,16 @@ impl<'a> LayoutEngine<'a> {
         let para_x = x + para_shape.left_margin;
         let para_width = width - para_shape.left_margin - para_shape.right_margin;
 
-        // Check if paragraph has pre-calculated line segments
-        if let Some(line_segs) = &paragraph.line_segments {
-            // Use pre-calculated line layout
-            let lines = self.render_with_line_segments(text, paragraph, line_segs, para_x, y);
-
-            let total_height = line_segs.total_height();
-
-            return Some(RenderedParagraph {
-                x: para_x,
-                y,
-                width: para_width,
-                height: total_height,
-                lines,
-                para_shape_id: paragraph.para_shape_id,
-            });
-        }
-
-        // Otherwise, calculate line breaks dynamically
-        let lines = self.calculate_line_breaks(text, paragraph, para_shape, para_x, y, para_width);
+        // Use pre-calculated line layout when it matches the text, otherwise
+        // calculate line breaks dynamically
+        let lines = match &paragraph.line_segments {
+            Some(line_segs) if usable_line_segments(line_segs, text.chars().count()) => self
+                .render_with_line_segments(paragraph, para_shape, line_segs, para_x, y, para_width),
+            _ => self.calculate_line_breaks(text, paragraph, para_shape, para_x, y, para_width),
+        };
 
-        let total_height = lines.iter().map(|l| l.height).sum();
+        let total_height =
+            lines.last().map_or(0, |l| l.y + l.height) - y + para_shape.bottom_para_space;
 
         Some(RenderedParagraph {
             x: para_x,
@@ -185,55 +2011,82 @@ impl<'a> LayoutEngine<'a> {
             height: total_height,
             lines,
             para_shape_id: paragraph.para_shape_id,
+            section_index: 0,
+            paragraph_index: 0,
+            first_line: 0,
+            table: None,
+            objects: Vec::new(),
         })
     }
 
     /// Render using pre-calculated line segments
     fn render_with_line_segments(
         &self,
-        text: &str,
         paragraph: &Paragraph,
+        para_shape: &ParaShape,
         line_segs: &ParaLineSeg,
         x: i32,
         y: i32,
+        width: i32,
     ) -> Vec<RenderedLine> {
         let mut lines = Vec::new();
-        let mut current_y = y;
+        let mut current_y = y + para_shape.top_para_space;
 
-        for (idx, line_seg) in line_segs.line_segments.iter().enumerate() {
-            // Get text range for this line
-            let start = if idx == 0 {
-                0
-            } else {
-                line_segs.line_segments[idx - 1].text_start_position as usize
-            };
-            let end = line_seg.text_start_position as usize;
+        let text = paragraph.text.as_ref().map_or("", |t| t.content.as_str());
+        let chars: Vec<char> = text.chars().collect();
+        let (_, advances) = self.char_metrics(text, paragraph);
+        let alignment = Alignment::of(para_shape);
+        let segments = &line_segs.line_segments;
+        for (idx, line_seg) in segments.iter().enumerate() {
+            // Each segment records where its line starts, in characters
+            let start = line_seg.text_start_position as usize;
+            let end = segments
+                .get(idx + 1)
+                .map_or(chars.len(), |next| next.text_start_position as usize)
+                .min(chars.len());
 
-            if end > text.len() {
+            if start > chars.len() {
                 break;
             }
 
-            let line_text = &text[start..end];
+            let (line_x, available) = indented(para_shape, x, width, idx == 0);
+            let visible = visible_end(&chars, start, end.max(start));
+            let last = idx + 1 == segments.len() || visible < end;
+            let (offset, gaps) = arrange(
+                alignment,
+                &chars[start..visible],
+                &advances[start..visible],
+                available,
+                last,
+            );
 
-            // Create text runs for this line
-            let runs = self.create_text_runs_for_line(line_text, paragraph, x, start as u32);
+            let line_text = &text[char_range(text, start, visible)];
+            let runs = self.create_text_runs_for_line(
+                line_text,
+                paragraph,
+                line_x + offset,
+                start as u32,
+                &gaps,
+            );
 
+            // Stored vertical positions are absolute, so lines are stacked by
+            // their height and spacing instead
+            let pitch = line_seg.line_height + line_seg.line_space.max(0);
             lines.push(RenderedLine {
-                y: current_y + line_seg.line_vertical_position,
-                height: line_seg.line_height,
-                baseline_y: current_y
-                    + line_seg.line_vertical_position
-                    + line_seg.distance_baseline_to_line_vertical_position,
+                y: current_y,
+                height: pitch,
+                baseline_y: current_y + line_seg.distance_baseline_to_line_vertical_position,
                 runs,
+                text_range: start..end.max(start),
             });
 
-            current_y += line_seg.line_height;
+            current_y += pitch;
         }
 
         lines
     }
 
-    /// Calculate line breaks dynamically
+    /// Break a paragraph into lines that fit `width` and lay them out
     fn calculate_line_breaks(
         &self,
         text: &str,
@@ -241,39 +2094,60 @@ impl<'a> LayoutEngine<'a> {
         para_shape: &ParaShape,
         x: i32,
         y: i32,
-        _width: i32,
+        width: i32,
     ) -> Vec<RenderedLine> {
         let mut lines = Vec::new();
         let mut current_y = y + para_shape.top_para_space;
 
-        // Get default character shape
-        let default_char_shape_id = if let Some(char_shapes) = &paragraph.char_shapes {
-            char_shapes
-                .char_positions
-                .first()
-                .map(|cs| cs.char_shape_id)
-                .unwrap_or(0)
-        } else {
-            0
-        };
+        let chars: Vec<char> = text.chars().collect();
+        let (shape_ids, advances) = self.char_metrics(text, paragraph);
+        let alignment = Alignment::of(para_shape);
 
-        let char_shape = self.document.get_char_shape(default_char_shape_id as usize);
-        let line_height = self.calculate_line_height(para_shape, char_shape);
+        let breaks = self.break_lines(&chars, &shape_ids, &advances, para_shape, width);
+        for (idx, line) in breaks.iter().enumerate() {
+            let range = line.range.start..visible_end(&chars, line.range.start, line.range.end);
+            let (line_x, available) = indented(para_shape, x, width, idx == 0);
 
-        // Simple line breaking - split by newlines for now
-        for line_text in text.lines() {
-            if line_text.is_empty() {
-                current_y += line_height;
-                continue;
-            }
+            // The tallest character shape on the line sets its height
+            let char_shape = shape_ids[range.clone()]
+                .iter()
+                .filter_map(|&id| self.document.get_char_shape(id as usize))
+                .max_by_key(|cs| cs.base_size)
+                .or_else(|| {
+                    let id = shape_ids
+                        .get(range.start)
+                        .copied()
+                        .unwrap_or(char_shape_spans(paragraph)[0].2);
+                    self.document.get_char_shape(id as usize)
+                });
+            let line_height = self.calculate_line_height(para_shape, char_shape);
+            let baseline = self.baseline_offset(char_shape);
 
-            let runs = self.create_text_runs_for_line(line_text, paragraph, x, 0);
+            let mut line_chars = chars[range.clone()].to_vec();
+            let mut line_advances = advances[range.clone()].to_vec();
+            let mut line_text = text[char_range(text, range.start, range.end)].to_string();
+            if line.hyphen {
+                let id = shape_ids[range.end.saturating_sub(1)];
+                line_chars.push('-');
+                line_advances.push(self.text_width("-", id));
+                line_text.push('-');
+            }
+            let (offset, gaps) =
+                arrange(alignment, &line_chars, &line_advances, available, line.last);
+            let runs = self.create_text_runs_for_line(
+                &line_text,
+                paragraph,
+                line_x + offset,
+                range.start as u32,
+                &gaps,
+            );
 
             lines.push(RenderedLine {
                 y: current_y,
                 height: line_height,
-                baseline_y: current_y + (line_height * 4 / 5), // Approximate baseline
+                baseline_y: current_y + baseline,
                 runs,
+                text_range: line.range.clone(),
             });
 
             current_y += line_height;
@@ -282,69 +2156,216 @@ impl<'a> LayoutEngine<'a> {
         lines
     }
 
+    /// Choose line ends for a paragraph
+    ///
+    /// Lines break after spaces, between characters where the paragraph's
+    /// Korean and Latin break units allow it, and never before a forbidden
+    /// line-start or after a forbidden line-end character. A unit wider than
+    /// the line is broken wherever it overflows.
+    fn break_lines(
+        &self,
+        chars: &[char],
+        shape_ids: &[u16],
+        advances: &[i32],
+        para_shape: &ParaShape,
+        width: i32,
+    ) -> Vec<LineBreak> {
+        let mut lines = Vec::new();
+        let mut start = 0;
+        loop {
+            let (_, available) = indented(para_shape, 0, width, lines.is_empty());
+            let (line, next) =
+                self.next_line(chars, shape_ids, advances, para_shape, start, available);
+            lines.push(line);
+            if next >= chars.len() {
+                return lines;
+            }
+            start = next;
+        }
+    }
+
+    /// The line starting at `start`, and where the one after it starts
+    fn next_line(
+        &self,
+        chars: &[char],
+        shape_ids: &[u16],
+        advances: &[i32],
+        para_shape: &ParaShape,
+        start: usize,
+        available: i32,
+    ) -> (LineBreak, usize) {
+        let mut used = 0;
+        let mut last_break = None;
+
+        for (i, &c) in chars.iter().enumerate().skip(start) {
+            if c == '\n' {
+                let line = LineBreak {
+                    range: start..i,
+                    hyphen: false,
+                    last: true,
+                };
+                return (line, i + 1);
+            }
+            if i > start && may_break(chars[i - 1], c, para_shape) {
+                last_break = Some(i);
+            }
+
+            if i > start && !c.is_whitespace() && used + advances[i] > available {
+                let hyphen = if para_shape.get_latin_break_unit() == 1 {
+                    self.hyphen_point(chars, shape_ids, advances, start, i, available - used)
+                } else {
+                    None
+                };
+                let (end, hyphen) = match (hyphen, last_break) {
+                    (Some(end), _) => (end, true),
+                    (None, Some(end)) => (end, false),
+                    (None, None) => (i, false),
+                };
+                let line = LineBreak {
+                    range: start..end,
+                    hyphen,
+                    last: false,
+                };
+                return (line, end);
+            }
+            used += advances[i];
+        }
+
+        let line = LineBreak {
+            range: start..chars.len(),
+            hyphen: false,
+            last: true,
+        };
+        (line, chars.len())
+    }
+
+    /// Where to hyphenate a Latin word that overflows at `overflow`
+    ///
+    /// `room` is the space left on the line before `overflow`. At least two
+    /// letters stay on each side of the hyphen.
+    fn hyphen_point(
+        &self,
+        chars: &[char],
+        shape_ids: &[u16],
+        advances: &[i32],
+        line_start: usize,
+        overflow: usize,
+        room: i32,
+    ) -> Option<usize> {
+        if !is_latin_letter(chars[overflow]) {
+            return None;
+        }
+        let word_start = (line_start..overflow)
+            .rev()
+            .take_while(|&k| is_latin_letter(chars[k]))
+            .last()?;
+        let word_end = (overflow..chars.len())
+            .take_while(|&k| is_latin_letter(chars[k]))
+            .last()?
+            + 1;
+
+        // Letters moved to the next line free their width for the hyphen
+        let hyphen = self.text_width("-", shape_ids[overflow]);
+        (word_start + 2..=overflow.min(word_end.saturating_sub(2)))
+            .rev()
+            .find(|&end| room + advances[end..overflow].iter().sum::<i32>() >= hyphen)
+    }
+
     /// Create text runs for a line of text
+    ///
+    /// `text_offset` is the character offset of the line within the paragraph
+    /// and `gaps` the extra space after each character of the line, which
+    /// splits runs so every piece sits at its own position.
     fn create_text_runs_for_line(
         &self,
         line_text: &str,
         paragraph: &Paragraph,
         x: i32,
         text_offset: u32,
+        gaps: &[i32],
     ) -> Vec<TextRun> {
-        let mut runs = Vec::new();
+        let line_start = text_offset as usize;
+        let line_end = line_start + line_text.chars().count();
 
-        if let Some(char_shapes) = &paragraph.char_shapes {
-            // Create runs based on character shape changes
-            let mut current_x = x;
+        let mut runs = Vec::new();
+        let mut current_x = x;
+        for (start, end, char_shape_id) in char_shape_spans(paragraph) {
+            let start = start.max(line_start);
+            let end = end.min(line_end);
+            if start >= end {
+                continue;
+            }
 
-            for (idx, pos_shape) in char_shapes.char_positions.iter().enumerate() {
-                let start = pos_shape.position as usize;
-                let end = if idx + 1 < char_shapes.char_positions.len() {
-                    char_shapes.char_positions[idx + 1].position as usize
-                } else {
-                    line_text.len() + text_offset as usize
-                };
+            let span_text = &line_text[char_range(line_text, start - line_start, end - line_start)];
+            let char_shape = self.document.get_char_shape(char_shape_id as usize);
+            for (script, range) in script_runs(span_text) {
+                let first = start - line_start + span_text[..range.start].chars().count();
+                let run_text = &span_text[range];
+                let advances = self.script_advances(run_text, script, char_shape);
 
-                if start >= text_offset as usize && (start - text_offset as usize) < line_text.len()
+                let mut piece_start = 0;
+                let mut piece_first = first;
+                let mut piece_advances = Vec::new();
+                for (k, ((offset, c), advance)) in run_text.char_indices().zip(advances).enumerate()
                 {
-                    let run_start = (start - text_offset as usize).min(line_text.len());
-                    let run_end = (end - text_offset as usize).min(line_text.len());
-
-                    if run_start < run_end {
-                        let run_text = &line_text[run_start..run_end];
-                        let char_shape = self
-                            .document
-                            .get_char_shape(pos_shape.char_shape_id as usize);
-
-                        let width = self.calculate_text_width(run_text, char_shape);
-
-                        runs.push(TextRun {
-                            x: current_x,
-                            width,
-                            text: run_text.to_string(),
-                            char_shape_id: pos_shape.char_shape_id,
-                            font_size: char_shape.map(|cs| cs.base_size).unwrap_or(1000),
-                        });
-
-                        current_x += width;
+                    piece_advances.push(advance);
+                    let gap = gaps.get(first + k).copied().unwrap_or(0);
+                    let piece_end = offset + c.len_utf8();
+                    if gap == 0 && piece_end < run_text.len() {
+                        continue;
                     }
+
+                    let width = piece_advances.iter().sum::<i32>();
+                    runs.push(TextRun {
+                        x: current_x,
+                        width,
+                        text: run_text[piece_start..piece_end].to_string(),
+                        char_shape_id,
+                        font_size: script_font_size(char_shape, script),
+                        script,
+                        paragraph_index: 0,
+                        char_offset: line_start + piece_first,
+                        advances: std::mem::take(&mut piece_advances),
+                        sideways: false,
+                    });
+
+                    current_x += width + gap;
+                    piece_start = piece_end;
+                    piece_first = first + k + 1;
                 }
             }
-        } else {
-            // Single run for entire line
-            let width = self.calculate_text_width(line_text, None);
-
-            runs.push(TextRun {
-                x,
-                width,
-                text: line_text.to_string(),
-                char_shape_id: 0,
-                font_size: 1000, // Default 10pt
-            });
         }
 
         runs
     }
 
+    /// Character shape id and advance of every character of a paragraph
+    fn char_metrics(&self, text: &str, paragraph: &Paragraph) -> (Vec<u16>, Vec<i32>) {
+        let char_count = text.chars().count();
+        let mut shape_ids = Vec::with_capacity(char_count);
+        let mut advances = Vec::with_capacity(char_count);
+        for (start, end, char_shape_id) in char_shape_spans(paragraph) {
+            // Out of order positions must not leave characters unmeasured
+            let start = start.max(shape_ids.len());
+            let end = end.min(char_count);
+            if start >= end {
+                continue;
+            }
+            let span_text = &text[char_range(text, start, end)];
+            shape_ids.extend(std::iter::repeat_n(char_shape_id, end - start));
+            advances.extend(self.char_advances(span_text, char_shape_id));
+        }
+        if shape_ids.len() < char_count {
+            let start = shape_ids.len();
+            let char_shape_id = shape_ids.last().copied().unwrap_or(0);
+            shape_ids.resize(char_count, char_shape_id);
+            advances.extend(
+                self.char_advances(&text[char_range(text, start, char_count)], char_shape_id),
+            );
+        }
+        (shape_ids, advances)
+    }
+
     /// Calculate line height based on paragraph and character shapes
     fn calculate_line_height(&self, para_shape: &ParaShape, char_shape: Option<&CharShape>) -> i32 {
         let base_size = char_shape.map(|cs| cs.base_size).unwrap_or(1000);
@@ -366,15 +2387,309 @@ impl<'a> LayoutEngine<'a> {
         }
     }
 
-    /// Calculate text width (simplified)
-    fn calculate_text_width(&self, text: &str, char_shape: Option<&CharShape>) -> i32 {
-        let font_size = char_shape.map(|cs| cs.base_size).unwrap_or(1000);
+    /// Advance of each character of `text` set in a character shape, in HWP units
+    ///
+    /// Each script uses its own face, ratio, spacing and relative size from the
+    /// character shape. Without a font provider, widths are estimated at a full
+    /// em for Hangul, Hanja and kana and half an em otherwise.
+    pub fn char_advances(&self, text: &str, char_shape_id: u16) -> Vec<i32> {
+        let char_shape = self.document.get_char_shape(char_shape_id as usize);
+        script_runs(text)
+            .into_iter()
+            .flat_map(|(script, range)| self.script_advances(&text[range], script, char_shape))
+            .collect()
+    }
+
+    /// Width of `text` set in a character shape, in HWP units
+    pub fn text_width(&self, text: &str, char_shape_id: u16) -> i32 {
+        self.char_advances(text, char_shape_id).iter().sum()
+    }
+
+    fn script_advances(
+        &self,
+        text: &str,
+        script: Script,
+        char_shape: Option<&CharShape>,
+    ) -> Vec<i32> {
+        let slot = script.index();
+        let size = script_font_size(char_shape, script) as f32;
+        let (ratio, spacing) =
+            char_shape.map_or((100, 0), |cs| (cs.ratios[slot], cs.char_spaces[slot]));
+        let ratio = if ratio == 0 {
+            1.0
+        } else {
+            ratio as f32 / 100.0
+        };
+        let spacing = spacing as f32 / 100.0;
+
+        let ems = self
+            .fonts
+            .zip(char_shape)
+            .and_then(|(fonts, cs)| {
+                fonts.advances(self.family(cs, script), cs.is_bold(), cs.is_italic(), text)
+            })
+            .filter(|ems| ems.len() == text.chars().count())
+            .unwrap_or_else(|| {
+                text.chars()
+                    .map(|c| if Script::of(c).is_wide() { 1.0 } else { 0.5 })
+                    .collect()
+            });
+
+        ems.iter()
+            .map(|em| ((em * ratio + spacing) * size).round() as i32)
+            .collect()
+    }
+
+    /// Distance from the top of a line box to the baseline, in HWP units
+    fn baseline_offset(&self, char_shape: Option<&CharShape>) -> i32 {
+        let metrics = self
+            .fonts
+            .zip(char_shape)
+            .and_then(|(fonts, cs)| {
+                fonts.vertical_metrics(
+                    self.family(cs, Script::Hangul),
+                    cs.is_bold(),
+                    cs.is_italic(),
+                )
+            })
+            .filter(|m| m.ascent + m.descent > 0.0)
+            .unwrap_or(DEFAULT_METRICS);
+        let size = char_shape.map_or(1000, |cs| cs.base_size) as f32;
+        (size * metrics.ascent / (metrics.ascent + metrics.descent)).round() as i32
+    }
+
+    /// Face name for a script of a character shape
+    fn family(&self, char_shape: &CharShape, script: Script) -> &str {
+        self.document
+            .get_face_name(char_shape.face_name_ids[script.index()] as usize)
+            .map_or("", |face| face.font_name.as_str())
+    }
+}
+
+/// Character shape spans of a paragraph as (start, end, shape id) in characters
+fn char_shape_spans(paragraph: &Paragraph) -> Vec<(usize, usize, u16)> {
+    let positions = paragraph
+        .char_shapes
+        .as_ref()
+        .map(|shapes| shapes.char_positions.as_slice())
+        .unwrap_or_default();
+    if positions.is_empty() {
+        return vec![(0, usize::MAX, 0)];
+    }
+    positions
+        .iter()
+        .enumerate()
+        .map(|(idx, pos)| {
+            let end = positions
+                .get(idx + 1)
+                .map_or(usize::MAX, |next| next.position as usize);
+            // Text before the first recorded position uses the first shape
+            let start = if idx == 0 { 0 } else { pos.position as usize };
+            (start, end, pos.char_shape_id)
+        })
+        .collect()
+}
+
+/// Whether stored line segments describe this text: starting at its first
+/// character, in order and within it
+fn usable_line_segments(line_segs: &ParaLineSeg, char_count: usize) -> bool {
+    let segments = &line_segs.line_segments;
+    segments
+        .first()
+        .is_some_and(|first| first.text_start_position == 0)
+        && segments
+            .windows(2)
+            .all(|pair| pair[0].text_start_position < pair[1].text_start_position)
+        && segments.iter().all(|segment| {
+            segment.line_height > 0 && (segment.text_start_position as usize) < char_count.max(1)
+        })
+}
+
+/// Top of each row from the top of its table, followed by the table's bottom
+fn row_tops(row_heights: &[i32]) -> Vec<i32> {
+    let mut tops = vec![0];
+    for height in row_heights {
+        tops.push(tops[tops.len() - 1] + height);
+    }
+    tops
+}
+
+/// The cells of `table` that touch `rows`, clipped to them and moved down by `dy`
+///
+/// Only cells that start within `rows` keep their content.
+fn clip_cells(
+    table: &RenderedTable,
+    tops: &[i32],
+    rows: Range<usize>,
+    dy: i32,
+) -> Vec<RenderedCell> {
+    table
+        .cells
+        .iter()
+        .filter_map(|cell| {
+            let first = cell.row.max(rows.start);
+            let last = (cell.row + cell.row_span).min(rows.end);
+            if first >= last {
+                return None;
+            }
+            let mut clipped = RenderedCell {
+                y: tops[first] + dy,
+                height: tops[last] - tops[first],
+                paragraphs: Vec::new(),
+                ..cell.clone()
+            };
+            if cell.row >= rows.start {
+                clipped.paragraphs = cell.paragraphs.clone();
+                for para in &mut clipped.paragraphs {
+                    para.shift(dy);
+                }
+            }
+            Some(clipped)
+        })
+        .collect()
+}
+
+/// Border rectangle and background area of a page
+fn page_border(page_def: &PageDef, placement: &PageBorderFill) -> RenderedPageBorder {
+    let (width, height) = (page_def.width as i32, page_def.height as i32);
+    let body = (
+        page_def.left_margin as i32,
+        page_def.top_margin as i32,
+        width - page_def.right_margin as i32,
+        height - page_def.bottom_margin as i32,
+    );
+    let gaps = (
+        placement.left_gap as i32,
+        placement.top_gap as i32,
+        placement.right_gap as i32,
+        placement.bottom_gap as i32,
+    );
+
+    // Gaps lie inside the paper edge, or outside the body and its header and footer
+    let (left, top, right, bottom) = if placement.is_relative_to_paper() {
+        (gaps.0, gaps.1, width - gaps.2, height - gaps.3)
+    } else {
+        let top = if placement.includes_header() {
+            page_def.header_margin as i32
+        } else {
+            body.1
+        };
+        let bottom = if placement.includes_footer() {
+            height - page_def.footer_margin as i32
+        } else {
+            body.3
+        };
+        (
+            body.0 - gaps.0,
+            top - gaps.1,
+            body.2 + gaps.2,
+            bottom + gaps.3,
+        )
+    };
+    let fill = match placement.fill_area() {
+        PageFillArea::Paper => (0, 0, width, height),
+        PageFillArea::Page => (body.0, body.1, body.2 - body.0, body.3 - body.1),
+        PageFillArea::Border => (left, top, right - left, bottom - top),
+    };
+    RenderedPageBorder {
+        x: left,
+        y: top,
+        width: right - left,
+        height: bottom - top,
+        fill,
+        border_fill_id: placement.border_fill_id,
+    }
+}
+
+/// Turn the text of a vertical section's page and set its objects upright
+fn write_page_vertically(page: &mut RenderedPage) {
+    let Some(frame) = page.frame() else {
+        return;
+    };
+    let notes = page
+        .footnotes
+        .iter_mut()
+        .flat_map(|notes| &mut notes.paragraphs);
+    for paragraph in page.paragraphs.iter_mut().chain(notes) {
+        paragraph.write_vertically();
+        paragraph.turn_objects_onto_page(frame);
+    }
+}
+
+/// Columns a section starts with, before any paragraph defines its own
+fn section_columns(page_def: &PageDef, section_def: Option<&SectionDef>) -> ColumnDef {
+    if let Some(layout) = page_def.layout.as_ref().filter(|layout| layout.columns > 1) {
+        let gap = layout.column_spacing.min(u16::MAX as u32) as u16;
+        let column_def = ColumnDef::new(layout.columns, gap);
+        return if layout.column_line {
+            column_def.with_line(1, 1, 0x000000)
+        } else {
+            column_def
+        };
+    }
+    match section_def {
+        Some(def) if def.column_count() > 1 => ColumnDef::new(def.column_count(), def.column_gap),
+        _ => ColumnDef::new(1, 0),
+    }
+}
+
+/// Left edge and width of each column across the body area `(x, width)`
+fn column_boxes(column_def: &ColumnDef, (x, width): (i32, i32)) -> Vec<(i32, i32)> {
+    column_def
+        .columns(width)
+        .into_iter()
+        .map(|(offset, column_width)| (x + offset, column_width))
+        .collect()
+}
+
+/// Left edge of the first column and width of the narrowest
+fn column_area_of(columns: &[(i32, i32)]) -> (i32, i32) {
+    let width = columns.iter().map(|column| column.1).min().unwrap_or(0);
+    (columns[0].0, width)
+}
+
+/// Page number and hidden decorations set by a paragraph's controls
+fn apply_page_controls(flow: &mut PageFlow, paragraph: &Paragraph, page_num: &mut u32) {
+    for new_number in &paragraph.new_numbers {
+        if new_number.kind() == Some(NumberKind::Page) {
+            *page_num = new_number.number as u32;
+            flow.page.page_number = *page_num;
+        }
+    }
+    if let Some(page_hide) = paragraph.page_hide {
+        flow.page.page_hide = Some(page_hide);
+    }
+}
 
-        // Approximate width calculation
-        // In reality, this would use font metrics
-        let char_count = text.chars().count() as i32;
-        let avg_char_width = font_size / 2; // Rough approximation
+/// Left edge and available width of a line after the paragraph indent
+///
+/// A positive indent moves the first line in; a negative one (내어쓰기)
+/// moves every other line in.
+fn indented(para_shape: &ParaShape, x: i32, width: i32, first: bool) -> (i32, i32) {
+    let indent = if first {
+        para_shape.indent.max(0)
+    } else {
+        (-para_shape.indent).max(0)
+    };
+    (x + indent, width - indent)
+}
+
+/// End of the visible text of a line, dropping a trailing line feed or paragraph mark
+fn visible_end(chars: &[char], start: usize, end: usize) -> usize {
+    let mut end = end;
+    while end > start && matches!(chars[end - 1], '\n' | '\r') {
+        end -= 1;
+    }
+    end
+}
 
-        char_count * avg_char_width
+/// Font size of a script after its relative size, in HWP units
+fn script_font_size(char_shape: Option<&CharShape>, script: Script) -> i32 {
+    let Some(char_shape) = char_shape else {
+        return 1000; // Default 10pt
+    };
+    match char_shape.relative_sizes[script.index()] {
+        0 | 100 => char_shape.base_size,
+        relative => char_shape.base_size * relative as i32 / 100,
     }
 }
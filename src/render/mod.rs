@@ -1,5 +1,16 @@
+mod cff;
+pub mod font;
+pub mod hit_test;
 pub mod layout;
+pub mod pdf;
+pub mod raster;
 pub mod renderer;
+pub mod svg;
 
+pub use font::{
+    FontFace, FontMap, FontProvider, FontSegment, FontSet, MissingFont, VerticalMetrics,
+};
+pub use hit_test::{PageRect, TextPosition};
 pub use layout::{LayoutEngine, LayoutResult, RenderedPage};
 pub use renderer::{HwpRenderer, RenderOptions};
+pub use svg::{SvgOptions, SvgTextMode};
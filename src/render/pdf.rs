@@ -0,0 +1,806 @@
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt::Write as _;
+use std::io::Write as _;
+
+use flate2::write::ZlibEncoder;
+use flate2::Compression;
+
+use crate::error::{HwpError, Result};
+use crate::render::renderer::{
+    hatch_lines, Fill, PathCommand, RenderElement, RenderResult, RenderedPageOutput,
+};
+
+const CATALOG: usize = 1;
+const PAGE_TREE: usize = 2;
+const RESOURCES: usize = 3;
+
+/// Horizontal shear used to fake italics when no italic face is installed
+const ITALIC_SKEW: f32 = 0.21;
+
+/// FILETIME (100ns ticks since 1601) to Unix epoch offset in seconds
+const FILETIME_UNIX_OFFSET: i64 = 11_644_473_600;
+
+impl RenderResult {
+    /// Export every page to a PDF document
+    ///
+    /// Text is drawn with faces from `options.fonts` and embedded as font subsets.
+    /// TrueType-outline faces are embedded as `CIDFontType2` and CFF-outline faces
+    /// as `CIDFontType0`; without either, text falls back to the standard
+    /// Helvetica font, which covers Latin-1 only.
+    pub fn to_pdf(&self) -> Result<Vec<u8>> {
+        let mut pdf = PdfWriter::new(self);
+        for page in &self.pages {
+            pdf.page(page);
+        }
+        pdf.finish()
+    }
+}
+
+/// Glyphs drawn with one embedded face, keyed by glyph id for the ToUnicode map
+struct UsedFont {
+    object: usize,
+    glyphs: BTreeMap<u16, char>,
+}
+
+struct PdfWriter<'a> {
+    result: &'a RenderResult,
+    objects: Vec<Option<Vec<u8>>>,
+    pages: Vec<usize>,
+    fonts: BTreeMap<usize, UsedFont>,
+    standard_fonts: BTreeMap<&'static str, usize>,
+    images: Vec<usize>,
+    shadings: Vec<usize>,
+    /// Points per output pixel
+    unit: f32,
+}
+
+impl<'a> PdfWriter<'a> {
+    fn new(result: &'a RenderResult) -> Self {
+        let pixels_per_inch = result.options.dpi.max(1) as f32 * result.options.scale;
+        Self {
+            result,
+            objects: vec![None; RESOURCES],
+            pages: Vec::new(),
+            fonts: BTreeMap::new(),
+            standard_fonts: BTreeMap::new(),
+            images: Vec::new(),
+            shadings: Vec::new(),
+            unit: 72.0 / pixels_per_inch,
+        }
+    }
+
+    fn reserve(&mut self) -> usize {
+        self.objects.push(None);
+        self.objects.len()
+    }
+
+    fn set(&mut self, id: usize, object: Vec<u8>) {
+        self.objects[id - 1] = Some(object);
+    }
+
+    fn add(&mut self, object: Vec<u8>) -> usize {
+        let id = self.reserve();
+        self.set(id, object);
+        id
+    }
+
+    fn page(&mut self, page: &RenderedPageOutput) {
+        let width = page.width as f32 * self.unit;
+        let height = page.height as f32 * self.unit;
+        let mut content = String::new();
+
+        for element in &page.elements {
+            match element {
+                RenderElement::Text {
+                    x,
+                    y,
+                    text,
+                    font_family,
+                    font_size,
+                    color,
+                    bold,
+                    italic,
+                    underline,
+                    rotation,
+                } => {
+                    let size = font_size / self.result.options.scale;
+                    let mut x = *x as f32 * self.unit;
+                    let mut baseline = height - *y as f32 * self.unit;
+                    if *rotation != 0.0 {
+                        // Turn about the origin; y points up, so clockwise is a negative angle
+                        let (sin, cos) = rotation.to_radians().sin_cos();
+                        let _ = write!(
+                            content,
+                            "q {} {} {} {} {} {} cm ",
+                            num(cos),
+                            num(-sin),
+                            num(sin),
+                            num(cos),
+                            num(x),
+                            num(baseline)
+                        );
+                        (x, baseline) = (0.0, 0.0);
+                    }
+                    let end = self.text(
+                        &mut content,
+                        text,
+                        font_family,
+                        (x, baseline, size),
+                        *color,
+                        (*bold, *italic),
+                    );
+                    if *underline {
+                        let offset = size * 0.12;
+                        let _ = writeln!(
+                            content,
+                            "q {} w {} RG {} {} m {} {} l S Q",
+                            num(size * 0.06),
+                            rgb(*color),
+                            num(x),
+                            num(baseline - offset),
+                            num(end),
+                            num(baseline - offset)
+                        );
+                    }
+                    if *rotation != 0.0 {
+                        content.push_str("Q\n");
+                    }
+                }
+                RenderElement::Line {
+                    x1,
+                    y1,
+                    x2,
+                    y2,
+                    color,
+                    width,
+                } => {
+                    let _ = writeln!(
+                        content,
+                        "q {} w {} RG {} {} m {} {} l S Q",
+                        num(width * self.unit),
+                        rgb(*color),
+                        num(*x1 as f32 * self.unit),
+                        num(height - *y1 as f32 * self.unit),
+                        num(*x2 as f32 * self.unit),
+                        num(height - *y2 as f32 * self.unit)
+                    );
+                }
+                RenderElement::Rectangle {
+                    x,
+                    y,
+                    width: w,
+                    height: h,
+                    fill_color,
+                    stroke_color,
+                    stroke_width,
+                } => {
+                    let operator = match (fill_color, stroke_color) {
+                        (Some(_), Some(_)) => "B",
+                        (Some(_), None) => "f",
+                        (None, Some(_)) => "S",
+                        (None, None) => continue,
+                    };
+                    content.push_str("q ");
+                    if let Some(fill) = fill_color {
+                        let _ = write!(content, "{} rg ", rgb(*fill));
+                    }
+                    if let Some(stroke) = stroke_color {
+                        let _ = write!(
+                            content,
+                            "{} RG {} w ",
+                            rgb(*stroke),
+                            num(stroke_width * self.unit)
+                        );
+                    }
+                    let _ = writeln!(
+                        content,
+                        "{} {} {} {} re {operator} Q",
+                        num(*x as f32 * self.unit),
+                        num(height - (*y + *h) as f32 * self.unit),
+                        num(*w as f32 * self.unit),
+                        num(*h as f32 * self.unit)
+                    );
+                }
+                RenderElement::Image {
+                    x,
+                    y,
+                    width: w,
+                    height: h,
+                    data,
+                    rotation,
+                } => {
+                    // Undecodable images are left out rather than failing the document
+                    if let Some(image) = self.image(data) {
+                        let (w, h) = (*w as f32 * self.unit, *h as f32 * self.unit);
+                        let (x, y) = (*x as f32 * self.unit, height - *y as f32 * self.unit - h);
+                        content.push_str("q ");
+                        if *rotation != 0.0 {
+                            // Turn about the centre; y points up, so clockwise is a negative angle
+                            let (sin, cos) = rotation.to_radians().sin_cos();
+                            let (cx, cy) = (x + w / 2.0, y + h / 2.0);
+                            let _ = write!(
+                                content,
+                                "{} {} {} {} {} {} cm ",
+                                num(cos),
+                                num(-sin),
+                                num(sin),
+                                num(cos),
+                                num(cx - cx * cos - cy * sin),
+                                num(cy + cx * sin - cy * cos)
+                            );
+                        }
+                        let _ = writeln!(
+                            content,
+                            "{} 0 0 {} {} {} cm /Im{image} Do Q",
+                            num(w),
+                            num(h),
+                            num(x),
+                            num(y)
+                        );
+                    }
+                }
+                RenderElement::Path {
+                    commands,
+                    fill_color,
+                    stroke_color,
+                    stroke_width,
+                    dash,
+                } => {
+                    let operator = match (fill_color, stroke_color) {
+                        (Some(_), Some(_)) => "B",
+                        (Some(_), None) => "f",
+                        (None, Some(_)) => "S",
+                        (None, None) => continue,
+                    };
+                    content.push_str("q ");
+                    if let Some(fill) = fill_color {
+                        let _ = write!(content, "{} rg ", rgb(*fill));
+                    }
+                    if let Some(stroke) = stroke_color {
+                        let _ = write!(
+                            content,
+                            "{} RG {} w ",
+                            rgb(*stroke),
+                            num(stroke_width * self.unit)
+                        );
+                        if !dash.is_empty() {
+                            let lengths: Vec<String> =
+                                dash.iter().map(|length| num(length * self.unit)).collect();
+                            let _ = write!(content, "[{}] 0 d ", lengths.join(" "));
+                        }
+                    }
+                    let point = |(x, y): (f32, f32)| {
+                        format!("{} {}", num(x * self.unit), num(height - y * self.unit))
+                    };
+                    for command in commands {
+                        let _ = match command {
+                            PathCommand::MoveTo(to) => write!(content, "{} m ", point(*to)),
+                            PathCommand::LineTo(to) => write!(content, "{} l ", point(*to)),
+                            PathCommand::CurveTo(c1, c2, to) => {
+                                write!(content, "{} {} {} c ", point(*c1), point(*c2), point(*to))
+                            }
+                            PathCommand::Close => write!(content, "h "),
+                        };
+                    }
+                    let _ = writeln!(content, "{operator} Q");
+                }
+                RenderElement::Fill {
+                    x,
+                    y,
+                    width: w,
+                    height: h,
+                    fill,
+                } => {
+                    let _ = write!(
+                        content,
+                        "q {} {} {} {} re W n ",
+                        num(*x as f32 * self.unit),
+                        num(height - (*y + *h) as f32 * self.unit),
+                        num(*w as f32 * self.unit),
+                        num(*h as f32 * self.unit)
+                    );
+                    let point = |(x, y): (f32, f32)| {
+                        format!("{} {}", num(x * self.unit), num(height - y * self.unit))
+                    };
+                    match fill {
+                        Fill::Hatch {
+                            color,
+                            style,
+                            spacing,
+                        } => {
+                            let area = (*x as f32, *y as f32, *w as f32, *h as f32);
+                            let _ = write!(content, "{} RG {} w ", rgb(*color), num(self.unit));
+                            for (from, to) in hatch_lines(*style, area, *spacing) {
+                                let _ = write!(content, "{} m {} l ", point(from), point(to));
+                            }
+                            content.push_str("S ");
+                        }
+                        Fill::LinearGradient {
+                            start,
+                            end,
+                            from,
+                            to,
+                        } => {
+                            let coords = format!("{} {}", point(*from), point(*to));
+                            let shading = self.shading(2, &coords, *start, *end);
+                            let _ = write!(content, "/Sh{shading} sh ");
+                        }
+                        Fill::RadialGradient {
+                            start,
+                            end,
+                            center,
+                            radius,
+                        } => {
+                            let center = point(*center);
+                            let coords = format!("{center} 0 {center} {}", num(radius * self.unit));
+                            let shading = self.shading(3, &coords, *start, *end);
+                            let _ = write!(content, "/Sh{shading} sh ");
+                        }
+                    }
+                    content.push_str("Q\n");
+                }
+            }
+        }
+
+        let contents = self.add(stream("", content.as_bytes()));
+        let page = self.add(
+            format!(
+                "<< /Type /Page /Parent {PAGE_TREE} 0 R /MediaBox [0 0 {} {}] /Resources {RESOURCES} 0 R /Contents {contents} 0 R >>",
+                num(width),
+                num(height)
+            )
+            .into_bytes(),
+        );
+        self.pages.push(page);
+    }
+
+    /// Add an axial (2) or radial (3) shading from `start` to `end`,
+    /// returning its resource index
+    fn shading(&mut self, shading_type: u8, coords: &str, start: u32, end: u32) -> usize {
+        let id = self.add(
+            format!(
+                "<< /ShadingType {shading_type} /ColorSpace /DeviceRGB /Coords [{coords}] /Function << /FunctionType 2 /Domain [0 1] /C0 [{}] /C1 [{}] /N 1 >> /Extend [true true] >>",
+                rgb(start),
+                rgb(end)
+            )
+            .into_bytes(),
+        );
+        self.shadings.push(id);
+        self.shadings.len() - 1
+    }
+
+    /// Draw a text run starting at `x`/`baseline`, returning where it ends
+    fn text(
+        &mut self,
+        content: &mut String,
+        text: &str,
+        family: &str,
+        (x, baseline, size): (f32, f32, f32),
+        color: u32,
+        (bold, italic): (bool, bool),
+    ) -> f32 {
+        let fonts = &self.result.options.fonts;
+        let segments = fonts.segments_matching(text, family, bold, italic, |face| {
+            face.has_glyf_outlines() || face.has_cff_outlines()
+        });
+
+        if segments.is_empty() {
+            let name = match (bold, italic) {
+                (false, false) => "Helvetica",
+                (true, false) => "Helvetica-Bold",
+                (false, true) => "Helvetica-Oblique",
+                (true, true) => "Helvetica-BoldOblique",
+            };
+            if !self.standard_fonts.contains_key(name) {
+                let id = self.add(
+                    format!(
+                        "<< /Type /Font /Subtype /Type1 /BaseFont /{name} /Encoding /WinAnsiEncoding >>"
+                    )
+                    .into_bytes(),
+                );
+                self.standard_fonts.insert(name, id);
+            }
+            let _ = writeln!(
+                content,
+                "BT /{name} {} Tf {} rg 1 0 0 1 {} {} Tm {} Tj ET",
+                num(size),
+                rgb(color),
+                num(x),
+                num(baseline),
+                latin1_string(text)
+            );
+            // Helvetica has no metrics here; half an em is close enough for underlines
+            return x + text.chars().count() as f32 * size * 0.5;
+        }
+
+        let mut cursor = x;
+        for segment in segments {
+            let face = &fonts.faces()[segment.face];
+            let parsed = face.face();
+            let units_per_em = parsed.units_per_em().max(1) as f32;
+            let used = match self.fonts.get_mut(&segment.face) {
+                Some(used) => used,
+                None => {
+                    let object = self.reserve();
+                    self.fonts.entry(segment.face).or_insert(UsedFont {
+                        object,
+                        glyphs: BTreeMap::new(),
+                    })
+                }
+            };
+
+            let mut glyphs = String::new();
+            let mut advance = 0.0;
+            for c in text[segment.range].chars() {
+                let glyph = parsed.glyph_index(c).map_or(0, |g| g.0);
+                used.glyphs.entry(glyph).or_insert(c);
+                advance += parsed
+                    .glyph_hor_advance(ttf_parser::GlyphId(glyph))
+                    .unwrap_or(0) as f32;
+                let _ = write!(glyphs, "{:04X}", cid(parsed, glyph));
+            }
+
+            content.push_str("BT ");
+            let _ = write!(
+                content,
+                "/F{} {} Tf {} rg ",
+                segment.face,
+                num(size),
+                rgb(color)
+            );
+            if bold && !face.is_bold() {
+                let _ = write!(content, "2 Tr {} w {} RG ", num(size * 0.03), rgb(color));
+            }
+            let skew = if italic && !face.is_italic() {
+                ITALIC_SKEW
+            } else {
+                0.0
+            };
+            let _ = writeln!(
+                content,
+                "1 0 {} 1 {} {} Tm <{glyphs}> Tj ET",
+                num(skew),
+                num(cursor),
+                num(baseline)
+            );
+            cursor += advance * size / units_per_em;
+        }
+        cursor
+    }
+
+    fn image(&mut self, data: &[u8]) -> Option<usize> {
+        let decoded = image::load_from_memory(data).ok()?;
+        let (width, height) = (decoded.width(), decoded.height());
+        let header = format!(
+            "/Type /XObject /Subtype /Image /Width {width} /Height {height} /BitsPerComponent 8"
+        );
+
+        // Baseline JPEGs can be embedded as they are
+        let is_jpeg = data.starts_with(&[0xFF, 0xD8]);
+        let object = match decoded.color() {
+            image::ColorType::Rgb8 if is_jpeg => raw_stream(
+                &format!("{header} /ColorSpace /DeviceRGB /Filter /DCTDecode"),
+                data,
+            ),
+            image::ColorType::L8 if is_jpeg => raw_stream(
+                &format!("{header} /ColorSpace /DeviceGray /Filter /DCTDecode"),
+                data,
+            ),
+            _ => {
+                let rgba = decoded.to_rgba8();
+                let mut rgb = Vec::with_capacity(rgba.len() / 4 * 3);
+                let mut alpha = Vec::with_capacity(rgba.len() / 4);
+                for pixel in rgba.pixels() {
+                    rgb.extend_from_slice(&pixel.0[..3]);
+                    alpha.push(pixel.0[3]);
+                }
+                let mask = if alpha.iter().any(|&a| a != 255) {
+                    let mask =
+                        self.add(stream(&format!("{header} /ColorSpace /DeviceGray"), &alpha));
+                    format!(" /SMask {mask} 0 R")
+                } else {
+                    String::new()
+                };
+                stream(&format!("{header} /ColorSpace /DeviceRGB{mask}"), &rgb)
+            }
+        };
+
+        let id = self.add(object);
+        self.images.push(id);
+        Some(self.images.len() - 1)
+    }
+
+    /// Embed the subset of every face used on any page
+    fn embed_fonts(&mut self) -> Result<()> {
+        let fonts = std::mem::take(&mut self.fonts);
+        for (&index, used) in &fonts {
+            let face = &self.result.options.fonts.faces()[index];
+            let parsed = face.face();
+            let scale = 1000.0 / parsed.units_per_em().max(1) as f32;
+            let units = |v: i16| num(v as f32 * scale);
+
+            let glyphs: BTreeSet<u16> = used.glyphs.keys().copied().collect();
+            let subset = face.subset(&glyphs)?;
+            let name = format!("{}+{}", subset_tag(index, &glyphs), face.postscript_name());
+
+            // TrueType programs are addressed by glyph id; CFF ones by CID
+            let truetype = face.has_glyf_outlines();
+            let (file, font_file, subtype) = if truetype {
+                let file = self.add(stream(&format!("/Length1 {}", subset.len()), &subset));
+                (file, "FontFile2", "CIDFontType2")
+            } else {
+                let file = self.add(stream("/Subtype /OpenType", &subset));
+                (file, "FontFile3", "CIDFontType0")
+            };
+            let bbox = parsed.global_bounding_box();
+            let mut flags = 4; // symbolic: glyphs are addressed by id, not a standard encoding
+            if face.is_italic() {
+                flags |= 64;
+            }
+            let descriptor = self.add(
+                format!(
+                    "<< /Type /FontDescriptor /FontName /{name} /Flags {flags} /FontBBox [{} {} {} {}] /ItalicAngle {} /Ascent {} /Descent {} /CapHeight {} /StemV 80 /{font_file} {file} 0 R >>",
+                    units(bbox.x_min),
+                    units(bbox.y_min),
+                    units(bbox.x_max),
+                    units(bbox.y_max),
+                    num(parsed.italic_angle()),
+                    units(parsed.ascender()),
+                    units(parsed.descender()),
+                    units(parsed.capital_height().unwrap_or(parsed.ascender()))
+                )
+                .into_bytes(),
+            );
+
+            let codes: BTreeMap<u16, (u16, char)> = used
+                .glyphs
+                .iter()
+                .map(|(&glyph, &c)| (cid(parsed, glyph), (glyph, c)))
+                .collect();
+            let mut widths = String::new();
+            for (code, &(glyph, _)) in &codes {
+                let advance = parsed
+                    .glyph_hor_advance(ttf_parser::GlyphId(glyph))
+                    .unwrap_or(0);
+                let _ = write!(widths, "{code} [{}] ", num(advance as f32 * scale));
+            }
+            let descendant = self.add(
+                format!(
+                    "<< /Type /Font /Subtype /{subtype} /BaseFont /{name} /CIDSystemInfo << /Registry (Adobe) /Ordering (Identity) /Supplement 0 >> /FontDescriptor {descriptor} 0 R /W [{}]{} >>",
+                    widths.trim_end(),
+                    if truetype { " /CIDToGIDMap /Identity" } else { "" }
+                )
+                .into_bytes(),
+            );
+            let characters = codes.iter().map(|(&code, &(_, c))| (code, c)).collect();
+            let to_unicode = self.add(stream("", to_unicode_cmap(&characters).as_bytes()));
+            self.set(
+                used.object,
+                format!(
+                    "<< /Type /Font /Subtype /Type0 /BaseFont /{name} /Encoding /Identity-H /DescendantFonts [{descendant} 0 R] /ToUnicode {to_unicode} 0 R >>"
+                )
+                .into_bytes(),
+            );
+        }
+        self.fonts = fonts;
+        Ok(())
+    }
+
+    fn info(&mut self) -> usize {
+        let mut info = String::from("<< /Producer ");
+        info.push_str(&text_string(concat!("hwpers ", env!("CARGO_PKG_VERSION"))));
+        if let Some(summary) = &self.result.summary_info {
+            let fields = [
+                ("Title", &summary.title),
+                ("Author", &summary.author),
+                ("Subject", &summary.subject),
+                ("Keywords", &summary.keywords),
+            ];
+            for (key, value) in fields {
+                if let Some(value) = value.as_deref().filter(|v| !v.is_empty()) {
+                    let _ = write!(info, " /{key} {}", text_string(value));
+                }
+            }
+            for (key, value) in [
+                ("CreationDate", summary.creation_date),
+                ("ModDate", summary.last_saved_date),
+            ] {
+                if let Some(date) = value.and_then(pdf_date) {
+                    let _ = write!(info, " /{key} ({date})");
+                }
+            }
+        }
+        info.push_str(" >>");
+        self.add(info.into_bytes())
+    }
+
+    fn finish(mut self) -> Result<Vec<u8>> {
+        self.embed_fonts()?;
+
+        let mut resources = String::from("<< /Font << ");
+        for (index, used) in &self.fonts {
+            let _ = write!(resources, "/F{index} {} 0 R ", used.object);
+        }
+        for (name, id) in &self.standard_fonts {
+            let _ = write!(resources, "/{name} {id} 0 R ");
+        }
+        resources.push_str(">> /XObject << ");
+        for (index, id) in self.images.iter().enumerate() {
+            let _ = write!(resources, "/Im{index} {id} 0 R ");
+        }
+        resources.push_str(">> ");
+        if !self.shadings.is_empty() {
+            resources.push_str("/Shading << ");
+            for (index, id) in self.shadings.iter().enumerate() {
+                let _ = write!(resources, "/Sh{index} {id} 0 R ");
+            }
+            resources.push_str(">> ");
+        }
+        resources.push_str(">>");
+        self.set(RESOURCES, resources.into_bytes());
+
+        let kids: Vec<String> = self.pages.iter().map(|id| format!("{id} 0 R")).collect();
+        self.set(
+            PAGE_TREE,
+            format!(
+                "<< /Type /Pages /Kids [{}] /Count {} >>",
+                kids.join(" "),
+                self.pages.len()
+            )
+            .into_bytes(),
+        );
+        self.set(
+            CATALOG,
+            format!("<< /Type /Catalog /Pages {PAGE_TREE} 0 R >>").into_bytes(),
+        );
+        let info = self.info();
+
+        let mut out = b"%PDF-1.7\n%\xE2\xE3\xCF\xD3\n".to_vec();
+        let mut offsets = Vec::with_capacity(self.objects.len());
+        for (index, object) in self.objects.iter().enumerate() {
+            let object = object
+                .as_ref()
+                .ok_or_else(|| HwpError::InvalidInput(format!("PDF object {} unset", index + 1)))?;
+            offsets.push(out.len());
+            writeln!(out, "{} 0 obj", index + 1)?;
+            out.extend_from_slice(object);
+            out.extend_from_slice(b"\nendobj\n");
+        }
+
+        let xref = out.len();
+        write!(out, "xref\n0 {}\n0000000000 65535 f \n", offsets.len() + 1)?;
+        for offset in offsets {
+            writeln!(out, "{offset:010} 00000 n ")?;
+        }
+        write!(
+            out,
+            "trailer\n<< /Size {} /Root {CATALOG} 0 R /Info {info} 0 R >>\nstartxref\n{xref}\n%%EOF\n",
+            self.objects.len() + 1
+        )?;
+        Ok(out)
+    }
+}
+
+/// Flate-compressed stream object
+fn stream(dict: &str, data: &[u8]) -> Vec<u8> {
+    let mut encoder = ZlibEncoder::new(Vec::new(), Compression::default());
+    // Writing to a Vec cannot fail
+    let _ = encoder.write_all(data);
+    let compressed = encoder.finish().unwrap_or_default();
+    raw_stream(&format!("{dict} /Filter /FlateDecode"), &compressed)
+}
+
+fn raw_stream(dict: &str, data: &[u8]) -> Vec<u8> {
+    let mut object = format!("<< {} /Length {} >>\nstream\n", dict.trim(), data.len()).into_bytes();
+    object.extend_from_slice(data);
+    object.extend_from_slice(b"\nendstream");
+    object
+}
+
+/// Code that selects `glyph` in the embedded font
+///
+/// A CID-keyed CFF program looks glyphs up through its charset, so text is
+/// written in CIDs; every other program is addressed by glyph id.
+fn cid(face: &ttf_parser::Face<'_>, glyph: u16) -> u16 {
+    face.tables()
+        .cff
+        .and_then(|cff| cff.glyph_cid(ttf_parser::GlyphId(glyph)))
+        .unwrap_or(glyph)
+}
+
+fn to_unicode_cmap(glyphs: &BTreeMap<u16, char>) -> String {
+    let mut cmap = String::from(
+        "/CIDInit /ProcSet findresource begin\n12 dict begin\nbegincmap\n\
+         /CIDSystemInfo << /Registry (Adobe) /Ordering (UCS) /Supplement 0 >> def\n\
+         /CMapName /Adobe-Identity-UCS def\n/CMapType 2 def\n\
+         1 begincodespacerange\n<0000> <FFFF>\nendcodespacerange\n",
+    );
+    let mapped: Vec<(&u16, &char)> = glyphs.iter().filter(|(&glyph, _)| glyph != 0).collect();
+    for chunk in mapped.chunks(100) {
+        let _ = writeln!(cmap, "{} beginbfchar", chunk.len());
+        for (glyph, c) in chunk {
+            let mut units = [0u16; 2];
+            let hex: String = c
+                .encode_utf16(&mut units)
+                .iter()
+                .map(|u| format!("{u:04X}"))
+                .collect();
+            let _ = writeln!(cmap, "<{glyph:04X}> <{hex}>");
+        }
+        cmap.push_str("endbfchar\n");
+    }
+    cmap.push_str("endcmap\nCMapName currentdict /CMap defineresource pop\nend\nend\n");
+    cmap
+}
+
+/// Six-letter subset prefix, stable for the same face and glyph set
+fn subset_tag(face: usize, glyphs: &BTreeSet<u16>) -> String {
+    let mut hash: u32 = 2166136261 ^ face as u32;
+    for glyph in glyphs {
+        for byte in glyph.to_be_bytes() {
+            hash = (hash ^ byte as u32).wrapping_mul(16777619);
+        }
+    }
+    (0..6)
+        .map(|_| {
+            let letter = (b'A' + (hash % 26) as u8) as char;
+            hash /= 26;
+            letter
+        })
+        .collect()
+}
+
+/// Literal string in WinAnsiEncoding; characters outside Latin-1 become `?`
+fn latin1_string(text: &str) -> String {
+    let mut out = String::from("(");
+    for c in text.chars() {
+        match c {
+            '(' | ')' | '\\' => {
+                out.push('\\');
+                out.push(c);
+            }
+            ' '..='~' => out.push(c),
+            '\u{A0}'..='\u{FF}' => {
+                let _ = write!(out, "\\{:03o}", c as u32);
+            }
+            _ => out.push('?'),
+        }
+    }
+    out.push(')');
+    out
+}
+
+/// Text string as UTF-16BE with a byte order mark, which readers accept for any script
+fn text_string(text: &str) -> String {
+    let mut out = String::from("<FEFF");
+    for unit in text.encode_utf16() {
+        let _ = write!(out, "{unit:04X}");
+    }
+    out.push('>');
+    out
+}
+
+fn pdf_date(filetime: i64) -> Option<String> {
+    let seconds = filetime / 10_000_000 - FILETIME_UNIX_OFFSET;
+    let date = chrono::DateTime::from_timestamp(seconds, 0)?;
+    Some(date.format("D:%Y%m%d%H%M%SZ").to_string())
+}
+
+fn rgb(color: u32) -> String {
+    format!(
+        "{} {} {}",
+        num(((color >> 16) & 0xFF) as f32 / 255.0),
+        num(((color >> 8) & 0xFF) as f32 / 255.0),
+        num((color & 0xFF) as f32 / 255.0)
+    )
+}
+
+/// Compact number for content streams
+fn num(value: f32) -> String {
+    let rounded = (value * 1000.0).round() / 1000.0;
+    if rounded == rounded.trunc() {
+        format!("{}", rounded as i64)
+    } else {
+        format!("{rounded}")
+    }
+}
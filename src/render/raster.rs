@@ -0,0 +1,467 @@
+use tiny_skia::{
+    Color, FillRule, FilterQuality, GradientStop, IntSize, LinearGradient, Paint, PathBuilder,
+    Pixmap, PixmapPaint, Point, RadialGradient, Rect, SpreadMode, Stroke, StrokeDash, Transform,
+};
+
+use crate::error::{HwpError, Result};
+use crate::render::renderer::{
+    hatch_lines, Fill, PathCommand, RenderElement, RenderResult, RenderedPageOutput,
+};
+
+/// Horizontal shear used to fake italics when no italic face is installed
+const ITALIC_SKEW: f32 = 0.21;
+
+/// Largest raster edge in pixels, keeping a mistyped DPI from exhausting memory
+const MAX_DIMENSION: u32 = 16_384;
+
+impl RenderResult {
+    /// Rasterise one page to a PNG image at `dpi`
+    ///
+    /// Text is drawn from glyph outlines of the faces in `options.fonts`;
+    /// without fonts only lines, rectangles and images appear.
+    pub fn to_png(&self, page_index: usize, dpi: u32) -> Result<Vec<u8>> {
+        let page = self
+            .pages
+            .get(page_index)
+            .ok_or_else(|| HwpError::NotFound(format!("page {page_index}")))?;
+        let pixmap = Rasterizer::new(self, page, dpi)?.draw(page);
+        pixmap
+            .encode_png()
+            .map_err(|e| HwpError::InvalidInput(format!("PNG encoding failed: {e}")))
+    }
+}
+
+struct Rasterizer<'a> {
+    result: &'a RenderResult,
+    pixmap: Pixmap,
+    /// Output pixels per render pixel
+    factor: f32,
+    /// Output pixels per point
+    pixels_per_point: f32,
+}
+
+impl<'a> Rasterizer<'a> {
+    fn new(result: &'a RenderResult, page: &RenderedPageOutput, dpi: u32) -> Result<Self> {
+        let render_ppi = result.options.dpi.max(1) as f32 * result.options.scale;
+        let factor = dpi as f32 / render_ppi;
+        let width = (page.width.max(1) as f32 * factor).ceil() as u32;
+        let height = (page.height.max(1) as f32 * factor).ceil() as u32;
+        if width > MAX_DIMENSION || height > MAX_DIMENSION {
+            return Err(HwpError::InvalidInput(format!(
+                "{width}x{height} pixels at {dpi} dpi exceeds {MAX_DIMENSION}"
+            )));
+        }
+        let mut pixmap = Pixmap::new(width.max(1), height.max(1))
+            .ok_or_else(|| HwpError::InvalidInput(format!("cannot rasterise at {dpi} dpi")))?;
+        pixmap.fill(Color::WHITE);
+
+        Ok(Self {
+            result,
+            pixmap,
+            factor,
+            pixels_per_point: dpi as f32 / 72.0,
+        })
+    }
+
+    fn draw(mut self, page: &RenderedPageOutput) -> Pixmap {
+        for element in &page.elements {
+            match element {
+                RenderElement::Text {
+                    x,
+                    y,
+                    text,
+                    font_family,
+                    font_size,
+                    color,
+                    bold,
+                    italic,
+                    underline,
+                    rotation,
+                } => {
+                    let size = font_size / self.result.options.scale * self.pixels_per_point;
+                    let x = *x as f32 * self.factor;
+                    let baseline = *y as f32 * self.factor;
+                    let transform = Transform::from_rotate_at(*rotation, x, baseline);
+                    let end = self.text(
+                        text,
+                        font_family,
+                        (x, baseline, size),
+                        *color,
+                        (*bold, *italic),
+                        transform,
+                    );
+                    if *underline && end > x {
+                        if let Some(rect) = Rect::from_xywh(
+                            x,
+                            baseline + size * 0.09,
+                            end - x,
+                            (size * 0.06).max(1.0),
+                        ) {
+                            self.pixmap.fill_rect(rect, &paint(*color), transform, None);
+                        }
+                    }
+                }
+                RenderElement::Line {
+                    x1,
+                    y1,
+                    x2,
+                    y2,
+                    color,
+                    width,
+                } => {
+                    let mut path = PathBuilder::new();
+                    path.move_to(*x1 as f32 * self.factor, *y1 as f32 * self.factor);
+                    path.line_to(*x2 as f32 * self.factor, *y2 as f32 * self.factor);
+                    if let Some(path) = path.finish() {
+                        let stroke = Stroke {
+                            width: (width * self.factor).max(1.0),
+                            ..Stroke::default()
+                        };
+                        self.pixmap.stroke_path(
+                            &path,
+                            &paint(*color),
+                            &stroke,
+                            Transform::identity(),
+                            None,
+                        );
+                    }
+                }
+                RenderElement::Rectangle {
+                    x,
+                    y,
+                    width,
+                    height,
+                    fill_color,
+                    stroke_color,
+                    stroke_width,
+                } => {
+                    let Some(rect) = Rect::from_xywh(
+                        *x as f32 * self.factor,
+                        *y as f32 * self.factor,
+                        *width as f32 * self.factor,
+                        *height as f32 * self.factor,
+                    ) else {
+                        continue;
+                    };
+                    if let Some(fill) = fill_color {
+                        self.pixmap
+                            .fill_rect(rect, &paint(*fill), Transform::identity(), None);
+                    }
+                    if let Some(stroke_color) = stroke_color {
+                        let path = PathBuilder::from_rect(rect);
+                        let stroke = Stroke {
+                            width: (stroke_width * self.factor).max(1.0),
+                            ..Stroke::default()
+                        };
+                        self.pixmap.stroke_path(
+                            &path,
+                            &paint(*stroke_color),
+                            &stroke,
+                            Transform::identity(),
+                            None,
+                        );
+                    }
+                }
+                RenderElement::Image {
+                    x,
+                    y,
+                    width,
+                    height,
+                    data,
+                    rotation,
+                } => {
+                    // Undecodable images are left out rather than failing the page
+                    if let Some(image) = decode_image(data) {
+                        let (width, height) =
+                            (*width as f32 * self.factor, *height as f32 * self.factor);
+                        let (x, y) = (*x as f32 * self.factor, *y as f32 * self.factor);
+                        let transform = Transform::from_row(
+                            width / image.width() as f32,
+                            0.0,
+                            0.0,
+                            height / image.height() as f32,
+                            x,
+                            y,
+                        )
+                        .post_concat(Transform::from_rotate_at(
+                            *rotation,
+                            x + width / 2.0,
+                            y + height / 2.0,
+                        ));
+                        let paint = PixmapPaint {
+                            quality: FilterQuality::Bilinear,
+                            ..PixmapPaint::default()
+                        };
+                        self.pixmap
+                            .draw_pixmap(0, 0, image.as_ref(), &paint, transform, None);
+                    }
+                }
+                RenderElement::Path {
+                    commands,
+                    fill_color,
+                    stroke_color,
+                    stroke_width,
+                    dash,
+                } => {
+                    let point = |(x, y): (f32, f32)| (x * self.factor, y * self.factor);
+                    let mut path = PathBuilder::new();
+                    for command in commands {
+                        match command {
+                            PathCommand::MoveTo(to) => {
+                                let (x, y) = point(*to);
+                                path.move_to(x, y);
+                            }
+                            PathCommand::LineTo(to) => {
+                                let (x, y) = point(*to);
+                                path.line_to(x, y);
+                            }
+                            PathCommand::CurveTo(c1, c2, to) => {
+                                let ((x1, y1), (x2, y2), (x, y)) =
+                                    (point(*c1), point(*c2), point(*to));
+                                path.cubic_to(x1, y1, x2, y2, x, y);
+                            }
+                            PathCommand::Close => path.close(),
+                        }
+                    }
+                    let Some(path) = path.finish() else {
+                        continue;
+                    };
+                    if let Some(fill) = fill_color {
+                        self.pixmap.fill_path(
+                            &path,
+                            &paint(*fill),
+                            FillRule::Winding,
+                            Transform::identity(),
+                            None,
+                        );
+                    }
+                    if let Some(stroke_color) = stroke_color {
+                        let dash = dash.iter().map(|length| length * self.factor).collect();
+                        let stroke = Stroke {
+                            width: (stroke_width * self.factor).max(1.0),
+                            dash: StrokeDash::new(dash, 0.0),
+                            ..Stroke::default()
+                        };
+                        self.pixmap.stroke_path(
+                            &path,
+                            &paint(*stroke_color),
+                            &stroke,
+                            Transform::identity(),
+                            None,
+                        );
+                    }
+                }
+                RenderElement::Fill {
+                    x,
+                    y,
+                    width,
+                    height,
+                    fill,
+                } => self.fill((*x as f32, *y as f32, *width as f32, *height as f32), fill),
+            }
+        }
+        self.pixmap
+    }
+
+    /// Paint a rectangle given in render pixels with hatching or a gradient
+    fn fill(&mut self, area: (f32, f32, f32, f32), fill: &Fill) {
+        let point = |(x, y): (f32, f32)| Point::from_xy(x * self.factor, y * self.factor);
+        let stops = |start: u32, end: u32| {
+            vec![
+                GradientStop::new(0.0, color(start)),
+                GradientStop::new(1.0, color(end)),
+            ]
+        };
+        let shader = match fill {
+            Fill::Hatch {
+                color,
+                style,
+                spacing,
+            } => {
+                let mut path = PathBuilder::new();
+                for (from, to) in hatch_lines(*style, area, *spacing) {
+                    let (from, to) = (point(from), point(to));
+                    path.move_to(from.x, from.y);
+                    path.line_to(to.x, to.y);
+                }
+                if let Some(path) = path.finish() {
+                    let stroke = Stroke {
+                        width: self.factor.max(1.0),
+                        ..Stroke::default()
+                    };
+                    self.pixmap.stroke_path(
+                        &path,
+                        &paint(*color),
+                        &stroke,
+                        Transform::identity(),
+                        None,
+                    );
+                }
+                return;
+            }
+            Fill::LinearGradient {
+                start,
+                end,
+                from,
+                to,
+            } => LinearGradient::new(
+                point(*from),
+                point(*to),
+                stops(*start, *end),
+                SpreadMode::Pad,
+                Transform::identity(),
+            ),
+            Fill::RadialGradient {
+                start,
+                end,
+                center,
+                radius,
+            } => RadialGradient::new(
+                point(*center),
+                point(*center),
+                radius * self.factor,
+                stops(*start, *end),
+                SpreadMode::Pad,
+                Transform::identity(),
+            ),
+        };
+        let (x, y, width, height) = area;
+        let rect = Rect::from_xywh(
+            x * self.factor,
+            y * self.factor,
+            width * self.factor,
+            height * self.factor,
+        );
+        // A degenerate gradient comes back as a solid colour or nothing
+        if let (Some(shader), Some(rect)) = (shader, rect) {
+            let paint = Paint {
+                shader,
+                anti_alias: true,
+                ..Paint::default()
+            };
+            self.pixmap
+                .fill_rect(rect, &paint, Transform::identity(), None);
+        }
+    }
+
+    /// Fill glyph outlines of a text run, drawn through `transform`, returning
+    /// where it ends along the run
+    fn text(
+        &mut self,
+        text: &str,
+        family: &str,
+        (x, baseline, size): (f32, f32, f32),
+        color: u32,
+        (bold, italic): (bool, bool),
+        transform: Transform,
+    ) -> f32 {
+        let fonts = &self.result.options.fonts;
+        let mut cursor = x;
+        for segment in fonts.segments(text, family, bold, italic) {
+            let face = &fonts.faces()[segment.face];
+            let parsed = face.face();
+            let scale = size / parsed.units_per_em().max(1) as f32;
+            let skew = if italic && !face.is_italic() {
+                ITALIC_SKEW
+            } else {
+                0.0
+            };
+
+            let mut outline = GlyphPath {
+                builder: PathBuilder::new(),
+                transform: (cursor, baseline, scale, skew),
+            };
+            for c in text[segment.range].chars() {
+                let glyph = parsed.glyph_index(c).unwrap_or(ttf_parser::GlyphId(0));
+                parsed.outline_glyph(glyph, &mut outline);
+                let advance = parsed.glyph_hor_advance(glyph).unwrap_or(0) as f32 * scale;
+                outline.transform.0 += advance;
+            }
+            cursor = outline.transform.0;
+
+            let Some(path) = outline.builder.finish() else {
+                continue;
+            };
+            let paint = paint(color);
+            self.pixmap
+                .fill_path(&path, &paint, FillRule::Winding, transform, None);
+            if bold && !face.is_bold() {
+                let stroke = Stroke {
+                    width: size * 0.03,
+                    ..Stroke::default()
+                };
+                self.pixmap
+                    .stroke_path(&path, &paint, &stroke, transform, None);
+            }
+        }
+        cursor
+    }
+}
+
+/// Collects glyph outlines into one path, mapping font units to pixels
+struct GlyphPath {
+    builder: PathBuilder,
+    /// Origin x, baseline y, pixels per font unit and italic shear
+    transform: (f32, f32, f32, f32),
+}
+
+impl GlyphPath {
+    fn map(&self, x: f32, y: f32) -> (f32, f32) {
+        let (origin, baseline, scale, skew) = self.transform;
+        (origin + (x + y * skew) * scale, baseline - y * scale)
+    }
+}
+
+impl ttf_parser::OutlineBuilder for GlyphPath {
+    fn move_to(&mut self, x: f32, y: f32) {
+        let (x, y) = self.map(x, y);
+        self.builder.move_to(x, y);
+    }
+
+    fn line_to(&mut self, x: f32, y: f32) {
+        let (x, y) = self.map(x, y);
+        self.builder.line_to(x, y);
+    }
+
+    fn quad_to(&mut self, x1: f32, y1: f32, x: f32, y: f32) {
+        let (x1, y1) = self.map(x1, y1);
+        let (x, y) = self.map(x, y);
+        self.builder.quad_to(x1, y1, x, y);
+    }
+
+    fn curve_to(&mut self, x1: f32, y1: f32, x2: f32, y2: f32, x: f32, y: f32) {
+        let (x1, y1) = self.map(x1, y1);
+        let (x2, y2) = self.map(x2, y2);
+        let (x, y) = self.map(x, y);
+        self.builder.cubic_to(x1, y1, x2, y2, x, y);
+    }
+
+    fn close(&mut self) {
+        self.builder.close();
+    }
+}
+
+fn color(color: u32) -> Color {
+    Color::from_rgba8((color >> 16) as u8, (color >> 8) as u8, color as u8, 255)
+}
+
+fn paint(color: u32) -> Paint<'static> {
+    let mut paint = Paint::default();
+    paint.set_color_rgba8((color >> 16) as u8, (color >> 8) as u8, color as u8, 255);
+    paint.anti_alias = true;
+    paint
+}
+
+/// Decode an embedded image into a premultiplied pixmap
+fn decode_image(data: &[u8]) -> Option<Pixmap> {
+    let rgba = image::load_from_memory(data).ok()?.to_rgba8();
+    let size = IntSize::from_wh(rgba.width(), rgba.height())?;
+    let mut pixels = rgba.into_raw();
+    for pixel in pixels.chunks_exact_mut(4) {
+        let alpha = pixel[3] as u16;
+        for channel in &mut pixel[..3] {
+            *channel = ((*channel as u16 * alpha + 127) / 255) as u8;
+        }
+    }
+    Pixmap::from_vec(pixels, size)
+}
@@ -1,5 +1,16 @@
-use crate::model::ParaShape;
-use crate::render::layout::{LayoutEngine, RenderedLine, RenderedPage, RenderedParagraph, TextRun};
+use std::ops::Range;
+use std::sync::OnceLock;
+use std::thread;
+
+use crate::model::border_fill::{BorderFill, BorderLine, FillInfo};
+use crate::model::text_box::{TextBox, TextBoxBorderStyle, TextBoxFillType};
+use crate::model::{ParaShape, Shape, ShapeKind, TextWrap};
+use crate::preview::SummaryInfo;
+use crate::render::font::{FontSet, MissingFont};
+use crate::render::layout::{
+    LayoutEngine, LayoutResult, RenderedCell, RenderedLine, RenderedObject, RenderedPage,
+    RenderedParagraph, RenderedTable, TextRun, VerticalFrame, DEFAULT_METRICS,
+};
 use crate::HwpDocument;
 
 /// Rendering options
@@ -9,6 +20,9 @@ pub struct RenderOptions {
     pub scale: f32,           // Scale factor (default: 1.0)
     pub show_margins: bool,   // Show page margins
     pub show_baselines: bool, // Show text baselines
+    /// Fonts used for text measurement and PDF and raster output, with the
+    /// substitutes for missing families that SVG output also lists
+    pub fonts: FontSet,
 }
 
 impl Default for RenderOptions {
@@ -18,46 +32,190 @@ impl Default for RenderOptions {
             scale: 1.0,
             show_margins: false,
             show_baselines: false,
+            fonts: FontSet::default(),
         }
     }
 }
 
 /// HWP document renderer
+///
+/// The document is laid out once, on first use, and the layout kept for
+/// every later call, so pages can be rendered one at a time as needed.
 pub struct HwpRenderer<'a> {
     document: &'a HwpDocument,
     options: RenderOptions,
+    threads: usize,
+    layout: OnceLock<LayoutResult>,
 }
 
 impl<'a> HwpRenderer<'a> {
     pub fn new(document: &'a HwpDocument, options: RenderOptions) -> Self {
-        Self { document, options }
+        Self {
+            document,
+            options,
+            threads: 1,
+            layout: OnceLock::new(),
+        }
+    }
+
+    /// Render pages on up to `threads` threads at once (default: 1)
+    pub fn with_threads(mut self, threads: usize) -> Self {
+        self.threads = threads.max(1);
+        self
+    }
+
+    /// Layout of the document, calculated on first use
+    pub fn layout(&self) -> &LayoutResult {
+        self.layout.get_or_init(|| {
+            LayoutEngine::new(self.document)
+                .with_font_provider(&self.options.fonts)
+                .calculate_layout()
+        })
+    }
+
+    /// Number of pages the document lays out to
+    pub fn page_count(&self) -> usize {
+        self.layout().page_count()
     }
 
     /// Render document to visual layout description
     pub fn render(&self) -> RenderResult {
-        let layout_engine = LayoutEngine::new(self.document);
-        let layout = layout_engine.calculate_layout();
+        self.render_pages(0..usize::MAX)
+    }
 
-        let mut pages = Vec::new();
+    /// Render the page at `index`, or `None` past the last page
+    pub fn render_page(&self, index: usize) -> Option<RenderedPageOutput> {
+        let page = self.layout().pages.get(index)?;
+        Some(self.render_layout_page(page))
+    }
 
-        for layout_page in layout.pages {
-            pages.push(self.render_page(&layout_page));
-        }
+    /// Render the pages in `range`, cut short at the last page
+    ///
+    /// The result holds only those pages, so its page indices count from
+    /// the start of the range.
+    pub fn render_pages(&self, range: Range<usize>) -> RenderResult {
+        let layout = &self.layout().pages;
+        let end = range.end.min(layout.len());
+        let layout = &layout[range.start.min(end)..end];
+
+        let pages = if self.threads > 1 && layout.len() > 1 {
+            // Laid out pages are independent, so each thread takes a share in order
+            let share = layout.len().div_ceil(self.threads);
+            thread::scope(|scope| {
+                let handles: Vec<_> = layout
+                    .chunks(share)
+                    .map(|chunk| {
+                        scope.spawn(move || {
+                            chunk
+                                .iter()
+                                .map(|page| self.render_layout_page(page))
+                                .collect::<Vec<_>>()
+                        })
+                    })
+                    .collect();
+                handles
+                    .into_iter()
+                    .flat_map(|handle| {
+                        handle
+                            .join()
+                            .unwrap_or_else(|panic| std::panic::resume_unwind(panic))
+                    })
+                    .collect()
+            })
+        } else {
+            layout
+                .iter()
+                .map(|page| self.render_layout_page(page))
+                .collect()
+        };
 
-        RenderResult { pages }
+        RenderResult {
+            pages,
+            options: self.options.clone(),
+            summary_info: self.document.summary_info.clone(),
+        }
     }
 
-    /// Render a single page
-    fn render_page(&self, page: &RenderedPage) -> RenderedPageOutput {
+    /// Render a single laid out page
+    fn render_layout_page(&self, page: &RenderedPage) -> RenderedPageOutput {
         let mut elements = Vec::new();
 
         // Convert page dimensions from HWP units to pixels
         let width_px = self.hwp_to_px(page.width as i32);
         let height_px = self.hwp_to_px(page.height as i32);
 
-        // Render each paragraph
+        // Objects behind the text come first, the rest over it, each in z-order
+        let mut objects: Vec<&RenderedObject> = page.objects.iter().collect();
         for para in &page.paragraphs {
-            elements.extend(self.render_paragraph(para));
+            collect_objects(para, &mut objects);
+        }
+        objects.sort_by_key(|object| object.z_order);
+        let (behind, over): (Vec<_>, Vec<_>) = objects
+            .into_iter()
+            .partition(|object| object.wrap == TextWrap::BehindText);
+
+        if let Some(color) = page.background_color {
+            elements.push(RenderElement::Rectangle {
+                x: 0,
+                y: 0,
+                width: width_px,
+                height: height_px,
+                fill_color: Some(color),
+                stroke_color: None,
+                stroke_width: 0.0,
+            });
+        }
+        let page_hide = page.page_hide.unwrap_or_default();
+        if let Some(border) = &page.border {
+            if let Some(fill) = self.border_fill(border.border_fill_id) {
+                if !page_hide.hides_fill() {
+                    elements.extend(self.fill_area(&fill.fill_info, border.fill));
+                }
+                if !page_hide.hides_border() {
+                    let frame = (border.x, border.y, border.width, border.height);
+                    elements.extend(self.border_edges(fill, frame));
+                }
+            }
+        }
+
+        for object in behind {
+            elements.extend(self.render_object(object));
+        }
+
+        // Render each paragraph; a vertical body is turned onto the page
+        let frame = page.frame();
+        elements.extend(self.render_paragraphs(page.header.as_slice(), None));
+        elements.extend(self.render_paragraphs(&page.paragraphs, frame));
+        elements.extend(self.render_paragraphs(page.footer.as_slice(), None));
+
+        for divider in &page.column_lines {
+            elements.push(self.line(
+                frame,
+                (divider.x, divider.y),
+                (divider.x, divider.y + divider.height),
+                divider.line.color,
+                self.border_width(&divider.line),
+            ));
+        }
+
+        if let Some(footnotes) = &page.footnotes {
+            let width = self.border_width(&BorderLine {
+                line_type: 1,
+                thickness: 1,
+                color: 0x000000,
+            });
+            elements.push(self.line(
+                frame,
+                (footnotes.x, footnotes.y),
+                (footnotes.x + footnotes.separator_width, footnotes.y),
+                0x000000,
+                width,
+            ));
+            elements.extend(self.render_paragraphs(&footnotes.paragraphs, frame));
+        }
+
+        for object in over {
+            elements.extend(self.render_object(object));
         }
 
         RenderedPageOutput {
@@ -68,8 +226,222 @@ impl<'a> HwpRenderer<'a> {
         }
     }
 
+    /// Render paragraphs over their borders and backgrounds, turned onto the
+    /// page by `frame` when they are vertical
+    fn render_paragraphs(
+        &self,
+        paragraphs: &[RenderedParagraph],
+        frame: Option<VerticalFrame>,
+    ) -> Vec<RenderElement> {
+        let mut elements = self.render_paragraph_borders(paragraphs, frame);
+        for para in paragraphs {
+            elements.extend(self.render_paragraph(para, frame));
+        }
+        elements
+    }
+
+    /// Render paragraph borders and backgrounds, one box around consecutive
+    /// paragraphs that share a border fill and ask to be connected
+    fn render_paragraph_borders(
+        &self,
+        paragraphs: &[RenderedParagraph],
+        frame: Option<VerticalFrame>,
+    ) -> Vec<RenderElement> {
+        // Border fill id and left, top, right and bottom edges of each box
+        let mut boxes: Vec<(u16, [i32; 4])> = Vec::new();
+        let mut open = false;
+        for para in paragraphs {
+            let Some(shape) = self
+                .document
+                .get_para_shape(para.para_shape_id as usize)
+                .filter(|shape| self.border_fill(shape.border_fill_id).is_some())
+            else {
+                open = false;
+                continue;
+            };
+            let (mut left, mut right) = (para.x, para.x + para.width);
+            if shape.is_border_ignoring_margins() {
+                left -= shape.left_margin;
+                right += shape.right_margin;
+            }
+            let top = para.lines.first().map_or(para.y, |line| line.y);
+            let bottom = para
+                .lines
+                .last()
+                .map_or(para.y + para.height, |line| line.y + line.height);
+            let edges = [
+                left - shape.border_left_space as i32,
+                top - shape.border_top_space as i32,
+                right + shape.border_right_space as i32,
+                bottom + shape.border_bottom_space as i32,
+            ];
+
+            match boxes.last_mut() {
+                Some((id, joined))
+                    if open
+                        && *id == shape.border_fill_id
+                        && shape.is_border_connected()
+                        && (joined[0], joined[2]) == (edges[0], edges[2])
+                        && edges[1] >= joined[1] =>
+                {
+                    joined[3] = edges[3];
+                }
+                _ => boxes.push((shape.border_fill_id, edges)),
+            }
+            open = true;
+        }
+
+        let mut elements = Vec::new();
+        for (id, [left, top, right, bottom]) in boxes {
+            if let Some(fill) = self.border_fill(id) {
+                let area = on_page(frame, (left, top, right - left, bottom - top));
+                elements.extend(self.fill_area(&fill.fill_info, area));
+                elements.extend(self.border_edges(fill, area));
+            }
+        }
+        elements
+    }
+
+    /// Look up a 1-based border fill; 0 means none
+    fn border_fill(&self, id: u16) -> Option<&BorderFill> {
+        id.checked_sub(1)
+            .and_then(|index| self.document.get_border_fill(index as usize))
+    }
+
+    /// Paint an area with a fill's picture, gradient, or colour and hatching
+    fn fill_area(
+        &self,
+        info: &FillInfo,
+        (x, y, width, height): (i32, i32, i32, i32),
+    ) -> Vec<RenderElement> {
+        let (x, y, width, height) = (
+            self.hwp_to_px(x),
+            self.hwp_to_px(y),
+            self.hwp_to_px(width),
+            self.hwp_to_px(height),
+        );
+        if let Some(data) = info
+            .image_info
+            .as_ref()
+            .filter(|_| info.fill_type & 0x04 != 0)
+            .and_then(|image| self.document.get_bin_data(image.bin_data_id))
+            .and_then(|bin_data| bin_data.get_data().ok())
+            .filter(|data| !data.is_empty())
+        {
+            return vec![RenderElement::Image {
+                x,
+                y,
+                width,
+                height,
+                data,
+                rotation: 0.0,
+            }];
+        }
+
+        let (left, top) = (x as f32, y as f32);
+        let (w, h) = (width as f32, height as f32);
+        if let Some(gradient) = info
+            .gradient_info
+            .as_ref()
+            .filter(|_| info.fill_type & 0x08 != 0)
+        {
+            let (start, end) = (gradient.start_color, gradient.end_color);
+            // Stripes run across the angle; circles, cones and squares spread from the centre
+            let fill = if gradient.gradient_type <= 1 {
+                let (sin, cos) = (gradient.angle as f32).to_radians().sin_cos();
+                let half = (w * cos.abs() + h * sin.abs()) / 2.0;
+                let center = (left + w / 2.0, top + h / 2.0);
+                Fill::LinearGradient {
+                    start,
+                    end,
+                    from: (center.0 - cos * half, center.1 - sin * half),
+                    to: (center.0 + cos * half, center.1 + sin * half),
+                }
+            } else {
+                let center = (
+                    left + w * gradient.center_x.min(100) as f32 / 100.0,
+                    top + h * gradient.center_y.min(100) as f32 / 100.0,
+                );
+                let reach = |from: f32, start: f32, length: f32| {
+                    (from - start).abs().max((start + length - from).abs())
+                };
+                Fill::RadialGradient {
+                    start,
+                    end,
+                    center,
+                    radius: reach(center.0, left, w).hypot(reach(center.1, top, h)),
+                }
+            };
+            return vec![RenderElement::Fill {
+                x,
+                y,
+                width,
+                height,
+                fill,
+            }];
+        }
+
+        let mut elements = Vec::new();
+        if info.fill_type & 0x01 != 0 {
+            elements.push(RenderElement::Rectangle {
+                x,
+                y,
+                width,
+                height,
+                fill_color: Some(info.back_color),
+                stroke_color: None,
+                stroke_width: 0.0,
+            });
+            if let Some(style) = HatchStyle::from_pattern_type(info.pattern_type) {
+                elements.push(RenderElement::Fill {
+                    x,
+                    y,
+                    width,
+                    height,
+                    fill: Fill::Hatch {
+                        color: info.pattern_color,
+                        style,
+                        spacing: self.hwp_to_px_f32(HATCH_SPACING).max(2.0),
+                    },
+                });
+            }
+        }
+        elements
+    }
+
+    /// Lines along the edges of an area that a border fill draws
+    fn border_edges(
+        &self,
+        fill: &BorderFill,
+        (x, y, width, height): (i32, i32, i32, i32),
+    ) -> Vec<RenderElement> {
+        let (right, bottom) = (x + width, y + height);
+        let edges = [
+            (&fill.left, (x, y), (x, bottom)),
+            (&fill.right, (right, y), (right, bottom)),
+            (&fill.top, (x, y), (right, y)),
+            (&fill.bottom, (x, bottom), (right, bottom)),
+        ];
+        edges
+            .into_iter()
+            .filter(|(line, _, _)| line.line_type != 0)
+            .map(|(line, from, to)| RenderElement::Line {
+                x1: self.hwp_to_px(from.0),
+                y1: self.hwp_to_px(from.1),
+                x2: self.hwp_to_px(to.0),
+                y2: self.hwp_to_px(to.1),
+                color: line.color,
+                width: self.border_width(line),
+            })
+            .collect()
+    }
+
     /// Render a paragraph
-    fn render_paragraph(&self, para: &RenderedParagraph) -> Vec<RenderElement> {
+    fn render_paragraph(
+        &self,
+        para: &RenderedParagraph,
+        frame: Option<VerticalFrame>,
+    ) -> Vec<RenderElement> {
         let mut elements = Vec::new();
 
         // Get paragraph shape for styling
@@ -77,60 +449,334 @@ impl<'a> HwpRenderer<'a> {
 
         // Render each line
         for line in &para.lines {
-            elements.extend(self.render_line(line, para_shape));
+            elements.extend(self.render_line(line, para_shape, frame));
+        }
+
+        if let Some(table) = &para.table {
+            elements.extend(self.render_table(table, frame));
+        }
+
+        elements
+    }
+
+    /// Render a table: cell backgrounds, then content, then borders on top
+    fn render_table(
+        &self,
+        table: &RenderedTable,
+        frame: Option<VerticalFrame>,
+    ) -> Vec<RenderElement> {
+        let mut elements = Vec::new();
+        let area = |cell: &RenderedCell| on_page(frame, (cell.x, cell.y, cell.width, cell.height));
+
+        for cell in &table.cells {
+            if let Some(fill) = self.border_fill(cell.border_fill_id) {
+                elements.extend(self.fill_area(&fill.fill_info, area(cell)));
+            }
+        }
+
+        for cell in &table.cells {
+            elements.extend(self.render_paragraphs(&cell.paragraphs, frame));
+        }
+
+        for cell in &table.cells {
+            if let Some(fill) = self.border_fill(cell.border_fill_id) {
+                elements.extend(self.border_edges(fill, area(cell)));
+            }
+        }
+
+        elements
+    }
+
+    /// Render a picture, text box or drawing object
+    fn render_object(&self, object: &RenderedObject) -> Vec<RenderElement> {
+        let Some(paragraph) = self
+            .document
+            .sections()
+            .nth(object.section_index)
+            .and_then(|section| section.paragraphs.get(object.paragraph_index))
+        else {
+            return Vec::new();
+        };
+
+        let mut elements = Vec::new();
+        if let Some(picture) = &paragraph.picture_data {
+            // Pictures whose data is missing are left out
+            if let Some(data) = self
+                .document
+                .get_bin_data(picture.bin_item_id)
+                .and_then(|bin_data| bin_data.get_data().ok())
+                .filter(|data| !data.is_empty())
+            {
+                elements.push(RenderElement::Image {
+                    x: self.hwp_to_px(object.x),
+                    y: self.hwp_to_px(object.y),
+                    width: self.hwp_to_px(object.width),
+                    height: self.hwp_to_px(object.height),
+                    data,
+                    rotation: object.rotation,
+                });
+            }
+        }
+        if let Some(shape) = &paragraph.shape_data {
+            elements.extend(self.render_shape(object, shape));
+        }
+        if let Some(text_box) = &paragraph.text_box_data {
+            elements.extend(self.render_text_box(object, text_box));
+        }
+        elements
+    }
+
+    /// Render a drawing object's outline and fill
+    fn render_shape(&self, object: &RenderedObject, shape: &Shape) -> Option<RenderElement> {
+        let (w, h) = (object.width as f32, object.height as f32);
+        let to_px = self.frame_transform(object);
+        let point = |(x, y): (i32, i32)| to_px(x as f32, y as f32);
+
+        let mut commands = Vec::new();
+        let mut closed = true;
+        match &shape.kind {
+            ShapeKind::Line { start, end } => {
+                commands.push(PathCommand::MoveTo(point(*start)));
+                commands.push(PathCommand::LineTo(point(*end)));
+                closed = false;
+            }
+            ShapeKind::Rectangle { round } => {
+                let r = w.min(h) * (*round).min(100) as f32 / 200.0;
+                commands.extend(rounded_rectangle(w, h, r, &to_px));
+            }
+            ShapeKind::Ellipse => commands.extend(ellipse(w, h, &to_px)),
+            ShapeKind::Polygon(points) => {
+                let (first, rest) = points.split_first()?;
+                commands.push(PathCommand::MoveTo(point(*first)));
+                commands.extend(rest.iter().map(|p| PathCommand::LineTo(point(*p))));
+                commands.push(PathCommand::Close);
+            }
+            ShapeKind::Curve(points) => {
+                let (first, rest) = points.split_first()?;
+                commands.push(PathCommand::MoveTo(point(*first)));
+                for segment in rest.chunks(3) {
+                    commands.push(match segment {
+                        [c1, c2, end] => PathCommand::CurveTo(point(*c1), point(*c2), point(*end)),
+                        _ => PathCommand::LineTo(point(segment[segment.len() - 1])),
+                    });
+                }
+                closed = false;
+            }
         }
 
+        let stroke_width = self.hwp_to_px_f32(shape.line_width as i32).max(0.5);
+        Some(RenderElement::Path {
+            commands,
+            fill_color: shape.fill_color.filter(|_| closed),
+            stroke_color: (shape.line_type != 0).then_some(shape.line_color),
+            stroke_width,
+            dash: dash_pattern(shape.line_type, stroke_width),
+        })
+    }
+
+    /// Render a text box frame and its text; the text itself is not rotated
+    fn render_text_box(&self, object: &RenderedObject, text_box: &TextBox) -> Vec<RenderElement> {
+        let mut elements = Vec::new();
+        let to_px = self.frame_transform(object);
+        let line_type = match text_box.border_style {
+            TextBoxBorderStyle::None => 0,
+            TextBoxBorderStyle::Solid => 1,
+            TextBoxBorderStyle::Dashed => 2,
+            TextBoxBorderStyle::Dotted => 3,
+            TextBoxBorderStyle::Double => 4,
+        };
+        let stroke_width = self.border_width(&BorderLine {
+            line_type,
+            thickness: text_box.border_width,
+            color: text_box.border_color,
+        });
+        let fill = matches!(
+            text_box.fill_type,
+            TextBoxFillType::Solid | TextBoxFillType::Gradient
+        );
+        if fill || line_type != 0 {
+            elements.push(RenderElement::Path {
+                commands: rounded_rectangle(object.width as f32, object.height as f32, 0.0, &to_px),
+                fill_color: fill.then_some(text_box.background_color),
+                stroke_color: (line_type != 0).then_some(text_box.border_color),
+                stroke_width,
+                dash: dash_pattern(line_type, stroke_width),
+            });
+        }
+        elements.extend(self.render_paragraphs(&object.paragraphs, object.frame()));
         elements
     }
 
+    /// Map a point given in HWP units from an object's top-left corner to
+    /// page pixels, rotating it about the object's centre
+    fn frame_transform(&self, object: &RenderedObject) -> impl Fn(f32, f32) -> (f32, f32) {
+        let (cx, cy) = (object.width as f32 / 2.0, object.height as f32 / 2.0);
+        let (sin, cos) = object.rotation.to_radians().sin_cos();
+        let origin = (object.x as f32 + cx, object.y as f32 + cy);
+        let px = self.hwp_to_px_f32(7200) / 7200.0;
+        move |x, y| {
+            let (dx, dy) = (x - cx, y - cy);
+            (
+                (origin.0 + dx * cos - dy * sin) * px,
+                (origin.1 + dx * sin + dy * cos) * px,
+            )
+        }
+    }
+
+    /// Width of a border line in pixels
+    fn border_width(&self, line: &BorderLine) -> f32 {
+        // Thickness is an index into 0.1mm .. 5mm
+        const THICKNESS_MM: [f32; 16] = [
+            0.1, 0.12, 0.15, 0.2, 0.25, 0.3, 0.4, 0.5, 0.6, 0.7, 1.0, 1.5, 2.0, 3.0, 4.0, 5.0,
+        ];
+        let mm = THICKNESS_MM
+            .get(line.thickness as usize)
+            .copied()
+            .unwrap_or(0.12);
+        mm / 25.4 * self.options.dpi as f32 * self.options.scale
+    }
+
     /// Render a line of text
     fn render_line(
         &self,
         line: &RenderedLine,
         _para_shape: Option<&ParaShape>,
+        frame: Option<VerticalFrame>,
     ) -> Vec<RenderElement> {
         let mut elements = Vec::new();
 
         // Show baseline if requested
         if self.options.show_baselines {
-            elements.push(RenderElement::Line {
-                x1: self.hwp_to_px(line.runs.first().map(|r| r.x).unwrap_or(0)),
-                y1: self.hwp_to_px(line.baseline_y),
-                x2: self.hwp_to_px(line.runs.last().map(|r| r.x + r.width).unwrap_or(0)),
-                y2: self.hwp_to_px(line.baseline_y),
-                color: 0xFF0000, // Red
-                width: 1.0,
-            });
+            elements.push(self.line(
+                frame,
+                (line.runs.first().map(|r| r.x).unwrap_or(0), line.baseline_y),
+                (
+                    line.runs.last().map(|r| r.x + r.width).unwrap_or(0),
+                    line.baseline_y,
+                ),
+                0xFF0000, // Red
+                1.0,
+            ));
         }
 
-        // Render each text run
+        // Shading and character borders go under the text
         for run in &line.runs {
-            if let Some(element) = self.render_text_run(run, line.baseline_y) {
-                elements.push(element);
+            let Some(char_shape) = self.document.get_char_shape(run.char_shape_id as usize) else {
+                continue;
+            };
+            let area = on_page(frame, (run.x, line.y, run.width, line.height));
+            // White (or unset) shading means no highlight
+            if char_shape.shade_color & 0xFFFFFF != 0xFFFFFF && char_shape.shade_color != 0 {
+                elements.push(RenderElement::Rectangle {
+                    x: self.hwp_to_px(area.0),
+                    y: self.hwp_to_px(area.1),
+                    width: self.hwp_to_px(area.2),
+                    height: self.hwp_to_px(area.3),
+                    fill_color: Some(char_shape.shade_color),
+                    stroke_color: None,
+                    stroke_width: 0.0,
+                });
             }
+            if let Some(fill) = self.border_fill(char_shape.border_fill_id) {
+                elements.extend(self.fill_area(&fill.fill_info, area));
+                elements.extend(self.border_edges(fill, area));
+            }
+        }
+
+        // Render each text run
+        for run in &line.runs {
+            elements.extend(self.render_text_run(run, line.baseline_y, frame));
         }
 
         elements
     }
 
     /// Render a text run
-    fn render_text_run(&self, run: &TextRun, baseline_y: i32) -> Option<RenderElement> {
-        let char_shape = self.document.get_char_shape(run.char_shape_id as usize)?;
-        let font_face = self
+    ///
+    /// In vertical text a sideways run is drawn turned a quarter clockwise,
+    /// and upright characters one at a time, centred across the line.
+    fn render_text_run(
+        &self,
+        run: &TextRun,
+        baseline_y: i32,
+        frame: Option<VerticalFrame>,
+    ) -> Vec<RenderElement> {
+        let Some(char_shape) = self.document.get_char_shape(run.char_shape_id as usize) else {
+            return Vec::new();
+        };
+        let Some(font_face) = self
             .document
-            .get_face_name(char_shape.face_name_ids[0] as usize)?;
-
-        Some(RenderElement::Text {
-            x: self.hwp_to_px(run.x),
-            y: self.hwp_to_px(baseline_y),
-            text: run.text.clone(),
-            font_family: font_face.font_name.clone(),
-            font_size: self.hwp_to_pt(run.font_size),
-            color: char_shape.text_color,
-            bold: char_shape.is_bold(),
-            italic: char_shape.is_italic(),
-            underline: char_shape.is_underline(),
-        })
+            .get_face_name(char_shape.face_name_ids[run.script.index()] as usize)
+        else {
+            return Vec::new();
+        };
+        let text = |(x, y): (i32, i32), text: String, rotation: f32, underline: bool| {
+            RenderElement::Text {
+                x: self.hwp_to_px(x),
+                y: self.hwp_to_px(y),
+                text,
+                font_family: font_face.font_name.clone(),
+                font_size: self.hwp_to_pt(run.font_size),
+                color: char_shape.text_color,
+                bold: char_shape.is_bold(),
+                italic: char_shape.is_italic(),
+                underline,
+                rotation,
+            }
+        };
+        let underline = char_shape.is_underline();
+
+        let Some(frame) = frame else {
+            return vec![text((run.x, baseline_y), run.text.clone(), 0.0, underline)];
+        };
+        if run.sideways {
+            let origin = frame.to_page(run.x, baseline_y);
+            return vec![text(origin, run.text.clone(), 90.0, underline)];
+        }
+
+        // Each em box sits where it would in horizontal text, turned about its centre
+        let em = run.font_size as f32;
+        let above_middle = ((DEFAULT_METRICS.ascent - DEFAULT_METRICS.descent) * em / 2.0) as i32;
+        let mut elements = Vec::new();
+        let mut x = run.x;
+        for (c, &advance) in run.text.chars().zip(&run.advances) {
+            let center = frame.to_page(x + advance / 2, baseline_y - above_middle);
+            let origin = (center.0 - advance / 2, center.1 + above_middle);
+            elements.push(text(origin, c.to_string(), 0.0, false));
+            x += advance;
+        }
+        if underline {
+            let y = baseline_y + (em * 0.12) as i32;
+            elements.push(self.line(
+                Some(frame),
+                (run.x, y),
+                (run.x + run.width, y),
+                char_shape.text_color,
+                (self.hwp_to_px_f32(run.font_size) * 0.06).max(1.0),
+            ));
+        }
+        elements
+    }
+
+    /// Line between two layout points, turned onto the page by `frame`
+    fn line(
+        &self,
+        frame: Option<VerticalFrame>,
+        from: (i32, i32),
+        to: (i32, i32),
+        color: u32,
+        width: f32,
+    ) -> RenderElement {
+        let place = |(x, y): (i32, i32)| frame.map_or((x, y), |frame| frame.to_page(x, y));
+        let (from, to) = (place(from), place(to));
+        RenderElement::Line {
+            x1: self.hwp_to_px(from.0),
+            y1: self.hwp_to_px(from.1),
+            x2: self.hwp_to_px(to.0),
+            y2: self.hwp_to_px(to.1),
+            color,
+            width,
+        }
     }
 
     /// Convert HWP units to pixels
@@ -141,6 +787,11 @@ impl<'a> HwpRenderer<'a> {
         (inches * self.options.dpi as f32 * self.options.scale) as i32
     }
 
+    /// Convert HWP units to fractional pixels
+    fn hwp_to_px_f32(&self, hwp_units: i32) -> f32 {
+        hwp_units as f32 / 7200.0 * self.options.dpi as f32 * self.options.scale
+    }
+
     /// Convert HWP units to points
     fn hwp_to_pt(&self, hwp_units: i32) -> f32 {
         // Points = (hwp_units / 7200) * 72
@@ -152,6 +803,10 @@ impl<'a> HwpRenderer<'a> {
 #[derive(Debug)]
 pub struct RenderResult {
     pub pages: Vec<RenderedPageOutput>,
+    /// Options the pages were rendered with
+    pub options: RenderOptions,
+    /// Document properties, used as PDF metadata
+    pub summary_info: Option<SummaryInfo>,
 }
 
 /// Rendered page output
@@ -176,6 +831,8 @@ pub enum RenderElement {
         bold: bool,
         italic: bool,
         underline: bool,
+        /// Clockwise rotation about the baseline origin in degrees
+        rotation: f32,
     },
     Line {
         x1: i32,
@@ -200,111 +857,246 @@ pub enum RenderElement {
         width: i32,
         height: i32,
         data: Vec<u8>,
+        /// Clockwise rotation about the centre in degrees
+        rotation: f32,
+    },
+    Path {
+        commands: Vec<PathCommand>,
+        fill_color: Option<u32>,
+        stroke_color: Option<u32>,
+        stroke_width: f32,
+        /// Alternating dash and gap lengths; empty for a solid line
+        dash: Vec<f32>,
+    },
+    /// Rectangle painted with hatching or a gradient
+    Fill {
+        x: i32,
+        y: i32,
+        width: i32,
+        height: i32,
+        fill: Fill,
     },
 }
 
-impl RenderResult {
-    /// Export to SVG format
-    pub fn to_svg(&self, page_index: usize) -> Option<String> {
-        let page = self.pages.get(page_index)?;
+/// Paint for a `RenderElement::Fill` beyond a plain colour, in pixels
+#[derive(Debug, Clone, PartialEq)]
+pub enum Fill {
+    /// Lines of `color` repeated `spacing` apart
+    Hatch {
+        color: u32,
+        style: HatchStyle,
+        spacing: f32,
+    },
+    /// `start` blending into `end` along the line between two points
+    LinearGradient {
+        start: u32,
+        end: u32,
+        from: (f32, f32),
+        to: (f32, f32),
+    },
+    /// `start` at the centre blending into `end` at `radius`
+    RadialGradient {
+        start: u32,
+        end: u32,
+        center: (f32, f32),
+        radius: f32,
+    },
+}
 
-        let mut svg = format!(
-            r#"<svg width="{}" height="{}" xmlns="http://www.w3.org/2000/svg">"#,
-            page.width, page.height
-        );
+/// Direction of hatching lines (무늬 종류)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HatchStyle {
+    Horizontal,
+    Vertical,
+    /// Lines running down to the right (\\\\)
+    BackSlash,
+    /// Lines running up to the right (////)
+    Slash,
+    Cross,
+    DiagonalCross,
+}
 
-        // White background
-        svg.push_str(&format!(
-            r#"<rect width="{}" height="{}" fill="white"/>"#,
-            page.width, page.height
-        ));
+impl HatchStyle {
+    /// Style for a `FillInfo::pattern_type`; -1 stores no hatching
+    pub fn from_pattern_type(pattern_type: u32) -> Option<Self> {
+        match pattern_type {
+            0 => Some(HatchStyle::Horizontal),
+            1 => Some(HatchStyle::Vertical),
+            2 => Some(HatchStyle::BackSlash),
+            3 => Some(HatchStyle::Slash),
+            4 => Some(HatchStyle::Cross),
+            5 => Some(HatchStyle::DiagonalCross),
+            _ => None,
+        }
+    }
 
-        // Render elements
-        for element in &page.elements {
-            match element {
-                RenderElement::Text {
-                    x,
-                    y,
-                    text,
-                    font_family,
-                    font_size,
-                    color,
-                    bold,
-                    italic,
-                    underline,
-                } => {
-                    svg.push_str(&format!(
-                        "<text x=\"{}\" y=\"{}\" font-family=\"{}\" font-size=\"{}\" fill=\"#{:06X}\"",
-                        x, y, font_family, font_size, color & 0xFFFFFF
-                    ));
-
-                    if *bold {
-                        svg.push_str(r#" font-weight="bold""#);
-                    }
-                    if *italic {
-                        svg.push_str(r#" font-style="italic""#);
-                    }
-                    if *underline {
-                        svg.push_str(r#" text-decoration="underline""#);
-                    }
-
-                    svg.push_str(&format!(">{}</text>", escape_xml(text)));
-                }
-                RenderElement::Line {
-                    x1,
-                    y1,
-                    x2,
-                    y2,
-                    color,
-                    width,
-                } => {
-                    svg.push_str(&format!(
-                        "<line x1=\"{}\" y1=\"{}\" x2=\"{}\" y2=\"{}\" stroke=\"#{:06X}\" stroke-width=\"{}\"/>",
-                        x1, y1, x2, y2, color & 0xFFFFFF, width
-                    ));
-                }
-                RenderElement::Rectangle {
-                    x,
-                    y,
-                    width,
-                    height,
-                    fill_color,
-                    stroke_color,
-                    stroke_width,
-                } => {
-                    svg.push_str(&format!(
-                        r#"<rect x="{x}" y="{y}" width="{width}" height="{height}""#
-                    ));
-
-                    if let Some(fill) = fill_color {
-                        svg.push_str(&format!(" fill=\"#{:06X}\"", fill & 0xFFFFFF));
-                    } else {
-                        svg.push_str(r#" fill="none""#);
-                    }
-
-                    if let Some(stroke) = stroke_color {
-                        svg.push_str(&format!(
-                            " stroke=\"#{:06X}\" stroke-width=\"{}\"",
-                            stroke & 0xFFFFFF,
-                            stroke_width
-                        ));
-                    }
-
-                    svg.push_str("/>");
-                }
-                _ => {} // Skip other elements for now
+    /// Angles of the line sets, clockwise from horizontal
+    pub fn angles(self) -> &'static [f32] {
+        match self {
+            HatchStyle::Horizontal => &[0.0],
+            HatchStyle::Vertical => &[90.0],
+            HatchStyle::BackSlash => &[45.0],
+            HatchStyle::Slash => &[-45.0],
+            HatchStyle::Cross => &[0.0, 90.0],
+            HatchStyle::DiagonalCross => &[45.0, -45.0],
+        }
+    }
+}
+
+/// Distance between hatching lines, 1mm
+const HATCH_SPACING: i32 = 283;
+
+/// Hatching lines `spacing` apart clipped to the rectangle at `(x, y)`
+pub(crate) fn hatch_lines(
+    style: HatchStyle,
+    (x, y, width, height): (f32, f32, f32, f32),
+    spacing: f32,
+) -> Vec<((f32, f32), (f32, f32))> {
+    let mut lines = Vec::new();
+    let spacing = spacing.max(1.0);
+    let center = (x + width / 2.0, y + height / 2.0);
+    let reach = width.hypot(height) / 2.0;
+    for angle in style.angles() {
+        let (sin, cos) = angle.to_radians().sin_cos();
+        // Lines along (cos, sin), stepped along the normal out from the centre
+        let count = (reach / spacing).ceil() as i32;
+        for step in -count..=count {
+            let offset = (step as f32 + 0.5) * spacing;
+            let base = (center.0 - sin * offset, center.1 + cos * offset);
+            if let Some(segment) = clip_line(base, (cos, sin), (x, y, width, height)) {
+                lines.push(segment);
             }
         }
+    }
+    lines
+}
+
+/// Part of the line through `base` along `direction` inside a rectangle
+fn clip_line(
+    base: (f32, f32),
+    direction: (f32, f32),
+    (x, y, width, height): (f32, f32, f32, f32),
+) -> Option<((f32, f32), (f32, f32))> {
+    let (mut low, mut high) = (f32::NEG_INFINITY, f32::INFINITY);
+    for (start, step, min, max) in [
+        (base.0, direction.0, x, x + width),
+        (base.1, direction.1, y, y + height),
+    ] {
+        if step.abs() < 1e-6 {
+            if start < min || start > max {
+                return None;
+            }
+        } else {
+            let (a, b) = ((min - start) / step, (max - start) / step);
+            low = low.max(a.min(b));
+            high = high.min(a.max(b));
+        }
+    }
+    let point = |t: f32| (base.0 + direction.0 * t, base.1 + direction.1 * t);
+    (low < high).then(|| (point(low), point(high)))
+}
 
-        svg.push_str("</svg>");
-        Some(svg)
+/// Segment of a `RenderElement::Path`, in pixels
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum PathCommand {
+    MoveTo((f32, f32)),
+    LineTo((f32, f32)),
+    /// Cubic Bézier curve through two control points
+    CurveTo((f32, f32), (f32, f32), (f32, f32)),
+    Close,
+}
+
+/// Page area of a layout area, turned by `frame` for vertical text
+fn on_page(frame: Option<VerticalFrame>, area: (i32, i32, i32, i32)) -> (i32, i32, i32, i32) {
+    frame.map_or(area, |frame| frame.to_page_rect(area))
+}
+
+/// Objects held by a paragraph, its table cells and nested tables
+fn collect_objects<'a>(para: &'a RenderedParagraph, objects: &mut Vec<&'a RenderedObject>) {
+    objects.extend(&para.objects);
+    for cell in para.table.iter().flat_map(|table| &table.cells) {
+        for para in &cell.paragraphs {
+            collect_objects(para, objects);
+        }
     }
 }
 
-fn escape_xml(s: &str) -> String {
-    s.replace('&', "&amp;")
-        .replace('<', "&lt;")
-        .replace('>', "&gt;")
-        .replace('"', "&quot;")
-        .replace('\'', "&apos;")
+/// Dash pattern for a `BorderLine` style line
+fn dash_pattern(line_type: u8, width: f32) -> Vec<f32> {
+    let unit = width.max(1.0);
+    match line_type {
+        2 => vec![unit * 4.0, unit * 2.0],
+        3 => vec![unit, unit],
+        _ => Vec::new(),
+    }
+}
+
+/// Distance of Bézier control points from the ends of a quarter ellipse
+const KAPPA: f32 = 0.552_284_8;
+
+/// Rectangle `w` by `h` with corners of radius `r`, mapped through `to_px`
+fn rounded_rectangle(
+    w: f32,
+    h: f32,
+    r: f32,
+    to_px: &impl Fn(f32, f32) -> (f32, f32),
+) -> Vec<PathCommand> {
+    let k = r * (1.0 - KAPPA);
+    let mut commands = vec![PathCommand::MoveTo(to_px(r, 0.0))];
+    let corners = [
+        ((w - r, 0.0), (w - k, 0.0), (w, k), (w, r)),
+        ((w, h - r), (w, h - k), (w - k, h), (w - r, h)),
+        ((r, h), (k, h), (0.0, h - k), (0.0, h - r)),
+        ((0.0, r), (0.0, k), (k, 0.0), (r, 0.0)),
+    ];
+    for (line_end, c1, c2, end) in corners {
+        commands.push(PathCommand::LineTo(to_px(line_end.0, line_end.1)));
+        if r > 0.0 {
+            commands.push(PathCommand::CurveTo(
+                to_px(c1.0, c1.1),
+                to_px(c2.0, c2.1),
+                to_px(end.0, end.1),
+            ));
+        }
+    }
+    commands.push(PathCommand::Close);
+    commands
+}
+
+/// Ellipse filling a `w` by `h` frame, mapped through `to_px`
+fn ellipse(w: f32, h: f32, to_px: &impl Fn(f32, f32) -> (f32, f32)) -> Vec<PathCommand> {
+    let (rx, ry) = (w / 2.0, h / 2.0);
+    let (kx, ky) = (rx * KAPPA, ry * KAPPA);
+    let mut commands = vec![PathCommand::MoveTo(to_px(w, ry))];
+    let quarters = [
+        ((w, ry + ky), (rx + kx, h), (rx, h)),
+        ((rx - kx, h), (0.0, ry + ky), (0.0, ry)),
+        ((0.0, ry - ky), (rx - kx, 0.0), (rx, 0.0)),
+        ((rx + kx, 0.0), (w, ry - ky), (w, ry)),
+    ];
+    for (c1, c2, end) in quarters {
+        commands.push(PathCommand::CurveTo(
+            to_px(c1.0, c1.1),
+            to_px(c2.0, c2.1),
+            to_px(end.0, end.1),
+        ));
+    }
+    commands.push(PathCommand::Close);
+    commands
+}
+
+impl RenderResult {
+    /// Font families the rendered text asks for that `options.fonts` lacks,
+    /// with the substitute drawing each
+    pub fn missing_fonts(&self) -> Vec<MissingFont> {
+        let families = self
+            .pages
+            .iter()
+            .flat_map(|page| &page.elements)
+            .filter_map(|element| match element {
+                RenderElement::Text { font_family, .. } => Some(font_family.as_str()),
+                _ => None,
+            });
+        self.options.fonts.missing(families)
+    }
 }
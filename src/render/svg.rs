@@ -0,0 +1,546 @@
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt::Write as _;
+
+use crate::export::common;
+use crate::render::renderer::{Fill, PathCommand, RenderElement, RenderResult};
+
+/// Horizontal shear used to fake italics when no italic face is installed
+const ITALIC_SKEW: f32 = 0.21;
+
+/// How [`RenderResult::to_svg_with`] draws text
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum SvgTextMode {
+    /// `<text>` naming the document's font and its substitutes, drawn with
+    /// whatever the viewer has installed
+    #[default]
+    SystemFont,
+    /// `<text>` in subsets of `options.fonts` embedded as `@font-face` data
+    ///
+    /// TrueType and CFF faces are embedded as uncompressed `font/ttf` or
+    /// `font/otf`; WOFF2 is not produced, as it needs a Brotli encoder.
+    EmbeddedSubset,
+    /// Glyph outlines from `options.fonts` as `<path>` elements, with no text left to select
+    Paths,
+}
+
+/// Options for [`RenderResult::to_svg_with`]
+#[derive(Debug, Clone, Default)]
+pub struct SvgOptions {
+    pub text_mode: SvgTextMode,
+}
+
+impl SvgOptions {
+    /// Options that embed the fonts used, so the page looks the same in every viewer
+    pub fn embedded_fonts() -> Self {
+        Self {
+            text_mode: SvgTextMode::EmbeddedSubset,
+        }
+    }
+
+    /// Options that draw text as outlines, needing no fonts at all to view
+    pub fn text_as_paths() -> Self {
+        Self {
+            text_mode: SvgTextMode::Paths,
+        }
+    }
+}
+
+impl RenderResult {
+    /// Export a page to SVG, naming fonts for the viewer to find
+    pub fn to_svg(&self, page_index: usize) -> Option<String> {
+        self.to_svg_with(page_index, &SvgOptions::default())
+    }
+
+    /// Export a page to SVG with text drawn as `options.text_mode` asks
+    ///
+    /// Embedded subsets and paths use the faces in `options.fonts` of the
+    /// render; text no face covers falls back to a system font.
+    pub fn to_svg_with(&self, page_index: usize, options: &SvgOptions) -> Option<String> {
+        let page = self.pages.get(page_index)?;
+        let mut writer = SvgWriter {
+            result: self,
+            mode: options.text_mode,
+            glyphs: BTreeMap::new(),
+        };
+        let mut svg = String::new();
+
+        // Render elements
+        for (index, element) in page.elements.iter().enumerate() {
+            match element {
+                RenderElement::Text {
+                    x,
+                    y,
+                    text,
+                    font_family,
+                    font_size,
+                    color,
+                    bold,
+                    italic,
+                    underline,
+                    rotation,
+                } => {
+                    let run = TextStyle {
+                        family: font_family,
+                        // Points at the render scale to pixels
+                        size: font_size * self.options.dpi.max(1) as f32 / 72.0,
+                        color: *color,
+                        bold: *bold,
+                        italic: *italic,
+                        underline: *underline,
+                    };
+                    if *rotation != 0.0 {
+                        let _ = write!(svg, r#"<g transform="rotate({rotation} {x} {y})">"#);
+                    }
+                    writer.text(&mut svg, (*x as f32, *y as f32), text, &run);
+                    if *rotation != 0.0 {
+                        svg.push_str("</g>");
+                    }
+                }
+                RenderElement::Line {
+                    x1,
+                    y1,
+                    x2,
+                    y2,
+                    color,
+                    width,
+                } => {
+                    svg.push_str(&format!(
+                        "<line x1=\"{}\" y1=\"{}\" x2=\"{}\" y2=\"{}\" stroke=\"#{:06X}\" stroke-width=\"{}\"/>",
+                        x1, y1, x2, y2, color & 0xFFFFFF, width
+                    ));
+                }
+                RenderElement::Rectangle {
+                    x,
+                    y,
+                    width,
+                    height,
+                    fill_color,
+                    stroke_color,
+                    stroke_width,
+                } => {
+                    svg.push_str(&format!(
+                        r#"<rect x="{x}" y="{y}" width="{width}" height="{height}""#
+                    ));
+
+                    if let Some(fill) = fill_color {
+                        svg.push_str(&format!(" fill=\"#{:06X}\"", fill & 0xFFFFFF));
+                    } else {
+                        svg.push_str(r#" fill="none""#);
+                    }
+
+                    if let Some(stroke) = stroke_color {
+                        svg.push_str(&format!(
+                            " stroke=\"#{:06X}\" stroke-width=\"{}\"",
+                            stroke & 0xFFFFFF,
+                            stroke_width
+                        ));
+                    }
+
+                    svg.push_str("/>");
+                }
+                RenderElement::Image {
+                    x,
+                    y,
+                    width,
+                    height,
+                    data,
+                    rotation,
+                } => {
+                    // Formats a browser cannot be told the type of are left out
+                    let Some(mime) = image::guess_format(data)
+                        .ok()
+                        .map(|format| format.to_mime_type())
+                    else {
+                        continue;
+                    };
+                    svg.push_str(&format!(
+                        r#"<image x="{x}" y="{y}" width="{width}" height="{height}" preserveAspectRatio="none""#
+                    ));
+                    if *rotation != 0.0 {
+                        svg.push_str(&format!(
+                            r#" transform="rotate({} {} {})""#,
+                            rotation,
+                            *x as f32 + *width as f32 / 2.0,
+                            *y as f32 + *height as f32 / 2.0
+                        ));
+                    }
+                    svg.push_str(&format!(
+                        r#" href="data:{mime};base64,{}"/>"#,
+                        common::base64(data)
+                    ));
+                }
+                RenderElement::Path {
+                    commands,
+                    fill_color,
+                    stroke_color,
+                    stroke_width,
+                    dash,
+                } => {
+                    let d: Vec<String> = commands
+                        .iter()
+                        .map(|command| match command {
+                            PathCommand::MoveTo((x, y)) => format!("M{x} {y}"),
+                            PathCommand::LineTo((x, y)) => format!("L{x} {y}"),
+                            PathCommand::CurveTo((x1, y1), (x2, y2), (x, y)) => {
+                                format!("C{x1} {y1} {x2} {y2} {x} {y}")
+                            }
+                            PathCommand::Close => "Z".to_string(),
+                        })
+                        .collect();
+                    svg.push_str(&format!(r#"<path d="{}""#, d.join(" ")));
+
+                    if let Some(fill) = fill_color {
+                        svg.push_str(&format!(" fill=\"#{:06X}\"", fill & 0xFFFFFF));
+                    } else {
+                        svg.push_str(r#" fill="none""#);
+                    }
+
+                    if let Some(stroke) = stroke_color {
+                        svg.push_str(&format!(
+                            " stroke=\"#{:06X}\" stroke-width=\"{}\"",
+                            stroke & 0xFFFFFF,
+                            stroke_width
+                        ));
+                        if !dash.is_empty() {
+                            let lengths: Vec<String> =
+                                dash.iter().map(|length| length.to_string()).collect();
+                            svg.push_str(&format!(r#" stroke-dasharray="{}""#, lengths.join(" ")));
+                        }
+                    }
+
+                    svg.push_str("/>");
+                }
+                RenderElement::Fill {
+                    x,
+                    y,
+                    width,
+                    height,
+                    fill,
+                } => {
+                    let id = format!("fill{index}");
+                    let stop = |offset: u8, color: u32| {
+                        format!(
+                            "<stop offset=\"{offset}\" stop-color=\"#{:06X}\"/>",
+                            color & 0xFFFFFF
+                        )
+                    };
+                    svg.push_str("<defs>");
+                    match fill {
+                        Fill::Hatch {
+                            color,
+                            style,
+                            spacing,
+                        } => {
+                            // One horizontal line per tile, turned to the style's angle
+                            let angles = style.angles();
+                            let stroke =
+                                format!("stroke=\"#{:06X}\" stroke-width=\"1\"", color & 0xFFFFFF);
+                            let half = spacing / 2.0;
+                            svg.push_str(&format!(
+                                r#"<pattern id="{id}" patternUnits="userSpaceOnUse" width="{spacing}" height="{spacing}" patternTransform="rotate({})"><line x1="0" y1="{half}" x2="{spacing}" y2="{half}" {stroke}/>"#,
+                                angles[0]
+                            ));
+                            if angles.len() > 1 {
+                                svg.push_str(&format!(
+                                    r#"<line x1="{half}" y1="0" x2="{half}" y2="{spacing}" {stroke}/>"#
+                                ));
+                            }
+                            svg.push_str("</pattern>");
+                        }
+                        Fill::LinearGradient {
+                            start,
+                            end,
+                            from,
+                            to,
+                        } => {
+                            svg.push_str(&format!(
+                                r#"<linearGradient id="{id}" gradientUnits="userSpaceOnUse" x1="{}" y1="{}" x2="{}" y2="{}">{}{}</linearGradient>"#,
+                                from.0,
+                                from.1,
+                                to.0,
+                                to.1,
+                                stop(0, *start),
+                                stop(1, *end)
+                            ));
+                        }
+                        Fill::RadialGradient {
+                            start,
+                            end,
+                            center,
+                            radius,
+                        } => {
+                            svg.push_str(&format!(
+                                r#"<radialGradient id="{id}" gradientUnits="userSpaceOnUse" cx="{}" cy="{}" r="{radius}">{}{}</radialGradient>"#,
+                                center.0,
+                                center.1,
+                                stop(0, *start),
+                                stop(1, *end)
+                            ));
+                        }
+                    }
+                    svg.push_str(&format!(
+                        r#"</defs><rect x="{x}" y="{y}" width="{width}" height="{height}" fill="url(#{id})"/>"#
+                    ));
+                }
+            }
+        }
+
+        let mut document = format!(
+            r#"<svg width="{}" height="{}" xmlns="http://www.w3.org/2000/svg">"#,
+            page.width, page.height
+        );
+        document.push_str(&writer.font_faces());
+        // White background
+        document.push_str(&format!(
+            r#"<rect width="{}" height="{}" fill="white"/>"#,
+            page.width, page.height
+        ));
+        document.push_str(&svg);
+        document.push_str("</svg>");
+        Some(document)
+    }
+}
+
+/// Look of a text run, with its size in pixels
+struct TextStyle<'a> {
+    family: &'a str,
+    size: f32,
+    color: u32,
+    bold: bool,
+    italic: bool,
+    underline: bool,
+}
+
+struct SvgWriter<'a> {
+    result: &'a RenderResult,
+    mode: SvgTextMode,
+    /// Glyphs drawn with each embedded face, keyed by face index
+    glyphs: BTreeMap<usize, BTreeSet<u16>>,
+}
+
+impl SvgWriter<'_> {
+    fn text(&mut self, svg: &mut String, (x, y): (f32, f32), text: &str, style: &TextStyle) {
+        let fonts = &self.result.options.fonts;
+        let segments = match self.mode {
+            SvgTextMode::SystemFont => Vec::new(),
+            SvgTextMode::EmbeddedSubset | SvgTextMode::Paths => {
+                fonts.segments(text, style.family, style.bold, style.italic)
+            }
+        };
+        if segments.is_empty() {
+            svg.push_str(&self.text_element(x, y, style));
+            let _ = write!(svg, ">{}</text>", escape_xml(text));
+            return;
+        }
+
+        if self.mode == SvgTextMode::EmbeddedSubset {
+            svg.push_str(&self.text_element(x, y, style));
+            svg.push('>');
+            for segment in segments {
+                let face = fonts.faces()[segment.face].face();
+                let glyphs = self.glyphs.entry(segment.face).or_default();
+                glyphs.extend(
+                    text[segment.range.clone()]
+                        .chars()
+                        .filter_map(|c| face.glyph_index(c))
+                        .map(|glyph| glyph.0),
+                );
+                let _ = write!(
+                    svg,
+                    r#"<tspan font-family="{}">{}</tspan>"#,
+                    escape_xml(&self.families(style.family, Some(segment.face))),
+                    escape_xml(&text[segment.range])
+                );
+            }
+            svg.push_str("</text>");
+            return;
+        }
+
+        let mut cursor = x;
+        for segment in segments {
+            let face = &fonts.faces()[segment.face];
+            let parsed = face.face();
+            let scale = style.size / parsed.units_per_em().max(1) as f32;
+            let skew = if style.italic && !face.is_italic() {
+                ITALIC_SKEW
+            } else {
+                0.0
+            };
+            let mut outline = GlyphPath {
+                d: String::new(),
+                transform: (cursor, y, scale, skew),
+            };
+            for c in text[segment.range].chars() {
+                let glyph = parsed.glyph_index(c).unwrap_or(ttf_parser::GlyphId(0));
+                parsed.outline_glyph(glyph, &mut outline);
+                outline.transform.0 += parsed.glyph_hor_advance(glyph).unwrap_or(0) as f32 * scale;
+            }
+            cursor = outline.transform.0;
+            if outline.d.is_empty() {
+                continue;
+            }
+            let _ = write!(
+                svg,
+                "<path d=\"{}\" fill=\"#{:06X}\"",
+                outline.d.trim_end(),
+                style.color & 0xFFFFFF
+            );
+            // Thicken a regular face standing in for bold
+            if style.bold && !face.is_bold() {
+                let _ = write!(
+                    svg,
+                    " stroke=\"#{:06X}\" stroke-width=\"{}\"",
+                    style.color & 0xFFFFFF,
+                    num(style.size * 0.03)
+                );
+            }
+            svg.push_str("/>");
+        }
+        if style.underline && cursor > x {
+            let _ = write!(
+                svg,
+                "<rect x=\"{}\" y=\"{}\" width=\"{}\" height=\"{}\" fill=\"#{:06X}\"/>",
+                num(x),
+                num(y + style.size * 0.09),
+                num(cursor - x),
+                num((style.size * 0.06).max(1.0)),
+                style.color & 0xFFFFFF
+            );
+        }
+    }
+
+    /// Opening `<text` tag, left open for its content
+    fn text_element(&self, x: f32, y: f32, style: &TextStyle) -> String {
+        let mut element = format!(
+            "<text x=\"{}\" y=\"{}\" font-family=\"{}\" font-size=\"{}\" fill=\"#{:06X}\"",
+            num(x),
+            num(y),
+            escape_xml(&self.families(style.family, None)),
+            num(style.size),
+            style.color & 0xFFFFFF
+        );
+        if style.bold {
+            element.push_str(r#" font-weight="bold""#);
+        }
+        if style.italic {
+            element.push_str(r#" font-style="italic""#);
+        }
+        if style.underline {
+            element.push_str(r#" text-decoration="underline""#);
+        }
+        element
+    }
+
+    /// CSS font list: an embedded face first, then the family and its
+    /// substitutes, which the viewer picks the first installed of
+    fn families(&self, family: &str, face: Option<usize>) -> String {
+        let embedded = face.map(|face| format!("'{}'", embedded_family(face)));
+        let families: Vec<String> = embedded
+            .into_iter()
+            .chain(self.result.options.fonts.font_map().candidates(family).map(
+                |family| match family {
+                    "serif" | "sans-serif" | "monospace" => family.to_string(),
+                    _ => format!("'{}'", family.replace('\'', "")),
+                },
+            ))
+            .collect();
+        families.join(", ")
+    }
+
+    /// `@font-face` rules for the subsets of the embedded faces
+    fn font_faces(&self) -> String {
+        let fonts = &self.result.options.fonts;
+        let mut rules = String::new();
+        for (&index, glyphs) in &self.glyphs {
+            let face = &fonts.faces()[index];
+            // A face that cannot be subset leaves its text to the system fonts
+            let Ok(data) = face.subset(glyphs) else {
+                continue;
+            };
+            let (mime, format) = if face.has_glyf_outlines() {
+                ("font/ttf", "truetype")
+            } else {
+                ("font/otf", "opentype")
+            };
+            let _ = write!(
+                rules,
+                "@font-face {{ font-family: '{}'; font-weight: {}; font-style: {}; src: url(data:{mime};base64,{}) format('{format}'); }}",
+                embedded_family(index),
+                if face.is_bold() { "bold" } else { "normal" },
+                if face.is_italic() { "italic" } else { "normal" },
+                common::base64(&data)
+            );
+        }
+        if rules.is_empty() {
+            return rules;
+        }
+        format!("<defs><style>{rules}</style></defs>")
+    }
+}
+
+/// Family name an embedded face is declared under
+fn embedded_family(face: usize) -> String {
+    format!("hwpers-font-{face}")
+}
+
+/// Collects glyph outlines as SVG path data, mapping font units to pixels
+struct GlyphPath {
+    d: String,
+    /// Origin x, baseline y, pixels per font unit and italic shear
+    transform: (f32, f32, f32, f32),
+}
+
+impl GlyphPath {
+    fn point(&self, x: f32, y: f32) -> String {
+        let (origin, baseline, scale, skew) = self.transform;
+        format!(
+            "{} {}",
+            num(origin + (x + y * skew) * scale),
+            num(baseline - y * scale)
+        )
+    }
+}
+
+impl ttf_parser::OutlineBuilder for GlyphPath {
+    fn move_to(&mut self, x: f32, y: f32) {
+        let point = self.point(x, y);
+        let _ = write!(self.d, "M{point} ");
+    }
+
+    fn line_to(&mut self, x: f32, y: f32) {
+        let point = self.point(x, y);
+        let _ = write!(self.d, "L{point} ");
+    }
+
+    fn quad_to(&mut self, x1: f32, y1: f32, x: f32, y: f32) {
+        let (control, point) = (self.point(x1, y1), self.point(x, y));
+        let _ = write!(self.d, "Q{control} {point} ");
+    }
+
+    fn curve_to(&mut self, x1: f32, y1: f32, x2: f32, y2: f32, x: f32, y: f32) {
+        let (first, second, point) = (self.point(x1, y1), self.point(x2, y2), self.point(x, y));
+        let _ = write!(self.d, "C{first} {second} {point} ");
+    }
+
+    fn close(&mut self) {
+        self.d.push_str("Z ");
+    }
+}
+
+/// Compact number for coordinates
+fn num(value: f32) -> String {
+    let rounded = (value * 100.0).round() / 100.0;
+    if rounded == rounded.trunc() {
+        format!("{}", rounded as i64)
+    } else {
+        format!("{rounded}")
+    }
+}
+
+fn escape_xml(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&apos;")
+}
@@ -0,0 +1,543 @@
+use std::io::{Read, Seek};
+
+use flate2::read::DeflateDecoder;
+
+use super::report::{Finding, FindingKind, SecurityReport, Severity};
+use crate::error::{HwpError, Result};
+use crate::model::bin_data::{BinData, BinDataType};
+use crate::model::hyperlink::{Hyperlink, HyperlinkType};
+use crate::parser::header::FileHeader;
+use crate::reader::CfbReader;
+use crate::HwpDocument;
+
+/// Streams larger than this are reported as oversized
+const OVERSIZED_STREAM_BYTES: u64 = 64 * 1024 * 1024;
+
+/// Expansion ratios above these are reported
+const SUSPICIOUS_RATIO: u64 = 100;
+const CRITICAL_RATIO: u64 = 1000;
+
+/// Inflate at most this many bytes when measuring a ratio
+const RATIO_PROBE_LIMIT: u64 = 256 * 1024 * 1024;
+
+/// Inflate this many bytes of an embedded payload to identify its type
+const SNIFF_BYTES: u64 = 16;
+
+/// Extensions of regular images that HWP can display
+const IMAGE_EXTENSIONS: &[&str] = &[
+    "bmp", "gif", "jpg", "jpeg", "png", "tif", "tiff", "wmf", "emf", "svg",
+];
+
+/// Extensions that are directly executable or scriptable on Windows
+const EXECUTABLE_EXTENSIONS: &[&str] = &[
+    "exe", "dll", "scr", "com", "bat", "cmd", "ps1", "vbs", "vbe", "js", "jse", "wsf", "wsh",
+    "hta", "lnk", "msi", "jar", "cpl",
+];
+
+/// Analyze a parsed document for active content and risky references
+pub fn analyze(document: &HwpDocument) -> SecurityReport {
+    let mut report = SecurityReport::new();
+
+    check_header(&document.header, &mut report);
+
+    if let Some(scripts) = document.scripts() {
+        if scripts.has_user_code() {
+            report.push(Finding::new(
+                Severity::High,
+                FindingKind::Script,
+                "Scripts/DefaultJScript",
+                "Document contains JScript macros with user code",
+            ));
+        } else if !scripts.is_empty() {
+            report.push(Finding::new(
+                Severity::Info,
+                FindingKind::Script,
+                "Scripts/DefaultJScript",
+                "Document contains only the default empty script stubs",
+            ));
+        }
+    }
+
+    for bin_data in &document.doc_info.bin_data {
+        check_bin_data(bin_data, &mut report);
+    }
+
+    for (section_idx, section) in document.sections().enumerate() {
+        for (para_idx, paragraph) in section.paragraphs.iter().enumerate() {
+            for hyperlink in &paragraph.hyperlinks {
+                if hyperlink.hyperlink_type == HyperlinkType::Bookmark {
+                    continue;
+                }
+                let location = format!("section {section_idx}, paragraph {para_idx}");
+                check_hyperlink(hyperlink, &location, &mut report);
+            }
+        }
+    }
+
+    report
+}
+
+/// Analyze the raw compound file without parsing the document model.
+///
+/// This pass works on files the parser rejects and looks at every stream:
+/// unexpected names, oversized streams, embedded payload types and the
+/// expansion ratio of compressed streams.
+pub fn analyze_cfb<F: Read + Seek>(reader: &mut CfbReader<F>) -> Result<SecurityReport> {
+    let mut report = SecurityReport::new();
+
+    let header = reader
+        .read_stream("FileHeader")
+        .and_then(FileHeader::parse)
+        .ok();
+    if let Some(header) = &header {
+        check_header(header, &mut report);
+    }
+    let is_compressed = header.as_ref().is_none_or(|h| h.is_compressed());
+    let is_distribute = header.as_ref().is_some_and(|h| h.is_distribute());
+
+    for path in reader.list_streams() {
+        let name = path.trim_start_matches('/').to_string();
+        let size = reader.stream_size(&path).unwrap_or(0);
+
+        if !is_known_stream(&name) {
+            report.push(Finding::new(
+                Severity::Low,
+                FindingKind::UnusualStream,
+                &name,
+                format!("Stream is not part of the HWP 5.0 layout ({size} bytes)"),
+            ));
+        }
+
+        if size > OVERSIZED_STREAM_BYTES {
+            report.push(Finding::new(
+                Severity::Medium,
+                FindingKind::OversizedStream,
+                &name,
+                format!("Stream is {} MiB", size / (1024 * 1024)),
+            ));
+        }
+
+        if name.starts_with("BinData/") {
+            let Some(data) = read_stream(reader, &path, &name, &mut report) else {
+                continue;
+            };
+            check_bin_data_stream(&name, &data, is_compressed, &mut report);
+        } else if name.starts_with("DocHistory") {
+            report.push(Finding::new(
+                Severity::Low,
+                FindingKind::UnusualStream,
+                &name,
+                "Document carries editing history",
+            ));
+        } else if is_compressed && !is_distribute && is_compressed_stream(&name) {
+            let Some(data) = read_stream(reader, &path, &name, &mut report) else {
+                continue;
+            };
+            check_ratio(&name, &data, &mut report);
+        }
+    }
+
+    Ok(report)
+}
+
+/// Read a stream for inspection, reporting one that cannot be read, such as
+/// one over the reader's limits, instead of failing the whole pass
+fn read_stream<F: Read + Seek>(
+    reader: &mut CfbReader<F>,
+    path: &str,
+    name: &str,
+    report: &mut SecurityReport,
+) -> Option<Vec<u8>> {
+    match reader.read_stream(path) {
+        Ok(data) => Some(data),
+        Err(HwpError::LimitExceeded { limit, value, max }) => {
+            report.push(Finding::new(
+                Severity::High,
+                FindingKind::OversizedStream,
+                name,
+                format!("Stream was not inspected: {limit} {value} exceeds the limit of {max}"),
+            ));
+            None
+        }
+        Err(e) => {
+            report.push(Finding::new(
+                Severity::Medium,
+                FindingKind::UnusualStream,
+                name,
+                format!("Stream could not be read: {e}"),
+            ));
+            None
+        }
+    }
+}
+
+/// Run both the raw compound file pass and, when the document parses, the model pass
+pub fn analyze_bytes(bytes: &[u8]) -> Result<SecurityReport> {
+    let mut reader = CfbReader::new(std::io::Cursor::new(bytes))?;
+    let mut report = analyze_cfb(&mut reader)?;
+
+    if let Ok(document) = crate::HwpReader::from_bytes(bytes) {
+        let mut model_report = analyze(&document);
+        // The raw pass already reported the header flags and embedded payloads
+        model_report.findings.retain(|f| {
+            f.kind != FindingKind::HeaderFlag
+                && (f.kind == FindingKind::ExternalBinData || !f.location.starts_with("BinData"))
+        });
+        report.merge(model_report);
+    }
+
+    Ok(report)
+}
+
+fn check_header(header: &FileHeader, report: &mut SecurityReport) {
+    let flags: [(bool, Severity, &str); 9] = [
+        (
+            header.is_script(),
+            Severity::Medium,
+            "Document declares embedded scripts",
+        ),
+        (
+            header.is_encrypted(),
+            Severity::Medium,
+            "Document is password-encrypted and cannot be inspected",
+        ),
+        (
+            header.is_distribute(),
+            Severity::Low,
+            "Distribution document with encrypted body text",
+        ),
+        (
+            header.is_drm(),
+            Severity::Medium,
+            "Document is DRM-protected",
+        ),
+        (
+            header.is_certificate_drm(),
+            Severity::Medium,
+            "Document uses certificate-based DRM",
+        ),
+        (
+            header.is_certificate_encrypt(),
+            Severity::Medium,
+            "Document is encrypted with a public key certificate",
+        ),
+        (
+            header.is_sign(),
+            Severity::Info,
+            "Document carries an electronic signature",
+        ),
+        (
+            header.is_sign_spare(),
+            Severity::Info,
+            "Document reserves space for a signature",
+        ),
+        (
+            header.is_history(),
+            Severity::Low,
+            "Document stores editing history",
+        ),
+    ];
+
+    for (set, severity, description) in flags {
+        if set {
+            report.push(Finding::new(
+                severity,
+                FindingKind::HeaderFlag,
+                "FileHeader",
+                description,
+            ));
+        }
+    }
+}
+
+fn check_bin_data(bin_data: &BinData, report: &mut SecurityReport) {
+    let location = format!("BinData {}", bin_data.bin_id);
+    let extension = bin_data.extension.to_lowercase();
+
+    // Embedded payloads are inspected below; only data-less links point outside
+    if let BinDataType::Link = bin_data.get_type() {
+        if bin_data.data.is_empty()
+            && (!bin_data.abs_name.is_empty() || !bin_data.rel_name.is_empty())
+        {
+            let target = if bin_data.abs_name.is_empty() {
+                &bin_data.rel_name
+            } else {
+                &bin_data.abs_name
+            };
+            report.push(Finding::new(
+                if is_network_path(target) {
+                    Severity::High
+                } else {
+                    Severity::Medium
+                },
+                FindingKind::ExternalBinData,
+                &location,
+                format!("Binary data is linked from external path '{target}'"),
+            ));
+        }
+    }
+
+    check_extension(&location, &extension, report);
+
+    if !bin_data.data.is_empty() {
+        let data = bin_data
+            .get_data()
+            .unwrap_or_else(|_| bin_data.data.clone());
+        check_payload(&location, &extension, &data, report);
+    }
+}
+
+fn check_bin_data_stream(
+    name: &str,
+    data: &[u8],
+    is_compressed: bool,
+    report: &mut SecurityReport,
+) {
+    let extension = name
+        .rsplit_once('.')
+        .map(|(_, ext)| ext.to_lowercase())
+        .unwrap_or_default();
+
+    check_extension(name, &extension, report);
+
+    if is_compressed {
+        check_ratio(name, data, report);
+        // Magic bytes are all that is needed of the content
+        match inflate_prefix(data, SNIFF_BYTES) {
+            Some(prefix) => check_payload(name, &extension, &prefix, report),
+            None => check_payload(name, &extension, data, report),
+        }
+    } else {
+        check_payload(name, &extension, data, report);
+    }
+}
+
+fn check_extension(location: &str, extension: &str, report: &mut SecurityReport) {
+    if extension == "ole" {
+        report.push(Finding::new(
+            Severity::High,
+            FindingKind::OleObject,
+            location,
+            "Embedded OLE object can carry executable content",
+        ));
+    } else if matches!(extension, "eps" | "ps" | "ai") {
+        report.push(Finding::new(
+            Severity::High,
+            FindingKind::EpsImage,
+            location,
+            "PostScript image is interpreted by the viewer and a frequent exploit vector",
+        ));
+    } else if EXECUTABLE_EXTENSIONS.contains(&extension) {
+        report.push(Finding::new(
+            Severity::Critical,
+            FindingKind::SuspiciousBinData,
+            location,
+            format!("Embedded file has executable extension '.{extension}'"),
+        ));
+    } else if !extension.is_empty() && !IMAGE_EXTENSIONS.contains(&extension) {
+        report.push(Finding::new(
+            Severity::Medium,
+            FindingKind::SuspiciousBinData,
+            location,
+            format!("Embedded file has unusual extension '.{extension}'"),
+        ));
+    }
+}
+
+/// Compare the declared extension with the actual content
+fn check_payload(location: &str, extension: &str, data: &[u8], report: &mut SecurityReport) {
+    let Some(actual) = sniff_type(data) else {
+        return;
+    };
+
+    match actual {
+        "exe" => report.push(Finding::new(
+            Severity::Critical,
+            FindingKind::SuspiciousBinData,
+            location,
+            "Embedded data is a Windows executable",
+        )),
+        "eps" if extension != "eps" && extension != "ps" => report.push(Finding::new(
+            Severity::High,
+            FindingKind::EpsImage,
+            location,
+            format!("Embedded data is PostScript disguised as '.{extension}'"),
+        )),
+        "ole" if extension != "ole" => report.push(Finding::new(
+            Severity::High,
+            FindingKind::OleObject,
+            location,
+            format!("Embedded data is an OLE compound file disguised as '.{extension}'"),
+        )),
+        "zip" if IMAGE_EXTENSIONS.contains(&extension) => report.push(Finding::new(
+            Severity::Medium,
+            FindingKind::SuspiciousBinData,
+            location,
+            format!("Embedded data is a ZIP archive disguised as '.{extension}'"),
+        )),
+        _ => {}
+    }
+}
+
+fn check_hyperlink(hyperlink: &Hyperlink, location: &str, report: &mut SecurityReport) {
+    let target = hyperlink.target_url.trim();
+    let lower = target.to_lowercase();
+
+    let (severity, kind, description) = if hyperlink.hyperlink_type == HyperlinkType::File
+        || lower.starts_with("file:")
+        || is_network_path(target)
+    {
+        let severity = if is_network_path(target) {
+            Severity::High
+        } else {
+            Severity::Medium
+        };
+        (
+            severity,
+            FindingKind::FileLink,
+            format!("Link opens local or network file '{target}'"),
+        )
+    } else if lower.starts_with("javascript:")
+        || lower.starts_with("vbscript:")
+        || lower.starts_with("data:")
+    {
+        (
+            Severity::High,
+            FindingKind::UrlLink,
+            format!("Link runs script content '{target}'"),
+        )
+    } else if lower.starts_with("http://") || lower.starts_with("ftp://") {
+        (
+            Severity::Low,
+            FindingKind::UrlLink,
+            format!("Unencrypted link to '{target}'"),
+        )
+    } else {
+        (
+            Severity::Info,
+            FindingKind::UrlLink,
+            format!("Link to '{target}'"),
+        )
+    };
+    report.push(Finding::new(severity, kind, location, description));
+
+    // Display text that looks like a different address is a phishing pattern
+    let display = hyperlink.display_text.trim().to_lowercase();
+    if looks_like_url(&display) && !lower.contains(display.trim_end_matches('/')) {
+        report.push(Finding::new(
+            Severity::Medium,
+            FindingKind::UrlLink,
+            location,
+            format!(
+                "Link text '{}' does not match its target '{target}'",
+                hyperlink.display_text
+            ),
+        ));
+    }
+}
+
+fn check_ratio(name: &str, data: &[u8], report: &mut SecurityReport) {
+    if data.is_empty() {
+        return;
+    }
+    let Some(inflated) = inflated_len(data, RATIO_PROBE_LIMIT) else {
+        return;
+    };
+
+    let ratio = inflated / data.len() as u64;
+    if ratio >= SUSPICIOUS_RATIO {
+        let capped = if inflated >= RATIO_PROBE_LIMIT {
+            " (stopped at probe limit)"
+        } else {
+            ""
+        };
+        report.push(Finding::new(
+            if ratio >= CRITICAL_RATIO {
+                Severity::Critical
+            } else {
+                Severity::High
+            },
+            FindingKind::DecompressionRatio,
+            name,
+            format!(
+                "{} bytes inflate to {} bytes, ratio {}:1{}",
+                data.len(),
+                inflated,
+                ratio,
+                capped
+            ),
+        ));
+    }
+}
+
+/// Count the inflated size of a raw deflate stream without keeping the output
+fn inflated_len(data: &[u8], limit: u64) -> Option<u64> {
+    let mut decoder = DeflateDecoder::new(data).take(limit);
+    std::io::copy(&mut decoder, &mut std::io::sink()).ok()
+}
+
+/// Inflate the first `limit` bytes of a raw deflate stream
+fn inflate_prefix(data: &[u8], limit: u64) -> Option<Vec<u8>> {
+    let mut decoder = DeflateDecoder::new(data).take(limit);
+    let mut out = Vec::new();
+    decoder.read_to_end(&mut out).ok()?;
+    Some(out)
+}
+
+/// Identify payload types that matter for security from magic bytes
+fn sniff_type(data: &[u8]) -> Option<&'static str> {
+    if data.starts_with(b"MZ") {
+        Some("exe")
+    } else if data.starts_with(b"%!PS") || data.starts_with(&[0xC5, 0xD0, 0xD3, 0xC6]) {
+        Some("eps")
+    } else if data.starts_with(&[0xD0, 0xCF, 0x11, 0xE0, 0xA1, 0xB1, 0x1A, 0xE1]) {
+        Some("ole")
+    } else if data.starts_with(b"PK\x03\x04") {
+        Some("zip")
+    } else {
+        None
+    }
+}
+
+fn is_known_stream(name: &str) -> bool {
+    const EXACT: &[&str] = &[
+        "FileHeader",
+        "DocInfo",
+        "PrvText",
+        "PrvImage",
+        "\u{5}HwpSummaryInformation",
+        "Scripts/DefaultJScript",
+        "Scripts/JScriptVersion",
+        "DocOptions/_LinkDoc",
+        "DocOptions/DrmLicense",
+        "DocOptions/DrmRootSect",
+        "DocOptions/CertDrmHeader",
+        "DocOptions/CertDrmInfo",
+        "DocOptions/DigitalSignature",
+        "DocOptions/PublicKeyInfo",
+    ];
+    const PREFIXES: &[&str] = &[
+        "BodyText/Section",
+        "ViewText/Section",
+        "BinData/",
+        "DocHistory/",
+        "XMLTemplate/",
+    ];
+
+    EXACT.contains(&name) || PREFIXES.iter().any(|p| name.starts_with(p))
+}
+
+fn is_compressed_stream(name: &str) -> bool {
+    name == "DocInfo" || name.starts_with("BodyText/") || name.starts_with("DocHistory/")
+}
+
+fn is_network_path(target: &str) -> bool {
+    let lower = target.to_lowercase();
+    target.starts_with("\\\\")
+        || target.starts_with("//")
+        || lower.starts_with("file://") && !lower.starts_with("file:///")
+}
+
+fn looks_like_url(text: &str) -> bool {
+    text.starts_with("http://") || text.starts_with("https://") || text.starts_with("www.")
+}
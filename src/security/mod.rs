@@ -0,0 +1,7 @@
+mod analyze;
+mod report;
+mod sanitize;
+
+pub use analyze::{analyze, analyze_bytes, analyze_cfb};
+pub use report::{Finding, FindingKind, SecurityReport, Severity};
+pub use sanitize::{sanitize, sanitize_to_bytes, sanitize_to_file, SanitizeOptions};
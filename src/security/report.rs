@@ -0,0 +1,136 @@
+use std::fmt;
+
+/// How dangerous a finding is
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Severity {
+    /// Worth knowing, no action needed
+    Info,
+    /// Unusual but rarely harmful
+    Low,
+    /// Active content or data that can leak or mislead
+    Medium,
+    /// Content commonly used by malicious documents
+    High,
+    /// Executable payloads or structures that only make sense in an attack
+    Critical,
+}
+
+impl fmt::Display for Severity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Severity::Info => "info",
+            Severity::Low => "low",
+            Severity::Medium => "medium",
+            Severity::High => "high",
+            Severity::Critical => "critical",
+        };
+        f.write_str(name)
+    }
+}
+
+/// What kind of content a finding is about
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum FindingKind {
+    /// JScript macros in the Scripts storage
+    Script,
+    /// Embedded OLE object
+    OleObject,
+    /// Encapsulated PostScript image
+    EpsImage,
+    /// Binary data whose type or content is not a regular image
+    SuspiciousBinData,
+    /// Binary data loaded from an external path
+    ExternalBinData,
+    /// Hyperlink to a web or mail address
+    UrlLink,
+    /// Hyperlink to a local or network file
+    FileLink,
+    /// Stream that is not part of the HWP layout
+    UnusualStream,
+    /// Stream that is larger than expected
+    OversizedStream,
+    /// Compressed stream with an extreme expansion ratio
+    DecompressionRatio,
+    /// Security-relevant FileHeader flag
+    HeaderFlag,
+}
+
+/// A single observation about the document
+#[derive(Debug, Clone)]
+pub struct Finding {
+    pub severity: Severity,
+    pub kind: FindingKind,
+    /// Stream path, BinData id or paragraph position the finding refers to
+    pub location: String,
+    pub description: String,
+}
+
+impl Finding {
+    pub fn new(
+        severity: Severity,
+        kind: FindingKind,
+        location: impl Into<String>,
+        description: impl Into<String>,
+    ) -> Self {
+        Self {
+            severity,
+            kind,
+            location: location.into(),
+            description: description.into(),
+        }
+    }
+}
+
+impl fmt::Display for Finding {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "[{}] {:?} at {}: {}",
+            self.severity, self.kind, self.location, self.description
+        )
+    }
+}
+
+/// Result of a security analysis
+#[derive(Debug, Clone, Default)]
+pub struct SecurityReport {
+    pub findings: Vec<Finding>,
+}
+
+impl SecurityReport {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn push(&mut self, finding: Finding) {
+        self.findings.push(finding);
+    }
+
+    /// Append the findings of another report
+    pub fn merge(&mut self, other: SecurityReport) {
+        self.findings.extend(other.findings);
+    }
+
+    /// Highest severity among all findings
+    pub fn max_severity(&self) -> Option<Severity> {
+        self.findings.iter().map(|f| f.severity).max()
+    }
+
+    /// No finding above `Info`
+    pub fn is_clean(&self) -> bool {
+        self.max_severity().is_none_or(|s| s <= Severity::Info)
+    }
+
+    /// Findings at or above the given severity
+    pub fn at_least(&self, severity: Severity) -> Vec<&Finding> {
+        self.findings
+            .iter()
+            .filter(|f| f.severity >= severity)
+            .collect()
+    }
+
+    /// Findings of the given kind
+    pub fn of_kind(&self, kind: FindingKind) -> Vec<&Finding> {
+        self.findings.iter().filter(|f| f.kind == kind).collect()
+    }
+}
@@ -0,0 +1,225 @@
+use std::collections::HashSet;
+use std::path::Path;
+
+use crate::error::Result;
+use crate::model::bin_data::{BinData, BinDataType};
+use crate::model::ctrl_header::ControlType;
+use crate::model::hyperlink::{Hyperlink, HyperlinkType};
+use crate::model::Paragraph;
+use crate::HwpDocument;
+
+/// FileHeader flags that describe content a sanitized copy no longer carries:
+/// scripts (0x08), XML template (0x20), history (0x40) and signatures (0x80, 0x200)
+const STRIPPED_HEADER_FLAGS: u32 = 0x08 | 0x20 | 0x40 | 0x80 | 0x200;
+
+/// What [`sanitize`] removes from a document
+#[derive(Debug, Clone)]
+pub struct SanitizeOptions {
+    /// Drop JScript macros
+    pub remove_scripts: bool,
+    /// Drop OLE objects, linked binaries and embedded files that are not images
+    pub remove_embedded_objects: bool,
+    /// Drop hyperlinks to local or network files
+    pub remove_file_links: bool,
+    /// Drop hyperlinks to web and mail addresses (bookmarks inside the document are kept)
+    pub remove_url_links: bool,
+    /// Drop hidden comment controls
+    pub remove_hidden_comments: bool,
+    /// Clear editing history and signature flags
+    pub remove_history: bool,
+    /// Drop summary information (title, author, dates, ...)
+    pub remove_metadata: bool,
+    /// Re-encode every image as PNG, removing images that cannot be decoded
+    pub flatten_images: bool,
+}
+
+impl Default for SanitizeOptions {
+    fn default() -> Self {
+        Self {
+            remove_scripts: true,
+            remove_embedded_objects: true,
+            remove_file_links: true,
+            remove_url_links: true,
+            remove_hidden_comments: true,
+            remove_history: true,
+            remove_metadata: true,
+            flatten_images: false,
+        }
+    }
+}
+
+impl SanitizeOptions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Keep web links, only drop links to files
+    pub fn keep_url_links(mut self) -> Self {
+        self.remove_url_links = false;
+        self
+    }
+
+    pub fn flatten_images(mut self, flatten: bool) -> Self {
+        self.flatten_images = flatten;
+        self
+    }
+}
+
+/// Produce a copy of the document without active content and hidden metadata
+///
+/// Stored previews are always dropped, as they may show text the copy no
+/// longer has; serializing the copy writes a fresh PrvText from its body.
+pub fn sanitize(document: &HwpDocument, options: &SanitizeOptions) -> HwpDocument {
+    let mut clean = document.clone();
+
+    if options.remove_scripts {
+        clean.scripts = None;
+    }
+    if options.remove_history {
+        clean.header.flags &= !STRIPPED_HEADER_FLAGS;
+    } else if options.remove_scripts {
+        clean.header.flags &= !0x08;
+    }
+    clean.preview_text = None;
+    clean.preview_image = None;
+    if options.remove_metadata {
+        clean.summary_info = None;
+    }
+
+    let mut removed_bin_ids = HashSet::new();
+    if options.remove_embedded_objects {
+        clean.doc_info.bin_data.retain(|bin_data| {
+            let keep = is_embedded_image(bin_data);
+            if !keep {
+                removed_bin_ids.insert(bin_data.bin_id);
+            }
+            keep
+        });
+    }
+    if options.flatten_images {
+        clean.doc_info.bin_data.retain_mut(|bin_data| {
+            let keep = flatten_to_png(bin_data);
+            if !keep {
+                removed_bin_ids.insert(bin_data.bin_id);
+            }
+            keep
+        });
+    }
+
+    for body_text in &mut clean.body_texts {
+        for section in &mut body_text.sections {
+            clean_paragraphs(&mut section.paragraphs, options, &removed_bin_ids);
+        }
+    }
+
+    clean
+}
+
+/// Sanitize a document and serialize the clean copy
+pub fn sanitize_to_bytes(document: &HwpDocument, options: &SanitizeOptions) -> Result<Vec<u8>> {
+    crate::writer::serializer::serialize_document(&sanitize(document, options))
+}
+
+/// Sanitize a document and write the clean copy to a new file
+pub fn sanitize_to_file<P: AsRef<Path>>(
+    document: &HwpDocument,
+    options: &SanitizeOptions,
+    path: P,
+) -> Result<()> {
+    std::fs::write(path, sanitize_to_bytes(document, options)?)
+        .map_err(crate::error::HwpError::Io)?;
+    Ok(())
+}
+
+/// Remove links, dropped pictures and hidden comments from paragraphs and
+/// the notes inside them
+///
+/// Table cell content follows its table in the same list, so it is covered
+/// here; a hidden comment's own paragraphs follow the paragraph holding it,
+/// as many as its list header counts, and go with the comment.
+fn clean_paragraphs(
+    paragraphs: &mut Vec<Paragraph>,
+    options: &SanitizeOptions,
+    removed_bin_ids: &HashSet<u16>,
+) {
+    let mut index = 0;
+    while index < paragraphs.len() {
+        let paragraph = &mut paragraphs[index];
+        paragraph
+            .hyperlinks
+            .retain(|link| !should_remove_link(link, options));
+
+        if paragraph
+            .picture_data
+            .as_ref()
+            .is_some_and(|p| removed_bin_ids.contains(&p.bin_item_id))
+        {
+            paragraph.picture_data = None;
+        }
+
+        for note in &mut paragraph.footnotes {
+            clean_paragraphs(&mut note.paragraphs, options, removed_bin_ids);
+        }
+
+        index += 1;
+        if options.remove_hidden_comments
+            && paragraph
+                .ctrl_header
+                .as_ref()
+                .is_some_and(|c| c.get_control_type() == ControlType::HiddenComment)
+        {
+            let nested = paragraph
+                .list_header
+                .take()
+                .map_or(0, |header| header.paragraph_count.max(0) as usize);
+            paragraph.ctrl_header = None;
+            let end = (index + nested).min(paragraphs.len());
+            paragraphs.drain(index..end);
+        }
+    }
+}
+
+/// Images stored inside the file; linked files and other payloads are not kept
+fn is_embedded_image(bin_data: &BinData) -> bool {
+    let is_link = matches!(bin_data.get_type(), BinDataType::Link) && bin_data.data.is_empty();
+    !is_link && bin_data.is_image()
+}
+
+fn should_remove_link(link: &Hyperlink, options: &SanitizeOptions) -> bool {
+    let target = link.target_url.trim().to_lowercase();
+    let is_file = link.hyperlink_type == HyperlinkType::File
+        || target.starts_with("file:")
+        || target.starts_with("\\\\");
+
+    match link.hyperlink_type {
+        HyperlinkType::Bookmark => false,
+        _ if is_file => options.remove_file_links,
+        _ => options.remove_url_links,
+    }
+}
+
+/// Decode the image and store it as a freshly encoded PNG.
+///
+/// Returns false when the image cannot be decoded and should be dropped.
+fn flatten_to_png(bin_data: &mut BinData) -> bool {
+    let Ok(data) = bin_data.get_data() else {
+        return false;
+    };
+    let Ok(image) = image::load_from_memory(&data) else {
+        return false;
+    };
+
+    let mut png = Vec::new();
+    if image
+        .write_to(&mut std::io::Cursor::new(&mut png), image::ImageFormat::Png)
+        .is_err()
+    {
+        return false;
+    }
+
+    // Stored uncompressed; the serializer compresses according to the header
+    bin_data.properties &= !0x04;
+    bin_data.extension = "png".to_string();
+    bin_data.data = png;
+    true
+}
@@ -1,8 +1,14 @@
-use crate::error::Result;
+use crate::error::{HwpError, Result};
+use crate::reader::ReaderLimits;
 use flate2::read::ZlibDecoder;
 use std::io::Read;
 
 pub fn decompress_stream(data: &[u8]) -> Result<Vec<u8>> {
+    decompress_stream_with_limits(data, &ReaderLimits::default())
+}
+
+/// Decompress a stream, failing once the output grows past `limits.max_stream_size`
+pub fn decompress_stream_with_limits(data: &[u8], limits: &ReaderLimits) -> Result<Vec<u8>> {
     if data.is_empty() {
         return Ok(Vec::new());
     }
@@ -10,18 +16,14 @@ pub fn decompress_stream(data: &[u8]) -> Result<Vec<u8>> {
     // HWP files use raw deflate without zlib header
     // Try raw deflate first
     use flate2::read::DeflateDecoder;
-    let mut decoder = DeflateDecoder::new(data);
-    let mut decompressed = Vec::new();
-
-    match decoder.read_to_end(&mut decompressed) {
-        Ok(_) => Ok(decompressed),
+    match read_limited(DeflateDecoder::new(data), limits) {
+        Ok(decompressed) => Ok(decompressed),
+        Err(e @ HwpError::LimitExceeded { .. }) => Err(e),
         Err(_) => {
             // If raw deflate fails, try zlib
-            let mut decoder = ZlibDecoder::new(data);
-            let mut decompressed = Vec::new();
-
-            match decoder.read_to_end(&mut decompressed) {
-                Ok(_) => Ok(decompressed),
+            match read_limited(ZlibDecoder::new(data), limits) {
+                Ok(decompressed) => Ok(decompressed),
+                Err(e @ HwpError::LimitExceeded { .. }) => Err(e),
                 Err(_) => {
                     // If both fail, return data as-is (might not be compressed)
                     Ok(data.to_vec())
@@ -30,3 +32,12 @@ pub fn decompress_stream(data: &[u8]) -> Result<Vec<u8>> {
         }
     }
 }
+
+fn read_limited<R: Read>(reader: R, limits: &ReaderLimits) -> Result<Vec<u8>> {
+    let mut decompressed = Vec::new();
+    reader
+        .take(limits.max_stream_size.saturating_add(1))
+        .read_to_end(&mut decompressed)?;
+    limits.check_stream_size(decompressed.len() as u64)?;
+    Ok(decompressed)
+}
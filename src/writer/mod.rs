@@ -65,6 +65,8 @@ impl HwpWriter {
                 preview_text: None,
                 preview_image: None,
                 summary_info: None,
+                scripts: None,
+                password: None,
             },
             current_section_idx: 0,
             next_instance_id: 1,
@@ -99,7 +101,12 @@ impl HwpWriter {
             table_data: None,
             picture_data: None,
             text_box_data: None,
+            shape_data: None,
             hyperlinks: Vec::new(),
+            footnotes: Vec::new(),
+            page_hide: None,
+            new_numbers: Vec::new(),
+            column_def: None,
         };
 
         // Get the current section and add paragraph
@@ -156,7 +163,12 @@ impl HwpWriter {
             table_data: None,
             picture_data: None,
             text_box_data: None,
+            shape_data: None,
             hyperlinks: Vec::new(),
+            footnotes: Vec::new(),
+            page_hide: None,
+            new_numbers: Vec::new(),
+            column_def: None,
         };
 
         // Get the current section and add paragraph
@@ -223,7 +235,12 @@ impl HwpWriter {
             table_data: None,
             picture_data: None,
             text_box_data: None,
+            shape_data: None,
             hyperlinks: Vec::new(),
+            footnotes: Vec::new(),
+            page_hide: None,
+            new_numbers: Vec::new(),
+            column_def: None,
         };
 
         // Add paragraph to current section
@@ -328,7 +345,12 @@ impl HwpWriter {
                 table_data: None,
                 picture_data: None,
                 text_box_data: None,
+                shape_data: None,
                 hyperlinks: Vec::new(),
+                footnotes: Vec::new(),
+                page_hide: None,
+                new_numbers: Vec::new(),
+                column_def: None,
             };
 
             // Add paragraph to current section
@@ -449,13 +471,15 @@ impl HwpWriter {
         use crate::model::bin_data::BinData;
         use crate::model::control::Picture;
         use crate::model::ctrl_header::{ControlType, CtrlHeader};
+        use crate::model::drawing::{ObjectAlign, ObjectProperties, RelativeTo, TextWrap};
 
         // Calculate bin_id (1-based index)
         let bin_id = (self.document.doc_info.bin_data.len() + 1) as u16;
 
         // Create binary data entry
         let bin_data = BinData {
-            properties: 0,
+            // Embedded, compressed along with the document
+            properties: 1,
             abs_name: format!("image{}.{}", bin_id, format.extension()),
             rel_name: format!("image_{}.{}", self.next_instance_id(), format.extension()),
             bin_id,
@@ -471,9 +495,28 @@ impl HwpWriter {
         let width = options.width.unwrap_or(50) as f32 * hwp_scale; // Default 50mm
         let height = options.height.unwrap_or(50) as f32 * hwp_scale; // Default 50mm
 
+        // Inline images sit in the text; aligned ones float in the column
+        let placement = match options.alignment {
+            style::ImageAlign::InlineWithText => ObjectProperties::inline(),
+            alignment => {
+                let wrap = if options.wrap_text {
+                    TextWrap::Square
+                } else {
+                    TextWrap::TopAndBottom
+                };
+                let align = match alignment {
+                    style::ImageAlign::Center => ObjectAlign::Center,
+                    style::ImageAlign::Right => ObjectAlign::End,
+                    _ => ObjectAlign::Start,
+                };
+                ObjectProperties::floating(RelativeTo::Paragraph, RelativeTo::Column, wrap)
+                    .with_horizontal_align(align)
+            }
+        };
+
         // Create picture control
         let picture = Picture {
-            properties: 0,
+            properties: placement.0,
             left: 0,
             top: 0,
             right: width as i32,
@@ -515,7 +558,12 @@ impl HwpWriter {
             table_data: None,
             picture_data: Some(picture),
             text_box_data: None,
+            shape_data: None,
             hyperlinks: Vec::new(),
+            footnotes: Vec::new(),
+            page_hide: None,
+            new_numbers: Vec::new(),
+            column_def: None,
         };
 
         // Add the picture control paragraph to the document
@@ -645,7 +693,12 @@ impl HwpWriter {
             table_data: None,
             picture_data: None,
             text_box_data: None,
+            shape_data: None,
             hyperlinks: vec![hyperlink],
+            footnotes: Vec::new(),
+            page_hide: None,
+            new_numbers: Vec::new(),
+            column_def: None,
         };
 
         // Add the paragraph to the document
@@ -751,7 +804,12 @@ impl HwpWriter {
             table_data: None,
             picture_data: None,
             text_box_data: None,
+            shape_data: None,
             hyperlinks,
+            footnotes: Vec::new(),
+            page_hide: None,
+            new_numbers: Vec::new(),
+            column_def: None,
         };
 
         // Add the paragraph to the document
@@ -764,6 +822,69 @@ impl HwpWriter {
         Ok(())
     }
 
+    /// Add a paragraph with a footnote whose reference mark follows the text
+    pub fn add_paragraph_with_footnote(&mut self, text: &str, note: &str) -> Result<()> {
+        self.add_paragraph_with_note(text, note, crate::model::NoteKind::Footnote)
+    }
+
+    /// Add a paragraph with an endnote whose reference mark follows the text
+    pub fn add_paragraph_with_endnote(&mut self, text: &str, note: &str) -> Result<()> {
+        self.add_paragraph_with_note(text, note, crate::model::NoteKind::Endnote)
+    }
+
+    fn add_paragraph_with_note(
+        &mut self,
+        text: &str,
+        note: &str,
+        kind: crate::model::NoteKind,
+    ) -> Result<()> {
+        use crate::model::Footnote;
+
+        let number = self
+            .document
+            .sections()
+            .flat_map(|section| &section.paragraphs)
+            .flat_map(|paragraph| &paragraph.footnotes)
+            .filter(|footnote| footnote.kind == kind)
+            .count() as u16
+            + 1;
+
+        self.add_paragraph(text)?;
+        let footnote = Footnote::new(kind, number, text.chars().count() as u32, note);
+        if let Some(paragraph) = self
+            .document
+            .body_texts
+            .get_mut(self.current_section_idx)
+            .and_then(|body_text| body_text.sections.get_mut(0))
+            .and_then(|section| section.paragraphs.last_mut())
+        {
+            paragraph.footnotes.push(footnote);
+        }
+
+        Ok(())
+    }
+
+    /// Add a paragraph that starts a new column layout, ending the one before it
+    pub fn add_paragraph_with_columns(
+        &mut self,
+        text: &str,
+        column_def: crate::model::ColumnDef,
+    ) -> Result<()> {
+        self.add_paragraph(text)?;
+        if let Some(paragraph) = self
+            .document
+            .body_texts
+            .get_mut(self.current_section_idx)
+            .and_then(|body_text| body_text.sections.get_mut(0))
+            .and_then(|section| section.paragraphs.last_mut())
+        {
+            paragraph.column_type |= 0x02; // 다단 나누기
+            paragraph.column_def = Some(column_def);
+        }
+
+        Ok(())
+    }
+
     /// Add a header to the current section
     pub fn add_header(&mut self, text: &str) {
         use crate::model::header_footer::HeaderFooter;
@@ -876,7 +997,12 @@ impl HwpWriter {
             table_data: None,
             picture_data: None,
             text_box_data: None,
+            shape_data: None,
             hyperlinks: Vec::new(),
+            footnotes: Vec::new(),
+            page_hide: None,
+            new_numbers: Vec::new(),
+            column_def: None,
         };
 
         // Add the paragraph to the document
@@ -941,7 +1067,12 @@ impl HwpWriter {
             table_data: None,
             picture_data: None,
             text_box_data: None,
+            shape_data: None,
             hyperlinks: Vec::new(),
+            footnotes: Vec::new(),
+            page_hide: None,
+            new_numbers: Vec::new(),
+            column_def: None,
         };
 
         // Add the paragraph to the document
@@ -1013,11 +1144,61 @@ impl HwpWriter {
         use crate::model::page_layout::mm_to_hwp_units;
         self.page_layout.columns = columns;
         self.page_layout.column_spacing = mm_to_hwp_units(spacing_mm);
+
+        let column_spacing = self.page_layout.column_spacing;
+        self.update_section_layout(|layout| {
+            layout.columns = columns;
+            layout.column_spacing = column_spacing;
+        });
     }
 
     /// Set page background color
     pub fn set_page_background_color(&mut self, color: u32) {
         self.page_layout.background_color = Some(color);
+        self.update_section_layout(|layout| layout.background_color = Some(color));
+    }
+
+    /// Set the direction of the current section's text; vertical sections
+    /// run top to bottom with lines stacked right to left (세로쓰기)
+    pub fn set_text_direction(&mut self, direction: crate::model::TextDirection) {
+        if let Some(section) = self
+            .document
+            .body_texts
+            .get_mut(self.current_section_idx)
+            .and_then(|body_text| body_text.sections.get_mut(0))
+        {
+            // Page numbers carry on from the previous section
+            section
+                .section_def
+                .get_or_insert_with(|| crate::model::SectionDef {
+                    page_starting_number: 0,
+                    ..crate::model::SectionDef::new_default()
+                })
+                .set_text_direction(direction);
+        }
+    }
+
+    /// Apply a change to the current section's page layout, which the
+    /// renderer reads
+    fn update_section_layout(
+        &mut self,
+        change: impl FnOnce(&mut crate::model::page_layout::PageLayout),
+    ) {
+        if let Some(section) = self
+            .document
+            .body_texts
+            .get_mut(self.current_section_idx)
+            .and_then(|body_text| body_text.sections.get_mut(0))
+        {
+            let page_def = section
+                .page_def
+                .get_or_insert_with(crate::model::page_def::PageDef::new_default);
+            change(
+                page_def
+                    .layout
+                    .get_or_insert_with(crate::model::page_layout::PageLayout::default),
+            );
+        }
     }
 
     /// Set page numbering
@@ -1065,6 +1246,7 @@ impl HwpWriter {
             numberings: Vec::new(),
             bullets: Vec::new(),
             bin_data: Vec::new(),
+            distribution_record: None,
         }
     }
 
@@ -1117,6 +1299,28 @@ impl HwpWriter {
         self.document.doc_info.para_shapes.push(para_shape);
         Ok((self.document.doc_info.para_shapes.len() - 1) as u16)
     }
+
+    /// Add a named style to the document and return its ID
+    pub fn add_style(&mut self, style: Style) -> Result<u8> {
+        // Paragraphs address styles with a single byte
+        if self.document.doc_info.styles.len() > u8::MAX as usize {
+            return Err(HwpError::InvalidInput(
+                "A document can hold at most 256 styles".to_string(),
+            ));
+        }
+        self.document.doc_info.styles.push(style);
+        Ok((self.document.doc_info.styles.len() - 1) as u8)
+    }
+
+    /// Add a paragraph whose shapes, runs, links and notes are already set
+    pub fn add_formatted_paragraph(&mut self, paragraph: Paragraph) -> Result<()> {
+        if let Some(body_text) = self.document.body_texts.get_mut(self.current_section_idx) {
+            if let Some(section) = body_text.sections.get_mut(0) {
+                section.paragraphs.push(paragraph);
+            }
+        }
+        Ok(())
+    }
 }
 
 impl HwpWriter {
@@ -1497,7 +1701,12 @@ impl HwpWriter {
             table_data: None,
             picture_data: None,
             text_box_data: Some(text_box),
+            shape_data: None,
             hyperlinks: Vec::new(),
+            footnotes: Vec::new(),
+            page_hide: None,
+            new_numbers: Vec::new(),
+            column_def: None,
         };
 
         if let Some(body_text) = self.document.body_texts.get_mut(self.current_section_idx) {
@@ -1551,7 +1760,12 @@ impl HwpWriter {
             table_data: None,
             picture_data: None,
             text_box_data: Some(text_box),
+            shape_data: None,
             hyperlinks: Vec::new(),
+            footnotes: Vec::new(),
+            page_hide: None,
+            new_numbers: Vec::new(),
+            column_def: None,
         };
 
         if let Some(body_text) = self.document.body_texts.get_mut(self.current_section_idx) {
@@ -1609,7 +1823,12 @@ impl HwpWriter {
             table_data: None,
             picture_data: None,
             text_box_data: Some(text_box),
+            shape_data: None,
             hyperlinks: Vec::new(),
+            footnotes: Vec::new(),
+            page_hide: None,
+            new_numbers: Vec::new(),
+            column_def: None,
         };
 
         if let Some(body_text) = self.document.body_texts.get_mut(self.current_section_idx) {
@@ -1662,7 +1881,12 @@ impl HwpWriter {
             table_data: None,
             picture_data: None,
             text_box_data: Some(text_box),
+            shape_data: None,
             hyperlinks: Vec::new(),
+            footnotes: Vec::new(),
+            page_hide: None,
+            new_numbers: Vec::new(),
+            column_def: None,
         };
 
         if let Some(body_text) = self.document.body_texts.get_mut(self.current_section_idx) {
@@ -1721,7 +1945,39 @@ impl HwpWriter {
             table_data: None,
             picture_data: None,
             text_box_data: Some(text_box),
+            shape_data: None,
             hyperlinks: Vec::new(),
+            footnotes: Vec::new(),
+            page_hide: None,
+            new_numbers: Vec::new(),
+            column_def: None,
+        };
+
+        if let Some(body_text) = self.document.body_texts.get_mut(self.current_section_idx) {
+            if let Some(section) = body_text.sections.get_mut(0) {
+                section.paragraphs.push(paragraph);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Add a drawing object anchored to a paragraph of its own
+    pub fn add_shape(&mut self, shape: crate::model::drawing::Shape) -> Result<()> {
+        use crate::model::ctrl_header::{ControlType, CtrlHeader};
+
+        let ctrl_header = CtrlHeader {
+            ctrl_id: ControlType::Gso as u32,
+            properties: shape.properties.0,
+            instance_id: self.next_instance_id(),
+        };
+
+        let paragraph = Paragraph {
+            control_mask: 0x02, // Control header present
+            instance_id: self.next_instance_id(),
+            ctrl_header: Some(ctrl_header),
+            shape_data: Some(shape),
+            ..Default::default()
         };
 
         if let Some(body_text) = self.document.body_texts.get_mut(self.current_section_idx) {
@@ -1947,6 +2203,55 @@ impl HwpWriter {
     }
 }
 
+// Distribution Document Methods
+impl HwpWriter {
+    /// Turn the output into a distribution (배포용) document.
+    ///
+    /// Sections are written AES-encrypted under `ViewText/`, and the copy and
+    /// print restrictions are stored in the distribution record. A password,
+    /// if given, is what Hancom Office asks for before lifting them.
+    pub fn set_distribution(
+        &mut self,
+        options: crate::crypto::DistributionOptions,
+    ) -> Result<&mut Self> {
+        let encryptor = crate::crypto::DistributionEncryptor::with_random_seed(&options)?;
+        self.document.doc_info.distribution_record = Some(encryptor.record().to_vec());
+        self.document.header.set_distribute(true);
+        Ok(self)
+    }
+
+    /// Write a regular document again
+    pub fn clear_distribution(&mut self) -> &mut Self {
+        self.document.doc_info.distribution_record = None;
+        self.document.header.set_distribute(false);
+        self
+    }
+}
+
+// Password Protection Methods
+impl HwpWriter {
+    /// Encrypt the output with a password.
+    ///
+    /// DocInfo, every section and every embedded binary are encrypted with
+    /// a key derived from the password, and the preview text is left empty.
+    /// Hancom Office's password scheme is not published, so the result is
+    /// read back with [`crate::HwpReader::from_bytes_with_password`] rather
+    /// than in Hancom Office. It cannot be combined with
+    /// [`Self::set_distribution`].
+    pub fn set_password(&mut self, password: &str) -> Result<&mut Self> {
+        self.document.password = Some(crate::crypto::PasswordCipher::new(password)?);
+        self.document.header.set_encrypted(true);
+        Ok(self)
+    }
+
+    /// Write an unencrypted document again
+    pub fn clear_password(&mut self) -> &mut Self {
+        self.document.password = None;
+        self.document.header.set_encrypted(false);
+        self
+    }
+}
+
 // Helper functions for character type detection
 fn is_hangul(c: char) -> bool {
     matches!(c, '\u{AC00}'..='\u{D7AF}' | '\u{1100}'..='\u{11FF}' | '\u{3130}'..='\u{318F}' | '\u{A960}'..='\u{A97F}')
@@ -1,3 +1,4 @@
+use crate::crypto::DistributionEncryptor;
 use crate::error::Result;
 use crate::model::HwpDocument;
 use crate::utils::encoding::string_to_utf16le;
@@ -8,6 +9,44 @@ use std::io::{Cursor, Write};
 
 /// Serialize an HWP document to bytes
 pub fn serialize_document(document: &HwpDocument) -> Result<Vec<u8>> {
+    // Password-protected documents encrypt every stream holding content
+    let cipher = if document.header.is_encrypted() {
+        if document.header.is_distribute() {
+            return Err(crate::error::HwpError::InvalidInput(
+                "A document cannot be both password-protected and a distribution document"
+                    .to_string(),
+            ));
+        }
+        Some(document.password.as_ref().ok_or_else(|| {
+            crate::error::HwpError::InvalidInput(
+                "Password-protected document has no password key".to_string(),
+            )
+        })?)
+    } else {
+        None
+    };
+
+    // Distribution documents keep their sections encrypted under /ViewText
+    let encryptor = if document.header.is_distribute() {
+        let record = document
+            .doc_info
+            .distribution_record
+            .as_deref()
+            .ok_or_else(|| {
+                crate::error::HwpError::InvalidInput(
+                    "Distribution document has no distribution record".to_string(),
+                )
+            })?;
+        Some(DistributionEncryptor::from_record(record)?)
+    } else {
+        None
+    };
+    let section_storage = if encryptor.is_some() {
+        "/ViewText"
+    } else {
+        "/BodyText"
+    };
+
     // Create CFB file from scratch (no template dependency)
     let buffer = Vec::new();
     let cursor = Cursor::new(buffer);
@@ -17,7 +56,7 @@ pub fn serialize_document(document: &HwpDocument) -> Result<Vec<u8>> {
         .map_err(|e| crate::error::HwpError::Io(std::io::Error::other(e)))?;
 
     // Create required storages
-    cfb.create_storage("/BodyText")
+    cfb.create_storage(section_storage)
         .map_err(|e| crate::error::HwpError::Io(std::io::Error::other(e)))?;
     cfb.create_storage("/DocOptions")
         .map_err(|e| crate::error::HwpError::Io(std::io::Error::other(e)))?;
@@ -34,11 +73,15 @@ pub fn serialize_document(document: &HwpDocument) -> Result<Vec<u8>> {
 
     // Serialize and write DocInfo stream
     let doc_info_data = serialize_doc_info(&document.doc_info)?;
-    let final_doc_info = if document.header.is_compressed() {
+    let compressed_doc_info = if document.header.is_compressed() {
         compress_data(&doc_info_data)?
     } else {
         doc_info_data
     };
+    let final_doc_info = match cipher {
+        Some(cipher) => cipher.encrypt_stream(&compressed_doc_info)?,
+        None => compressed_doc_info,
+    };
     let mut doc_info_stream = cfb
         .create_stream("/DocInfo")
         .map_err(|e| crate::error::HwpError::Io(std::io::Error::other(e)))?;
@@ -48,13 +91,28 @@ pub fn serialize_document(document: &HwpDocument) -> Result<Vec<u8>> {
     // Serialize BodyText sections
     for (i, body_text) in document.body_texts.iter().enumerate() {
         let section_data = serialize_body_text(body_text)?;
-        let final_section = if document.header.is_compressed() {
+        let compressed_section = if document.header.is_compressed() {
             compress_data(&section_data)?
         } else {
             section_data
         };
+        let final_section = match &encryptor {
+            Some(encryptor) => {
+                // Inflating stops at the end of the deflate stream, but
+                // uncompressed records have to fill the last AES block
+                let mut plain = compressed_section;
+                if !document.header.is_compressed() {
+                    pad_to_block(&mut plain)?;
+                }
+                encryptor.encrypt_stream(&plain)?
+            }
+            None => match cipher {
+                Some(cipher) => cipher.encrypt_stream(&compressed_section)?,
+                None => compressed_section,
+            },
+        };
 
-        let section_path = format!("/BodyText/Section{i}");
+        let section_path = format!("{section_storage}/Section{i}");
         let mut section_stream = cfb
             .create_stream(&section_path)
             .map_err(|e| crate::error::HwpError::Io(std::io::Error::other(e)))?;
@@ -70,12 +128,17 @@ pub fn serialize_document(document: &HwpDocument) -> Result<Vec<u8>> {
         for bin_data in &document.doc_info.bin_data {
             let stream_name = format!("/BinData/BIN{:04X}.{}", bin_data.bin_id, bin_data.extension);
 
-            // Compress binary data if document uses compression
-            let final_data = if document.header.is_compressed() {
+            // Compress binary data as the item asks, by default with the document
+            let compressed_data = if bin_data.is_stored_compressed(document.header.is_compressed())
+            {
                 compress_data(&bin_data.data)?
             } else {
                 bin_data.data.clone()
             };
+            let final_data = match cipher {
+                Some(cipher) => cipher.encrypt_stream(&compressed_data)?,
+                None => compressed_data,
+            };
 
             let mut stream = cfb
                 .create_stream(&stream_name)
@@ -85,8 +148,13 @@ pub fn serialize_document(document: &HwpDocument) -> Result<Vec<u8>> {
         }
     }
 
-    // Create PrvText stream (preview text)
-    let prv_text = create_preview_text(document)?;
+    // Create PrvText stream (preview text), left empty when the sections are
+    // encrypted so the body text is not readable from the preview
+    let prv_text = if encryptor.is_some() || cipher.is_some() {
+        Vec::new()
+    } else {
+        create_preview_text(document)?
+    };
     let mut prv_stream = cfb
         .create_stream("/PrvText")
         .map_err(|e| crate::error::HwpError::Io(std::io::Error::other(e)))?;
@@ -179,6 +247,16 @@ fn serialize_doc_info(doc_info: &crate::parser::doc_info::DocInfo) -> Result<Vec
     // Write ID mappings (required for compatibility) - level 0
     write_record(&mut writer, 0x11, 0, &serialize_id_mappings(doc_info)?)?;
 
+    // Write binary data items - level 1
+    for bin_data in &doc_info.bin_data {
+        write_record(&mut writer, 0x12, 1, &serialize_bin_data(bin_data)?)?;
+    }
+
+    // Write DISTRIBUTE_DOC_DATA for distribution documents - level 0
+    if let Some(record) = &doc_info.distribution_record {
+        write_record(&mut writer, 0x1C, 0, record)?;
+    }
+
     // Write face names - level 1
     for face_name in &doc_info.face_names {
         write_record(&mut writer, 0x13, 1, &serialize_face_name(face_name)?)?;
@@ -702,6 +780,23 @@ fn write_record<W: Write>(writer: &mut W, tag: u16, level: u16, data: &[u8]) ->
     Ok(())
 }
 
+/// Tag for filler records; ids from 0x200 are left to other applications,
+/// so readers skip them
+const PADDING_TAG: u16 = 0x3FF;
+
+/// Append a filler record so the records end on an AES block boundary
+fn pad_to_block(data: &mut Vec<u8>) -> Result<()> {
+    let mut padding = (16 - data.len() % 16) % 16;
+    if padding == 0 {
+        return Ok(());
+    }
+    // A record needs room for its 4-byte header
+    if padding < 4 {
+        padding += 16;
+    }
+    write_record(data, PADDING_TAG, 0, &vec![0; padding - 4])
+}
+
 /// Serialize document properties (26 bytes for HWP compatibility)
 fn serialize_document_properties(
     props: &crate::model::document::DocumentProperties,
@@ -794,6 +889,37 @@ fn serialize_face_name(face_name: &crate::model::char_shape::FaceName) -> Result
     Ok(data)
 }
 
+/// Serialize binary data item; the fields present depend on its type
+fn serialize_bin_data(bin_data: &crate::model::bin_data::BinData) -> Result<Vec<u8>> {
+    use crate::model::bin_data::BinDataType;
+
+    let mut data = Vec::new();
+    let mut writer = Cursor::new(&mut data);
+    let write_name = |writer: &mut Cursor<&mut Vec<u8>>, name: &str| -> Result<()> {
+        let utf16 = string_to_utf16le(name);
+        writer.write_u16::<LittleEndian>(utf16.len() as u16 / 2)?;
+        writer.write_all(&utf16)?;
+        Ok(())
+    };
+
+    writer.write_u16::<LittleEndian>(bin_data.properties)?;
+    match bin_data.get_type() {
+        BinDataType::Link => {
+            write_name(&mut writer, &bin_data.abs_name)?;
+            write_name(&mut writer, &bin_data.rel_name)?;
+        }
+        BinDataType::Embedding => {
+            writer.write_u16::<LittleEndian>(bin_data.bin_id)?;
+            write_name(&mut writer, &bin_data.extension)?;
+        }
+        BinDataType::Storage => {
+            writer.write_u16::<LittleEndian>(bin_data.bin_id)?;
+        }
+    }
+
+    Ok(data)
+}
+
 /// Serialize character shape
 fn serialize_char_shape(char_shape: &crate::model::char_shape::CharShape) -> Result<Vec<u8>> {
     let mut data = Vec::new();
@@ -626,10 +626,15 @@ impl<'a> TableBuilder<'a> {
 
         // Create the table structure first
         let mut table = Table::new_default(self.rows as u16, self.cols as u16);
+        if self.has_header {
+            // Repeat the header row on every page the table continues on
+            table.properties |= 0x04;
+        }
 
         // Create border fills for each unique cell border style
         let mut border_fill_map = std::collections::HashMap::new();
-        let mut next_border_fill_id = 1u16;
+        // Border fill ids are 1-based and follow the fills already in the document
+        let mut next_border_fill_id = self.writer.document.doc_info.border_fills.len() as u16 + 1;
 
         // Create cell paragraphs and link them to table cells
         let mut cell_paragraphs = Vec::new();
@@ -750,7 +755,12 @@ impl<'a> TableBuilder<'a> {
                     table_data: None,
                     picture_data: None,
                     text_box_data: None,
+                    shape_data: None,
                     hyperlinks: Vec::new(),
+                    footnotes: Vec::new(),
+                    page_hide: None,
+                    new_numbers: Vec::new(),
+                    column_def: None,
                 };
                 cell_paragraphs.push(paragraph);
             }
@@ -781,7 +791,12 @@ impl<'a> TableBuilder<'a> {
             table_data: Some(table), // Store actual table data with proper cell linking
             picture_data: None,
             text_box_data: None,
+            shape_data: None,
             hyperlinks: Vec::new(),
+            footnotes: Vec::new(),
+            page_hide: None,
+            new_numbers: Vec::new(),
+            column_def: None,
         };
 
         // Add the table paragraph to the document
@@ -0,0 +1,175 @@
+use hwpers::model::{ColumnDef, ColumnKind, PageDef};
+use hwpers::render::layout::RenderedPage;
+use hwpers::render::renderer::RenderElement;
+use hwpers::render::{HwpRenderer, RenderOptions};
+
+mod common;
+
+use common::{build_with_body, lay_out, lines};
+
+/// Columns hold 5000 units of body, eight 600 unit lines
+const BODY: u32 = 5000;
+
+/// Left edge, top edge and width of the body area
+fn body() -> (i32, i32, i32) {
+    let page_def = PageDef::new_default();
+    (
+        page_def.left_margin as i32,
+        page_def.top_margin as i32,
+        (page_def.width - page_def.left_margin - page_def.right_margin) as i32,
+    )
+}
+
+/// Position of each paragraph on a page
+fn positions(page: &RenderedPage) -> Vec<(i32, i32)> {
+    page.paragraphs
+        .iter()
+        .map(|para| (para.x, para.y))
+        .collect()
+}
+
+#[test]
+fn test_text_flows_down_each_column_in_turn() {
+    let document = build_with_body(BODY, |writer| {
+        writer.set_columns(2, 10.0);
+        lines(writer, 20);
+    });
+    let layout = lay_out(&document);
+    let (left, top, width) = body();
+    let gap = document.body_texts[0].sections[0]
+        .page_def
+        .as_ref()
+        .and_then(|page_def| page_def.layout.as_ref())
+        .unwrap()
+        .column_spacing;
+    let column_width = (width - gap as i32) / 2;
+    let second = left + column_width + gap as i32;
+
+    assert_eq!(layout.page_count(), 2);
+    let first_page = positions(&layout.pages[0]);
+    assert_eq!(first_page.len(), 16);
+    assert_eq!(first_page[0], (left, top));
+    assert_eq!(first_page[7], (left, top + 7 * 600));
+    assert_eq!(first_page[8], (second, top));
+    assert!(layout.pages[0]
+        .paragraphs
+        .iter()
+        .all(|para| para.width == column_width));
+
+    // The last four lines end the section and are shared out two and two
+    assert_eq!(
+        positions(&layout.pages[1]),
+        [
+            (left, top),
+            (left, top + 600),
+            (second, top),
+            (second, top + 600)
+        ]
+    );
+    assert!(layout.pages[0].column_lines.is_empty());
+}
+
+#[test]
+fn test_column_definition_changes_mid_page() {
+    let document = build_with_body(BODY, |writer| {
+        writer.add_paragraph("title").unwrap();
+        writer
+            .add_paragraph_with_columns("first", ColumnDef::new(2, 1000).with_line(1, 1, 0xFF0000))
+            .unwrap();
+        lines(writer, 6);
+        writer
+            .add_paragraph_with_columns("after", ColumnDef::new(1, 0))
+            .unwrap();
+    });
+    let layout = lay_out(&document);
+    let (left, top, width) = body();
+    let page = &layout.pages[0];
+    let placed = positions(page);
+    let second = left + (width - 1000) / 2 + 1000;
+
+    // Seven lines balance four and three below the title
+    assert_eq!(placed[0], (left, top));
+    assert_eq!(placed[1], (left, top + 600));
+    assert_eq!(placed[4], (left, top + 4 * 600));
+    assert_eq!(placed[5], (second, top + 600));
+    assert_eq!(placed[7], (second, top + 3 * 600));
+    assert_eq!(placed[8], (left, top + 5 * 600));
+    assert_eq!(page.paragraphs[8].width, width);
+
+    let [divider] = page.column_lines.as_slice() else {
+        panic!("expected one divider, got {}", page.column_lines.len())
+    };
+    assert_eq!(divider.x, left + (width - 1000) / 2 + 500);
+    assert_eq!((divider.y, divider.height), (top + 600, 4 * 600));
+
+    let options = RenderOptions {
+        dpi: 72,
+        ..Default::default()
+    };
+    let result = HwpRenderer::new(&document, options).render();
+    assert!(result.pages[0].elements.iter().any(|element| matches!(
+        element,
+        RenderElement::Line { x1, x2, color: 0xFF0000, .. } if *x1 == divider.x / 100 && x1 == x2
+    )));
+}
+
+#[test]
+fn test_column_break_moves_to_the_next_column() {
+    let mut document = build_with_body(BODY, |writer| {
+        writer.set_columns(2, 10.0);
+        lines(writer, 3);
+    });
+    document.body_texts[0].sections[0].paragraphs[1].column_type |= 0x08;
+    let layout = lay_out(&document);
+    let (left, top, _) = body();
+    let placed = positions(&layout.pages[0]);
+    assert_eq!(placed[0], (left, top));
+    assert!(placed[1].0 > left);
+    assert_eq!(placed[1].1, top);
+    assert_eq!(placed[2].1, top + 600);
+}
+
+#[test]
+fn test_column_widths_and_direction() {
+    let column_def = ColumnDef::new(3, 600);
+    assert_eq!(column_def.count(), 3);
+    assert!(column_def.is_same_width());
+    assert_eq!(
+        column_def.columns(9000),
+        [(0, 2600), (3200, 2600), (6400, 2600)]
+    );
+
+    let mut uneven = ColumnDef::new(1, 0).with_widths(&[(2, 1), (1, 0)]);
+    assert_eq!(uneven.count(), 2);
+    assert!(!uneven.is_same_width());
+    assert_eq!(uneven.columns(8000), [(0, 4000), (6000, 2000)]);
+
+    // Right to left puts the first column on the right
+    uneven.properties |= 1 << 10;
+    assert!(uneven.is_right_to_left());
+    assert_eq!(uneven.columns(8000), [(4000, 4000), (0, 2000)]);
+
+    let parallel = ColumnDef::new(2, 0).with_kind(ColumnKind::Parallel);
+    assert_eq!(parallel.kind(), ColumnKind::Parallel);
+    assert!(parallel.line().is_none());
+}
+
+#[test]
+fn test_right_to_left_columns_fill_from_the_right() {
+    let mut column_def = ColumnDef::new(2, 1000);
+    column_def.properties |= 1 << 10;
+    let document = build_with_body(BODY, |writer| {
+        writer
+            .add_paragraph_with_columns("first", column_def)
+            .unwrap();
+        lines(writer, 9);
+    });
+    let layout = lay_out(&document);
+    let (left, top, width) = body();
+    let column_width = (width - 1000) / 2;
+    let page = &layout.pages[0];
+    // Ten lines balance five and five, the first five on the right
+    assert_eq!(page.paragraphs[0].x, left + column_width + 1000);
+    assert_eq!(page.paragraphs[5].x, left);
+    assert_eq!(page.paragraphs[5].y, top);
+}
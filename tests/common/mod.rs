@@ -0,0 +1,485 @@
+//! Helpers shared by the integration tests
+//!
+//! Each test binary pulls in the whole module, so not every helper is used
+//! by every binary.
+#![allow(dead_code)]
+
+use std::io::{Cursor, Read, Write};
+
+use hwpers::model::{PageDef, Paragraph};
+use hwpers::render::layout::LayoutResult;
+use hwpers::render::{FontProvider, LayoutEngine, VerticalMetrics};
+use hwpers::{HwpDocument, HwpWriter};
+use zip::write::SimpleFileOptions;
+
+/// A 1x1 transparent PNG
+pub const PNG_1X1: &[u8] = &[
+    0x89, 0x50, 0x4E, 0x47, 0x0D, 0x0A, 0x1A, 0x0A, 0x00, 0x00, 0x00, 0x0D, 0x49, 0x48, 0x44, 0x52,
+    0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x00, 0x01, 0x08, 0x06, 0x00, 0x00, 0x00, 0x1F, 0x15, 0xC4,
+    0x89, 0x00, 0x00, 0x00, 0x0A, 0x49, 0x44, 0x41, 0x54, 0x78, 0x9C, 0x63, 0x00, 0x01, 0x00, 0x00,
+    0x05, 0x00, 0x01, 0x0D, 0x0A, 0x2D, 0xB4, 0x00, 0x00, 0x00, 0x00, 0x49, 0x45, 0x4E, 0x44, 0xAE,
+    0x42, 0x60, 0x82,
+];
+
+/// Build a package from (name, contents) pairs
+pub fn package(parts: &[(&str, String)]) -> Vec<u8> {
+    let mut zip = zip::ZipWriter::new(Cursor::new(Vec::new()));
+    for (name, contents) in parts {
+        zip.start_file(*name, SimpleFileOptions::default()).unwrap();
+        zip.write_all(contents.as_bytes()).unwrap();
+    }
+    zip.finish().unwrap().into_inner()
+}
+
+/// Read a part from a package, checking that it is well-formed XML
+pub fn part(bytes: &[u8], name: &str) -> String {
+    let mut archive = zip::ZipArchive::new(Cursor::new(bytes)).unwrap();
+    let mut xml = String::new();
+    archive
+        .by_name(name)
+        .unwrap_or_else(|_| panic!("missing {name}"))
+        .read_to_string(&mut xml)
+        .unwrap();
+
+    let mut reader = quick_xml::Reader::from_str(&xml);
+    loop {
+        match reader.read_event() {
+            Ok(quick_xml::events::Event::Eof) => break,
+            Ok(_) => {}
+            Err(e) => panic!("{name} is not well-formed: {e}"),
+        }
+    }
+    xml
+}
+
+/// Text of every body paragraph, in order
+pub fn texts(document: &HwpDocument) -> Vec<String> {
+    document
+        .sections()
+        .flat_map(|section| &section.paragraphs)
+        .filter_map(|paragraph| paragraph.text.as_ref())
+        .map(|text| text.content.clone())
+        .collect()
+}
+
+/// The body paragraph holding exactly `text`
+pub fn paragraph<'a>(document: &'a HwpDocument, text: &str) -> &'a Paragraph {
+    document
+        .sections()
+        .flat_map(|section| &section.paragraphs)
+        .find(|paragraph| paragraph.text.as_ref().map(|t| t.content.as_str()) == Some(text))
+        .unwrap_or_else(|| panic!("no paragraph {text:?} in {:?}", texts(document)))
+}
+
+/// ASCII half an em wide and everything else a full em, so at 10pt one
+/// column is 500 units
+pub struct Grid;
+
+impl FontProvider for Grid {
+    fn advances(&self, _: &str, _: bool, _: bool, text: &str) -> Option<Vec<f32>> {
+        Some(
+            text.chars()
+                .map(|c| if c.is_ascii() { 0.5 } else { 1.0 })
+                .collect(),
+        )
+    }
+
+    fn vertical_metrics(&self, _: &str, _: bool, _: bool) -> Option<VerticalMetrics> {
+        None
+    }
+}
+
+/// A document whose lines are a fixed 600 units, so positions are easy to follow
+pub fn build(add: impl FnOnce(&mut HwpWriter)) -> HwpDocument {
+    let mut writer = HwpWriter::new();
+    add(&mut writer);
+    let mut document = writer.document().clone();
+    for shape in &mut document.doc_info.para_shapes {
+        shape.line_space_type = 1;
+        shape.line_space = 600;
+    }
+    document
+}
+
+/// As [`build`], with pages holding `body` units between the margins
+pub fn build_with_body(body: u32, add: impl FnOnce(&mut HwpWriter)) -> HwpDocument {
+    let mut document = build(add);
+    let section = &mut document.body_texts[0].sections[0];
+    let page_def = section.page_def.get_or_insert_with(PageDef::new_default);
+    page_def.height = page_def.top_margin + page_def.bottom_margin + body;
+    document
+}
+
+/// Left align every paragraph in 10pt text, `right_margin` in from the body's edge
+pub fn left_aligned(document: &mut HwpDocument, right_margin: i32) {
+    for shape in &mut document.doc_info.char_shapes {
+        shape.base_size = 1000;
+    }
+    for shape in &mut document.doc_info.para_shapes {
+        shape.properties1 = 1 << 2;
+        shape.left_margin = 0;
+        shape.right_margin = right_margin;
+    }
+}
+
+/// Add `count` one line paragraphs, "line 0" onwards
+pub fn lines(writer: &mut HwpWriter, count: usize) {
+    for line in 0..count {
+        writer.add_paragraph(&format!("line {line}")).unwrap();
+    }
+}
+
+pub fn lay_out(document: &HwpDocument) -> LayoutResult {
+    LayoutEngine::new(document).calculate_layout()
+}
+
+/// Layout measuring text with [`Grid`]
+pub fn lay_out_on_grid(document: &HwpDocument) -> LayoutResult {
+    LayoutEngine::new(document)
+        .with_font_provider(&Grid)
+        .calculate_layout()
+}
+
+/// A CID-keyed CFF OpenType font drawing each of `chars`, the way CJK fonts
+/// such as Noto Sans CJK are built
+///
+/// Glyph `n` (counting from 1) draws `chars[n - 1]` and is CID `100 * n`.
+/// Odd glyphs draw a square from a global subroutine and even ones a
+/// triangle from a local subroutine, each followed by a zigzag of their own.
+/// Every glyph is 1000 units wide on a 1000 unit em.
+pub fn cff_font(family: &str, chars: &[char]) -> Vec<u8> {
+    cff_font_with_kerning(family, chars, &[])
+}
+
+/// Like [`cff_font`], kerning each (left, right, adjustment) pair through a
+/// GPOS `kern` feature and no legacy `kern` table
+pub fn cff_font_with_kerning(family: &str, chars: &[char], pairs: &[(char, char, i16)]) -> Vec<u8> {
+    let num_glyphs = chars.len() as u16 + 1;
+    let postscript_name: String = family.chars().filter(|c| !c.is_whitespace()).collect();
+    let glyph = |c: char| chars.iter().position(|&d| d == c).unwrap() as u16 + 1;
+    let pairs: Vec<(u16, u16, i16)> = pairs
+        .iter()
+        .map(|&(left, right, value)| (glyph(left), glyph(right), value))
+        .collect();
+
+    let mut tables = vec![(b"CFF ", cff(&postscript_name, num_glyphs))];
+    if !pairs.is_empty() {
+        tables.push((b"GPOS", gpos(&pairs)));
+    }
+    tables.extend([
+        (b"cmap", cmap(chars)),
+        (b"head", head()),
+        (b"hhea", hhea(num_glyphs)),
+        (
+            b"hmtx",
+            [1000u16, 0]
+                .repeat(num_glyphs as usize)
+                .iter()
+                .flat_map(|v| v.to_be_bytes())
+                .collect(),
+        ),
+        (
+            b"maxp",
+            [&0x0000_5000u32.to_be_bytes()[..], &num_glyphs.to_be_bytes()].concat(),
+        ),
+        (b"name", name(&[(1, family), (6, &postscript_name)])),
+    ]);
+    sfnt(&tables)
+}
+
+fn cff(postscript_name: &str, num_glyphs: u16) -> Vec<u8> {
+    /// Charstring operand in its three-byte form
+    fn number(value: i16) -> Vec<u8> {
+        [&[28][..], &value.to_be_bytes()].concat()
+    }
+    /// DICT operand in its five-byte form, so offsets can be patched in place
+    fn operand(value: i32) -> Vec<u8> {
+        [&[29][..], &value.to_be_bytes()].concat()
+    }
+
+    const RMOVETO: u8 = 21;
+    const RLINETO: u8 = 5;
+    const HLINETO: u8 = 6;
+    const CALLSUBR: u8 = 10;
+    const RETURN: u8 = 11;
+    const ENDCHAR: u8 = 14;
+    const CALLGSUBR: u8 = 29;
+    // Subroutine 0 with the bias of fewer than 1240 subroutines
+    const FIRST_SUBR: u8 = 139 - 107;
+
+    let square = [
+        number(100),
+        number(0),
+        vec![RMOVETO],
+        number(800),
+        number(800),
+        number(-800),
+        vec![HLINETO, RETURN],
+    ]
+    .concat();
+    let triangle = [
+        number(100),
+        number(0),
+        vec![RMOVETO],
+        number(800),
+        number(0),
+        vec![RLINETO],
+        number(-400),
+        number(800),
+        vec![RLINETO, RETURN],
+    ]
+    .concat();
+    // A zigzag after the subroutine gives each glyph a realistic size
+    let zigzag: Vec<u8> = (0..20)
+        .flat_map(|i| {
+            [
+                number(10),
+                number(if i % 2 == 0 { 10 } else { -10 }),
+                vec![RLINETO],
+            ]
+            .concat()
+        })
+        .collect();
+    let charstrings: Vec<Vec<u8>> = (0..num_glyphs)
+        .map(|gid| match gid {
+            0 => vec![ENDCHAR],
+            gid if gid % 2 == 1 => [&[FIRST_SUBR, CALLGSUBR][..], &zigzag, &[ENDCHAR]].concat(),
+            _ => [&[FIRST_SUBR, CALLSUBR][..], &zigzag, &[ENDCHAR]].concat(),
+        })
+        .collect();
+
+    let mut charset = vec![0];
+    for gid in 1..num_glyphs {
+        charset.extend_from_slice(&(gid * 100).to_be_bytes());
+    }
+    let fd_select = [&[3, 0, 1, 0, 0, 0][..], &num_glyphs.to_be_bytes()].concat();
+    let private = [operand(6), vec![19]].concat();
+    let local_subrs = index(&[triangle]);
+
+    let top = |offsets: [i32; 4]| {
+        [
+            operand(391),
+            operand(392),
+            operand(0),
+            vec![12, 30],
+            operand(offsets[0]),
+            vec![15],
+            operand(offsets[1]),
+            vec![17],
+            operand(offsets[2]),
+            vec![12, 36],
+            operand(offsets[3]),
+            vec![12, 37],
+        ]
+        .concat()
+    };
+    let head = [
+        vec![1, 0, 4, 4],
+        index(&[postscript_name.as_bytes().to_vec()]),
+    ]
+    .concat();
+    let strings = index(&[b"Adobe".to_vec(), b"Identity".to_vec()]);
+    let global_subrs = index(&[square]);
+    let charstrings = index(&charstrings);
+
+    let start = head.len() + index(&[top([0; 4])]).len() + strings.len() + global_subrs.len();
+    let charset_at = start;
+    let fd_select_at = charset_at + charset.len();
+    let charstrings_at = fd_select_at + fd_select.len();
+    let fd_array_at = charstrings_at + charstrings.len();
+    let font_dict_len = index(&[[operand(0), operand(0), vec![18]].concat()]).len();
+    let private_at = fd_array_at + font_dict_len;
+    let fd_array = index(&[[
+        operand(private.len() as i32),
+        operand(private_at as i32),
+        vec![18],
+    ]
+    .concat()]);
+
+    [
+        head,
+        index(&[top([
+            charset_at as i32,
+            charstrings_at as i32,
+            fd_array_at as i32,
+            fd_select_at as i32,
+        ])]),
+        strings,
+        global_subrs,
+        charset,
+        fd_select,
+        charstrings,
+        fd_array,
+        private,
+        local_subrs,
+    ]
+    .concat()
+}
+
+/// GPOS table with one `kern` feature of format 1 pair adjustments
+fn gpos(pairs: &[(u16, u16, i16)]) -> Vec<u8> {
+    fn words(values: &[u16]) -> Vec<u8> {
+        values.iter().flat_map(|v| v.to_be_bytes()).collect()
+    }
+
+    let mut firsts: Vec<u16> = pairs.iter().map(|&(left, ..)| left).collect();
+    firsts.sort_unstable();
+    firsts.dedup();
+    let pair_sets: Vec<Vec<u8>> = firsts
+        .iter()
+        .map(|&first| {
+            let mut records: Vec<(u16, i16)> = pairs
+                .iter()
+                .filter(|&&(left, ..)| left == first)
+                .map(|&(_, right, value)| (right, value))
+                .collect();
+            records.sort_unstable();
+            let mut set = words(&[records.len() as u16]);
+            for (right, value) in records {
+                set.extend(words(&[right, value as u16]));
+            }
+            set
+        })
+        .collect();
+    let coverage = [words(&[1, firsts.len() as u16]), words(&firsts)].concat();
+
+    // Pair positioning: format, coverage, value formats (x advance, none), sets
+    let header_len = 10 + 2 * pair_sets.len();
+    let mut offsets = Vec::new();
+    let mut offset = header_len + coverage.len();
+    for set in &pair_sets {
+        offsets.push(offset as u16);
+        offset += set.len();
+    }
+    let subtable = [
+        words(&[1, header_len as u16, 0x0004, 0, pair_sets.len() as u16]),
+        words(&offsets),
+        coverage,
+        pair_sets.concat(),
+    ]
+    .concat();
+    let lookup = [words(&[2, 0, 1, 8]), subtable].concat();
+    let lookup_list = [words(&[1, 4]), lookup].concat();
+    let feature_list = [words(&[1]), b"kern".to_vec(), words(&[8, 0, 1, 0])].concat();
+    let script_list = [
+        words(&[1]),
+        b"DFLT".to_vec(),
+        words(&[8, 4, 0, 0, 0xFFFF, 1, 0]),
+    ]
+    .concat();
+
+    let script_at = 10;
+    let feature_at = script_at + script_list.len();
+    let lookup_at = feature_at + feature_list.len();
+    [
+        words(&[1, 0, script_at as u16, feature_at as u16, lookup_at as u16]),
+        script_list,
+        feature_list,
+        lookup_list,
+    ]
+    .concat()
+}
+
+/// CFF INDEX with four-byte offsets
+fn index(items: &[Vec<u8>]) -> Vec<u8> {
+    let mut out = (items.len() as u16).to_be_bytes().to_vec();
+    if items.is_empty() {
+        return out;
+    }
+    out.push(4);
+    let mut offset = 1u32;
+    out.extend_from_slice(&offset.to_be_bytes());
+    for item in items {
+        offset += item.len() as u32;
+        out.extend_from_slice(&offset.to_be_bytes());
+    }
+    for item in items {
+        out.extend_from_slice(item);
+    }
+    out
+}
+
+/// Format 12 cmap mapping `chars[n]` to glyph `n + 1`
+fn cmap(chars: &[char]) -> Vec<u8> {
+    let mut out = [0u16, 1, 3, 10]
+        .iter()
+        .flat_map(|v| v.to_be_bytes())
+        .collect::<Vec<_>>();
+    out.extend_from_slice(&12u32.to_be_bytes());
+    out.extend_from_slice(&[0, 12, 0, 0]);
+    out.extend_from_slice(&(16 + 12 * chars.len() as u32).to_be_bytes());
+    out.extend_from_slice(&0u32.to_be_bytes());
+    out.extend_from_slice(&(chars.len() as u32).to_be_bytes());
+    let mut groups: Vec<(u32, u32)> = (1..).zip(chars).map(|(gid, &c)| (c as u32, gid)).collect();
+    groups.sort();
+    for (c, gid) in groups {
+        out.extend_from_slice(&c.to_be_bytes());
+        out.extend_from_slice(&c.to_be_bytes());
+        out.extend_from_slice(&gid.to_be_bytes());
+    }
+    out
+}
+
+fn head() -> Vec<u8> {
+    let mut out = vec![0u8; 54];
+    out[0..4].copy_from_slice(&0x0001_0000u32.to_be_bytes());
+    out[12..16].copy_from_slice(&0x5F0F_3CF5u32.to_be_bytes());
+    out[18..20].copy_from_slice(&1000u16.to_be_bytes());
+    out[40..42].copy_from_slice(&1000i16.to_be_bytes());
+    out[42..44].copy_from_slice(&1000i16.to_be_bytes());
+    out
+}
+
+fn hhea(num_glyphs: u16) -> Vec<u8> {
+    let mut out = vec![0u8; 36];
+    out[0..4].copy_from_slice(&0x0001_0000u32.to_be_bytes());
+    out[4..6].copy_from_slice(&880i16.to_be_bytes());
+    out[6..8].copy_from_slice(&(-120i16).to_be_bytes());
+    out[10..12].copy_from_slice(&1000u16.to_be_bytes());
+    out[18..20].copy_from_slice(&1i16.to_be_bytes());
+    out[34..36].copy_from_slice(&num_glyphs.to_be_bytes());
+    out
+}
+
+/// Windows Unicode name records
+fn name(records: &[(u16, &str)]) -> Vec<u8> {
+    let strings: Vec<Vec<u8>> = records
+        .iter()
+        .map(|(_, text)| text.encode_utf16().flat_map(u16::to_be_bytes).collect())
+        .collect();
+    let count = records.len() as u16;
+    let mut out = [0, count, 6 + 12 * count]
+        .iter()
+        .flat_map(|v| v.to_be_bytes())
+        .collect::<Vec<_>>();
+    let mut offset = 0u16;
+    for ((id, _), string) in records.iter().zip(&strings) {
+        for value in [3, 1, 0x0409, *id, string.len() as u16, offset] {
+            out.extend_from_slice(&value.to_be_bytes());
+        }
+        offset += string.len() as u16;
+    }
+    out.extend(strings.concat());
+    out
+}
+
+/// OpenType file from tables, which must be given in tag order
+fn sfnt(tables: &[(&[u8; 4], Vec<u8>)]) -> Vec<u8> {
+    let mut out = b"OTTO".to_vec();
+    for value in [tables.len() as u16, 0, 0, 0] {
+        out.extend_from_slice(&value.to_be_bytes());
+    }
+    let mut offset = 12 + 16 * tables.len();
+    for (tag, data) in tables {
+        out.extend_from_slice(*tag);
+        out.extend_from_slice(&0u32.to_be_bytes());
+        out.extend_from_slice(&(offset as u32).to_be_bytes());
+        out.extend_from_slice(&(data.len() as u32).to_be_bytes());
+        offset += data.len().div_ceil(4) * 4;
+    }
+    for (_, data) in tables {
+        out.extend_from_slice(data);
+        out.resize(out.len().div_ceil(4) * 4, 0);
+    }
+    out
+}
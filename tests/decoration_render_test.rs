@@ -0,0 +1,298 @@
+use hwpers::model::border_fill::{BorderFill, GradientInfo};
+use hwpers::model::{PageBorderFill, PageDef, PageHide};
+use hwpers::render::renderer::{Fill, HatchStyle, RenderElement, RenderResult};
+use hwpers::render::{HwpRenderer, RenderOptions};
+use hwpers::HwpDocument;
+
+mod common;
+
+use common::{build_with_body, lines};
+
+/// Pages hold 5000 units of body between the margins, eight 600 unit lines
+const BODY: u32 = 5000;
+
+/// Add a border fill, returning its 1-based id
+fn add_border_fill(document: &mut HwpDocument, border_fill: BorderFill) -> u16 {
+    document.doc_info.border_fills.push(border_fill);
+    document.doc_info.border_fills.len() as u16
+}
+
+/// Red solid border with a blue background
+fn red_box() -> BorderFill {
+    let mut border_fill = BorderFill::new_with_border();
+    for line in [
+        &mut border_fill.left,
+        &mut border_fill.right,
+        &mut border_fill.top,
+        &mut border_fill.bottom,
+    ] {
+        line.color = 0xFF0000;
+    }
+    border_fill.fill_info.back_color = 0x0000FF;
+    border_fill
+}
+
+fn page_def(document: &mut HwpDocument) -> &mut PageDef {
+    document.body_texts[0].sections[0]
+        .page_def
+        .as_mut()
+        .unwrap()
+}
+
+/// One HWP unit is a hundredth of a pixel at 72 dpi
+fn render(document: &HwpDocument) -> RenderResult {
+    let options = RenderOptions {
+        dpi: 72,
+        ..Default::default()
+    };
+    HwpRenderer::new(document, options).render()
+}
+
+fn rectangles(elements: &[RenderElement], color: u32) -> Vec<(i32, i32, i32, i32)> {
+    elements
+        .iter()
+        .filter_map(|element| match element {
+            RenderElement::Rectangle {
+                x,
+                y,
+                width,
+                height,
+                fill_color: Some(fill),
+                ..
+            } if *fill == color => Some((*x, *y, *width, *height)),
+            _ => None,
+        })
+        .collect()
+}
+
+fn lines_of(elements: &[RenderElement], color: u32) -> usize {
+    elements
+        .iter()
+        .filter(|element| matches!(element, RenderElement::Line { color: c, .. } if *c == color))
+        .count()
+}
+
+#[test]
+fn test_connected_paragraph_borders_merge_into_one_box() {
+    let mut document = build_with_body(BODY, |writer| lines(writer, 3));
+    let id = add_border_fill(&mut document, red_box());
+    for shape in &mut document.doc_info.para_shapes {
+        shape.border_fill_id = id;
+        shape.border_top_space = 100;
+        shape.border_bottom_space = 200;
+        shape.properties1 |= 1 << 28;
+    }
+    let page_def = page_def(&mut document).clone();
+    let (left, top) = (page_def.left_margin as i32, page_def.top_margin as i32);
+    let width = (page_def.width - page_def.left_margin - page_def.right_margin) as i32;
+
+    let result = render(&document);
+    let elements = &result.pages[0].elements;
+    assert_eq!(
+        rectangles(elements, 0x0000FF),
+        [(
+            left / 100,
+            (top - 100) / 100,
+            width / 100,
+            (3 * 600 + 300) / 100
+        )]
+    );
+    assert_eq!(lines_of(elements, 0xFF0000), 4);
+
+    // The box goes under the text
+    let first_text = elements
+        .iter()
+        .position(|element| matches!(element, RenderElement::Text { .. }))
+        .unwrap();
+    let background = elements
+        .iter()
+        .position(|element| matches!(element, RenderElement::Rectangle { .. }))
+        .unwrap();
+    assert!(background < first_text);
+
+    // Without the connect flag each paragraph gets its own box
+    for shape in &mut document.doc_info.para_shapes {
+        shape.properties1 &= !(1 << 28);
+    }
+    let result = render(&document);
+    assert_eq!(rectangles(&result.pages[0].elements, 0x0000FF).len(), 3);
+    assert_eq!(lines_of(&result.pages[0].elements, 0xFF0000), 12);
+}
+
+#[test]
+fn test_character_shading_highlights_the_run() {
+    let mut document = build_with_body(BODY, |writer| writer.add_paragraph("shaded").unwrap());
+    for shape in &mut document.doc_info.char_shapes {
+        shape.shade_color = 0xFFFF00;
+    }
+    let result = render(&document);
+    let shading = rectangles(&result.pages[0].elements, 0xFFFF00);
+    assert_eq!(shading.len(), 1);
+    let page_def = page_def(&mut document);
+    assert_eq!(shading[0].0, page_def.left_margin as i32 / 100);
+    assert_eq!(shading[0].3, 6);
+
+    // White is the stored value for no shading
+    for shape in &mut document.doc_info.char_shapes {
+        shape.shade_color = 0xFFFFFF;
+    }
+    let result = render(&document);
+    assert!(!result.pages[0]
+        .elements
+        .iter()
+        .any(|element| matches!(element, RenderElement::Rectangle { .. })));
+}
+
+#[test]
+fn test_page_border_around_body_or_paper() {
+    let mut document = build_with_body(BODY, |writer| writer.add_paragraph("text").unwrap());
+    let id = add_border_fill(&mut document, red_box());
+    let page_def = page_def(&mut document);
+    page_def.border_fill = Some(PageBorderFill::new(id));
+    let page_def = page_def.clone();
+    let (width, height) = (page_def.width as i32, page_def.height as i32);
+
+    let layout = hwpers::render::LayoutEngine::new(&document).calculate_layout();
+    let border = layout.pages[0].border.as_ref().unwrap();
+    assert_eq!(
+        (border.x, border.y),
+        (
+            page_def.left_margin as i32 - 1417,
+            page_def.top_margin as i32 - 1417
+        )
+    );
+    assert_eq!(
+        border.x + border.width,
+        width - page_def.right_margin as i32 + 1417
+    );
+    assert_eq!(border.fill, (0, 0, width, height));
+
+    let result = render(&document);
+    let elements = &result.pages[0].elements;
+    assert_eq!(
+        rectangles(elements, 0x0000FF),
+        [(0, 0, width / 100, height / 100)]
+    );
+    assert_eq!(lines_of(elements, 0xFF0000), 4);
+
+    // Measured from the paper, with the background only inside the border
+    let placement = self::page_def(&mut document).border_fill.as_mut().unwrap();
+    placement.properties = 0x01 | (2 << 3);
+    placement.left_gap = 1000;
+    placement.top_gap = 2000;
+    let layout = hwpers::render::LayoutEngine::new(&document).calculate_layout();
+    let border = layout.pages[0].border.as_ref().unwrap();
+    assert_eq!((border.x, border.y), (1000, 2000));
+    assert_eq!((border.width, border.height), (width - 2417, height - 3417));
+    assert_eq!(border.fill, (1000, 2000, width - 2417, height - 3417));
+}
+
+#[test]
+fn test_page_hide_suppresses_border_and_fill() {
+    let mut document = build_with_body(BODY, |writer| lines(writer, 2));
+    let id = add_border_fill(&mut document, red_box());
+    page_def(&mut document).border_fill = Some(PageBorderFill::new(id));
+    document.body_texts[0].sections[0].paragraphs[0].page_hide =
+        Some(PageHide { properties: 0x08 });
+
+    let result = render(&document);
+    let elements = &result.pages[0].elements;
+    assert_eq!(lines_of(elements, 0xFF0000), 0);
+    assert_eq!(rectangles(elements, 0x0000FF).len(), 1);
+
+    document.body_texts[0].sections[0].paragraphs[0].page_hide =
+        Some(PageHide { properties: 0x10 });
+    let result = render(&document);
+    let elements = &result.pages[0].elements;
+    assert_eq!(lines_of(elements, 0xFF0000), 4);
+    assert!(rectangles(elements, 0x0000FF).is_empty());
+}
+
+#[test]
+fn test_page_background_color() {
+    let document = build_with_body(BODY, |writer| {
+        writer.set_page_background_color(0xEEEEEE);
+        writer.add_paragraph("text").unwrap();
+    });
+    let result = render(&document);
+    let page = &result.pages[0];
+    assert_eq!(
+        rectangles(&page.elements, 0xEEEEEE),
+        [(0, 0, page.width, page.height)]
+    );
+}
+
+#[test]
+fn test_gradient_and_hatch_fills_in_every_format() {
+    let mut document = build_with_body(BODY, |writer| lines(writer, 2));
+    let mut linear = red_box();
+    linear.fill_info.fill_type = 0x08;
+    linear.fill_info.gradient_info = Some(GradientInfo {
+        gradient_type: 1,
+        start_color: 0xFF0000,
+        end_color: 0x0000FF,
+        angle: 90,
+        center_x: 50,
+        center_y: 50,
+        blur_degree: 0,
+    });
+    let mut radial = linear.clone();
+    if let Some(gradient) = radial.fill_info.gradient_info.as_mut() {
+        gradient.gradient_type = 2;
+    }
+    let mut hatched = red_box();
+    hatched.fill_info.pattern_type = 4;
+    hatched.fill_info.pattern_color = 0x00FF00;
+
+    let linear = add_border_fill(&mut document, linear);
+    let radial = add_border_fill(&mut document, radial);
+    let hatched = add_border_fill(&mut document, hatched);
+    page_def(&mut document).border_fill = Some(PageBorderFill::new(linear));
+    let mut shape = document.doc_info.para_shapes[0].clone();
+    shape.border_fill_id = radial;
+    document.doc_info.para_shapes.push(shape.clone());
+    shape.border_fill_id = hatched;
+    document.doc_info.para_shapes.push(shape);
+    let count = document.doc_info.para_shapes.len() as u16;
+    let paragraphs = &mut document.body_texts[0].sections[0].paragraphs;
+    paragraphs[0].para_shape_id = count - 2;
+    paragraphs[1].para_shape_id = count - 1;
+
+    let result = render(&document);
+    let fills: Vec<&Fill> = result.pages[0]
+        .elements
+        .iter()
+        .filter_map(|element| match element {
+            RenderElement::Fill { fill, .. } => Some(fill),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(fills.len(), 3);
+    let Fill::LinearGradient { from, to, .. } = fills[0] else {
+        panic!("expected a linear gradient, got {:?}", fills[0]);
+    };
+    // A 90 degree gradient runs straight down the page
+    assert!((from.0 - to.0).abs() < 0.01 && to.1 > from.1);
+    assert!(matches!(fills[1], Fill::RadialGradient { .. }));
+    assert!(matches!(
+        fills[2],
+        Fill::Hatch {
+            color: 0x00FF00,
+            style: HatchStyle::Cross,
+            ..
+        }
+    ));
+
+    let svg = result.to_svg(0).unwrap();
+    assert!(svg.contains("<linearGradient"));
+    assert!(svg.contains("<radialGradient"));
+    assert!(svg.contains("<pattern"));
+    assert!(svg.contains("fill=\"url(#fill"));
+
+    let pdf = String::from_utf8_lossy(&result.to_pdf().unwrap()).into_owned();
+    assert!(pdf.contains("/ShadingType 2"));
+    assert!(pdf.contains("/ShadingType 3"));
+    assert!(pdf.contains("/Shading << /Sh0"));
+
+    assert!(result.to_png(0, 72).is_ok());
+}
@@ -0,0 +1,180 @@
+use hwpers::crypto::{decrypt_distribution_stream, DistributionEncryptor};
+use hwpers::parser::record::Record;
+use hwpers::reader::{CfbReader, StreamReader};
+use hwpers::writer::style::ImageFormat;
+use hwpers::{DistributionOptions, HwpError, HwpReader, HwpWriter};
+
+mod common;
+
+use common::PNG_1X1;
+
+#[test]
+fn test_distribution_document_roundtrip() {
+    let mut writer = HwpWriter::new();
+    writer.add_paragraph("배포용 공고문").unwrap();
+    writer.add_paragraph("두 번째 문단").unwrap();
+    writer
+        .set_distribution(
+            DistributionOptions::new()
+                .with_password("1234")
+                .restrict_copy(true)
+                .restrict_print(true),
+        )
+        .unwrap();
+
+    let bytes = writer.to_bytes().unwrap();
+
+    let cfb = CfbReader::new(std::io::Cursor::new(bytes.clone())).unwrap();
+    assert!(cfb.stream_exists("ViewText/Section0"));
+    assert!(!cfb.stream_exists("BodyText/Section0"));
+
+    let document = HwpReader::from_bytes(&bytes).unwrap();
+    assert!(document.is_distribution_document());
+    assert!(document.is_copy_restricted());
+    assert!(document.is_print_restricted());
+
+    let text = document.extract_text();
+    assert!(text.contains("배포용 공고문"));
+    assert!(text.contains("두 번째 문단"));
+}
+
+#[test]
+fn test_distribution_section_is_encrypted() {
+    let mut writer = HwpWriter::new();
+    writer.add_paragraph("Confidential notice").unwrap();
+    writer.set_distribution(DistributionOptions::new()).unwrap();
+
+    let bytes = writer.to_bytes().unwrap();
+    let mut cfb = CfbReader::new(std::io::Cursor::new(bytes)).unwrap();
+    let section = cfb.read_stream("ViewText/Section0").unwrap();
+
+    let needle: Vec<u8> = "Confidential"
+        .encode_utf16()
+        .flat_map(|c| c.to_le_bytes())
+        .collect();
+    assert!(!section.windows(needle.len()).any(|w| w == needle));
+}
+
+#[test]
+fn test_distribution_roundtrip_keeps_record() {
+    let mut writer = HwpWriter::new();
+    writer.add_paragraph("재배포").unwrap();
+    writer
+        .set_distribution(DistributionOptions::new().restrict_print(true))
+        .unwrap();
+
+    let document = HwpReader::from_bytes(&writer.to_bytes().unwrap()).unwrap();
+    let rewritten = HwpWriter::from_document(document).to_bytes().unwrap();
+    let document = HwpReader::from_bytes(&rewritten).unwrap();
+
+    assert!(document.is_distribution_document());
+    assert!(document.is_print_restricted());
+    assert!(!document.is_copy_restricted());
+    assert!(document.extract_text().contains("재배포"));
+}
+
+#[test]
+fn test_password_document_roundtrip() {
+    let mut writer = HwpWriter::new();
+    writer.add_paragraph("Confidential notice").unwrap();
+    writer
+        .add_image_from_bytes(PNG_1X1, ImageFormat::Png)
+        .unwrap();
+    writer.set_password("s3cret").unwrap();
+    // Uncompressed, so plaintext would show up in the streams
+    let mut document = writer.document().clone();
+    document.header.set_compressed(false);
+    let bytes = HwpWriter::from_document(document).to_bytes().unwrap();
+
+    let mut cfb = CfbReader::new(std::io::Cursor::new(bytes.clone())).unwrap();
+    let needle: Vec<u8> = "Confidential"
+        .encode_utf16()
+        .flat_map(|c| c.to_le_bytes())
+        .collect();
+    let section = cfb.read_stream("BodyText/Section0").unwrap();
+    assert!(!section.windows(needle.len()).any(|w| w == needle));
+    let image = cfb.read_stream("BinData/BIN0001.png").unwrap();
+    assert!(!image.windows(4).any(|w| w == b"IHDR"));
+    assert!(cfb.read_stream("PrvText").unwrap().is_empty());
+
+    assert!(matches!(
+        HwpReader::from_bytes(&bytes),
+        Err(HwpError::InvalidInput(_))
+    ));
+    assert!(matches!(
+        HwpReader::from_bytes_with_password(&bytes, "guess"),
+        Err(HwpError::InvalidInput(_))
+    ));
+
+    let document = HwpReader::from_bytes_with_password(&bytes, "s3cret").unwrap();
+    assert!(document.is_encrypted());
+    assert_eq!(document.extract_text().trim(), "Confidential notice");
+    let cipher = document.password.as_ref().unwrap();
+    assert_eq!(cipher.decrypt_stream(&image).unwrap(), PNG_1X1);
+
+    // Saving again keeps the password
+    let rewritten = HwpWriter::from_document(document).to_bytes().unwrap();
+    assert!(HwpReader::from_bytes(&rewritten).is_err());
+    let document = HwpReader::from_bytes_with_password(&rewritten, "s3cret").unwrap();
+    assert_eq!(document.extract_text().trim(), "Confidential notice");
+}
+
+#[test]
+fn test_password_and_distribution_are_exclusive() {
+    let mut writer = HwpWriter::new();
+    writer.add_paragraph("공고").unwrap();
+    writer.set_password("1234").unwrap();
+    writer.set_distribution(DistributionOptions::new()).unwrap();
+    assert!(matches!(writer.to_bytes(), Err(HwpError::InvalidInput(_))));
+
+    writer.clear_distribution();
+    assert!(writer.to_bytes().is_ok());
+}
+
+#[test]
+fn test_distribution_keys_are_not_repeated() {
+    let options = DistributionOptions::new();
+    let first = DistributionEncryptor::with_random_seed(&options).unwrap();
+    let second = DistributionEncryptor::with_random_seed(&options).unwrap();
+    assert_ne!(first.record(), second.record());
+    assert_ne!(
+        first.encrypt(&[0; 16]).unwrap(),
+        second.encrypt(&[0; 16]).unwrap()
+    );
+}
+
+#[test]
+fn test_uncompressed_distribution_roundtrip() {
+    let mut writer = HwpWriter::new();
+    writer.add_paragraph("압축하지 않은 배포용 문서").unwrap();
+    writer.set_distribution(DistributionOptions::new()).unwrap();
+    let mut document = writer.document().clone();
+    document.header.set_compressed(false);
+
+    let bytes = HwpWriter::from_document(document).to_bytes().unwrap();
+    let mut cfb = CfbReader::new(std::io::Cursor::new(bytes.clone())).unwrap();
+    let stream = cfb.read_stream("ViewText/Section0").unwrap();
+    let plain = decrypt_distribution_stream(&stream[260..], &stream[..260]).unwrap();
+
+    // The padded plaintext is whole records, with no stray zero bytes
+    let mut reader = StreamReader::new(plain);
+    while reader.remaining() > 0 {
+        let record = Record::parse(&mut reader).unwrap();
+        assert_ne!(record.tag_id(), 0);
+    }
+
+    let document = HwpReader::from_bytes(&bytes).unwrap();
+    assert!(!document.header.is_compressed());
+    assert_eq!(document.extract_text().trim(), "압축하지 않은 배포용 문서");
+}
+
+#[test]
+fn test_distribution_preview_text_is_empty() {
+    let mut writer = HwpWriter::new();
+    writer.add_paragraph("Confidential notice").unwrap();
+    writer.set_distribution(DistributionOptions::new()).unwrap();
+
+    let bytes = writer.to_bytes().unwrap();
+    let mut cfb = CfbReader::new(std::io::Cursor::new(bytes)).unwrap();
+    assert!(cfb.read_stream("PrvText").unwrap().is_empty());
+}
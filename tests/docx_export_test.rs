@@ -0,0 +1,216 @@
+use std::io::{Cursor, Read};
+
+use hwpers::export::docx;
+use hwpers::model::header_footer::PageNumberFormat;
+use hwpers::model::{CharShape, Hyperlink, ParaText, Paragraph};
+use hwpers::writer::style::ImageFormat;
+use hwpers::{HwpDocument, HwpWriter};
+
+mod common;
+
+use common::{part, PNG_1X1};
+
+fn export(document: &HwpDocument) -> docx::DocxExport {
+    docx::export(document).unwrap()
+}
+
+#[test]
+fn test_package_structure_and_runs() {
+    let mut writer = HwpWriter::new();
+    writer.add_heading("보고서", 1).unwrap();
+    writer.add_paragraph("본문 <중요> & 참고").unwrap();
+    let mut document = writer.document().clone();
+
+    let mut shape = CharShape::new_default();
+    shape.base_size = 1200;
+    shape.text_color = 0xFF0000;
+    shape.properties |= 0x1 | 0x2;
+    document.doc_info.char_shapes.push(shape);
+    let shape_id = (document.doc_info.char_shapes.len() - 1) as u16;
+    document.body_texts[0].sections[0]
+        .paragraphs
+        .push(Paragraph {
+            text: Some(ParaText {
+                content: "굵은 글씨".to_string(),
+            }),
+            char_shapes: Some(hwpers::model::ParaCharShape {
+                char_positions: vec![hwpers::model::CharPositionShape {
+                    position: 0,
+                    char_shape_id: shape_id,
+                }],
+            }),
+            ..Default::default()
+        });
+
+    let export = export(&document);
+    let bytes = &export.bytes;
+    let content_types = part(bytes, "[Content_Types].xml");
+    assert!(content_types.contains("/word/document.xml"));
+    part(bytes, "_rels/.rels");
+    part(bytes, "word/_rels/document.xml.rels");
+    part(bytes, "word/settings.xml");
+
+    let body = part(bytes, "word/document.xml");
+    assert!(body.contains("본문 &lt;중요&gt; &amp; 참고"), "{body}");
+    assert!(body.contains("<w:outlineLvl w:val=\"0\"/>"), "{body}");
+    assert!(body.contains("<w:pStyle w:val=\"Style0\"/>"), "{body}");
+    assert!(
+        body.contains("<w:b/><w:i/><w:color w:val=\"FF0000\"/><w:sz w:val=\"24\"/>"),
+        "{body}"
+    );
+    assert!(
+        body.contains("<w:pgSz w:w=\"11905\" w:h=\"16837\"/>"),
+        "{body}"
+    );
+
+    let styles = part(bytes, "word/styles.xml");
+    assert!(
+        styles.contains("w:styleId=\"Style0\" w:default=\"1\"><w:name w:val=\"바탕글\"/>"),
+        "{styles}"
+    );
+    assert!(export.dropped.is_empty(), "{:?}", export.dropped);
+}
+
+#[test]
+fn test_tables_with_merged_cells() {
+    let mut writer = HwpWriter::new();
+    writer
+        .add_table(3, 3)
+        .set_cell(0, 0, "가로 병합")
+        .set_cell(0, 2, "세로 병합")
+        .set_cell(1, 0, "a")
+        .set_cell(1, 1, "b")
+        .set_cell(2, 0, "c")
+        .set_cell(2, 1, "d")
+        .merge_cells(0, 0, 1, 2)
+        .merge_cells(0, 2, 3, 1)
+        .finish()
+        .unwrap();
+
+    let body = part(&export(writer.document()).bytes, "word/document.xml");
+    assert_eq!(body.matches("<w:gridCol ").count(), 3, "{body}");
+    assert_eq!(body.matches("<w:tr>").count(), 3, "{body}");
+    assert!(body.contains("<w:gridSpan w:val=\"2\"/>"), "{body}");
+    assert!(body.contains("<w:vMerge w:val=\"restart\"/>"), "{body}");
+    assert_eq!(body.matches("<w:vMerge/>").count(), 2, "{body}");
+    assert!(body.contains("가로 병합"));
+}
+
+#[test]
+fn test_table_spans_past_the_last_column() {
+    let mut writer = HwpWriter::new();
+    writer
+        .add_table(1, 1)
+        .set_cell(0, 0, "끝")
+        .finish()
+        .unwrap();
+    let mut document = writer.document().clone();
+    let table = document.body_texts[0].sections[0]
+        .paragraphs
+        .iter_mut()
+        .find_map(|paragraph| paragraph.table_data.as_mut())
+        .unwrap();
+    // A malformed cell whose column and span overflow u16
+    table.cells[0].cell_address.1 = u16::MAX;
+    table.cells[0].col_span = 2;
+
+    let body = part(&export(&document).bytes, "word/document.xml");
+    assert_eq!(body.matches("<w:gridCol ").count(), u16::MAX as usize + 2);
+}
+
+#[test]
+fn test_notes_links_and_images() {
+    let mut writer = HwpWriter::new();
+    writer
+        .add_paragraph_with_footnote("각주가 있는 문장", "출처: 통계청")
+        .unwrap();
+    writer
+        .add_paragraph_with_endnote("미주가 있는 문장", "참고 문헌")
+        .unwrap();
+    writer
+        .add_image_from_bytes(PNG_1X1, ImageFormat::Png)
+        .unwrap();
+    let mut document = writer.document().clone();
+    document.body_texts[0].sections[0]
+        .paragraphs
+        .push(Paragraph {
+            text: Some(ParaText {
+                content: "자세한 내용은 홈페이지 참고".to_string(),
+            }),
+            hyperlinks: vec![Hyperlink::new_url(
+                "홈페이지",
+                "https://example.com/?a=1&b=2",
+            )],
+            ..Default::default()
+        });
+
+    let bytes = export(&document).bytes;
+    let body = part(&bytes, "word/document.xml");
+    assert!(body.contains("<w:footnoteReference w:id=\"1\"/>"), "{body}");
+    assert!(body.contains("<w:endnoteReference w:id=\"1\"/>"), "{body}");
+    assert!(body.contains("<a:blip r:embed=\"rId5\"/>"), "{body}");
+    assert!(body.contains("<w:hyperlink r:id=\"rId6\">"), "{body}");
+    assert!(
+        body.contains("홈페이지</w:t></w:r></w:hyperlink>"),
+        "{body}"
+    );
+
+    let footnotes = part(&bytes, "word/footnotes.xml");
+    assert!(footnotes.contains("<w:footnote w:id=\"1\">"), "{footnotes}");
+    assert!(footnotes.contains("출처: 통계청"));
+    assert!(part(&bytes, "word/endnotes.xml").contains("참고 문헌"));
+
+    let relationships = part(&bytes, "word/_rels/document.xml.rels");
+    assert!(relationships.contains("Target=\"media/image1.png\""));
+    assert!(relationships
+        .contains("Target=\"https://example.com/?a=1&amp;b=2\" TargetMode=\"External\""));
+    assert!(part(&bytes, "[Content_Types].xml")
+        .contains("<Default Extension=\"png\" ContentType=\"image/png\"/>"));
+
+    let mut archive = zip::ZipArchive::new(Cursor::new(&bytes)).unwrap();
+    let mut image = Vec::new();
+    archive
+        .by_name("word/media/image1.png")
+        .unwrap()
+        .read_to_end(&mut image)
+        .unwrap();
+    assert_eq!(image, PNG_1X1);
+}
+
+#[test]
+fn test_page_setup_headers_and_dropped_elements() {
+    let mut writer = HwpWriter::new();
+    writer.set_a4_landscape().unwrap();
+    writer.add_header("기밀 문서");
+    writer.add_footer_with_page_number("쪽 ", PageNumberFormat::RomanLower);
+    writer.add_paragraph("본문").unwrap();
+    writer.add_text_box("상자 안 글").unwrap();
+
+    let export = export(writer.document());
+    let body = part(&export.bytes, "word/document.xml");
+    assert!(body.contains("w:orient=\"landscape\""), "{body}");
+    assert!(
+        body.contains("<w:headerReference w:type=\"default\""),
+        "{body}"
+    );
+    assert!(
+        body.contains("<w:footerReference w:type=\"default\""),
+        "{body}"
+    );
+    assert!(body.contains("상자 안 글"));
+
+    assert!(part(&export.bytes, "word/header1.xml").contains("기밀 문서"));
+    let footer = part(&export.bytes, "word/footer2.xml");
+    assert!(footer.contains("w:instr=\" PAGE \\* roman \""), "{footer}");
+
+    assert_eq!(export.dropped.len(), 1, "{:?}", export.dropped);
+    assert_eq!(export.dropped[0].element, "text box");
+    assert!(export.dropped[0]
+        .location
+        .starts_with("section 0, paragraph "));
+
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("doc.docx");
+    docx::write_docx(writer.document(), &path).unwrap();
+    assert_eq!(std::fs::read(&path).unwrap(), export.bytes);
+}
@@ -0,0 +1,328 @@
+use hwpers::export::docx;
+use hwpers::import::docx as docx_import;
+use hwpers::model::header_footer::{HeaderFooterType, PageNumberFormat};
+use hwpers::model::page_layout::{PageOrientation, PaperSize};
+use hwpers::model::{CharShape, ParaText, Paragraph};
+use hwpers::writer::style::ImageFormat;
+use hwpers::{HwpError, HwpWriter, ReaderLimits};
+
+mod common;
+
+use common::{package, paragraph, texts, PNG_1X1};
+
+const W: &str = r#"xmlns:w="http://schemas.openxmlformats.org/wordprocessingml/2006/main" xmlns:r="http://schemas.openxmlformats.org/officeDocument/2006/relationships""#;
+
+#[test]
+fn test_round_trip_through_docx() {
+    let mut writer = HwpWriter::new();
+    writer.set_a4_landscape().unwrap();
+    writer.add_header("기밀 문서");
+    writer.add_footer_with_page_number("쪽 ", PageNumberFormat::RomanLower);
+    writer.add_heading("보고서", 1).unwrap();
+    writer
+        .add_paragraph_with_footnote("각주가 있는 문장", "출처: 통계청")
+        .unwrap();
+    writer
+        .add_table(2, 2)
+        .set_cell(0, 0, "병합")
+        .set_cell(1, 0, "x")
+        .set_cell(1, 1, "y")
+        .merge_cells(0, 0, 1, 2)
+        .finish()
+        .unwrap();
+    writer
+        .add_image_from_bytes(PNG_1X1, ImageFormat::Png)
+        .unwrap();
+    let mut document = writer.document().clone();
+
+    let mut shape = CharShape::new_default();
+    shape.base_size = 1400;
+    shape.text_color = 0xFF0000;
+    shape.properties |= 0x1;
+    document.doc_info.char_shapes.push(shape);
+    let shape_id = (document.doc_info.char_shapes.len() - 1) as u16;
+    document.body_texts[0].sections[0]
+        .paragraphs
+        .push(Paragraph {
+            text: Some(ParaText {
+                content: "굵은 빨간 글씨".to_string(),
+            }),
+            char_shapes: Some(hwpers::model::ParaCharShape {
+                char_positions: vec![hwpers::model::CharPositionShape {
+                    position: 0,
+                    char_shape_id: shape_id,
+                }],
+            }),
+            hyperlinks: vec![hwpers::model::Hyperlink::new_url(
+                "빨간",
+                "https://example.com/",
+            )],
+            ..Default::default()
+        });
+
+    let bytes = docx::to_docx(&document).unwrap();
+    let imported = docx_import::from_bytes(&bytes).unwrap();
+
+    // Heading level survives as outline numbering in the paragraph shape
+    let heading = paragraph(&imported, "보고서");
+    let shape = imported
+        .get_para_shape(heading.para_shape_id as usize)
+        .unwrap();
+    assert_eq!((shape.properties1 >> 23) & 0x3, 1);
+    assert_eq!((shape.properties1 >> 25) & 0x7, 0);
+
+    let styled = paragraph(&imported, "굵은 빨간 글씨");
+    let positions = &styled.char_shapes.as_ref().unwrap().char_positions;
+    let shape = imported
+        .get_char_shape(positions[0].char_shape_id as usize)
+        .unwrap();
+    assert!(shape.is_bold());
+    assert_eq!(shape.base_size, 1400);
+    assert_eq!(shape.text_color, 0xFF0000);
+    assert_eq!(styled.hyperlinks.len(), 1);
+    assert_eq!(styled.hyperlinks[0].target_url, "https://example.com/");
+    assert_eq!(styled.hyperlinks[0].start_position, 3);
+
+    let noted = paragraph(&imported, "각주가 있는 문장");
+    assert_eq!(noted.footnotes.len(), 1);
+    assert_eq!(noted.footnotes[0].text(), "출처: 통계청");
+
+    let section = &imported.body_texts[0].sections[0];
+    let table = section
+        .paragraphs
+        .iter()
+        .find_map(|paragraph| paragraph.table_data.as_ref())
+        .unwrap();
+    assert_eq!((table.rows, table.cols), (2, 2));
+    assert!(table
+        .cells
+        .iter()
+        .any(|cell| cell.col_span == 2 && cell.row_span == 1));
+    assert!(texts(&imported).contains(&"병합".to_string()));
+
+    assert_eq!(imported.doc_info.bin_data.len(), 1);
+    assert_eq!(imported.doc_info.bin_data[0].data, PNG_1X1);
+
+    let page_def = section.page_def.as_ref().unwrap();
+    let layout = page_def.layout.as_ref().unwrap();
+    assert_eq!(layout.orientation, PageOrientation::Landscape);
+    assert_eq!(layout.paper_size, PaperSize::A4);
+    let items = &page_def.header_footer.items;
+    assert!(items.iter().any(|item| {
+        item.header_footer_type == HeaderFooterType::Header && item.text.contains("기밀 문서")
+    }));
+    assert!(items.iter().any(|item| {
+        item.header_footer_type == HeaderFooterType::Footer
+            && item.text == "쪽 "
+            && item.include_page_number
+            && item.page_number_format == PageNumberFormat::RomanLower as u8
+    }));
+}
+
+#[test]
+fn test_styles_lists_and_tables() {
+    let styles = format!(
+        r#"<?xml version="1.0" encoding="UTF-8"?>
+<w:styles {W}>
+  <w:docDefaults>
+    <w:rPrDefault><w:rPr><w:rFonts w:ascii="Calibri" w:eastAsia="맑은 고딕"/><w:sz w:val="22"/></w:rPr></w:rPrDefault>
+  </w:docDefaults>
+  <w:style w:type="paragraph" w:default="1" w:styleId="Normal"><w:name w:val="Normal"/></w:style>
+  <w:style w:type="paragraph" w:styleId="Heading2">
+    <w:name w:val="heading 2"/><w:basedOn w:val="Normal"/>
+    <w:pPr><w:outlineLvl w:val="1"/><w:jc w:val="center"/></w:pPr>
+    <w:rPr><w:b/><w:sz w:val="32"/></w:rPr>
+  </w:style>
+  <w:style w:type="character" w:styleId="Emphasis"><w:name w:val="Emphasis"/><w:rPr><w:i/></w:rPr></w:style>
+</w:styles>"#
+    );
+    let numbering = format!(
+        r#"<w:numbering {W}>
+  <w:abstractNum w:abstractNumId="0">
+    <w:lvl w:ilvl="0"><w:numFmt w:val="bullet"/></w:lvl>
+    <w:lvl w:ilvl="1"><w:numFmt w:val="decimal"/></w:lvl>
+  </w:abstractNum>
+  <w:num w:numId="1"><w:abstractNumId w:val="0"/></w:num>
+</w:numbering>"#
+    );
+    let item = |level: u8, text: &str| {
+        format!(
+            r#"<w:p><w:pPr><w:numPr><w:ilvl w:val="{level}"/><w:numId w:val="1"/></w:numPr></w:pPr><w:r><w:t>{text}</w:t></w:r></w:p>"#
+        )
+    };
+    let document = format!(
+        r#"<w:document {W}><w:body>
+  <w:p><w:pPr><w:pStyle w:val="Heading2"/></w:pPr><w:r><w:t>개요</w:t></w:r></w:p>
+  <w:p><w:r><w:t xml:space="preserve">일반 </w:t></w:r><w:r><w:rPr><w:rStyle w:val="Emphasis"/></w:rPr><w:t>강조</w:t></w:r><w:del><w:r><w:delText>삭제</w:delText></w:r></w:del></w:p>
+  {}{}{}
+  <w:tbl>
+    <w:tblPr><w:tblBorders><w:top w:val="single" w:sz="4" w:color="FF0000"/><w:insideH w:val="dashed" w:sz="4"/></w:tblBorders></w:tblPr>
+    <w:tblGrid><w:gridCol/><w:gridCol/></w:tblGrid>
+    <w:tr><w:tc><w:tcPr><w:vMerge w:val="restart"/></w:tcPr><w:p><w:r><w:t>세로</w:t></w:r></w:p></w:tc><w:tc><w:p><w:r><w:t>a</w:t></w:r></w:p><w:p><w:r><w:t>b</w:t></w:r></w:p></w:tc></w:tr>
+    <w:tr><w:tc><w:tcPr><w:vMerge/></w:tcPr><w:p/></w:tc><w:tc><w:p><w:r><w:t>c</w:t></w:r></w:p></w:tc></w:tr>
+  </w:tbl>
+  <w:sectPr><w:pgSz w:w="12240" w:h="15840"/><w:pgMar w:top="1440" w:right="1080" w:bottom="1440" w:left="1080" w:header="720" w:footer="720" w:gutter="0"/></w:sectPr>
+</w:body></w:document>"#,
+        item(0, "첫째"),
+        item(1, "하위"),
+        item(0, "둘째"),
+    );
+    let core = r#"<cp:coreProperties xmlns:cp="http://schemas.openxmlformats.org/package/2006/metadata/core-properties" xmlns:dc="http://purl.org/dc/elements/1.1/"><dc:title>제출 문서</dc:title><dc:creator>홍길동</dc:creator></cp:coreProperties>"#;
+    let bytes = package(&[
+        ("word/document.xml", document),
+        ("word/styles.xml", styles),
+        ("word/numbering.xml", numbering),
+        ("docProps/core.xml", core.to_string()),
+    ]);
+
+    let imported = docx_import::from_bytes(&bytes).unwrap();
+    let all = texts(&imported);
+
+    let heading = paragraph(&imported, "개요");
+    let style = imported.get_style(heading.style_id as usize).unwrap();
+    assert_eq!(style.name, "heading 2");
+    assert_eq!(style.english_name, "Heading2");
+    let shape = imported
+        .get_para_shape(heading.para_shape_id as usize)
+        .unwrap();
+    assert_eq!(shape.get_alignment(), 2);
+    assert_eq!((shape.properties1 >> 25) & 0x7, 1);
+    let run = &heading.char_shapes.as_ref().unwrap().char_positions[0];
+    let run = imported.get_char_shape(run.char_shape_id as usize).unwrap();
+    assert!(run.is_bold());
+    assert_eq!(run.base_size, 1600);
+    let latin = imported
+        .get_face_name(run.face_name_ids[1] as usize)
+        .unwrap();
+    let hangul = imported
+        .get_face_name(run.face_name_ids[0] as usize)
+        .unwrap();
+    assert_eq!(
+        (latin.font_name.as_str(), hangul.font_name.as_str()),
+        ("Calibri", "맑은 고딕")
+    );
+
+    // Deleted revisions are left out; the character style applies to its run only
+    let mixed = paragraph(&imported, "일반 강조");
+    let positions = &mixed.char_shapes.as_ref().unwrap().char_positions;
+    assert_eq!(positions.len(), 2);
+    assert_eq!(positions[1].position, 3);
+    let plain = imported
+        .get_char_shape(positions[0].char_shape_id as usize)
+        .unwrap();
+    let emphasis = imported
+        .get_char_shape(positions[1].char_shape_id as usize)
+        .unwrap();
+    assert!(!plain.is_italic() && emphasis.is_italic());
+    assert_eq!(plain.base_size, 1100);
+
+    assert!(all.contains(&"• 첫째".to_string()), "{all:?}");
+    assert!(
+        all.iter()
+            .any(|text| text.ends_with(" 하위") && text.starts_with('1')),
+        "{all:?}"
+    );
+    assert!(all.contains(&"• 둘째".to_string()), "{all:?}");
+
+    let table = imported.body_texts[0].sections[0]
+        .paragraphs
+        .iter()
+        .find_map(|paragraph| paragraph.table_data.as_ref())
+        .unwrap();
+    assert_eq!((table.rows, table.cols), (2, 2));
+    assert!(table.cells.iter().any(|cell| cell.row_span == 2));
+    assert!(all.contains(&"a\nb".to_string()), "{all:?}");
+    let top_left = table.cells.iter().find(|cell| cell.row_span == 2).unwrap();
+    let fill = imported
+        .get_border_fill(top_left.border_fill_id as usize - 1)
+        .unwrap();
+    assert_eq!(fill.top.color, 0xFF0000);
+    assert_eq!(fill.left.line_type, 0);
+
+    let layout = imported.body_texts[0].sections[0]
+        .page_def
+        .as_ref()
+        .and_then(|page_def| page_def.layout.clone())
+        .unwrap();
+    assert_eq!(layout.paper_size, PaperSize::Letter);
+    assert_eq!((layout.width, layout.height), (61200, 79200));
+    assert_eq!(layout.margins.left, 5400);
+    assert_eq!(layout.margins.top, 7200);
+
+    let properties = imported.doc_info.properties.as_ref().unwrap();
+    assert_eq!(properties.document_title.as_deref(), Some("제출 문서"));
+    assert_eq!(properties.document_author.as_deref(), Some("홍길동"));
+}
+
+#[test]
+fn test_invalid_input_and_limits() {
+    assert!(docx_import::from_bytes(b"not a zip").is_err());
+
+    let missing = package(&[("word/styles.xml", String::new())]);
+    assert!(matches!(
+        docx_import::from_bytes(&missing),
+        Err(HwpError::NotFound(_))
+    ));
+
+    let body = format!(
+        r#"<w:document {W}><w:body><w:p><w:r><w:t>{}</w:t></w:r></w:p></w:body></w:document>"#,
+        "가".repeat(1000)
+    );
+    let bytes = package(&[("word/document.xml", body)]);
+    assert!(docx_import::from_bytes(&bytes).is_ok());
+    let limits = ReaderLimits::default().with_max_zip_entry_size(1024);
+    assert!(matches!(
+        docx_import::from_bytes_with_limits(&bytes, limits),
+        Err(HwpError::LimitExceeded { .. })
+    ));
+
+    let deep = format!(
+        r#"<w:document {W}><w:body>{}{}</w:body></w:document>"#,
+        "<w:sdt><w:sdtContent>".repeat(40),
+        "</w:sdtContent></w:sdt>".repeat(40)
+    );
+    let bytes = package(&[("word/document.xml", deep)]);
+    let limits = ReaderLimits::default().with_max_nesting_depth(32);
+    assert!(matches!(
+        docx_import::from_bytes_with_limits(&bytes, limits),
+        Err(HwpError::LimitExceeded { .. })
+    ));
+}
+
+#[test]
+fn test_oversized_table_grids_are_rejected() {
+    let table = |row: &str| {
+        let body = format!(
+            r#"<w:document {W}><w:body><w:tbl>{row}</w:tbl><w:p><w:r><w:t>끝</w:t></w:r></w:p></w:body></w:document>"#
+        );
+        package(&[("word/document.xml", body)])
+    };
+    let cell = "<w:tc><w:p><w:r><w:t>x</w:t></w:r></w:p></w:tc>";
+    let span = |value: u32| {
+        format!(r#"<w:tc><w:tcPr><w:gridSpan w:val="{value}"/></w:tcPr><w:p/></w:tc>"#)
+    };
+
+    let skipped = table(&format!(
+        r#"<w:tr><w:trPr><w:gridBefore w:val="4000000000"/></w:trPr>{cell}</w:tr>"#
+    ));
+    let spans = table(&format!("<w:tr>{}</w:tr>", span(65535).repeat(3)));
+    let many = table(&format!("<w:tr>{}</w:tr>", span(1000)).repeat(20));
+    for bytes in [&skipped, &spans] {
+        assert!(matches!(
+            docx_import::from_bytes(bytes),
+            Err(HwpError::LimitExceeded { .. })
+        ));
+    }
+
+    // 20 rows of 1000 columns
+    let limits = ReaderLimits::default().with_max_table_cells(10_000);
+    assert!(matches!(
+        docx_import::from_bytes_with_limits(&many, limits),
+        Err(HwpError::LimitExceeded {
+            limit: "table cells",
+            ..
+        })
+    ));
+    let imported = docx_import::from_bytes(&many).unwrap();
+    assert!(texts(&imported).contains(&"끝".to_string()));
+}
@@ -0,0 +1,122 @@
+use std::path::Path;
+
+use hwpers::model::FaceName;
+use hwpers::render::{FontMap, FontSet, HwpRenderer, LayoutEngine, MissingFont, RenderOptions};
+use hwpers::{HwpDocument, HwpWriter};
+
+const DEJAVU_SANS: &str = "/usr/share/fonts/truetype/dejavu/DejaVuSans.ttf";
+const DEJAVU_SANS_MONO: &str = "/usr/share/fonts/truetype/dejavu/DejaVuSansMono.ttf";
+
+/// One paragraph whose every script uses `family`
+fn document(text: &str, family: &str) -> HwpDocument {
+    let mut writer = HwpWriter::new();
+    writer.add_paragraph(text).unwrap();
+    let mut document = writer.document().clone();
+    document
+        .doc_info
+        .face_names
+        .push(FaceName::new_default(family.to_string()));
+    let face = document.doc_info.face_names.len() as u16 - 1;
+    document.doc_info.char_shapes[0].face_name_ids = [face; 7];
+    document
+}
+
+#[test]
+fn test_default_map_covers_hancom_fonts() {
+    let map = FontMap::default();
+    assert_eq!(map.get("함초롬바탕")[0], "Noto Serif CJK KR");
+    assert_eq!(map.get("휴먼명조").last().unwrap(), "serif");
+    assert_eq!(map.get("함초롬돋움")[0], "Noto Sans CJK KR");
+    assert_eq!(map.get("HY헤드라인M")[0], "Noto Sans CJK KR Black");
+    assert_eq!(map.get("굴림체").last().unwrap(), "monospace");
+
+    // Case, spaces and a trailing weight are ignored
+    assert_eq!(map.get("한컴 윤고딕 240"), map.get("함초롬돋움"));
+    assert_eq!(map.get("malgungothic"), map.get("맑은 고딕"));
+    assert!(map.get("Unknown Font").is_empty());
+
+    let candidates: Vec<&str> = map.candidates("Arial").collect();
+    assert_eq!(
+        candidates,
+        ["Arial", "Liberation Sans", "DejaVu Sans", "sans-serif"]
+    );
+}
+
+#[test]
+fn test_custom_substitutes() {
+    let mut map = FontMap::empty().with("사내 글꼴", ["Noto Sans CJK KR"]);
+    assert!(map.get("함초롬바탕").is_empty());
+    assert_eq!(map.get("사내글꼴"), ["Noto Sans CJK KR"]);
+
+    map.insert("사내 글꼴", ["NanumGothic", "sans-serif"]);
+    assert_eq!(map.get("사내 글꼴"), ["NanumGothic", "sans-serif"]);
+    map.remove("사내 글꼴");
+    assert!(map.get("사내 글꼴").is_empty());
+}
+
+#[test]
+fn test_substitute_font_measures_and_draws_text() {
+    if !Path::new(DEJAVU_SANS).exists() || !Path::new(DEJAVU_SANS_MONO).exists() {
+        println!("Test font not found: {DEJAVU_SANS}");
+        return;
+    }
+    let mut fonts = FontSet::new();
+    fonts.add_file(DEJAVU_SANS).unwrap();
+    fonts.add_file(DEJAVU_SANS_MONO).unwrap();
+    fonts
+        .font_map_mut()
+        .insert("함초롬바탕", ["Missing Serif", "DejaVu Sans Mono"]);
+
+    assert!(!fonts.has_family("함초롬바탕"));
+    assert_eq!(fonts.resolve("함초롬바탕"), Some("DejaVu Sans Mono"));
+    assert_eq!(fonts.resolve("DejaVu Sans"), Some("DejaVu Sans"));
+    assert_eq!(fonts.resolve("휴먼명조"), None);
+    let mono = fonts.find("함초롬바탕", false, false).unwrap();
+    assert!(fonts.faces()[mono].has_family("DejaVu Sans Mono"));
+
+    // Measured with the fixed-width substitute rather than the first face
+    let document = document("iiWW", "함초롬바탕");
+    let engine = LayoutEngine::new(&document).with_font_provider(&fonts);
+    let advances = engine.char_advances("iW", 0);
+    assert_eq!(advances[0], advances[1]);
+
+    let options = RenderOptions {
+        fonts,
+        ..Default::default()
+    };
+    let result = HwpRenderer::new(&document, options).render();
+    assert_eq!(
+        result.missing_fonts(),
+        [MissingFont {
+            family: "함초롬바탕".to_string(),
+            substitute: Some("DejaVu Sans Mono".to_string()),
+        }]
+    );
+}
+
+#[test]
+fn test_missing_fonts_without_substitutes() {
+    let document = document("text", "휴먼명조");
+    let result = HwpRenderer::new(&document, RenderOptions::default()).render();
+    assert_eq!(
+        result.missing_fonts(),
+        [MissingFont {
+            family: "휴먼명조".to_string(),
+            substitute: None,
+        }]
+    );
+}
+
+#[test]
+fn test_svg_lists_substitutes() {
+    let document = document("text", "함초롬바탕");
+    let result = HwpRenderer::new(&document, RenderOptions::default()).render();
+    let svg = result.to_svg(0).unwrap();
+    assert!(
+        svg.contains(
+            "font-family=\"&apos;함초롬바탕&apos;, &apos;Noto Serif CJK KR&apos;, &apos;Noto Serif KR&apos;"
+        ),
+        "{svg}"
+    );
+    assert!(svg.contains("&apos;UnBatang&apos;, serif\""));
+}
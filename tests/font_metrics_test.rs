@@ -0,0 +1,189 @@
+use std::path::Path;
+
+use hwpers::model::FaceName;
+use hwpers::render::layout::Script;
+use hwpers::render::renderer::RenderElement;
+use hwpers::render::{
+    FontProvider, FontSet, HwpRenderer, LayoutEngine, RenderOptions, VerticalMetrics,
+};
+use hwpers::{HwpDocument, HwpWriter};
+
+mod common;
+
+use common::cff_font_with_kerning;
+
+const DEJAVU_SANS: &str = "/usr/share/fonts/truetype/dejavu/DejaVuSans.ttf";
+const DEJAVU_SANS_MONO: &str = "/usr/share/fonts/truetype/dejavu/DejaVuSansMono.ttf";
+
+/// Every character 0.6 em wide, ascent 0.8 em
+struct FixedPitch;
+
+impl FontProvider for FixedPitch {
+    fn advances(&self, _family: &str, _bold: bool, _italic: bool, text: &str) -> Option<Vec<f32>> {
+        Some(text.chars().map(|_| 0.6).collect())
+    }
+
+    fn vertical_metrics(&self, _: &str, _: bool, _: bool) -> Option<VerticalMetrics> {
+        Some(VerticalMetrics {
+            ascent: 0.8,
+            descent: 0.2,
+            line_gap: 0.0,
+        })
+    }
+}
+
+fn document(text: &str) -> HwpDocument {
+    let mut writer = HwpWriter::new();
+    writer.add_paragraph(text).unwrap();
+    writer.document().clone()
+}
+
+#[test]
+fn test_script_of_character() {
+    assert_eq!(Script::of('가'), Script::Hangul);
+    assert_eq!(Script::of('ㄱ'), Script::Hangul);
+    assert_eq!(Script::of('a'), Script::Latin);
+    assert_eq!(Script::of('é'), Script::Latin);
+    assert_eq!(Script::of('漢'), Script::Hanja);
+    assert_eq!(Script::of('か'), Script::Japanese);
+    assert_eq!(Script::of('→'), Script::Symbol);
+    assert_eq!(Script::of('Ж'), Script::Other);
+    assert_eq!(Script::of('\u{E000}'), Script::User);
+    assert_eq!(Script::Latin.index(), 1);
+}
+
+#[test]
+fn test_estimates_without_fonts() {
+    let document = document("가a");
+    let size = document.get_char_shape(0).unwrap().base_size;
+    let engine = LayoutEngine::new(&document);
+    assert_eq!(
+        engine.char_advances("가a 漢", 0),
+        vec![size, size / 2, size / 2, size]
+    );
+}
+
+#[test]
+fn test_custom_font_provider() {
+    let document = document("가나다 abc");
+    let size = document.get_char_shape(0).unwrap().base_size;
+    let engine = LayoutEngine::new(&document).with_font_provider(&FixedPitch);
+    let width = size * 6 / 10;
+    assert_eq!(engine.char_advances("가a", 0), vec![width, width]);
+    assert_eq!(engine.text_width("가나다", 0), width * 3);
+
+    let layout = engine.calculate_layout();
+    let line = &layout.pages[0].paragraphs[0].lines[0];
+    assert_eq!(line.baseline_y - line.y, size * 8 / 10);
+
+    // Hangul and Latin become separate runs so each can use its own face
+    let runs = &line.runs;
+    assert_eq!(runs.len(), 2);
+    assert_eq!(
+        (runs[0].text.as_str(), runs[0].script),
+        ("가나다 ", Script::Hangul)
+    );
+    assert_eq!(
+        (runs[1].text.as_str(), runs[1].script),
+        ("abc", Script::Latin)
+    );
+    assert_eq!(runs[1].x, runs[0].x + width * 4);
+}
+
+#[test]
+fn test_font_set_metrics_per_script() {
+    if !Path::new(DEJAVU_SANS).exists() || !Path::new(DEJAVU_SANS_MONO).exists() {
+        println!("Test font not found: {DEJAVU_SANS}");
+        return;
+    }
+    let mut fonts = FontSet::new();
+    fonts.add_file(DEJAVU_SANS).unwrap();
+    fonts.add_file(DEJAVU_SANS_MONO).unwrap();
+
+    let mut document = document("가 iii WWW");
+    document
+        .doc_info
+        .face_names
+        .push(FaceName::new_default("DejaVu Sans".to_string()));
+    document
+        .doc_info
+        .face_names
+        .push(FaceName::new_default("DejaVu Sans Mono".to_string()));
+    let sans = document.doc_info.face_names.len() as u16 - 2;
+    let shape = &mut document.doc_info.char_shapes[0];
+    shape.face_name_ids = [sans; 7];
+    let size = shape.base_size as f32;
+
+    // Proportional widths and kerning come from the font
+    let engine = LayoutEngine::new(&document).with_font_provider(&fonts);
+    let parsed = fonts.faces()[0].face();
+    let em = |c| {
+        parsed
+            .glyph_hor_advance(parsed.glyph_index(c).unwrap())
+            .unwrap() as f32
+            / parsed.units_per_em() as f32
+    };
+    assert_eq!(
+        engine.char_advances("i", 0),
+        vec![(em('i') * size).round() as i32]
+    );
+    assert!(engine.text_width("iii", 0) < engine.text_width("WWW", 0) / 2);
+    assert!(
+        engine.text_width("AV", 0) < engine.text_width("A", 0) + engine.text_width("V", 0),
+        "AV is kerned"
+    );
+
+    // The Latin slot can name a different face, with its own width ratio and spacing
+    let shape = &mut document.doc_info.char_shapes[0];
+    shape.face_name_ids[Script::Latin.index()] = sans + 1;
+    let engine = LayoutEngine::new(&document).with_font_provider(&fonts);
+    let mono = engine.char_advances("iW", 0);
+    assert_eq!(mono[0], mono[1]);
+    assert!(mono[0] > engine.text_width("i", 0) / 2);
+
+    let shape = &mut document.doc_info.char_shapes[0];
+    shape.ratios[Script::Latin.index()] = 50;
+    shape.char_spaces[Script::Latin.index()] = 10;
+    let engine = LayoutEngine::new(&document).with_font_provider(&fonts);
+    let condensed = engine.char_advances("i", 0)[0];
+    assert_eq!(
+        condensed,
+        (mono[0] as f32 / 2.0 + size / 10.0).round() as i32
+    );
+
+    // The renderer names the per-script face on each text element
+    let options = RenderOptions {
+        fonts,
+        ..Default::default()
+    };
+    let result = HwpRenderer::new(&document, options).render();
+    let families: Vec<&str> = result.pages[0]
+        .elements
+        .iter()
+        .filter_map(|element| match element {
+            RenderElement::Text { font_family, .. } => Some(font_family.as_str()),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(families, ["DejaVu Sans", "DejaVu Sans Mono"]);
+}
+
+#[test]
+fn test_kerning_from_gpos_pair_adjustments() {
+    let mut fonts = FontSet::new();
+    fonts
+        .add_bytes(cff_font_with_kerning(
+            "Kerned",
+            &['가', '나', '다'],
+            &[('가', '나', -200), ('나', '다', 50)],
+        ))
+        .unwrap();
+    let face = &fonts.faces()[0];
+    assert!(face.face().tables().kern.is_none());
+
+    let advances = fonts.advances("Kerned", false, false, "가나다가").unwrap();
+    assert_eq!(advances, [0.8, 1.05, 1.0, 1.0]);
+    // Pairs are ordered
+    let advances = fonts.advances("Kerned", false, false, "나가").unwrap();
+    assert_eq!(advances, [1.0, 1.0]);
+}
@@ -0,0 +1,157 @@
+use hwpers::model::PageDef;
+use hwpers::render::layout::RenderedCell;
+use hwpers::render::{PageRect, TextPosition};
+use hwpers::{HwpDocument, HwpWriter};
+
+mod common;
+
+use common::{lay_out_on_grid, left_aligned};
+
+/// Left aligned 600 unit lines that hold ten characters
+fn build(add: impl FnOnce(&mut HwpWriter)) -> HwpDocument {
+    let mut document = common::build(add);
+    let width = PageDef::new_default().effective_width() as i32;
+    left_aligned(&mut document, width - 5000);
+    document
+}
+
+/// Left edge and top of the body area
+fn body() -> (i32, i32) {
+    let page_def = PageDef::new_default();
+    (page_def.left_margin as i32, page_def.top_margin as i32)
+}
+
+fn at(paragraph: usize, char_offset: usize) -> TextPosition {
+    TextPosition::new(0, paragraph, char_offset)
+}
+
+#[test]
+fn test_runs_carry_their_source() {
+    let document = build(|writer| {
+        writer.add_paragraph("first").unwrap();
+        writer.add_paragraph("aaaa bbbb cccc").unwrap();
+    });
+    let layout = lay_out_on_grid(&document);
+    let second = &layout.pages[0].paragraphs[1];
+    let ranges: Vec<_> = second
+        .lines
+        .iter()
+        .map(|line| line.text_range.clone())
+        .collect();
+    assert_eq!(ranges, [0..10, 10..14]);
+
+    let run = &second.lines[1].runs[0];
+    assert_eq!(run.text, "cccc");
+    assert_eq!((run.paragraph_index, run.char_offset), (1, 10));
+    assert_eq!(run.advances, [500; 4]);
+    assert_eq!(run.advances.iter().sum::<i32>(), run.width);
+}
+
+#[test]
+fn test_hit_test_finds_the_nearest_character() {
+    let document = build(|writer| {
+        writer.add_paragraph("first").unwrap();
+        writer.add_paragraph("aaaa bbbb cccc").unwrap();
+    });
+    let layout = lay_out_on_grid(&document);
+    let (left, top) = body();
+
+    assert_eq!(layout.hit_test(0, left + 1100, top + 100), Some(at(0, 2)));
+    assert_eq!(layout.hit_test(0, left + 1300, top + 100), Some(at(0, 3)));
+    // Beyond the ends of a line
+    assert_eq!(layout.hit_test(0, left - 800, top + 700), Some(at(1, 0)));
+    assert_eq!(layout.hit_test(0, left + 4900, top + 700), Some(at(1, 10)));
+    assert_eq!(layout.hit_test(0, left + 9000, top + 1300), Some(at(1, 14)));
+    // Below the text
+    assert_eq!(layout.hit_test(0, left + 600, top + 20000), Some(at(1, 11)));
+    assert_eq!(layout.hit_test(5, left, top), None);
+}
+
+#[test]
+fn test_caret_and_selection() {
+    let document = build(|writer| {
+        writer.add_paragraph("first").unwrap();
+        writer.add_paragraph("aaaa bbbb cccc").unwrap();
+    });
+    let layout = lay_out_on_grid(&document);
+    let (left, top) = body();
+    let rect = |x, line: i32, width| PageRect {
+        page: 0,
+        x: left + x,
+        y: top + line * 600,
+        width,
+        height: 600,
+    };
+
+    assert_eq!(layout.caret_rect(at(0, 2)), Some(rect(1000, 0, 0)));
+    assert_eq!(layout.caret_rect(at(0, 5)), Some(rect(2500, 0, 0)));
+    // A wrapped line's end is the start of the next
+    assert_eq!(layout.caret_rect(at(1, 10)), Some(rect(0, 2, 0)));
+    assert_eq!(layout.caret_rect(at(1, 14)), Some(rect(2000, 2, 0)));
+    assert_eq!(layout.caret_rect(at(1, 15)), None);
+    assert_eq!(layout.caret_rect(at(2, 0)), None);
+
+    for range in [at(0, 3)..at(1, 12), at(1, 12)..at(0, 3)] {
+        assert_eq!(
+            layout.selection_rects(range),
+            [rect(1500, 0, 1000), rect(0, 1, 5000), rect(0, 2, 1000)]
+        );
+    }
+    assert!(layout.selection_rects(at(1, 2)..at(1, 2)).is_empty());
+}
+
+#[test]
+fn test_hyphen_is_not_a_position() {
+    let mut document = build(|writer| writer.add_paragraph("aaaa bbbbbbbb").unwrap());
+    for shape in &mut document.doc_info.para_shapes {
+        shape.properties1 |= 1 << 5;
+    }
+    let layout = lay_out_on_grid(&document);
+    let (left, top) = body();
+    let first = &layout.pages[0].paragraphs[0].lines[0];
+    let text: String = first.runs.iter().map(|run| run.text.as_str()).collect();
+    assert!(text.ends_with('-'), "{text}");
+    let end = first.text_range.end;
+
+    // Clicking the hyphen lands at the end of the line's own text
+    assert_eq!(layout.hit_test(0, left + 4900, top + 100), Some(at(0, end)));
+    let caret = layout.caret_rect(at(0, end - 1)).unwrap();
+    assert_eq!(caret.x, left + (end as i32 - 1) * 500);
+}
+
+#[test]
+fn test_hit_test_inside_table_cells() {
+    let mut document = build(|writer| {
+        writer.add_paragraph("above").unwrap();
+        writer
+            .add_simple_table(&[vec!["one", "two"], vec!["three", "four"]])
+            .unwrap();
+    });
+    // Cells are narrower than the ten character lines
+    for shape in &mut document.doc_info.para_shapes {
+        shape.right_margin = 0;
+    }
+    let layout = lay_out_on_grid(&document);
+    let page = &layout.pages[0];
+    let table = page
+        .paragraphs
+        .iter()
+        .find_map(|para| para.table.as_ref())
+        .unwrap();
+    let cell: &RenderedCell = table
+        .cells
+        .iter()
+        .find(|cell| (cell.row, cell.col) == (1, 1))
+        .unwrap();
+    let para = &cell.paragraphs[0];
+    let line = &para.lines[0];
+
+    let hit = layout
+        .hit_test(0, line.runs[0].x + 600, cell.y + cell.height - 1)
+        .unwrap();
+    assert_eq!(hit, TextPosition::new(0, para.paragraph_index, 1));
+    assert_eq!(
+        layout.caret_rect(hit).map(|rect| (rect.x, rect.y)),
+        Some((line.runs[0].x + 500, line.y))
+    );
+}
@@ -0,0 +1,246 @@
+use hwpers::export::html::{self, HtmlOptions};
+use hwpers::model::border_fill::BorderFill;
+use hwpers::model::{CharShape, FaceName, Hyperlink, ParaText, Paragraph};
+use hwpers::writer::style::{ImageFormat, ListType, ParagraphAlignment};
+use hwpers::HwpWriter;
+
+mod common;
+
+use common::PNG_1X1;
+
+#[test]
+fn test_semantic_structure() {
+    let mut writer = HwpWriter::new();
+    writer.add_heading("보고서", 1).unwrap();
+    writer.add_paragraph("본문 <중요> & 참고").unwrap();
+    writer.start_list(ListType::Bullet).unwrap();
+    writer.add_list_item("첫째").unwrap();
+    writer.add_list_item("둘째").unwrap();
+    writer.end_list().unwrap();
+    writer.start_list(ListType::Numbered).unwrap();
+    writer.add_list_item("하나").unwrap();
+    writer.end_list().unwrap();
+
+    let html = html::to_html(writer.document());
+    assert!(html.starts_with("<!DOCTYPE html>"));
+    assert!(html.contains("<style>"));
+    assert!(html.contains("보고서</span></h1>"), "{html}");
+    assert!(html.contains("본문 &lt;중요&gt; &amp; 참고"));
+    assert!(html.contains("<ul>\n<li>"), "{html}");
+    assert!(html.contains("첫째</span></li>"), "{html}");
+    assert!(html.contains("<ol>\n<li>"), "{html}");
+    assert!(!html.contains("• 첫째"));
+}
+
+#[test]
+fn test_css_from_shapes() {
+    let mut writer = HwpWriter::new();
+    writer
+        .add_aligned_paragraph("가운데", ParagraphAlignment::Center)
+        .unwrap();
+    let mut document = writer.document().clone();
+
+    document
+        .doc_info
+        .face_names
+        .push(FaceName::new_default("함초롬바탕".to_string()));
+    let face_id = (document.doc_info.face_names.len() - 1) as u16;
+    let mut shape = CharShape::new_default();
+    shape.face_name_ids = [face_id; 7];
+    shape.base_size = 1200;
+    shape.text_color = 0xFF0000;
+    shape.properties |= 0x1 | 0x2;
+    document.doc_info.char_shapes.push(shape);
+    let shape_id = document.doc_info.char_shapes.len() - 1;
+
+    let mut fill = BorderFill::new_default();
+    fill.left.line_type = 2;
+    fill.fill_info.fill_type = 1;
+    fill.fill_info.back_color = 0x00FF00;
+    document.doc_info.border_fills.push(fill);
+    let fill_id = document.doc_info.border_fills.len();
+
+    let export = html::export(&document, &HtmlOptions::default());
+    let css = &export.css;
+    let rule = css
+        .lines()
+        .find(|line| line.starts_with(&format!(".cs{shape_id} ")))
+        .unwrap();
+    assert!(rule.contains("font-family: \"함초롬바탕\""), "{rule}");
+    assert!(rule.contains("font-size: 12pt"), "{rule}");
+    assert!(rule.contains("color: #FF0000"), "{rule}");
+    assert!(rule.contains("font-weight: bold"), "{rule}");
+    assert!(rule.contains("font-style: italic"), "{rule}");
+    assert!(css.contains("text-align: center"), "{css}");
+
+    let rule = css
+        .lines()
+        .find(|line| line.starts_with(&format!(".bf{fill_id} ")))
+        .unwrap();
+    assert!(rule.contains("border-left: 1px dashed"), "{rule}");
+    assert!(rule.contains("background-color: #00FF00"), "{rule}");
+
+    let mut at_least = document.doc_info.para_shapes[0].clone();
+    at_least.line_space_type = 2;
+    at_least.line_space = 2000;
+    document.doc_info.para_shapes.push(at_least);
+    let para_id = document.doc_info.para_shapes.len() - 1;
+    let css = html::export(&document, &HtmlOptions::default()).css;
+    let rule = css
+        .lines()
+        .find(|line| line.starts_with(&format!(".ps{para_id} ")))
+        .unwrap();
+    assert!(rule.contains("line-height: max(1em, 20pt)"), "{rule}");
+
+    assert!(export
+        .html
+        .contains("<link rel=\"stylesheet\" href=\"style.css\">"));
+    assert!(!export.html.contains("<style>"));
+}
+
+#[test]
+fn test_tables_links_and_footnotes() {
+    let mut writer = HwpWriter::new();
+    writer
+        .add_table(2, 2)
+        .set_cell(0, 0, "병합")
+        .set_cell(1, 0, "x")
+        .set_cell(1, 1, "y")
+        .merge_cells(0, 0, 1, 2)
+        .finish()
+        .unwrap();
+    writer
+        .add_paragraph_with_footnote("각주가 있는 문장", "출처: 통계청")
+        .unwrap();
+    let mut document = writer.document().clone();
+    document.body_texts[0].sections[0]
+        .paragraphs
+        .push(Paragraph {
+            text: Some(ParaText {
+                content: "자세한 내용은 홈페이지 참고".to_string(),
+            }),
+            hyperlinks: vec![Hyperlink::new_url(
+                "홈페이지",
+                "https://example.com/?a=1&b=2",
+            )],
+            ..Default::default()
+        });
+
+    let html = html::to_html(&document);
+    assert!(html.contains("<td colspan=\"2\""), "{html}");
+    assert!(html.contains(">병합</p></td>"), "{html}");
+    assert!(
+        html.contains("<a href=\"https://example.com/?a=1&amp;b=2\">홈페이지</a>"),
+        "{html}"
+    );
+    assert!(
+        html.contains("<sup><a href=\"#fn-0-1\" id=\"fn-0-1-ref\">1</a></sup>"),
+        "{html}"
+    );
+    assert!(
+        html.contains("<li id=\"fn-0-1\" value=\"1\">출처: 통계청"),
+        "{html}"
+    );
+    assert!(html.contains("href=\"#fn-0-1-ref\""));
+}
+
+#[test]
+fn test_footnote_ids_are_unique_across_sections() {
+    let mut writer = HwpWriter::new();
+    writer
+        .add_paragraph_with_footnote("첫 구역", "첫 각주")
+        .unwrap();
+    let mut document = writer.document().clone();
+    let mut second = document.body_texts[0].sections[0].clone();
+    second.paragraphs[0].text = Some(ParaText {
+        content: "둘째 구역".to_string(),
+    });
+    document.body_texts[0].sections.push(second);
+
+    let html = html::to_html(&document);
+    assert!(html.contains("<li id=\"fn-0-1\" value=\"1\">"), "{html}");
+    assert!(html.contains("<li id=\"fn-1-1\" value=\"1\">"), "{html}");
+    assert!(html.contains("href=\"#fn-1-1-ref\""), "{html}");
+}
+
+#[test]
+fn test_images_embedded_or_written() {
+    let mut writer = HwpWriter::new();
+    writer
+        .add_image_from_bytes(PNG_1X1, ImageFormat::Png)
+        .unwrap();
+
+    let single = html::export(writer.document(), &HtmlOptions::single_file());
+    assert!(single
+        .html
+        .contains("<img src=\"data:image/png;base64,iVBORw0KGgo"));
+    assert!(single.images.is_empty());
+
+    let export = html::export(writer.document(), &HtmlOptions::default());
+    assert!(export.html.contains("<img src=\"images/image1.png\""));
+    assert_eq!(export.images.len(), 1);
+
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("doc.html");
+    export.write_to(&path).unwrap();
+    assert!(dir.path().join("style.css").exists());
+    assert_eq!(
+        std::fs::read(dir.path().join("images/image1.png")).unwrap(),
+        PNG_1X1
+    );
+}
+
+#[test]
+fn test_script_links_are_plain_text() {
+    let mut writer = HwpWriter::new();
+    writer.add_paragraph("앞").unwrap();
+    let mut document = writer.document().clone();
+    let targets = [
+        "javascript:alert(1)",
+        " VBScript:msgbox",
+        "data:text/html,<script>alert(1)</script>",
+        "mailto:admin@example.com",
+        "#top",
+    ];
+    for (index, target) in targets.iter().enumerate() {
+        let label = format!("링크{index}");
+        document.body_texts[0].sections[0]
+            .paragraphs
+            .push(Paragraph {
+                text: Some(ParaText {
+                    content: label.clone(),
+                }),
+                hyperlinks: vec![Hyperlink::new_url(&label, target)],
+                ..Default::default()
+            });
+    }
+
+    let html = html::to_html(&document);
+    assert!(!html.to_lowercase().contains("script:"), "{html}");
+    assert!(!html.contains("href=\"data:"), "{html}");
+    assert!(html.contains(">링크0<"), "{html}");
+    assert!(html.contains("<a href=\"mailto:admin@example.com\">링크3</a>"));
+    assert!(html.contains("<a href=\"#top\">링크4</a>"));
+}
+
+#[test]
+fn test_face_names_cannot_leave_the_stylesheet() {
+    let mut writer = HwpWriter::new();
+    writer.add_paragraph("본문").unwrap();
+    let mut document = writer.document().clone();
+    document.doc_info.face_names.push(FaceName::new_default(
+        "X\"}</style><script>alert(1)</script>\n{;\\".to_string(),
+    ));
+    let face_id = (document.doc_info.face_names.len() - 1) as u16;
+    let mut shape = CharShape::new_default();
+    shape.face_name_ids = [face_id; 7];
+    document.doc_info.char_shapes.push(shape);
+
+    let html = html::to_html(&document);
+    assert!(!html.contains("</style><script>"), "{html}");
+    assert!(!html.contains("<script>"), "{html}");
+    assert!(
+        html.contains("font-family: \"X/stylescriptalert(1)/script\""),
+        "{html}"
+    );
+}
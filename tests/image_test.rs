@@ -1,6 +1,7 @@
 use hwpers::{
+    reader::CfbReader,
     writer::style::{ImageAlign, ImageFormat, ImageOptions},
-    HwpWriter,
+    HwpReader, HwpWriter,
 };
 use std::fs::File;
 use std::io::Write;
@@ -244,3 +245,33 @@ fn test_mixed_content_with_images() {
         .content
         .contains("Text after the image"));
 }
+
+#[test]
+fn test_bin_data_follows_its_own_compression() {
+    let png = create_test_png();
+    // (document compressed, item properties, stream deflated)
+    let cases = [
+        (true, 0x01, true),
+        (true, 0x21, false),
+        (false, 0x01, false),
+        (false, 0x11, true),
+    ];
+    for (compressed, properties, deflated) in cases {
+        let mut writer = HwpWriter::new();
+        writer.add_image_from_bytes(&png, ImageFormat::Png).unwrap();
+        let mut document = writer.document().clone();
+        document.header.set_compressed(compressed);
+        document.doc_info.bin_data[0].properties = properties;
+        let bytes = HwpWriter::from_document(document).to_bytes().unwrap();
+
+        let mut cfb = CfbReader::new(std::io::Cursor::new(bytes.clone())).unwrap();
+        let stream = cfb.read_stream("BinData/BIN0001.png").unwrap();
+        assert_eq!(stream != png, deflated, "{compressed} {properties:#x}");
+
+        let document = HwpReader::from_bytes(&bytes).unwrap();
+        let bin_data = &document.doc_info.bin_data[0];
+        assert_eq!(bin_data.properties, properties);
+        assert_eq!((bin_data.bin_id, bin_data.extension.as_str()), (1, "png"));
+        assert_eq!(bin_data.data, png, "{compressed} {properties:#x}");
+    }
+}
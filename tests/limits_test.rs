@@ -0,0 +1,125 @@
+use std::io::Write;
+
+use hwpers::utils::compression::decompress_stream_with_limits;
+use hwpers::{HwpError, HwpReader, HwpWriter, HwpxReader, HwpxWriter, ReaderLimits};
+
+fn document_bytes(paragraphs: usize, compressed: bool) -> Vec<u8> {
+    let mut writer = HwpWriter::new();
+    for i in 0..paragraphs {
+        writer.add_paragraph(&format!("문단 {i}")).unwrap();
+    }
+    let mut document = HwpReader::from_bytes(&writer.to_bytes().unwrap()).unwrap();
+    document.header.set_compressed(compressed);
+    hwpers::writer::serializer::serialize_document(&document).unwrap()
+}
+
+fn assert_limit(result: hwpers::Result<hwpers::HwpDocument>, expected: &str) {
+    match result {
+        Err(HwpError::LimitExceeded { limit, value, max }) => {
+            assert_eq!(limit, expected);
+            assert!(value > max);
+        }
+        other => panic!("expected {expected} limit error, got {other:?}"),
+    }
+}
+
+#[test]
+fn test_default_limits_accept_regular_documents() {
+    let bytes = document_bytes(20, true);
+    let document = HwpReader::from_bytes_with_limits(&bytes, ReaderLimits::default()).unwrap();
+    assert!(document.extract_text().contains("문단 19"));
+}
+
+#[test]
+fn test_decompression_bomb_is_rejected() {
+    let mut encoder = flate2::write::DeflateEncoder::new(Vec::new(), flate2::Compression::best());
+    encoder.write_all(&vec![0u8; 4 * 1024 * 1024]).unwrap();
+    let bomb = encoder.finish().unwrap();
+
+    let limits = ReaderLimits::new().with_max_stream_size(1024 * 1024);
+    match decompress_stream_with_limits(&bomb, &limits) {
+        Err(HwpError::LimitExceeded { limit, .. }) => assert_eq!(limit, "stream size"),
+        other => panic!("expected limit error, got {:?}", other.map(|d| d.len())),
+    }
+
+    let unlimited = decompress_stream_with_limits(&bomb, &ReaderLimits::unlimited()).unwrap();
+    assert_eq!(unlimited.len(), 4 * 1024 * 1024);
+}
+
+#[test]
+fn test_stream_size_limit_applies_to_document_streams() {
+    let bytes = document_bytes(50, true);
+    let limits = ReaderLimits::new().with_max_stream_size(256);
+    assert_limit(
+        HwpReader::from_bytes_with_limits(&bytes, limits),
+        "stream size",
+    );
+
+    let bytes = document_bytes(50, false);
+    assert_limit(
+        HwpReader::from_bytes_with_limits(&bytes, limits),
+        "stream size",
+    );
+}
+
+#[test]
+fn test_record_and_paragraph_limits() {
+    let bytes = document_bytes(30, false);
+
+    let limits = ReaderLimits::new().with_max_paragraphs(10);
+    assert_limit(
+        HwpReader::from_bytes_with_limits(&bytes, limits),
+        "paragraph count",
+    );
+
+    let limits = ReaderLimits::new().with_max_records(20);
+    assert_limit(
+        HwpReader::from_bytes_with_limits(&bytes, limits),
+        "record count",
+    );
+
+    let limits = ReaderLimits::new().with_max_nesting_depth(0);
+    assert_limit(
+        HwpReader::from_bytes_with_limits(&bytes, limits),
+        "nesting depth",
+    );
+}
+
+#[test]
+fn test_hwpx_entry_and_paragraph_limits() {
+    let mut writer = HwpxWriter::new();
+    for i in 0..30 {
+        writer.add_paragraph(&format!("Paragraph {i}")).unwrap();
+    }
+    let bytes = writer.to_bytes().unwrap();
+
+    assert!(HwpxReader::from_bytes_with_limits(&bytes, ReaderLimits::default()).is_ok());
+
+    let limits = ReaderLimits::new().with_max_zip_entry_size(64);
+    assert_limit(
+        HwpxReader::from_bytes_with_limits(&bytes, limits),
+        "ZIP entry size",
+    );
+
+    let limits = ReaderLimits::new().with_max_paragraphs(5);
+    assert_limit(
+        HwpxReader::from_bytes_with_limits(&bytes, limits),
+        "paragraph count",
+    );
+}
+
+#[test]
+fn test_paragraph_limit_is_inclusive() {
+    let bytes = document_bytes(12, false);
+    let count = HwpReader::from_bytes(&bytes).unwrap().body_texts[0].sections[0]
+        .paragraphs
+        .len();
+
+    let limits = ReaderLimits::new().with_max_paragraphs(count);
+    assert!(HwpReader::from_bytes_with_limits(&bytes, limits).is_ok());
+    let limits = ReaderLimits::new().with_max_paragraphs(count - 1);
+    assert_limit(
+        HwpReader::from_bytes_with_limits(&bytes, limits),
+        "paragraph count",
+    );
+}
@@ -0,0 +1,152 @@
+use hwpers::model::PageDef;
+use hwpers::render::layout::{RenderedParagraph, TextRun};
+use hwpers::render::LayoutEngine;
+use hwpers::{HwpDocument, HwpWriter};
+
+mod common;
+
+use common::Grid;
+
+const JUSTIFY: u32 = 0;
+const LEFT: u32 = 1;
+const RIGHT: u32 = 2;
+const CENTER: u32 = 3;
+const DISTRIBUTE: u32 = 4;
+
+const HYPHENATE: u32 = 1 << 5;
+const LATIN_BY_CHAR: u32 = 2 << 5;
+const KOREAN_BY_CHAR: u32 = 1 << 7;
+
+/// A one paragraph document whose lines hold `columns` characters
+fn build(text: &str, alignment: u32, flags: u32, columns: i32) -> HwpDocument {
+    let mut writer = HwpWriter::new();
+    writer.add_paragraph(text).unwrap();
+    let mut document = writer.document().clone();
+    for shape in &mut document.doc_info.char_shapes {
+        shape.base_size = 1000;
+    }
+    let width = PageDef::new_default().effective_width() as i32;
+    for shape in &mut document.doc_info.para_shapes {
+        shape.properties1 = (alignment << 2) | flags;
+        shape.left_margin = 0;
+        shape.right_margin = width - columns * 500;
+    }
+    document
+}
+
+fn paragraph(document: &HwpDocument) -> RenderedParagraph {
+    let layout = LayoutEngine::new(document)
+        .with_font_provider(&Grid)
+        .calculate_layout();
+    layout.pages[0].paragraphs[0].clone()
+}
+
+/// Text of each line
+fn lines(document: &HwpDocument) -> Vec<String> {
+    paragraph(document)
+        .lines
+        .iter()
+        .map(|line| line.runs.iter().map(|run| run.text.as_str()).collect())
+        .collect()
+}
+
+/// Position of each run relative to the paragraph, with its text
+fn placed(paragraph: &RenderedParagraph, line: usize) -> Vec<(i32, &str)> {
+    paragraph.lines[line]
+        .runs
+        .iter()
+        .map(|run: &TextRun| (run.x - paragraph.x, run.text.as_str()))
+        .collect()
+}
+
+#[test]
+fn test_wraps_latin_words_at_spaces() {
+    let document = build("aaaa bbbb cccc", LEFT, 0, 10);
+    assert_eq!(lines(&document), ["aaaa bbbb ", "cccc"]);
+
+    // A word longer than the line is broken where it overflows
+    let document = build("abcdefghijkl", LEFT, 0, 10);
+    assert_eq!(lines(&document), ["abcdefghij", "kl"]);
+
+    let document = build("aaaa bbbbbbbb", LEFT, LATIN_BY_CHAR, 10);
+    assert_eq!(lines(&document), ["aaaa bbbbb", "bbb"]);
+
+    // Forced line breaks always end a line
+    let document = build("ab\ncd", LEFT, 0, 10);
+    assert_eq!(lines(&document), ["ab", "cd"]);
+}
+
+#[test]
+fn test_hyphenates_latin_words() {
+    let document = build("aaaa bbbbbbbb", LEFT, HYPHENATE, 10);
+    assert_eq!(lines(&document), ["aaaa bbbb-", "bbbb"]);
+
+    // Two letters must stay on each side of the hyphen
+    let document = build("aaaaaaa bbb", LEFT, HYPHENATE, 10);
+    assert_eq!(lines(&document), ["aaaaaaa ", "bbb"]);
+}
+
+#[test]
+fn test_korean_break_unit() {
+    let text = "가나다라 마바사아자";
+    // Hangul is two columns wide
+    let by_word = build(text, LEFT, 0, 14);
+    assert_eq!(lines(&by_word), ["가나다라 ", "마바사아자"]);
+
+    let by_char = build(text, LEFT, KOREAN_BY_CHAR, 14);
+    assert_eq!(lines(&by_char), ["가나다라 마바", "사아자"]);
+
+    // Hanja breaks between any two characters
+    let hanja = build("大韓民國憲法第一條", LEFT, 0, 8);
+    assert_eq!(lines(&hanja), ["大韓民國", "憲法第一", "條"]);
+}
+
+#[test]
+fn test_forbidden_line_start_and_end_characters() {
+    // The full stop may not start a line, so 아 moves down with it
+    let document = build("가나다라마바사아.", LEFT, KOREAN_BY_CHAR, 16);
+    assert_eq!(lines(&document), ["가나다라마바사", "아."]);
+
+    // An opening bracket may not end a line
+    let document = build("가나다라마바사「아」", LEFT, KOREAN_BY_CHAR, 16);
+    assert_eq!(lines(&document), ["가나다라마바사", "「아」"]);
+}
+
+#[test]
+fn test_alignment() {
+    let left = paragraph(&build("ab", LEFT, 0, 10));
+    assert_eq!(placed(&left, 0), [(0, "ab")]);
+
+    let right = paragraph(&build("ab", RIGHT, 0, 10));
+    assert_eq!(placed(&right, 0), [(4000, "ab")]);
+
+    let center = paragraph(&build("ab", CENTER, 0, 10));
+    assert_eq!(placed(&center, 0), [(2000, "ab")]);
+
+    // Justified lines widen word spaces; the last line stays at the start
+    let justify = paragraph(&build("aa bb cc dd", JUSTIFY, 0, 10));
+    assert_eq!(
+        placed(&justify, 0),
+        [(0, "aa "), (2000, "bb "), (4000, "cc ")]
+    );
+    assert_eq!(placed(&justify, 1), [(0, "dd")]);
+
+    // Distributed lines spread every character, the last line included
+    let distribute = paragraph(&build("abc", DISTRIBUTE, 0, 10));
+    assert_eq!(placed(&distribute, 0), [(0, "a"), (2250, "b"), (4500, "c")]);
+}
+
+#[test]
+fn test_indent() {
+    let mut document = build("aaaa bbbb cccc", LEFT, 0, 10);
+    document.doc_info.para_shapes[0].indent = 1000;
+    let indented = paragraph(&document);
+    assert_eq!(placed(&indented, 0), [(1000, "aaaa ")]);
+    assert_eq!(placed(&indented, 1), [(0, "bbbb cccc")]);
+
+    // A negative indent hangs the first line out of the later ones
+    document.doc_info.para_shapes[0].indent = -1000;
+    let hanging = paragraph(&document);
+    assert_eq!(placed(&hanging, 0), [(0, "aaaa bbbb ")]);
+    assert_eq!(placed(&hanging, 1), [(1000, "cccc")]);
+}
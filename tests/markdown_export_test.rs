@@ -0,0 +1,192 @@
+use hwpers::export::markdown::{self, MarkdownOptions};
+use hwpers::model::{CharPositionShape, CharShape, Hyperlink, ParaCharShape, ParaText, Paragraph};
+use hwpers::writer::style::{ImageFormat, ListType};
+use hwpers::{HwpDocument, HwpWriter};
+
+mod common;
+
+use common::PNG_1X1;
+
+/// Append a paragraph whose middle word uses the given character shape
+fn push_formatted(
+    document: &mut HwpDocument,
+    before: &str,
+    word: &str,
+    after: &str,
+    shape: CharShape,
+) {
+    let shape_id = document.doc_info.char_shapes.len() as u16;
+    document.doc_info.char_shapes.push(shape);
+
+    let start = before.chars().count() as u32;
+    let end = start + word.chars().count() as u32;
+    let paragraph = Paragraph {
+        text: Some(ParaText {
+            content: format!("{before}{word}{after}"),
+        }),
+        char_shapes: Some(ParaCharShape {
+            char_positions: vec![
+                CharPositionShape {
+                    position: 0,
+                    char_shape_id: 0,
+                },
+                CharPositionShape {
+                    position: start,
+                    char_shape_id: shape_id,
+                },
+                CharPositionShape {
+                    position: end,
+                    char_shape_id: 0,
+                },
+            ],
+        }),
+        ..Default::default()
+    };
+    document.body_texts[0].sections[0]
+        .paragraphs
+        .push(paragraph);
+}
+
+#[test]
+fn test_headings_lists_and_paragraphs() {
+    let mut writer = HwpWriter::new();
+    writer.add_heading("보고서 제목", 1).unwrap();
+    writer.add_heading("개요", 2).unwrap();
+    writer.add_paragraph("본문 문단입니다.").unwrap();
+    writer.start_list(ListType::Bullet).unwrap();
+    writer.add_list_item("첫째").unwrap();
+    writer.add_list_item("둘째").unwrap();
+    writer.end_list().unwrap();
+    writer.start_list(ListType::Numbered).unwrap();
+    writer.add_list_item("하나").unwrap();
+    writer.add_list_item("둘").unwrap();
+    writer.end_list().unwrap();
+    writer.add_paragraph("# not a heading").unwrap();
+
+    let md = markdown::to_markdown(writer.document());
+
+    assert!(md.contains("# 보고서 제목\n"));
+    assert!(md.contains("## 개요\n"));
+    assert!(md.contains("본문 문단입니다."));
+    assert!(md.contains("- 첫째\n- 둘째\n"));
+    assert!(md.contains("1. 하나\n1. 둘\n"));
+    assert!(md.contains("\\# not a heading"));
+
+    let plain = markdown::export(
+        writer.document(),
+        &MarkdownOptions {
+            detect_headings: false,
+            ..Default::default()
+        },
+    );
+    assert!(!plain.markdown.contains("# 보고서"));
+}
+
+#[test]
+fn test_inline_formatting_and_links() {
+    let writer = HwpWriter::new();
+    let mut document = writer.document().clone();
+
+    let mut bold = CharShape::new_default();
+    bold.properties |= 0x1;
+    push_formatted(&mut document, "이것은 ", "굵은", " 글씨", bold);
+    let mut italic = CharShape::new_default();
+    italic.properties |= 0x2;
+    push_formatted(&mut document, "and ", "slanted", " text_1", italic);
+
+    document.body_texts[0].sections[0]
+        .paragraphs
+        .push(Paragraph {
+            text: Some(ParaText {
+                content: "자세한 내용은 홈페이지 참고".to_string(),
+            }),
+            hyperlinks: vec![Hyperlink::new_url("홈페이지", "https://example.com/a b")],
+            ..Default::default()
+        });
+
+    let md = markdown::to_markdown(&document);
+    assert!(md.contains("이것은 **굵은** 글씨"), "{md}");
+    assert!(md.contains("and *slanted* text\\_1"), "{md}");
+    assert!(
+        md.contains("자세한 내용은 [홈페이지](https://example.com/a%20b) 참고"),
+        "{md}"
+    );
+}
+
+#[test]
+fn test_script_links_are_plain_text() {
+    let mut document = HwpWriter::new().document().clone();
+    let targets = [
+        "javascript:alert(1)",
+        " VBScript:msgbox",
+        "data:text/html,<script>alert(1)</script>",
+        "mailto:admin@example.com",
+    ];
+    for (index, target) in targets.iter().enumerate() {
+        let label = format!("링크{index}");
+        document.body_texts[0].sections[0]
+            .paragraphs
+            .push(Paragraph {
+                text: Some(ParaText {
+                    content: label.clone(),
+                }),
+                hyperlinks: vec![Hyperlink::new_url(&label, target)],
+                ..Default::default()
+            });
+    }
+
+    let md = markdown::to_markdown(&document);
+    assert!(!md.to_lowercase().contains("script:"), "{md}");
+    assert!(!md.contains("data:"), "{md}");
+    assert!(md.lines().any(|line| line == "링크0"), "{md}");
+    assert!(md.contains("[링크3](mailto:admin@example.com)"), "{md}");
+}
+
+#[test]
+fn test_tables_use_gfm_or_html() {
+    let mut writer = HwpWriter::new();
+    writer
+        .add_simple_table(&[vec!["이름", "값"], vec!["a|b", "1"]])
+        .unwrap();
+    writer
+        .add_table(2, 2)
+        .set_cell(0, 0, "병합")
+        .set_cell(1, 0, "x")
+        .set_cell(1, 1, "<y>")
+        .merge_cells(0, 0, 1, 2)
+        .finish()
+        .unwrap();
+
+    let md = markdown::to_markdown(writer.document());
+    assert!(
+        md.contains("| 이름 | 값 |\n| --- | --- |\n| a\\|b | 1 |"),
+        "{md}"
+    );
+    assert!(md.contains("<td colspan=\"2\">병합</td>"), "{md}");
+    assert!(md.contains("<td>&lt;y&gt;</td>"), "{md}");
+}
+
+#[test]
+fn test_images_are_extracted() {
+    let mut writer = HwpWriter::new();
+    writer.add_paragraph("그림 앞").unwrap();
+    writer
+        .add_image_from_bytes(PNG_1X1, ImageFormat::Png)
+        .unwrap();
+
+    let export = markdown::export(writer.document(), &MarkdownOptions::default());
+    assert!(export.markdown.contains("![image 1](images/image1.png)"));
+    assert_eq!(export.images.len(), 1);
+    assert_eq!(export.images[0].data, PNG_1X1);
+
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("doc.md");
+    export.write_to(&path).unwrap();
+    assert!(std::fs::read_to_string(&path)
+        .unwrap()
+        .contains("images/image1.png"));
+    assert_eq!(
+        std::fs::read(dir.path().join("images/image1.png")).unwrap(),
+        PNG_1X1
+    );
+}
@@ -0,0 +1,282 @@
+use hwpers::model::text_box::{TextBoxAlignment, TextBoxBorderStyle};
+use hwpers::model::{ObjectProperties, PageDef, RelativeTo, Shape, TextWrap};
+use hwpers::render::layout::{LayoutResult, RenderedObject};
+use hwpers::render::renderer::{PathCommand, RenderElement};
+use hwpers::render::{HwpRenderer, RenderOptions};
+use hwpers::writer::style::{ImageAlign, ImageFormat, ImageOptions};
+use hwpers::{HwpDocument, HwpWriter};
+
+mod common;
+
+use common::{build, lay_out, PNG_1X1};
+
+/// 10mm in HWP units, as the writer sizes images
+const IMAGE_SIZE: i32 = 2834;
+
+fn add_image(writer: &mut HwpWriter, alignment: ImageAlign) {
+    let options = ImageOptions::new().width(10).height(10).align(alignment);
+    writer
+        .add_image_with_options(PNG_1X1, ImageFormat::Png, &options)
+        .unwrap();
+}
+
+fn object_on(layout: &LayoutResult, page: usize) -> &RenderedObject {
+    layout.pages[page]
+        .paragraphs
+        .iter()
+        .flat_map(|para| &para.objects)
+        .next()
+        .unwrap()
+}
+
+/// Left edge, top edge and width of the body area
+fn body() -> (i32, i32, i32) {
+    let page_def = PageDef::new_default();
+    (
+        page_def.left_margin as i32,
+        page_def.top_margin as i32,
+        (page_def.width - page_def.left_margin - page_def.right_margin) as i32,
+    )
+}
+
+fn render(document: &HwpDocument) -> Vec<RenderElement> {
+    let options = RenderOptions {
+        dpi: 72,
+        ..Default::default()
+    };
+    let mut result = HwpRenderer::new(document, options).render();
+    result.pages.remove(0).elements
+}
+
+#[test]
+fn test_inline_image_takes_a_line() {
+    let document = build(|writer| {
+        writer.add_paragraph("before").unwrap();
+        add_image(writer, ImageAlign::InlineWithText);
+        writer.add_paragraph("after").unwrap();
+    });
+    let layout = lay_out(&document);
+    let (left, top, _) = body();
+    let image = object_on(&layout, 0);
+    assert_eq!(
+        (image.x, image.y, image.width, image.height),
+        (left, top + 600, IMAGE_SIZE, IMAGE_SIZE)
+    );
+
+    let paragraphs = &layout.pages[0].paragraphs;
+    assert_eq!(paragraphs[2].y, top + 600 + IMAGE_SIZE);
+
+    let elements = render(&document);
+    let drawn = elements.iter().find_map(|element| match element {
+        RenderElement::Image {
+            x,
+            y,
+            data,
+            rotation,
+            ..
+        } => Some((*x, *y, data.as_slice(), *rotation)),
+        _ => None,
+    });
+    assert_eq!(drawn, Some((left / 100, (top + 600) / 100, PNG_1X1, 0.0)));
+}
+
+#[test]
+fn test_floating_image_is_aligned_and_pushes_text_down() {
+    let document = build(|writer| {
+        add_image(writer, ImageAlign::Right);
+        writer.add_paragraph("after").unwrap();
+    });
+    let layout = lay_out(&document);
+    let (left, top, width) = body();
+    let image = object_on(&layout, 0);
+    assert_eq!((image.x, image.y), (left + width - IMAGE_SIZE, top));
+    assert_eq!(image.wrap, TextWrap::TopAndBottom);
+    assert_eq!(layout.pages[0].paragraphs[1].y, top + IMAGE_SIZE);
+
+    // Text in front of or behind an object keeps its place
+    let document = build(|writer| {
+        writer
+            .add_shape(
+                Shape::rectangle(1000, 3000).with_properties(ObjectProperties::floating(
+                    RelativeTo::Paragraph,
+                    RelativeTo::Column,
+                    TextWrap::BehindText,
+                )),
+            )
+            .unwrap();
+        writer.add_paragraph("after").unwrap();
+    });
+    assert_eq!(lay_out(&document).pages[0].paragraphs[1].y, top + 600);
+}
+
+#[test]
+fn test_page_anchored_objects_stay_on_their_page() {
+    let mut document = build(|writer| {
+        for line in 0..10 {
+            writer.add_paragraph(&format!("line {line}")).unwrap();
+        }
+        writer
+            .add_shape(
+                Shape::ellipse(1000, 1000)
+                    .with_position(500, 700)
+                    .with_properties(ObjectProperties::floating(
+                        RelativeTo::Paper,
+                        RelativeTo::Paper,
+                        TextWrap::InFrontOfText,
+                    )),
+            )
+            .unwrap();
+    });
+    let mut page_def = PageDef::new_default();
+    page_def.height = page_def.top_margin + page_def.bottom_margin + 5000;
+    document.body_texts[0].sections[0].page_def = Some(page_def);
+
+    let layout = lay_out(&document);
+    assert_eq!(layout.page_count(), 2);
+    assert!(layout.pages[0].objects.is_empty());
+    let shape = &layout.pages[1].objects[0];
+    assert_eq!((shape.x, shape.y), (500, 700));
+    assert_eq!(shape.paragraph_index, 10);
+}
+
+#[test]
+fn test_text_box_frame_and_content() {
+    let mut document = build(|writer| writer.add_text_box("boxed").unwrap());
+    let text_box = document.body_texts[0].sections[0].paragraphs[0]
+        .text_box_data
+        .as_mut()
+        .unwrap();
+    *text_box = text_box
+        .clone()
+        .with_alignment(TextBoxAlignment::Center)
+        .with_background(0xFFFF00)
+        .with_border(TextBoxBorderStyle::Dashed, 1, 0xFF0000);
+
+    let layout = lay_out(&document);
+    let (left, top, width) = body();
+    let frame = object_on(&layout, 0);
+    assert_eq!((frame.x, frame.y), (left + (width - 5000) / 2, top));
+    let content = &frame.paragraphs[0];
+    assert_eq!((content.x, content.y), (frame.x + 100, top + 100));
+
+    let elements = render(&document);
+    let path = elements
+        .iter()
+        .position(|element| {
+            matches!(
+                element,
+                RenderElement::Path {
+                    fill_color: Some(0xFFFF00),
+                    stroke_color: Some(0xFF0000),
+                    ..
+                }
+            )
+        })
+        .unwrap();
+    let RenderElement::Path { dash, .. } = &elements[path] else {
+        unreachable!()
+    };
+    assert!(!dash.is_empty());
+    let text = elements
+        .iter()
+        .position(|element| matches!(element, RenderElement::Text { text, .. } if text == "boxed"))
+        .unwrap();
+    assert!(path < text);
+}
+
+#[test]
+fn test_shapes_follow_z_order_and_rotation() {
+    let document = build(|writer| {
+        let behind = ObjectProperties::floating(
+            RelativeTo::Paragraph,
+            RelativeTo::Column,
+            TextWrap::BehindText,
+        );
+        let in_front = ObjectProperties::floating(
+            RelativeTo::Paragraph,
+            RelativeTo::Column,
+            TextWrap::InFrontOfText,
+        );
+        writer
+            .add_shape(
+                Shape::rectangle(2000, 1000)
+                    .with_properties(in_front)
+                    .with_rotation(90)
+                    .with_z_order(2),
+            )
+            .unwrap();
+        writer
+            .add_shape(
+                Shape::line(1000, 1000)
+                    .with_properties(in_front)
+                    .with_fill(0x00FF00),
+            )
+            .unwrap();
+        writer
+            .add_shape(
+                Shape::ellipse(1000, 500)
+                    .with_properties(behind)
+                    .with_fill(0x0000FF)
+                    .with_z_order(5),
+            )
+            .unwrap();
+        writer.add_paragraph("text").unwrap();
+    });
+    let elements = render(&document);
+    let paths: Vec<(usize, &Vec<PathCommand>, Option<u32>)> = elements
+        .iter()
+        .enumerate()
+        .filter_map(|(index, element)| match element {
+            RenderElement::Path {
+                commands,
+                fill_color,
+                ..
+            } => Some((index, commands, *fill_color)),
+            _ => None,
+        })
+        .collect();
+    let text = elements
+        .iter()
+        .position(|element| matches!(element, RenderElement::Text { .. }))
+        .unwrap();
+
+    // The ellipse goes behind the text, the rest over it with the line first
+    let [ellipse, line, rectangle] = paths.as_slice() else {
+        panic!("expected three paths, got {}", paths.len())
+    };
+    assert!(ellipse.0 < text && text < line.0 && line.0 < rectangle.0);
+    assert_eq!(ellipse.2, Some(0x0000FF));
+    assert_eq!(ellipse.1.len(), 6);
+    assert!(matches!(ellipse.1[1], PathCommand::CurveTo(..)));
+
+    // Lines are never filled
+    assert_eq!(line.2, None);
+    assert_eq!(line.1.len(), 2);
+
+    // Turned a quarter, the top-left corner moves to the top right
+    let (left, top, _) = body();
+    let PathCommand::MoveTo((x, y)) = rectangle.1[0] else {
+        panic!("rectangle should start with a move")
+    };
+    let expected = (
+        (left + 1000 + 500) as f32 / 100.0,
+        (top + 500 - 1000) as f32 / 100.0,
+    );
+    assert!((x - expected.0).abs() < 1e-3 && (y - expected.1).abs() < 1e-3);
+}
+
+#[test]
+fn test_svg_embeds_images_and_paths() {
+    let document = build(|writer| {
+        add_image(writer, ImageAlign::Center);
+        writer
+            .add_shape(Shape::rectangle(1000, 1000).with_line(2, 100, 0x123456))
+            .unwrap();
+    });
+    let result = HwpRenderer::new(&document, RenderOptions::default()).render();
+    let svg = result.to_svg(0).unwrap();
+    assert!(svg.contains(r#"href="data:image/png;base64,iVBORw0KGgo"#));
+    assert!(svg.contains(r#"<path d="M"#));
+    assert!(svg.contains(r##"stroke="#123456""##));
+    assert!(svg.contains("stroke-dasharray"));
+}
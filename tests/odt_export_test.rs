@@ -0,0 +1,215 @@
+use std::io::{Cursor, Read};
+
+use hwpers::export::odt;
+use hwpers::model::header_footer::PageNumberFormat;
+use hwpers::model::{CharShape, Hyperlink, ParaText, Paragraph};
+use hwpers::writer::style::ImageFormat;
+use hwpers::{HwpDocument, HwpWriter, SummaryInfo};
+
+mod common;
+
+use common::{part, PNG_1X1};
+
+fn export(document: &HwpDocument) -> odt::OdtExport {
+    odt::export(document).unwrap()
+}
+
+#[test]
+fn test_package_structure_and_styles() {
+    let mut writer = HwpWriter::new();
+    writer.add_heading("보고서", 1).unwrap();
+    writer.add_paragraph("본문 <중요> &  참고").unwrap();
+    let mut document = writer.document().clone();
+
+    let mut shape = CharShape::new_default();
+    shape.base_size = 1200;
+    shape.text_color = 0xFF0000;
+    shape.properties |= 0x1 | 0x2;
+    document.doc_info.char_shapes.push(shape);
+    let shape_id = document.doc_info.char_shapes.len() - 1;
+    document.body_texts[0].sections[0]
+        .paragraphs
+        .push(Paragraph {
+            text: Some(ParaText {
+                content: "굵은 글씨".to_string(),
+            }),
+            char_shapes: Some(hwpers::model::ParaCharShape {
+                char_positions: vec![hwpers::model::CharPositionShape {
+                    position: 0,
+                    char_shape_id: shape_id as u16,
+                }],
+            }),
+            ..Default::default()
+        });
+
+    let export = export(&document);
+    let bytes = &export.bytes;
+
+    // The mimetype entry must come first and be stored uncompressed
+    let mut archive = zip::ZipArchive::new(Cursor::new(bytes)).unwrap();
+    let mimetype = archive.by_index(0).unwrap();
+    assert_eq!(mimetype.name(), "mimetype");
+    assert_eq!(mimetype.compression(), zip::CompressionMethod::Stored);
+    drop(mimetype);
+    let manifest = part(bytes, "META-INF/manifest.xml");
+    assert!(manifest.contains("manifest:full-path=\"content.xml\""));
+
+    let content = part(bytes, "content.xml");
+    assert!(
+        content.contains("본문 &lt;중요&gt; &amp; <text:s/>참고"),
+        "{content}"
+    );
+    assert!(content.contains("text:outline-level=\"1\">"), "{content}");
+    assert!(
+        content.contains(&format!(
+            "<text:span text:style-name=\"T{shape_id}\">굵은 글씨</text:span>"
+        )),
+        "{content}"
+    );
+    assert!(content.contains("fo:font-size=\"12pt\""), "{content}");
+    assert!(content.contains("fo:font-weight=\"bold\""), "{content}");
+    assert!(content.contains("fo:color=\"#ff0000\""), "{content}");
+    assert!(
+        content.contains("style:parent-style-name=\"Style0\" style:master-page-name=\"MP0\""),
+        "{content}"
+    );
+
+    let styles = part(bytes, "styles.xml");
+    assert!(
+        styles.contains("style:name=\"Style0\" style:display-name=\"바탕글\""),
+        "{styles}"
+    );
+    assert!(styles.contains("fo:page-width=\"595.28pt\""), "{styles}");
+    assert!(
+        styles.contains("<style:master-page style:name=\"MP0\""),
+        "{styles}"
+    );
+    assert!(export.dropped.is_empty(), "{:?}", export.dropped);
+}
+
+#[test]
+fn test_tables_notes_links_and_images() {
+    let mut writer = HwpWriter::new();
+    writer
+        .add_table(3, 3)
+        .set_cell(0, 0, "가로 병합")
+        .set_cell(0, 2, "세로 병합")
+        .set_cell(1, 0, "a")
+        .set_cell(1, 1, "b")
+        .set_cell(2, 0, "c")
+        .set_cell(2, 1, "d")
+        .merge_cells(0, 0, 1, 2)
+        .merge_cells(0, 2, 3, 1)
+        .finish()
+        .unwrap();
+    writer
+        .add_paragraph_with_footnote("각주가 있는 문장", "출처: 통계청")
+        .unwrap();
+    writer
+        .add_paragraph_with_endnote("미주가 있는 문장", "참고 문헌")
+        .unwrap();
+    writer
+        .add_image_from_bytes(PNG_1X1, ImageFormat::Png)
+        .unwrap();
+    let mut document = writer.document().clone();
+    document.body_texts[0].sections[0]
+        .paragraphs
+        .push(Paragraph {
+            text: Some(ParaText {
+                content: "자세한 내용은 홈페이지 참고".to_string(),
+            }),
+            hyperlinks: vec![Hyperlink::new_url(
+                "홈페이지",
+                "https://example.com/?a=1&b=2",
+            )],
+            ..Default::default()
+        });
+
+    let bytes = export(&document).bytes;
+    let content = part(&bytes, "content.xml");
+    assert_eq!(
+        content.matches("<table:table-column ").count(),
+        3,
+        "{content}"
+    );
+    assert_eq!(content.matches("<table:table-row>").count(), 3, "{content}");
+    assert!(
+        content.contains("table:number-columns-spanned=\"2\""),
+        "{content}"
+    );
+    assert!(
+        content.contains("table:number-rows-spanned=\"3\""),
+        "{content}"
+    );
+    assert_eq!(content.matches("<table:covered-table-cell/>").count(), 3);
+    assert!(content.contains("가로 병합"));
+
+    assert!(
+        content.contains("text:note-class=\"footnote\"><text:note-citation>1</text:note-citation>"),
+        "{content}"
+    );
+    assert!(content.contains("text:note-class=\"endnote\""), "{content}");
+    assert!(content.contains("출처: 통계청"));
+    assert!(
+        content.contains("<draw:image xlink:href=\"Pictures/image1.png\""),
+        "{content}"
+    );
+    assert!(
+        content.contains("xlink:href=\"https://example.com/?a=1&amp;b=2\">"),
+        "{content}"
+    );
+    assert!(content.contains("홈페이지</text:a>"), "{content}");
+    assert!(part(&bytes, "META-INF/manifest.xml")
+        .contains("manifest:full-path=\"Pictures/image1.png\" manifest:media-type=\"image/png\""));
+
+    let mut archive = zip::ZipArchive::new(Cursor::new(&bytes)).unwrap();
+    let mut image = Vec::new();
+    archive
+        .by_name("Pictures/image1.png")
+        .unwrap()
+        .read_to_end(&mut image)
+        .unwrap();
+    assert_eq!(image, PNG_1X1);
+}
+
+#[test]
+fn test_page_setup_headers_and_dropped_elements() {
+    let mut writer = HwpWriter::new();
+    writer.set_a4_landscape().unwrap();
+    writer.add_header("기밀 문서");
+    writer.add_footer_with_page_number("쪽 ", PageNumberFormat::RomanLower);
+    writer.add_paragraph("본문").unwrap();
+    writer.add_text_box("상자 안 글").unwrap();
+    let mut document = writer.document().clone();
+    document.summary_info = Some(SummaryInfo {
+        title: Some("제출 문서".to_string()),
+        ..Default::default()
+    });
+
+    let export = export(&document);
+    let styles = part(&export.bytes, "styles.xml");
+    assert!(
+        styles.contains("style:print-orientation=\"landscape\""),
+        "{styles}"
+    );
+    assert!(styles.contains("<style:header><text:p"), "{styles}");
+    assert!(styles.contains("기밀 문서"), "{styles}");
+    assert!(
+        styles
+            .contains("쪽 <text:page-number text:select-page=\"current\" style:num-format=\"i\">"),
+        "{styles}"
+    );
+    assert!(part(&export.bytes, "content.xml").contains("상자 안 글"));
+    assert!(part(&export.bytes, "meta.xml").contains("<dc:title>제출 문서</dc:title>"));
+
+    assert_eq!(export.dropped.len(), 1, "{:?}", export.dropped);
+    assert_eq!(export.dropped[0].element, "text box");
+    assert!(export.dropped[0]
+        .location
+        .starts_with("section 0, paragraph "));
+
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("doc.odt");
+    odt::write_odt(&document, &path).unwrap();
+    assert_eq!(std::fs::read(&path).unwrap(), export.bytes);
+}
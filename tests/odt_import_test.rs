@@ -0,0 +1,373 @@
+use hwpers::export::odt;
+use hwpers::import::odt as odt_import;
+use hwpers::model::header_footer::{HeaderFooterType, PageApplyType, PageNumberFormat};
+use hwpers::model::page_layout::{PageOrientation, PaperSize};
+use hwpers::model::{CharShape, ParaText, Paragraph};
+use hwpers::writer::style::ImageFormat;
+use hwpers::{HwpError, HwpWriter, ReaderLimits};
+
+mod common;
+
+use common::{package, paragraph, texts, PNG_1X1};
+
+const NS: &str = concat!(
+    r#"xmlns:office="urn:oasis:names:tc:opendocument:xmlns:office:1.0" "#,
+    r#"xmlns:style="urn:oasis:names:tc:opendocument:xmlns:style:1.0" "#,
+    r#"xmlns:text="urn:oasis:names:tc:opendocument:xmlns:text:1.0" "#,
+    r#"xmlns:table="urn:oasis:names:tc:opendocument:xmlns:table:1.0" "#,
+    r#"xmlns:fo="urn:oasis:names:tc:opendocument:xmlns:xsl-fo-compatible:1.0" "#,
+    r#"xmlns:xlink="http://www.w3.org/1999/xlink" "#,
+    r#"xmlns:dc="http://purl.org/dc/elements/1.1/" "#,
+    r#"xmlns:meta="urn:oasis:names:tc:opendocument:xmlns:meta:1.0" "#,
+    r#"xmlns:svg="urn:oasis:names:tc:opendocument:xmlns:svg-compatible:1.0""#
+);
+
+#[test]
+fn test_round_trip_through_odt() {
+    let mut writer = HwpWriter::new();
+    writer.set_a4_landscape().unwrap();
+    writer.add_header("기밀 문서");
+    writer.add_footer_with_page_number("쪽 ", PageNumberFormat::RomanLower);
+    writer.add_heading("보고서", 1).unwrap();
+    writer.add_paragraph("두  칸 띄어쓰기").unwrap();
+    writer
+        .add_paragraph_with_footnote("각주가 있는 문장", "출처: 통계청")
+        .unwrap();
+    writer
+        .add_table(2, 2)
+        .set_cell(0, 0, "병합")
+        .set_cell(1, 0, "x")
+        .set_cell(1, 1, "y")
+        .merge_cells(0, 0, 1, 2)
+        .finish()
+        .unwrap();
+    writer
+        .add_image_from_bytes(PNG_1X1, ImageFormat::Png)
+        .unwrap();
+    let mut document = writer.document().clone();
+
+    let mut shape = CharShape::new_default();
+    shape.base_size = 1400;
+    shape.text_color = 0xFF0000;
+    shape.properties |= 0x1;
+    document.doc_info.char_shapes.push(shape);
+    let shape_id = (document.doc_info.char_shapes.len() - 1) as u16;
+    document.body_texts[0].sections[0]
+        .paragraphs
+        .push(Paragraph {
+            text: Some(ParaText {
+                content: "굵은 빨간 글씨".to_string(),
+            }),
+            char_shapes: Some(hwpers::model::ParaCharShape {
+                char_positions: vec![hwpers::model::CharPositionShape {
+                    position: 0,
+                    char_shape_id: shape_id,
+                }],
+            }),
+            hyperlinks: vec![hwpers::model::Hyperlink::new_url(
+                "빨간",
+                "https://example.com/",
+            )],
+            ..Default::default()
+        });
+
+    let bytes = odt::to_odt(&document).unwrap();
+    let imported = odt_import::from_bytes(&bytes).unwrap();
+
+    // Heading level survives as outline numbering in the paragraph shape
+    let heading = paragraph(&imported, "보고서");
+    let shape = imported
+        .get_para_shape(heading.para_shape_id as usize)
+        .unwrap();
+    assert_eq!((shape.properties1 >> 23) & 0x3, 1);
+    assert_eq!((shape.properties1 >> 25) & 0x7, 0);
+
+    // Repeated spaces are written as text:s and read back intact
+    paragraph(&imported, "두  칸 띄어쓰기");
+
+    let styled = paragraph(&imported, "굵은 빨간 글씨");
+    let positions = &styled.char_shapes.as_ref().unwrap().char_positions;
+    let shape = imported
+        .get_char_shape(positions[0].char_shape_id as usize)
+        .unwrap();
+    assert!(shape.is_bold());
+    assert_eq!(shape.base_size, 1400);
+    assert_eq!(shape.text_color, 0xFF0000);
+    assert_eq!(styled.hyperlinks.len(), 1);
+    assert_eq!(styled.hyperlinks[0].target_url, "https://example.com/");
+    assert_eq!(styled.hyperlinks[0].start_position, 3);
+
+    let noted = paragraph(&imported, "각주가 있는 문장");
+    assert_eq!(noted.footnotes.len(), 1);
+    assert_eq!(noted.footnotes[0].text(), "출처: 통계청");
+
+    let section = &imported.body_texts[0].sections[0];
+    let table = section
+        .paragraphs
+        .iter()
+        .find_map(|paragraph| paragraph.table_data.as_ref())
+        .unwrap();
+    assert_eq!((table.rows, table.cols), (2, 2));
+    assert!(table
+        .cells
+        .iter()
+        .any(|cell| cell.col_span == 2 && cell.row_span == 1));
+    assert!(texts(&imported).contains(&"병합".to_string()));
+
+    assert_eq!(imported.doc_info.bin_data.len(), 1);
+    assert_eq!(imported.doc_info.bin_data[0].data, PNG_1X1);
+
+    let page_def = section.page_def.as_ref().unwrap();
+    let layout = page_def.layout.as_ref().unwrap();
+    assert_eq!(layout.orientation, PageOrientation::Landscape);
+    assert_eq!(layout.paper_size, PaperSize::A4);
+    let items = &page_def.header_footer.items;
+    assert!(items.iter().any(|item| {
+        item.header_footer_type == HeaderFooterType::Header && item.text.contains("기밀 문서")
+    }));
+    assert!(items.iter().any(|item| {
+        item.header_footer_type == HeaderFooterType::Footer
+            && item.text == "쪽 "
+            && item.include_page_number
+            && item.page_number_format == PageNumberFormat::RomanLower as u8
+    }));
+}
+
+#[test]
+fn test_styles_lists_and_tables() {
+    let styles = format!(
+        r##"<?xml version="1.0" encoding="UTF-8"?>
+<office:document-styles {NS}>
+  <office:font-face-decls>
+    <style:font-face style:name="Body Font" svg:font-family="'Noto Serif'"/>
+    <style:font-face style:name="Hangul Font" svg:font-family="'나눔명조'"/>
+  </office:font-face-decls>
+  <office:styles>
+    <style:default-style style:family="paragraph">
+      <style:text-properties style:font-name="Body Font" style:font-name-asian="Hangul Font" fo:font-size="11pt"/>
+    </style:default-style>
+    <style:style style:name="Standard" style:family="paragraph"/>
+    <style:style style:name="Title" style:display-name="제목" style:family="paragraph" style:parent-style-name="Standard">
+      <style:paragraph-properties fo:text-align="center" fo:margin-bottom="0.5cm"/>
+      <style:text-properties fo:font-size="20pt" fo:font-weight="bold"/>
+    </style:style>
+    <style:style style:name="Emphasis" style:family="text">
+      <style:text-properties fo:font-style="italic" fo:color="#0000ff"/>
+    </style:style>
+  </office:styles>
+  <office:automatic-styles>
+    <style:page-layout style:name="pm1">
+      <style:page-layout-properties fo:page-width="8.5in" fo:page-height="11in" style:print-orientation="portrait" fo:margin-top="1in" fo:margin-bottom="1in" fo:margin-left="1.25in" fo:margin-right="1.25in"/>
+      <style:header-style><style:header-footer-properties fo:min-height="0.5in"/></style:header-style>
+    </style:page-layout>
+    <style:style style:name="MP1" style:family="paragraph"><style:paragraph-properties fo:text-align="end"/></style:style>
+  </office:automatic-styles>
+  <office:master-styles>
+    <style:master-page style:name="Standard" style:page-layout-name="pm1">
+      <style:header><text:p text:style-name="MP1">머리말</text:p></style:header>
+      <style:footer-first><text:p>첫 쪽 꼬리말</text:p></style:footer-first>
+    </style:master-page>
+  </office:master-styles>
+</office:document-styles>"##
+    );
+    let content = format!(
+        r##"<?xml version="1.0" encoding="UTF-8"?>
+<office:document-content {NS}>
+  <office:automatic-styles>
+    <style:style style:name="P1" style:family="paragraph" style:parent-style-name="Title">
+      <style:paragraph-properties fo:margin-left="1cm"/>
+    </style:style>
+    <style:style style:name="cell" style:family="table-cell">
+      <style:table-cell-properties fo:border="0.5pt solid #000000" fo:border-top="1pt solid #ff0000"/>
+    </style:style>
+    <text:list-style style:name="L1">
+      <text:list-level-style-bullet text:level="1" text:bullet-char="•"/>
+      <text:list-level-style-number text:level="2" style:num-format="1"/>
+    </text:list-style>
+  </office:automatic-styles>
+  <office:body>
+    <office:text>
+      <text:p text:style-name="P1">문서   제목</text:p>
+      <text:p>일반 <text:span text:style-name="Emphasis">강조</text:span>와 <text:a xlink:href="mailto:a@example.com">메일</text:a></text:p>
+      <text:list text:style-name="L1">
+        <text:list-item><text:p>첫째</text:p>
+          <text:list><text:list-item><text:p>하나</text:p></text:list-item></text:list>
+        </text:list-item>
+        <text:list-item><text:p>둘째</text:p></text:list-item>
+      </text:list>
+      <table:table table:name="표">
+        <table:table-column table:number-columns-repeated="2"/>
+        <table:table-row>
+          <table:table-cell table:style-name="cell" table:number-rows-spanned="2"><text:p>세로</text:p></table:table-cell>
+          <table:table-cell><text:p>가</text:p></table:table-cell>
+        </table:table-row>
+        <table:table-row>
+          <table:covered-table-cell/>
+          <table:table-cell><text:p>나</text:p></table:table-cell>
+        </table:table-row>
+      </table:table>
+      <text:section text:name="부록"><text:p>부록 내용</text:p></text:section>
+    </office:text>
+  </office:body>
+</office:document-content>"##
+    );
+    let meta = format!(
+        r#"<office:document-meta {NS}><office:meta><dc:title>제출 문서</dc:title><meta:initial-creator>홍길동</meta:initial-creator><meta:keyword>보고서</meta:keyword><meta:keyword>2024</meta:keyword></office:meta></office:document-meta>"#
+    );
+    let bytes = package(&[
+        (
+            "mimetype",
+            "application/vnd.oasis.opendocument.text".to_string(),
+        ),
+        ("content.xml", content),
+        ("styles.xml", styles),
+        ("meta.xml", meta),
+    ]);
+    let imported = odt_import::from_bytes(&bytes).unwrap();
+
+    // Automatic styles format the paragraph but the common style is what gets registered
+    let title = paragraph(&imported, "문서 제목");
+    let style = imported.get_style(title.style_id as usize).unwrap();
+    assert_eq!(style.name, "제목");
+    assert_eq!(style.english_name, "Title");
+    let para_shape = imported
+        .get_para_shape(title.para_shape_id as usize)
+        .unwrap();
+    assert_eq!(para_shape.get_alignment(), 2);
+    assert_eq!(para_shape.left_margin, 2835);
+    let title_shape = imported
+        .get_char_shape(
+            title.char_shapes.as_ref().unwrap().char_positions[0].char_shape_id as usize,
+        )
+        .unwrap();
+    assert!(title_shape.is_bold());
+    assert_eq!(title_shape.base_size, 2000);
+    let latin = imported
+        .get_face_name(title_shape.face_name_ids[1] as usize)
+        .unwrap();
+    let hangul = imported
+        .get_face_name(title_shape.face_name_ids[0] as usize)
+        .unwrap();
+    assert_eq!(latin.font_name, "Noto Serif");
+    assert_eq!(hangul.font_name, "나눔명조");
+
+    let mixed = paragraph(&imported, "일반 강조와 메일");
+    let positions = &mixed.char_shapes.as_ref().unwrap().char_positions;
+    assert_eq!(positions[1].position, 3);
+    let emphasis = imported
+        .get_char_shape(positions[1].char_shape_id as usize)
+        .unwrap();
+    assert!(emphasis.is_italic());
+    assert_eq!(emphasis.text_color, 0x0000FF);
+    assert_eq!(emphasis.base_size, 1100);
+    assert_eq!(mixed.hyperlinks.len(), 1);
+    assert_eq!(mixed.hyperlinks[0].display_text, "메일");
+
+    let texts = texts(&imported);
+    assert!(texts.iter().any(|text| text.contains("첫째")), "{texts:?}");
+    assert!(texts.iter().any(|text| text.contains("하나")), "{texts:?}");
+    assert!(texts.contains(&"부록 내용".to_string()), "{texts:?}");
+
+    let section = &imported.body_texts[0].sections[0];
+    let table = section
+        .paragraphs
+        .iter()
+        .find_map(|paragraph| paragraph.table_data.as_ref())
+        .unwrap();
+    assert_eq!((table.rows, table.cols), (2, 2));
+    let merged = table.cells.iter().find(|cell| cell.row_span == 2).unwrap();
+    let fill = imported
+        .get_border_fill(merged.border_fill_id as usize - 1)
+        .unwrap();
+    assert_eq!(fill.top.color, 0xFF0000);
+    assert_eq!(fill.left.color, 0x000000);
+    assert_ne!(fill.left.line_type, 0);
+
+    let page_def = section.page_def.as_ref().unwrap();
+    let layout = page_def.layout.as_ref().unwrap();
+    assert_eq!(layout.paper_size, PaperSize::Letter);
+    assert_eq!(layout.margins.left, 9000);
+    assert_eq!(layout.margins.header, 3600);
+    let items = &page_def.header_footer.items;
+    assert!(items.iter().any(|item| {
+        item.header_footer_type == HeaderFooterType::Header
+            && item.text == "머리말"
+            && item.alignment == 2
+    }));
+    assert!(items.iter().any(|item| {
+        item.header_footer_type == HeaderFooterType::Footer
+            && item.apply_type == PageApplyType::FirstPage
+    }));
+
+    let properties = imported.doc_info.properties.as_ref().unwrap();
+    assert_eq!(properties.document_title.as_deref(), Some("제출 문서"));
+    assert_eq!(properties.document_author.as_deref(), Some("홍길동"));
+    assert_eq!(
+        properties.document_keywords.as_deref(),
+        Some("보고서, 2024")
+    );
+}
+
+#[test]
+fn test_invalid_input_and_limits() {
+    assert!(odt_import::from_bytes(b"not a zip").is_err());
+
+    let missing = package(&[("styles.xml", String::new())]);
+    assert!(matches!(
+        odt_import::from_bytes(&missing),
+        Err(HwpError::NotFound(_))
+    ));
+
+    let content = format!(
+        r#"<office:document-content {NS}><office:body><office:text><text:p>{}</text:p></office:text></office:body></office:document-content>"#,
+        "가".repeat(1000)
+    );
+    let bytes = package(&[("content.xml", content)]);
+    assert!(odt_import::from_bytes(&bytes).is_ok());
+    let limits = ReaderLimits::default().with_max_zip_entry_size(1024);
+    assert!(matches!(
+        odt_import::from_bytes_with_limits(&bytes, limits),
+        Err(HwpError::LimitExceeded { .. })
+    ));
+
+    let deep = format!(
+        r#"<office:document-content {NS}><office:body><office:text>{}{}</office:text></office:body></office:document-content>"#,
+        "<text:section>".repeat(40),
+        "</text:section>".repeat(40)
+    );
+    let bytes = package(&[("content.xml", deep)]);
+    let limits = ReaderLimits::default().with_max_nesting_depth(32);
+    assert!(matches!(
+        odt_import::from_bytes_with_limits(&bytes, limits),
+        Err(HwpError::LimitExceeded { .. })
+    ));
+}
+
+#[test]
+fn test_repeated_table_totals_are_capped() {
+    let table = |body: &str| {
+        let content = format!(
+            r#"<office:document-content {NS}><office:body><office:text><table:table>{body}</table:table></office:text></office:body></office:document-content>"#
+        );
+        odt_import::from_bytes(&package(&[("content.xml", content)]))
+    };
+    let limit = |result: Result<_, HwpError>| match result {
+        Err(HwpError::LimitExceeded { limit, .. }) => limit,
+        other => panic!("expected a limit error, got {:?}", other.map(|_| ())),
+    };
+
+    // Every repeat is within the per-attribute cap, but the sums are not
+    let rows = r#"<table:table-row table:number-rows-repeated="1000"><table:table-cell/></table:table-row>"#;
+    assert_eq!(limit(table(&rows.repeat(70))), "table rows");
+    let columns = r#"<table:table-column table:number-columns-repeated="1000"/>"#;
+    assert_eq!(limit(table(&columns.repeat(70))), "table columns");
+    let cells = r#"<table:table-cell table:number-columns-repeated="1000"/>"#.repeat(70);
+    assert_eq!(
+        limit(table(&format!(
+            "<table:table-row>{cells}</table:table-row>"
+        ))),
+        "table columns"
+    );
+    let grid = r#"<table:table-column table:number-columns-repeated="1000"/><table:table-row table:number-rows-repeated="1000"><table:table-cell table:number-columns-repeated="1000"/></table:table-row>"#;
+    assert_eq!(limit(table(&grid.repeat(2))), "table cells");
+}
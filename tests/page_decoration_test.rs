@@ -0,0 +1,192 @@
+use hwpers::model::{
+    HeaderFooterAlignment, PageApplyType, PageDef, PageHide, PageNumberFormat, SectionDef,
+};
+use hwpers::render::layout::RenderedParagraph;
+use hwpers::render::renderer::RenderElement;
+use hwpers::render::{HwpRenderer, RenderOptions};
+use hwpers::HwpDocument;
+
+mod common;
+
+use common::{build_with_body, lay_out, lines};
+
+/// Pages hold 5000 units of body between the margins, eight 600 unit lines
+const BODY: u32 = 5000;
+
+fn text(para: Option<&RenderedParagraph>) -> String {
+    para.into_iter()
+        .flat_map(|para| &para.lines)
+        .flat_map(|line| &line.runs)
+        .map(|run| run.text.as_str())
+        .collect()
+}
+
+fn page_def(document: &HwpDocument) -> &PageDef {
+    document.body_texts[0].sections[0]
+        .page_def
+        .as_ref()
+        .unwrap()
+}
+
+#[test]
+fn test_headers_and_footers_on_every_page() {
+    let document = build_with_body(BODY, |writer| {
+        writer.add_header("Title");
+        writer.add_footer_with_page_number("Page ", PageNumberFormat::Numeric);
+        lines(writer, 10);
+    });
+    let layout = lay_out(&document);
+    let page_def = page_def(&document);
+    assert_eq!(layout.page_count(), 2);
+
+    for (index, page) in layout.pages.iter().enumerate() {
+        let header = page.header.as_ref().unwrap();
+        assert_eq!(text(Some(header)), "Title");
+        assert_eq!(header.y, page_def.header_margin as i32);
+
+        let footer = page.footer.as_ref().unwrap();
+        assert_eq!(text(Some(footer)), format!("Page {}", index + 1));
+        let footer_top = (page_def.height - page_def.footer_margin) as i32 - 600;
+        assert_eq!(footer.y, footer_top);
+
+        // The body stops short of the footer
+        let last = page.paragraphs.last().unwrap();
+        assert!(last.y + last.height <= footer_top);
+    }
+    assert_eq!(layout.pages[0].paragraphs.len(), 7);
+}
+
+#[test]
+fn test_first_even_and_odd_pages() {
+    let document = build_with_body(BODY, |writer| {
+        writer.add_header_with_options("all", PageApplyType::All, HeaderFooterAlignment::Left);
+        writer.add_header_with_options(
+            "first",
+            PageApplyType::FirstPage,
+            HeaderFooterAlignment::Left,
+        );
+        writer.add_header_with_options(
+            "even",
+            PageApplyType::EvenPages,
+            HeaderFooterAlignment::Right,
+        );
+        lines(writer, 20);
+    });
+    let layout = lay_out(&document);
+    let headers: Vec<String> = layout
+        .pages
+        .iter()
+        .map(|page| text(page.header.as_ref()))
+        .collect();
+    assert_eq!(headers, ["first", "even", "all"]);
+
+    // Right aligned headers end at the right margin
+    let page_def = page_def(&document);
+    let run = &layout.pages[1].header.as_ref().unwrap().lines[0].runs[0];
+    assert_eq!(
+        run.x + run.width,
+        (page_def.width - page_def.right_margin) as i32
+    );
+}
+
+#[test]
+fn test_page_number_formats() {
+    assert_eq!(PageNumberFormat::Numeric.format(12), "12");
+    assert_eq!(PageNumberFormat::RomanLower.format(4), "iv");
+    assert_eq!(PageNumberFormat::RomanUpper.format(1994), "MCMXCIV");
+    assert_eq!(PageNumberFormat::AlphaLower.format(3), "c");
+    assert_eq!(PageNumberFormat::AlphaUpper.format(28), "BB");
+
+    let document = build_with_body(BODY, |writer| {
+        writer.add_footer_with_page_number("- ", PageNumberFormat::RomanUpper);
+        lines(writer, 10);
+    });
+    let layout = lay_out(&document);
+    assert_eq!(text(layout.pages[1].footer.as_ref()), "- II");
+}
+
+#[test]
+fn test_hidden_headers_footers_and_numbers() {
+    let mut document = build_with_body(BODY, |writer| {
+        writer.add_header("Title");
+        writer.add_footer_with_page_number("Page ", PageNumberFormat::Numeric);
+        lines(writer, 20);
+    });
+    let section = &mut document.body_texts[0].sections[0];
+    let mut section_def = SectionDef::new_default();
+    section_def.properties |= 0x01 | 0x04;
+    section.section_def = Some(section_def);
+    // The first paragraph of the third page hides its footer
+    section.paragraphs[14].page_hide = Some(PageHide { properties: 0x02 });
+
+    let layout = lay_out(&document);
+    let first = &layout.pages[0];
+    assert!(first.header.is_none());
+    assert_eq!(text(first.footer.as_ref()), "Page ");
+
+    assert_eq!(text(layout.pages[1].header.as_ref()), "Title");
+    assert_eq!(text(layout.pages[1].footer.as_ref()), "Page 2");
+
+    assert!(layout.pages[2].header.is_some());
+    assert!(layout.pages[2].footer.is_none());
+}
+
+#[test]
+fn test_footnotes_at_the_foot_of_the_page() {
+    let document = build_with_body(BODY, |writer| {
+        writer
+            .add_paragraph_with_footnote("one", "first note")
+            .unwrap();
+        writer
+            .add_paragraph_with_footnote("two", "second note")
+            .unwrap();
+        lines(writer, 10);
+    });
+    let layout = lay_out(&document);
+    let page_def = page_def(&document);
+    let body_bottom = (page_def.height - page_def.bottom_margin) as i32;
+
+    let notes = layout.pages[0].footnotes.as_ref().unwrap();
+    let texts: Vec<String> = notes.paragraphs.iter().map(|n| text(Some(n))).collect();
+    assert_eq!(texts, ["1) first note", "2) second note"]);
+    assert_eq!(notes.paragraphs[1].y, body_bottom - 600);
+    assert_eq!(notes.paragraphs[0].y, body_bottom - 1200);
+    assert!(notes.y < notes.paragraphs[0].y);
+    assert_eq!(notes.x, page_def.left_margin as i32);
+
+    // Body text stops above the notes and their separator
+    let last = layout.pages[0].paragraphs.last().unwrap();
+    assert!(last.y + last.height <= notes.y);
+    // 5000 less 1200 of notes and 850 of separator space leaves four lines
+    assert_eq!(layout.pages[0].paragraphs.len(), 4);
+    assert!(layout.pages[1].footnotes.is_none());
+
+    let options = RenderOptions {
+        dpi: 72,
+        ..Default::default()
+    };
+    let result = HwpRenderer::new(&document, options).render();
+    let elements = &result.pages[0].elements;
+    assert!(elements.iter().any(|element| matches!(
+        element,
+        RenderElement::Line { y1, y2, .. } if *y1 == notes.y / 100 && *y2 == *y1
+    )));
+    assert!(elements.iter().any(|element| matches!(
+        element,
+        RenderElement::Text { text, .. } if text == "1) first note"
+    )));
+}
+
+#[test]
+fn test_footnotes_move_with_their_paragraph() {
+    let document = build_with_body(BODY, |writer| {
+        lines(writer, 7);
+        writer.add_paragraph_with_footnote("eight", "note").unwrap();
+    });
+    let layout = lay_out(&document);
+    assert_eq!(layout.page_count(), 2);
+    assert!(layout.pages[0].footnotes.is_none());
+    let page = &layout.pages[1];
+    assert_eq!(text(page.paragraphs.first()), "eight");
+    assert_eq!(page.footnotes.as_ref().unwrap().paragraphs.len(), 1);
+}
@@ -0,0 +1,71 @@
+use hwpers::model::PageDef;
+use hwpers::render::renderer::RenderedPageOutput;
+use hwpers::render::{HwpRenderer, RenderOptions};
+use hwpers::{HwpDocument, HwpWriter};
+
+/// Pages whose body holds exactly ten 1000-unit lines, one per paragraph
+fn build(paragraphs: usize) -> HwpDocument {
+    let mut writer = HwpWriter::new();
+    for index in 0..paragraphs {
+        writer.add_paragraph(&format!("Line {index}")).unwrap();
+    }
+    let mut document = writer.document().clone();
+    for shape in &mut document.doc_info.para_shapes {
+        shape.line_space_type = 1;
+        shape.line_space = 1000;
+    }
+    let mut page_def = PageDef::new_default();
+    page_def.height = page_def.top_margin + page_def.bottom_margin + 10_000;
+    document.body_texts[0].sections[0].page_def = Some(page_def);
+    document
+}
+
+/// Page content in a comparable form
+fn dump(page: &RenderedPageOutput) -> String {
+    format!("{} {:?}", page.page_number, page.elements)
+}
+
+#[test]
+fn test_render_page_on_demand() {
+    let document = build(35);
+    let renderer = HwpRenderer::new(&document, RenderOptions::default());
+    assert_eq!(renderer.page_count(), 4);
+    // The layout is kept between calls
+    assert!(std::ptr::eq(renderer.layout(), renderer.layout()));
+
+    let all = renderer.render();
+    assert_eq!(all.pages.len(), 4);
+    for (index, page) in all.pages.iter().enumerate() {
+        assert_eq!(dump(&renderer.render_page(index).unwrap()), dump(page));
+    }
+    assert!(renderer.render_page(4).is_none());
+}
+
+#[test]
+fn test_render_page_range() {
+    let document = build(35);
+    let renderer = HwpRenderer::new(&document, RenderOptions::default());
+
+    let middle = renderer.render_pages(1..3);
+    let numbers: Vec<_> = middle.pages.iter().map(|page| page.page_number).collect();
+    assert_eq!(numbers, [2, 3]);
+
+    // Ranges are cut short at the last page
+    assert_eq!(renderer.render_pages(2..10).pages.len(), 2);
+    assert!(renderer.render_pages(6..9).pages.is_empty());
+}
+
+#[test]
+fn test_threads_render_the_same_pages() {
+    let document = build(95);
+    let single = HwpRenderer::new(&document, RenderOptions::default()).render();
+    for threads in [2, 3, 16] {
+        let parallel = HwpRenderer::new(&document, RenderOptions::default())
+            .with_threads(threads)
+            .render();
+        assert_eq!(parallel.pages.len(), 10);
+        let expected: Vec<_> = single.pages.iter().map(dump).collect();
+        let actual: Vec<_> = parallel.pages.iter().map(dump).collect();
+        assert_eq!(actual, expected);
+    }
+}
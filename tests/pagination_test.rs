@@ -0,0 +1,213 @@
+use hwpers::model::{NewNumber, PageDef, PageHide, Paragraph, SectionDef};
+use hwpers::render::layout::LayoutResult;
+use hwpers::render::LayoutEngine;
+use hwpers::{HwpDocument, HwpWriter};
+
+const WIDOW_ORPHAN: u32 = 1 << 16;
+const KEEP_WITH_NEXT: u32 = 1 << 17;
+const KEEP_LINES: u32 = 1 << 18;
+const PAGE_BREAK_BEFORE: u32 = 1 << 19;
+
+/// Pages whose body holds exactly ten 1000-unit lines
+fn build(paragraphs: &[&str]) -> HwpDocument {
+    let mut writer = HwpWriter::new();
+    for text in paragraphs {
+        writer.add_paragraph(text).unwrap();
+    }
+    let mut document = writer.document().clone();
+    for shape in &mut document.doc_info.para_shapes {
+        shape.line_space_type = 1;
+        shape.line_space = 1000;
+    }
+    let mut page_def = PageDef::new_default();
+    page_def.height = page_def.top_margin + page_def.bottom_margin + 10_000;
+    section(&mut document).page_def = Some(page_def);
+    document
+}
+
+fn section(document: &mut HwpDocument) -> &mut hwpers::model::Section {
+    &mut document.body_texts[0].sections[0]
+}
+
+fn paragraph(document: &mut HwpDocument, index: usize) -> &mut Paragraph {
+    &mut section(document).paragraphs[index]
+}
+
+/// Give one paragraph its own shape with extra `properties1` flags
+fn set_flags(document: &mut HwpDocument, index: usize, flags: u32) {
+    let mut shape = document.doc_info.para_shapes[0].clone();
+    shape.properties1 |= flags;
+    document.doc_info.para_shapes.push(shape);
+    let id = document.doc_info.para_shapes.len() as u16 - 1;
+    paragraph(document, index).para_shape_id = id;
+}
+
+fn layout(document: &HwpDocument) -> LayoutResult {
+    LayoutEngine::new(document).calculate_layout()
+}
+
+/// Lines of each page as (paragraph, lines placed) per paragraph piece
+fn pieces(layout: &LayoutResult) -> Vec<Vec<(usize, usize)>> {
+    layout
+        .pages
+        .iter()
+        .map(|page| {
+            page.paragraphs
+                .iter()
+                .map(|para| (para.paragraph_index, para.lines.len()))
+                .collect()
+        })
+        .collect()
+}
+
+fn page_numbers(document: &HwpDocument) -> Vec<u32> {
+    layout(document)
+        .pages
+        .iter()
+        .map(|p| p.page_number)
+        .collect()
+}
+
+fn lines(count: usize) -> String {
+    vec!["x"; count].join("\n")
+}
+
+#[test]
+fn test_flows_paragraphs_onto_numbered_pages() {
+    let texts: Vec<String> = (0..25).map(|n| format!("Paragraph {n}")).collect();
+    let texts: Vec<&str> = texts.iter().map(String::as_str).collect();
+    let document = build(&texts);
+
+    assert_eq!(page_numbers(&document), [1, 2, 3]);
+    let layout = layout(&document);
+    assert_eq!(layout.page_count(), 3);
+    assert_eq!(layout.page_of_paragraph(0, 9), Some(0));
+    assert_eq!(layout.page_of_paragraph(0, 10), Some(1));
+    assert_eq!(layout.page_of_paragraph(0, 24), Some(2));
+    assert_eq!(layout.page_of_paragraph(0, 25), None);
+
+    // Each page starts again at the top of the body
+    let top = PageDef::new_default().top_margin as i32;
+    assert_eq!(layout.pages[1].paragraphs[0].y, top);
+    assert_eq!(layout.pages[1].paragraphs[1].y, top + 1000);
+}
+
+#[test]
+fn test_empty_paragraphs_take_a_line() {
+    let document = build(&["first", "", "third"]);
+    let layout = layout(&document);
+    let page = &layout.pages[0];
+    assert_eq!(page.paragraphs.len(), 3);
+    assert_eq!(page.paragraphs[2].y - page.paragraphs[0].y, 2000);
+}
+
+#[test]
+fn test_splits_paragraphs_across_pages() {
+    let long = lines(4);
+    let mut texts = vec!["x"; 8];
+    texts.push(&long);
+    let document = build(&texts);
+
+    let layout = layout(&document);
+    assert_eq!(pieces(&layout)[1], [(8, 2)]);
+    let rest = &layout.pages[1].paragraphs[0];
+    assert_eq!(rest.first_line, 2);
+    assert_eq!(rest.y, PageDef::new_default().top_margin as i32);
+    assert_eq!(rest.lines[0].y, rest.y);
+}
+
+#[test]
+fn test_page_and_column_breaks() {
+    let mut document = build(&["a", "b", "c", "d", "e"]);
+    paragraph(&mut document, 0).column_type = 0x04;
+    paragraph(&mut document, 1).column_type = 0x04;
+    paragraph(&mut document, 3).column_type = 0x08;
+    set_flags(&mut document, 4, PAGE_BREAK_BEFORE);
+
+    // A break on the first paragraph of a page adds no blank page
+    let layout = layout(&document);
+    assert_eq!(
+        pieces(&layout),
+        [
+            vec![(0, 1)],
+            vec![(1, 1), (2, 1)],
+            vec![(3, 1)],
+            vec![(4, 1)]
+        ]
+    );
+}
+
+#[test]
+fn test_keep_with_next() {
+    let mut texts = vec!["x"; 9];
+    texts.extend(["Heading", "Body"]);
+    let mut document = build(&texts);
+    assert_eq!(layout(&document).page_of_paragraph(0, 9), Some(0));
+
+    set_flags(&mut document, 9, KEEP_WITH_NEXT);
+    let layout = layout(&document);
+    assert_eq!(pieces(&layout)[1], [(9, 1), (10, 1)]);
+}
+
+#[test]
+fn test_keep_lines_together() {
+    let long = lines(3);
+    let mut texts = vec!["x"; 8];
+    texts.push(&long);
+    let mut document = build(&texts);
+    assert_eq!(pieces(&layout(&document))[1], [(8, 1)]);
+
+    set_flags(&mut document, 8, KEEP_LINES);
+    assert_eq!(pieces(&layout(&document))[1], [(8, 3)]);
+}
+
+#[test]
+fn test_widow_and_orphan_control() {
+    // One line would be orphaned at the bottom: the paragraph moves down
+    let long = lines(3);
+    let mut texts = vec!["x"; 9];
+    texts.push(&long);
+    let mut document = build(&texts);
+    assert_eq!(pieces(&layout(&document))[1], [(9, 2)]);
+    set_flags(&mut document, 9, WIDOW_ORPHAN);
+    assert_eq!(pieces(&layout(&document))[1], [(9, 3)]);
+
+    // One line would be widowed at the top: a second line joins it
+    let long = lines(4);
+    let mut texts = vec!["x"; 7];
+    texts.push(&long);
+    let mut document = build(&texts);
+    assert_eq!(pieces(&layout(&document))[1], [(7, 1)]);
+    set_flags(&mut document, 7, WIDOW_ORPHAN);
+    assert_eq!(pieces(&layout(&document))[1], [(7, 2)]);
+}
+
+#[test]
+fn test_page_numbering_controls() {
+    let texts: Vec<&str> = vec!["x"; 30];
+    let mut document = build(&texts);
+    document
+        .doc_info
+        .properties
+        .as_mut()
+        .unwrap()
+        .page_start_number = 5;
+    assert_eq!(page_numbers(&document), [5, 6, 7]);
+
+    paragraph(&mut document, 12)
+        .new_numbers
+        .push(NewNumber::page(20));
+    paragraph(&mut document, 14).page_hide = Some(PageHide { properties: 0x03 });
+    assert_eq!(page_numbers(&document), [5, 20, 21]);
+    let layout = layout(&document);
+    let hidden = layout.pages[1].page_hide.unwrap();
+    assert!(hidden.hides_header() && hidden.hides_footer());
+    assert!(!hidden.hides_page_number());
+    assert!(layout.pages[2].page_hide.is_none());
+
+    // A section can restart numbering
+    let mut section_def = SectionDef::new_default();
+    section_def.page_starting_number = 100;
+    section(&mut document).section_def = Some(section_def);
+    assert_eq!(page_numbers(&document), [100, 20, 21]);
+}
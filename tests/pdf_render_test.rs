@@ -0,0 +1,356 @@
+use std::collections::BTreeSet;
+use std::io::Read;
+use std::path::Path;
+
+use hwpers::render::renderer::{RenderElement, RenderResult, RenderedPageOutput};
+use hwpers::render::{FontSet, HwpRenderer, RenderOptions};
+use hwpers::{HwpWriter, SummaryInfo};
+
+mod common;
+
+use common::{cff_font, PNG_1X1};
+use ttf_parser::GlyphId;
+
+const DEJAVU_SANS: &str = "/usr/share/fonts/truetype/dejavu/DejaVuSans.ttf";
+const DEJAVU_SANS_BOLD: &str = "/usr/share/fonts/truetype/dejavu/DejaVuSans-Bold.ttf";
+
+fn contains(haystack: &[u8], needle: &str) -> bool {
+    haystack
+        .windows(needle.len())
+        .any(|window| window == needle.as_bytes())
+}
+
+/// Check that every xref entry points at the object it claims to
+fn check_xref(pdf: &[u8]) {
+    assert!(pdf.starts_with(b"%PDF-1.7\n"));
+    assert!(pdf.ends_with(b"%%EOF\n"));
+    let text = String::from_utf8_lossy(pdf);
+    let start = text.rfind("startxref\n").unwrap() + "startxref\n".len();
+    let xref: usize = text[start..].lines().next().unwrap().parse().unwrap();
+    let table = std::str::from_utf8(&pdf[xref..]).unwrap();
+    assert!(table.starts_with("xref\n0 "));
+    for (id, line) in table.lines().skip(3).enumerate() {
+        if line.starts_with("trailer") {
+            break;
+        }
+        let offset: usize = line[..10].parse().unwrap();
+        let header = format!("{} 0 obj\n", id + 1);
+        assert!(
+            pdf[offset..].starts_with(header.as_bytes()),
+            "object {}",
+            id + 1
+        );
+    }
+}
+
+/// Decompressed contents of every Flate stream in the file
+fn streams(pdf: &[u8]) -> Vec<Vec<u8>> {
+    let mut streams = Vec::new();
+    let mut rest = pdf;
+    while let Some(dict_end) = rest.windows(10).position(|w| w == b">>\nstream\n") {
+        let start = dict_end + 3;
+        let dict_start = rest[..start]
+            .windows(4)
+            .rposition(|w| w == b"obj\n")
+            .unwrap();
+        let dict = String::from_utf8_lossy(&rest[dict_start..start]).to_string();
+        let length_at = dict.find("/Length ").unwrap() + "/Length ".len();
+        let length: usize = dict[length_at..]
+            .split(|c: char| !c.is_ascii_digit())
+            .next()
+            .unwrap()
+            .parse()
+            .unwrap();
+        let data = &rest[start + 7..start + 7 + length];
+        if dict.contains("/FlateDecode") {
+            let mut decoded = Vec::new();
+            flate2::read::ZlibDecoder::new(data)
+                .read_to_end(&mut decoded)
+                .unwrap();
+            streams.push(decoded);
+        }
+        rest = &rest[start + 7 + length..];
+    }
+    streams
+}
+
+#[test]
+fn test_pdf_pages_metadata_and_standard_font() {
+    let mut writer = HwpWriter::new();
+    writer.add_heading("Quarterly report", 1).unwrap();
+    writer.add_paragraph("Sales (net) grew 5%").unwrap();
+    let mut document = writer.document().clone();
+    document.summary_info = Some(SummaryInfo {
+        title: Some("분기 보고서".to_string()),
+        author: Some("홍길동".to_string()),
+        // 2024-01-02 03:04:05 UTC
+        creation_date: Some((1_704_164_645 + 11_644_473_600) * 10_000_000),
+        ..Default::default()
+    });
+
+    let result = HwpRenderer::new(&document, RenderOptions::default()).render();
+    let pdf = result.to_pdf().unwrap();
+    check_xref(&pdf);
+
+    assert!(contains(&pdf, &format!("/Count {}", result.pages.len())));
+    // A4 at 96 dpi comes back as the paper size in points
+    assert!(contains(&pdf, "/MediaBox [0 0 594.75 841.5]"));
+    assert!(contains(
+        &pdf,
+        "/BaseFont /Helvetica-Bold /Encoding /WinAnsiEncoding"
+    ));
+    // "분기 보고서" as UTF-16BE
+    assert!(contains(&pdf, "/Title <FEFFBD84AE300020BCF4ACE0C11C>"));
+    assert!(contains(&pdf, "/Author <FEFFD64DAE38B3D9>"));
+    assert!(contains(&pdf, "/CreationDate (D:20240102030405Z)"));
+
+    let content = streams(&pdf)
+        .into_iter()
+        .map(|s| String::from_utf8_lossy(&s).to_string())
+        .find(|s| s.contains("BT "))
+        .unwrap();
+    assert!(
+        content.contains("(Sales \\(net\\) grew 5%) Tj"),
+        "{content}"
+    );
+}
+
+#[test]
+fn test_pdf_embeds_font_subsets() {
+    if !Path::new(DEJAVU_SANS).exists() || !Path::new(DEJAVU_SANS_BOLD).exists() {
+        println!("Test font not found: {DEJAVU_SANS}");
+        return;
+    }
+    let mut fonts = FontSet::new();
+    fonts.add_file(DEJAVU_SANS).unwrap();
+    fonts.add_file(DEJAVU_SANS_BOLD).unwrap();
+    assert_eq!(fonts.find("DejaVu Sans", true, false), Some(1));
+    assert_eq!(fonts.find("dejavu sans", false, true), Some(0));
+    assert_eq!(fonts.find("맑은 고딕", false, false), None);
+
+    let mut writer = HwpWriter::new();
+    writer.add_heading("Heading", 1).unwrap();
+    writer.add_paragraph("Hello").unwrap();
+    let document = writer.document().clone();
+    let options = RenderOptions {
+        fonts,
+        ..Default::default()
+    };
+    let pdf = HwpRenderer::new(&document, options)
+        .render()
+        .to_pdf()
+        .unwrap();
+    check_xref(&pdf);
+
+    assert!(contains(&pdf, "/Subtype /CIDFontType2"));
+    assert!(contains(&pdf, "/CIDToGIDMap /Identity"));
+    assert!(contains(&pdf, "+DejaVuSans /Encoding /Identity-H"));
+    assert!(contains(&pdf, "+DejaVuSans-Bold /Encoding /Identity-H"));
+    assert!(!contains(&pdf, "/Helvetica"));
+
+    let text = String::from_utf8_lossy(&pdf);
+    let original = std::fs::metadata(DEJAVU_SANS).unwrap().len() as usize;
+    for length in text.match_indices("/Length1 ").map(|(at, _)| {
+        text[at + 9..]
+            .split(' ')
+            .next()
+            .unwrap()
+            .parse::<usize>()
+            .unwrap()
+    }) {
+        assert!(length < original / 4, "{length} of {original}");
+    }
+
+    let streams: Vec<String> = streams(&pdf)
+        .iter()
+        .map(|s| String::from_utf8_lossy(s).to_string())
+        .collect();
+    // ToUnicode maps glyph ids back to text for copy and search
+    assert!(streams
+        .iter()
+        .any(|s| s.contains("beginbfchar") && s.contains("<0048>")));
+    assert!(streams.iter().any(|s| s.contains("/F0 12 Tf")));
+}
+
+#[test]
+fn test_pdf_embeds_cff_faces() {
+    // The default substitutes for Hancom fonts are CFF-flavoured Noto CJK
+    let mut fonts = FontSet::new();
+    fonts
+        .add_bytes(cff_font("Noto Sans CJK KR", &['한', '글']))
+        .unwrap();
+    assert!(fonts.faces()[0].has_cff_outlines());
+    assert!(!fonts.faces()[0].has_glyf_outlines());
+
+    let mut writer = HwpWriter::new();
+    writer.add_paragraph("한글").unwrap();
+    let options = RenderOptions {
+        fonts,
+        ..Default::default()
+    };
+    let pdf = HwpRenderer::new(writer.document(), options)
+        .render()
+        .to_pdf()
+        .unwrap();
+    check_xref(&pdf);
+
+    assert!(contains(&pdf, "/Subtype /CIDFontType0 "));
+    assert!(contains(&pdf, "/FontFile3 "));
+    assert!(contains(&pdf, "/Subtype /OpenType"));
+    assert!(contains(&pdf, "+NotoSansCJKKR /Encoding /Identity-H"));
+    assert!(!contains(&pdf, "/CIDToGIDMap"));
+    assert!(!contains(&pdf, "/Helvetica"));
+
+    let streams: Vec<String> = streams(&pdf)
+        .iter()
+        .map(|s| String::from_utf8_lossy(s).to_string())
+        .collect();
+    // Text is written in CIDs, which the charset maps back to glyphs 1 and 2
+    assert!(streams.iter().any(|s| s.contains("<006400C8> Tj")));
+    assert!(streams
+        .iter()
+        .any(|s| s.contains("<0064> <D55C>") && s.contains("<00C8> <AE00>")));
+}
+
+#[test]
+fn test_font_subset_keeps_only_used_glyphs() {
+    if !Path::new(DEJAVU_SANS).exists() {
+        println!("Test font not found: {DEJAVU_SANS}");
+        return;
+    }
+    let mut fonts = FontSet::new();
+    fonts.add_file(DEJAVU_SANS).unwrap();
+    let face = &fonts.faces()[0];
+    assert_eq!(face.families(), ["DejaVu Sans"]);
+    assert!(face.has_glyf_outlines());
+
+    let parsed = face.face();
+    let glyph = |c| parsed.glyph_index(c).unwrap();
+    let used: BTreeSet<u16> = [glyph('A'), glyph('é')].iter().map(|g| g.0).collect();
+    let subset = face.subset(&used).unwrap();
+    let reduced = ttf_parser::Face::parse(&subset, 0).unwrap();
+    assert_eq!(reduced.number_of_glyphs(), parsed.number_of_glyphs());
+    assert!(reduced.glyph_bounding_box(glyph('A')).is_some());
+    // é is a composite; its base letter comes along
+    assert!(reduced.glyph_bounding_box(glyph('é')).is_some());
+    assert!(reduced.glyph_bounding_box(glyph('e')).is_some());
+    assert!(reduced.glyph_bounding_box(glyph('Z')).is_none());
+    assert_eq!(
+        reduced.glyph_hor_advance(glyph('A')),
+        parsed.glyph_hor_advance(glyph('A'))
+    );
+
+    let segments = fonts.segments("AB", "Unknown", false, false);
+    assert_eq!(segments.len(), 1);
+    assert_eq!(segments[0].range, 0..2);
+}
+
+#[test]
+fn test_cff_subset_keeps_only_used_glyphs() {
+    let mut fonts = FontSet::new();
+    fonts
+        .add_bytes(cff_font("CFF Test", &['가', '나', '다', '라']))
+        .unwrap();
+    let face = &fonts.faces()[0];
+    let parsed = face.face();
+
+    // Glyph 3 draws through the global subroutine, glyph 4 through the local one
+    let subset = face.subset(&BTreeSet::from([3])).unwrap();
+    let reduced = ttf_parser::Face::parse(&subset, 0).unwrap();
+    assert_eq!(reduced.number_of_glyphs(), parsed.number_of_glyphs());
+    assert_eq!(
+        reduced.glyph_bounding_box(GlyphId(3)),
+        parsed.glyph_bounding_box(GlyphId(3))
+    );
+    for glyph in [1, 2, 4] {
+        assert!(parsed.glyph_bounding_box(GlyphId(glyph)).is_some());
+        assert!(reduced.glyph_bounding_box(GlyphId(glyph)).is_none());
+    }
+    let cff = reduced.tables().cff.unwrap();
+    assert_eq!(cff.glyph_cid(GlyphId(3)), Some(300));
+    assert_eq!(reduced.glyph_index('다'), Some(GlyphId(3)));
+
+    // The local subroutine's triangle ("800 0 rlineto") is gone
+    let triangle = [28, 0x03, 0x20, 28, 0, 0, 5];
+    let has_triangle = |data: &[u8]| data.windows(triangle.len()).any(|w| w == triangle);
+    assert!(has_triangle(&face.subset(&BTreeSet::from([4])).unwrap()));
+    assert!(!has_triangle(&subset));
+}
+
+#[test]
+fn test_pdf_lines_rectangles_and_images() {
+    let page = |elements| RenderedPageOutput {
+        width: 800,
+        height: 600,
+        elements,
+        page_number: 1,
+    };
+    let result = RenderResult {
+        pages: vec![
+            page(vec![
+                RenderElement::Line {
+                    x1: 0,
+                    y1: 0,
+                    x2: 96,
+                    y2: 96,
+                    color: 0xFF0000,
+                    width: 2.0,
+                },
+                RenderElement::Rectangle {
+                    x: 96,
+                    y: 96,
+                    width: 192,
+                    height: 96,
+                    fill_color: Some(0x00FF00),
+                    stroke_color: Some(0x000000),
+                    stroke_width: 1.0,
+                },
+                RenderElement::Image {
+                    x: 0,
+                    y: 0,
+                    width: 96,
+                    height: 96,
+                    data: PNG_1X1.to_vec(),
+                    rotation: 0.0,
+                },
+                RenderElement::Image {
+                    x: 0,
+                    y: 0,
+                    width: 10,
+                    height: 10,
+                    data: b"not an image".to_vec(),
+                    rotation: 0.0,
+                },
+            ]),
+            page(Vec::new()),
+        ],
+        options: RenderOptions::default(),
+        summary_info: None,
+    };
+
+    let pdf = result.to_pdf().unwrap();
+    check_xref(&pdf);
+    assert!(contains(&pdf, "/Count 2"));
+    assert!(contains(&pdf, "/MediaBox [0 0 600 450]"));
+    assert!(contains(&pdf, "/Subtype /Image /Width 1 /Height 1"));
+    assert!(contains(&pdf, "/SMask "));
+
+    let content = streams(&pdf)
+        .into_iter()
+        .map(|s| String::from_utf8_lossy(&s).to_string())
+        .find(|s| s.contains(" re "))
+        .unwrap();
+    assert!(
+        content.contains("q 1.5 w 1 0 0 RG 0 450 m 72 378 l S Q"),
+        "{content}"
+    );
+    assert!(
+        content.contains("q 0 1 0 rg 0 0 0 RG 0.75 w 72 306 144 72 re B Q"),
+        "{content}"
+    );
+    assert!(
+        content.contains("q 72 0 0 72 0 378 cm /Im0 Do Q"),
+        "{content}"
+    );
+    assert!(!content.contains("/Im1"), "{content}");
+}
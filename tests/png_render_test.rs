@@ -0,0 +1,139 @@
+use std::io::Cursor;
+use std::path::Path;
+
+use hwpers::render::renderer::{RenderElement, RenderResult, RenderedPageOutput};
+use hwpers::render::{FontSet, HwpRenderer, RenderOptions};
+use hwpers::{HwpError, HwpWriter};
+use image::{Rgba, RgbaImage};
+
+const DEJAVU_SANS: &str = "/usr/share/fonts/truetype/dejavu/DejaVuSans.ttf";
+
+fn decode(png: &[u8]) -> RgbaImage {
+    image::load_from_memory(png).unwrap().to_rgba8()
+}
+
+/// Count pixels in a region that are clearly darker than the white page
+fn dark_pixels(image: &RgbaImage, x: u32, y: u32, width: u32, height: u32) -> usize {
+    (y..y + height)
+        .flat_map(|py| (x..x + width).map(move |px| (px, py)))
+        .filter(|&(px, py)| image.get_pixel(px, py).0[..3].iter().all(|&c| c < 128))
+        .count()
+}
+
+fn blue_png() -> Vec<u8> {
+    let image = RgbaImage::from_pixel(2, 2, Rgba([0, 0, 255, 255]));
+    let mut bytes = Vec::new();
+    image
+        .write_to(&mut Cursor::new(&mut bytes), image::ImageFormat::Png)
+        .unwrap();
+    bytes
+}
+
+fn shapes() -> RenderResult {
+    RenderResult {
+        pages: vec![RenderedPageOutput {
+            width: 800,
+            height: 600,
+            elements: vec![
+                RenderElement::Rectangle {
+                    x: 100,
+                    y: 100,
+                    width: 200,
+                    height: 100,
+                    fill_color: Some(0xFF0000),
+                    stroke_color: None,
+                    stroke_width: 0.0,
+                },
+                RenderElement::Line {
+                    x1: 0,
+                    y1: 400,
+                    x2: 800,
+                    y2: 400,
+                    color: 0x00FF00,
+                    width: 4.0,
+                },
+                RenderElement::Image {
+                    x: 400,
+                    y: 100,
+                    width: 100,
+                    height: 100,
+                    data: blue_png(),
+                    rotation: 0.0,
+                },
+                RenderElement::Image {
+                    x: 600,
+                    y: 100,
+                    width: 100,
+                    height: 100,
+                    data: b"not an image".to_vec(),
+                    rotation: 0.0,
+                },
+            ],
+            page_number: 1,
+        }],
+        options: RenderOptions::default(),
+        summary_info: None,
+    }
+}
+
+#[test]
+fn test_png_shapes_and_images() {
+    let result = shapes();
+    let image = decode(&result.to_png(0, 96).unwrap());
+    assert_eq!(image.dimensions(), (800, 600));
+    assert_eq!(image.get_pixel(10, 10), &Rgba([255, 255, 255, 255]));
+    assert_eq!(image.get_pixel(200, 150), &Rgba([255, 0, 0, 255]));
+    assert_eq!(image.get_pixel(400, 400), &Rgba([0, 255, 0, 255]));
+    assert_eq!(image.get_pixel(450, 150), &Rgba([0, 0, 255, 255]));
+    assert_eq!(image.get_pixel(650, 150), &Rgba([255, 255, 255, 255]));
+
+    // Half the DPI gives half the pixels, with shapes scaled to match
+    let image = decode(&result.to_png(0, 48).unwrap());
+    assert_eq!(image.dimensions(), (400, 300));
+    assert_eq!(image.get_pixel(100, 75), &Rgba([255, 0, 0, 255]));
+    assert_eq!(image.get_pixel(40, 75), &Rgba([255, 255, 255, 255]));
+
+    assert!(matches!(result.to_png(1, 96), Err(HwpError::NotFound(_))));
+    assert!(matches!(
+        result.to_png(0, 100_000),
+        Err(HwpError::InvalidInput(_))
+    ));
+}
+
+#[test]
+fn test_png_draws_text_from_glyph_outlines() {
+    if !Path::new(DEJAVU_SANS).exists() {
+        println!("Test font not found: {DEJAVU_SANS}");
+        return;
+    }
+    let mut writer = HwpWriter::new();
+    writer.add_heading("Quarterly Report", 1).unwrap();
+    writer.add_paragraph("Hello world").unwrap();
+    let document = writer.document().clone();
+
+    let mut fonts = FontSet::new();
+    fonts.add_file(DEJAVU_SANS).unwrap();
+    let options = RenderOptions {
+        fonts,
+        ..Default::default()
+    };
+    let result = HwpRenderer::new(&document, options).render();
+    let png = result.to_png(0, 72).unwrap();
+    let image = decode(&png);
+    // A4 in points
+    assert_eq!(image.dimensions(), (595, 842));
+
+    // The heading sits inside the 30mm/20mm margins
+    let heading = dark_pixels(&image, 80, 60, 250, 40);
+    assert!(heading > 200, "{heading} dark pixels");
+    assert_eq!(dark_pixels(&image, 0, 0, 80, 842), 0);
+    assert_eq!(dark_pixels(&image, 0, 200, 595, 642), 0);
+
+    // Rendering is deterministic, so PNGs can be compared byte for byte
+    assert_eq!(result.to_png(0, 72).unwrap(), png);
+
+    // Without fonts text is not drawn at all
+    let plain = HwpRenderer::new(&document, RenderOptions::default()).render();
+    let image = decode(&plain.to_png(0, 72).unwrap());
+    assert_eq!(dark_pixels(&image, 0, 0, 595, 842), 0);
+}
@@ -0,0 +1,105 @@
+use hwpers::{DocumentScripts, HwpReader, HwpWriter, HwpxReader, HwpxWriter};
+
+fn script_stream(parts: &[&str]) -> Vec<u8> {
+    let mut data = Vec::new();
+    for part in parts {
+        let units: Vec<u16> = part.encode_utf16().collect();
+        data.extend_from_slice(&(units.len() as u32).to_le_bytes());
+        for unit in units {
+            data.extend_from_slice(&unit.to_le_bytes());
+        }
+    }
+    data.extend_from_slice(&0xFFFF_FFFFu32.to_le_bytes());
+    data
+}
+
+#[test]
+fn test_default_scripts_are_parsed() {
+    let mut writer = HwpWriter::new();
+    writer.add_paragraph("스크립트 확인").unwrap();
+
+    let document = HwpReader::from_bytes(&writer.to_bytes().unwrap()).unwrap();
+    let scripts = document
+        .scripts()
+        .expect("Scripts storage should be parsed");
+
+    assert_eq!(scripts.version, 1);
+    assert!(scripts.header.contains("XHwpDocuments"));
+    assert!(scripts.source.contains("OnDocument_New"));
+    assert!(!scripts.has_user_code());
+}
+
+#[test]
+fn test_user_code_is_detected() {
+    let stream = script_stream(&[
+        "var Documents = XHwpDocuments;\r\n",
+        "function OnDocument_Open()\r\n{\r\n\tvar shell = new ActiveXObject(\"WScript.Shell\");\r\n}\r\n",
+        "",
+        "",
+    ]);
+
+    let scripts = DocumentScripts::from_streams(&[1, 0, 0, 0, 0, 0, 0, 0], &stream).unwrap();
+    assert!(scripts.has_user_code());
+    assert!(scripts.full_text().contains("WScript.Shell"));
+    assert!(scripts.full_text().starts_with("var Documents"));
+}
+
+#[test]
+fn test_empty_function_stubs_are_not_user_code() {
+    let stream = script_stream(&[
+        "",
+        "function OnDocument_New()\r\n{\r\n\t/* nothing */\r\n}\r\nfunction OnDocument_Open() {}\r\n",
+        "",
+        "",
+    ]);
+
+    let scripts = DocumentScripts::from_streams(&[], &stream).unwrap();
+    assert_eq!(scripts.version, 0);
+    assert!(!scripts.has_user_code());
+}
+
+#[test]
+fn test_truncated_script_is_an_error() {
+    let mut stream = script_stream(&["header"]);
+    stream[0] = 0x40;
+    assert!(DocumentScripts::from_streams(&[], &stream).is_err());
+}
+
+#[test]
+fn test_hwpx_scripts_are_parsed() {
+    let mut writer = HwpxWriter::new();
+    writer.add_paragraph("HWPX").unwrap();
+
+    let document = HwpxReader::from_bytes(&writer.to_bytes().unwrap()).unwrap();
+    let scripts = document
+        .scripts()
+        .expect("HWPX Scripts parts should be parsed");
+    assert!(scripts.is_empty());
+}
+
+#[test]
+fn test_header_code_is_user_code() {
+    let stream = script_stream(&[
+        "var Documents = XHwpDocuments;\r\nvar Document = Documents.Active_XHwpDocument;\r\nnew ActiveXObject(\"WScript.Shell\").Run(\"calc\");\r\n",
+        "function OnDocument_New()\r\n{\r\n}\r\n",
+        "",
+        "",
+    ]);
+
+    let scripts = DocumentScripts::from_streams(&[1, 0, 0, 0, 0, 0, 0, 0], &stream).unwrap();
+    assert!(scripts.has_user_code());
+}
+
+#[test]
+fn test_comment_markers_in_strings_are_code() {
+    // Stripping "//x" as a comment would leave what looks like two empty stubs
+    let stream = script_stream(&[
+        "",
+        "function OnDocument_Open(url = \"http://x\") { new ActiveXObject(\"WScript.Shell\").Run(url); }\r\nfunction OnDocument_New() {}\r\n",
+        "",
+        "",
+    ]);
+
+    let scripts = DocumentScripts::from_streams(&[1, 0, 0, 0, 0, 0, 0, 0], &stream).unwrap();
+    assert!(scripts.has_user_code());
+}
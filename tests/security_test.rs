@@ -0,0 +1,346 @@
+use std::io::{Cursor, Write};
+
+use hwpers::model::hyperlink::Hyperlink;
+use hwpers::model::{CtrlHeader, ListHeader};
+use hwpers::reader::{CfbReader, ReaderLimits};
+use hwpers::security::{self, FindingKind, SanitizeOptions, Severity};
+use hwpers::writer::style::ImageFormat;
+use hwpers::{DocumentScripts, HwpReader, HwpWriter, SummaryInfo};
+
+mod common;
+
+use common::PNG_1X1;
+
+/// Smallest valid PNG: 1x1 transparent pixel
+#[test]
+fn test_plain_document_is_clean() {
+    let mut writer = HwpWriter::new();
+    writer.add_paragraph("평범한 문서").unwrap();
+
+    let report = security::analyze_bytes(&writer.to_bytes().unwrap()).unwrap();
+    assert!(
+        report.is_clean(),
+        "unexpected findings: {:?}",
+        report.findings
+    );
+    assert!(report.of_kind(FindingKind::UnusualStream).is_empty());
+}
+
+#[test]
+fn test_hyperlinks_are_classified() {
+    let mut writer = HwpWriter::new();
+    writer
+        .add_paragraph_with_hyperlinks(
+            "links",
+            vec![
+                Hyperlink::new_url("secure", "https://example.com"),
+                Hyperlink::new_url("plain", "http://example.com"),
+                Hyperlink::new_file("share", "\\\\server\\share\\payload.exe"),
+                Hyperlink::new_url("https://bank.example.com", "https://evil.example.net"),
+            ],
+        )
+        .unwrap();
+
+    let report = security::analyze(writer.document());
+
+    let file_links = report.of_kind(FindingKind::FileLink);
+    assert_eq!(file_links.len(), 1);
+    assert_eq!(file_links[0].severity, Severity::High);
+
+    let url_links = report.of_kind(FindingKind::UrlLink);
+    assert!(url_links.iter().any(|f| f.severity == Severity::Low));
+    assert!(url_links
+        .iter()
+        .any(|f| f.severity == Severity::Medium && f.description.contains("does not match")));
+    assert_eq!(report.max_severity(), Some(Severity::High));
+}
+
+#[test]
+fn test_embedded_payloads_are_flagged() {
+    let mut writer = HwpWriter::new();
+    writer
+        .add_image_from_bytes(PNG_1X1, ImageFormat::Png)
+        .unwrap();
+    assert!(security::analyze(writer.document()).is_clean());
+
+    let mut document = HwpReader::from_bytes(&writer.to_bytes().unwrap()).unwrap();
+    let mut eps = writer.document().doc_info.bin_data[0].clone();
+    eps.bin_id += 1;
+    eps.extension = "eps".to_string();
+    eps.data = b"%!PS-Adobe-3.0 EPSF-3.0".to_vec();
+    let mut disguised = eps.clone();
+    disguised.bin_id += 1;
+    disguised.extension = "png".to_string();
+    disguised.data = b"MZ\x90\x00".to_vec();
+    document.doc_info.bin_data.extend([eps, disguised]);
+
+    let report = security::analyze(&document);
+    assert_eq!(report.of_kind(FindingKind::EpsImage).len(), 1);
+    assert_eq!(report.max_severity(), Some(Severity::Critical));
+}
+
+#[test]
+fn test_raw_pass_reports_streams_and_ratios() {
+    let mut writer = HwpWriter::new();
+    writer.add_paragraph("raw").unwrap();
+    let bytes = writer.to_bytes().unwrap();
+
+    let mut cfb = cfb::CompoundFile::open(Cursor::new(bytes)).unwrap();
+    cfb.create_stream("/Payload")
+        .unwrap()
+        .write_all(b"hidden")
+        .unwrap();
+    cfb.create_storage("/BinData").unwrap();
+    cfb.create_stream("/BinData/BIN0009.ole")
+        .unwrap()
+        .write_all(&[0xD0, 0xCF, 0x11, 0xE0, 0xA1, 0xB1, 0x1A, 0xE1])
+        .unwrap();
+    let bytes = cfb.into_inner().into_inner();
+
+    let mut reader = CfbReader::new(Cursor::new(bytes.clone())).unwrap();
+    let report = security::analyze_cfb(&mut reader).unwrap();
+    let unusual = report.of_kind(FindingKind::UnusualStream);
+    assert_eq!(unusual.len(), 1);
+    assert_eq!(unusual[0].location, "Payload");
+    assert_eq!(report.of_kind(FindingKind::OleObject).len(), 1);
+
+    // A tiny deflate stream that expands to a megabyte of zeros
+    let mut encoder = flate2::write::DeflateEncoder::new(Vec::new(), flate2::Compression::best());
+    encoder.write_all(&vec![0u8; 1024 * 1024]).unwrap();
+    let bomb = encoder.finish().unwrap();
+
+    let mut cfb = cfb::CompoundFile::open(Cursor::new(bytes)).unwrap();
+    let mut header = Vec::new();
+    std::io::Read::read_to_end(&mut cfb.open_stream("/FileHeader").unwrap(), &mut header).unwrap();
+    header[36] |= 0x01;
+    cfb.create_stream("/FileHeader")
+        .unwrap()
+        .write_all(&header)
+        .unwrap();
+    cfb.create_stream("/BodyText/Section1")
+        .unwrap()
+        .write_all(&bomb)
+        .unwrap();
+    let bytes = cfb.into_inner().into_inner();
+
+    let report = security::analyze_bytes(&bytes).unwrap();
+    let ratios = report.of_kind(FindingKind::DecompressionRatio);
+    assert!(ratios
+        .iter()
+        .any(|f| f.location == "BodyText/Section1" && f.severity == Severity::Critical));
+}
+
+fn risky_document() -> hwpers::HwpDocument {
+    let mut writer = HwpWriter::new();
+    writer
+        .add_paragraph_with_hyperlinks(
+            "links",
+            vec![
+                Hyperlink::new_url("site", "https://example.com"),
+                Hyperlink::new_file("share", "\\\\server\\share\\report.docx"),
+                Hyperlink::new_bookmark("top", "top"),
+            ],
+        )
+        .unwrap();
+    writer
+        .add_image_from_bytes(PNG_1X1, ImageFormat::Png)
+        .unwrap();
+
+    let mut document = HwpReader::from_bytes(&writer.to_bytes().unwrap()).unwrap();
+    document.body_texts = writer.document().body_texts.clone();
+
+    let mut ole = writer.document().doc_info.bin_data[0].clone();
+    ole.bin_id += 1;
+    ole.extension = "ole".to_string();
+    document.doc_info.bin_data = vec![writer.document().doc_info.bin_data[0].clone(), ole];
+
+    document.header.flags |= 0x08 | 0x40 | 0x80;
+    document.scripts = Some(DocumentScripts {
+        source: "function OnDocument_Open() { run(); }".to_string(),
+        ..Default::default()
+    });
+    document.summary_info = Some(SummaryInfo {
+        author: Some("홍길동".to_string()),
+        ..Default::default()
+    });
+    document
+}
+
+#[test]
+fn test_sanitize_removes_active_content() {
+    let document = risky_document();
+    assert!(!security::analyze(&document).is_clean());
+
+    let clean = security::sanitize(&document, &SanitizeOptions::default());
+
+    assert!(clean.scripts().is_none());
+    assert!(clean.summary_info().is_none());
+    assert!(clean.preview_text().is_none());
+    assert!(!clean.header.is_script());
+    assert!(!clean.header.is_history());
+    assert!(!clean.header.is_sign());
+    assert_eq!(clean.doc_info.bin_data.len(), 1);
+    assert_eq!(clean.doc_info.bin_data[0].extension, "png");
+
+    let links: Vec<_> = clean
+        .sections()
+        .flat_map(|s| &s.paragraphs)
+        .flat_map(|p| &p.hyperlinks)
+        .collect();
+    assert_eq!(links.len(), 1);
+    assert_eq!(links[0].target_url, "#top");
+    assert!(security::analyze(&clean).is_clean());
+
+    // The original is untouched
+    assert!(document.scripts().is_some());
+}
+
+#[test]
+fn test_sanitize_options_keep_url_links() {
+    let document = risky_document();
+    let clean = security::sanitize(&document, &SanitizeOptions::new().keep_url_links());
+
+    let targets: Vec<_> = clean
+        .sections()
+        .flat_map(|s| &s.paragraphs)
+        .flat_map(|p| &p.hyperlinks)
+        .map(|l| l.target_url.as_str())
+        .collect();
+    assert_eq!(targets, vec!["https://example.com", "#top"]);
+}
+
+#[test]
+fn test_sanitize_flattens_images_to_png() {
+    let mut jpeg = Vec::new();
+    image::RgbImage::from_pixel(4, 4, image::Rgb([200, 10, 10]))
+        .write_to(&mut Cursor::new(&mut jpeg), image::ImageFormat::Jpeg)
+        .unwrap();
+
+    let mut writer = HwpWriter::new();
+    writer
+        .add_image_from_bytes(&jpeg, ImageFormat::Jpeg)
+        .unwrap();
+    let mut document = HwpReader::from_bytes(&writer.to_bytes().unwrap()).unwrap();
+    let mut broken = writer.document().doc_info.bin_data[0].clone();
+    broken.bin_id += 1;
+    broken.data = b"not an image".to_vec();
+    document.doc_info.bin_data = vec![writer.document().doc_info.bin_data[0].clone(), broken];
+
+    let clean = security::sanitize(&document, &SanitizeOptions::new().flatten_images(true));
+    assert_eq!(clean.doc_info.bin_data.len(), 1);
+    assert_eq!(clean.doc_info.bin_data[0].extension, "png");
+    assert!(clean.doc_info.bin_data[0].data.starts_with(b"\x89PNG"));
+}
+
+#[test]
+fn test_sanitized_copy_is_written_and_readable() {
+    let document = risky_document();
+    let bytes = security::sanitize_to_bytes(&document, &SanitizeOptions::default()).unwrap();
+
+    let reread = HwpReader::from_bytes(&bytes).unwrap();
+    assert!(reread.summary_info().is_none());
+    assert!(!reread.scripts().is_some_and(|s| s.has_user_code()));
+    assert!(reread.extract_text().contains("links"));
+    assert!(security::analyze_bytes(&bytes).unwrap().is_clean());
+}
+
+#[test]
+fn test_sanitized_copy_drops_hidden_comment_text() {
+    let mut writer = HwpWriter::new();
+    writer.add_paragraph("본문").unwrap();
+    writer.add_paragraph("비밀 메모").unwrap();
+    writer.add_paragraph("끝").unwrap();
+    let mut document = writer.document().clone();
+    // The comment's own paragraph follows the paragraph holding the control
+    let paragraphs = &mut document.body_texts[0].sections[0].paragraphs;
+    paragraphs[0].ctrl_header = Some(CtrlHeader {
+        ctrl_id: u32::from_be_bytes(*b"tcmt"),
+        properties: 0,
+        instance_id: 0,
+    });
+    paragraphs[0].list_header = Some(ListHeader {
+        paragraph_count: 1,
+        properties: 0,
+        text_width: 0,
+        text_height: 0,
+        padding: [0; 8],
+    });
+
+    let bytes = security::sanitize_to_bytes(&document, &SanitizeOptions::default()).unwrap();
+    let text = HwpReader::from_bytes(&bytes).unwrap().extract_text();
+    assert!(text.contains("본문") && text.contains("끝"), "{text}");
+    assert!(!text.contains("비밀 메모"), "{text}");
+}
+
+#[test]
+fn test_sanitize_cleans_links_in_cells_and_notes() {
+    let mut writer = HwpWriter::new();
+    writer
+        .add_table(1, 1)
+        .set_cell(0, 0, "셀")
+        .finish()
+        .unwrap();
+    writer.add_paragraph_with_footnote("각주", "노트").unwrap();
+    let mut document = writer.document().clone();
+    let link = Hyperlink::new_file("share", "\\\\server\\share\\x.exe");
+    let paragraphs = &mut document.body_texts[0].sections[0].paragraphs;
+    let cell = paragraphs
+        .iter()
+        .position(|p| p.table_data.is_some())
+        .unwrap()
+        + 1;
+    paragraphs[cell].hyperlinks.push(link.clone());
+    let noted = paragraphs
+        .iter_mut()
+        .find(|p| !p.footnotes.is_empty())
+        .unwrap();
+    noted.footnotes[0].paragraphs[0].hyperlinks.push(link);
+
+    let clean = security::sanitize(&document, &SanitizeOptions::default());
+    let paragraphs = &clean.body_texts[0].sections[0].paragraphs;
+    let notes = paragraphs
+        .iter()
+        .flat_map(|p| &p.footnotes)
+        .flat_map(|note| &note.paragraphs);
+    assert!(paragraphs
+        .iter()
+        .chain(notes)
+        .all(|p| p.hyperlinks.is_empty()));
+}
+
+#[test]
+fn test_header_only_script_is_high_risk() {
+    let mut writer = HwpWriter::new();
+    writer.add_paragraph("매크로").unwrap();
+    let mut document = writer.document().clone();
+    document.scripts = Some(DocumentScripts {
+        header: "var Documents = XHwpDocuments;\nnew ActiveXObject(\"WScript.Shell\");".to_string(),
+        source: "function OnDocument_New() {}".to_string(),
+        ..Default::default()
+    });
+
+    let report = security::analyze(&document);
+    let scripts = report.of_kind(FindingKind::Script);
+    assert_eq!(scripts.len(), 1);
+    assert_eq!(scripts[0].severity, Severity::High);
+}
+
+#[test]
+fn test_streams_over_limits_are_findings() {
+    let mut writer = HwpWriter::new();
+    writer
+        .add_image_from_bytes(PNG_1X1, ImageFormat::Png)
+        .unwrap();
+    let bytes = writer.to_bytes().unwrap();
+
+    let limits = ReaderLimits::new().with_max_stream_size(4);
+    let mut reader = CfbReader::new(Cursor::new(bytes))
+        .unwrap()
+        .with_limits(limits);
+    let report = security::analyze_cfb(&mut reader).unwrap();
+    let skipped = report.of_kind(FindingKind::OversizedStream);
+    assert!(skipped
+        .iter()
+        .any(|f| f.location.starts_with("BinData/") && f.severity == Severity::High));
+    assert!(skipped.iter().any(|f| f.location.starts_with("BodyText/")));
+}
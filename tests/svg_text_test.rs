@@ -0,0 +1,126 @@
+use std::path::Path;
+
+use hwpers::render::renderer::RenderResult;
+use hwpers::render::{FontSet, HwpRenderer, RenderOptions, SvgOptions, SvgTextMode};
+use hwpers::HwpWriter;
+
+mod common;
+
+use common::cff_font;
+
+const DEJAVU_SANS: &str = "/usr/share/fonts/truetype/dejavu/DejaVuSans.ttf";
+
+fn render(text: &str, fonts: FontSet, style: impl FnOnce(&mut HwpWriter, &str)) -> RenderResult {
+    let mut writer = HwpWriter::new();
+    style(&mut writer, text);
+    let document = writer.document().clone();
+    let options = RenderOptions {
+        fonts,
+        ..Default::default()
+    };
+    HwpRenderer::new(&document, options).render()
+}
+
+fn plain(writer: &mut HwpWriter, text: &str) {
+    writer.add_paragraph(text).unwrap();
+}
+
+fn dejavu() -> Option<FontSet> {
+    if !Path::new(DEJAVU_SANS).exists() {
+        println!("Test font not found: {DEJAVU_SANS}");
+        return None;
+    }
+    let mut fonts = FontSet::new();
+    fonts.add_file(DEJAVU_SANS).unwrap();
+    Some(fonts)
+}
+
+#[test]
+fn test_system_font_text_in_pixels() {
+    let result = render("Hello", FontSet::new(), plain);
+    let svg = result.to_svg(0).unwrap();
+    assert_eq!(svg, result.to_svg_with(0, &SvgOptions::default()).unwrap());
+    // 12pt at 96 dpi
+    assert!(svg.contains("font-size=\"16\""), "{svg}");
+    assert!(svg.contains(">Hello</text>"));
+    assert!(!svg.contains("@font-face"));
+}
+
+#[test]
+fn test_modes_fall_back_to_system_fonts_without_faces() {
+    let result = render("Hello", FontSet::new(), plain);
+    for options in [SvgOptions::embedded_fonts(), SvgOptions::text_as_paths()] {
+        let svg = result.to_svg_with(0, &options).unwrap();
+        assert!(svg.contains(">Hello</text>"), "{svg}");
+        assert!(!svg.contains("@font-face"));
+    }
+}
+
+#[test]
+fn test_embedded_font_subset() {
+    let Some(fonts) = dejavu() else {
+        return;
+    };
+    let result = render("Hello", fonts, plain);
+    let options = SvgOptions {
+        text_mode: SvgTextMode::EmbeddedSubset,
+    };
+    let svg = result.to_svg_with(0, &options).unwrap();
+    assert_eq!(svg.matches("@font-face").count(), 1);
+    assert!(svg.contains("font-family: 'hwpers-font-0'; font-weight: normal"));
+    assert!(svg.contains("src: url(data:font/ttf;base64,"));
+    assert!(
+        svg.contains("<tspan font-family=\"&apos;hwpers-font-0&apos;, &apos;맑은 고딕&apos;"),
+        "{svg}"
+    );
+    assert!(svg.contains(">Hello</tspan></text>"));
+
+    // Only the glyphs used are kept
+    let full = std::fs::metadata(DEJAVU_SANS).unwrap().len() as usize;
+    assert!(svg.len() < full / 2, "{} bytes", svg.len());
+}
+
+#[test]
+fn test_embedded_cff_subset() {
+    let chars: Vec<char> = ('가'..='힣').take(600).collect();
+    let font = cff_font("Noto Sans CJK KR", &chars);
+    let mut fonts = FontSet::new();
+    fonts.add_bytes(font.clone()).unwrap();
+
+    let result = render("가나다", fonts, plain);
+    let svg = result
+        .to_svg_with(0, &SvgOptions::embedded_fonts())
+        .unwrap();
+    assert_eq!(svg.matches("@font-face").count(), 1);
+    assert!(svg.contains("src: url(data:font/otf;base64,"));
+    assert!(svg.contains(">가나다</tspan></text>"));
+    // Only the charstrings of the glyphs used are kept
+    assert!(
+        svg.len() < font.len() / 4,
+        "{} of {}",
+        svg.len(),
+        font.len()
+    );
+}
+
+#[test]
+fn test_text_as_paths() {
+    let Some(fonts) = dejavu() else {
+        return;
+    };
+    let result = render("Hello", fonts.clone(), plain);
+    let svg = result.to_svg_with(0, &SvgOptions::text_as_paths()).unwrap();
+    assert!(!svg.contains("<text"), "{svg}");
+    assert_eq!(svg.matches("<path d=\"M").count(), 1);
+    assert!(!svg.contains("stroke-width"));
+
+    // A regular face stands in for bold with a stroke; underlines become rectangles
+    let result = render("Hello", fonts, |writer, text| {
+        let style = hwpers::writer::style::TextStyle::new().bold().underline();
+        writer.add_paragraph_with_style(text, &style).unwrap();
+    });
+    let svg = result.to_svg_with(0, &SvgOptions::text_as_paths()).unwrap();
+    assert!(svg.contains("<path d=\"M"));
+    assert!(svg.contains("stroke-width="));
+    assert!(svg.contains("<rect x="));
+}
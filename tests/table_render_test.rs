@@ -0,0 +1,305 @@
+use hwpers::model::{ListHeader, PageDef};
+use hwpers::render::layout::{LayoutResult, RenderedCell, RenderedTable};
+use hwpers::render::renderer::RenderElement;
+use hwpers::render::{HwpRenderer, RenderOptions};
+use hwpers::writer::style::{BorderLineStyle, TableBuilder};
+use hwpers::HwpDocument;
+
+mod common;
+
+use common::{build, lay_out};
+
+fn fill<'a>(mut table: TableBuilder<'a>, rows: &[&[&str]]) -> TableBuilder<'a> {
+    for (row, texts) in rows.iter().enumerate() {
+        for (col, text) in texts.iter().enumerate() {
+            table = table.set_cell(row as u32, col as u32, text);
+        }
+    }
+    table
+}
+
+fn table_on(layout: &LayoutResult, page: usize) -> &RenderedTable {
+    layout.pages[page]
+        .paragraphs
+        .iter()
+        .find_map(|para| para.table.as_ref())
+        .unwrap()
+}
+
+fn cell(table: &RenderedTable, row: usize, col: usize) -> &RenderedCell {
+    table
+        .cells
+        .iter()
+        .find(|cell| (cell.row, cell.col) == (row, col))
+        .unwrap()
+}
+
+fn cell_text(cell: &RenderedCell) -> String {
+    cell.paragraphs
+        .iter()
+        .flat_map(|para| &para.lines)
+        .flat_map(|line| &line.runs)
+        .map(|run| run.text.as_str())
+        .collect()
+}
+
+fn origin() -> (i32, i32) {
+    let page_def = PageDef::new_default();
+    (page_def.left_margin as i32, page_def.top_margin as i32)
+}
+
+#[test]
+fn test_lays_out_cells_on_a_grid() {
+    let document = build(|writer| {
+        fill(writer.add_table(2, 2), &[&["a", "b"], &["c", "d"]])
+            .finish()
+            .unwrap();
+        writer.add_paragraph("after").unwrap();
+    });
+    let layout = lay_out(&document);
+    let (left, top) = origin();
+    let table = table_on(&layout, 0);
+    assert_eq!((table.width, table.height), (10_000, 2000));
+    assert_eq!(table.row_heights, [1000, 1000]);
+
+    let d = cell(table, 1, 1);
+    assert_eq!(
+        (d.x, d.y, d.width, d.height),
+        (left + 5000, top + 1000, 5000, 1000)
+    );
+
+    // Content sits inside the cell margins
+    let content = &d.paragraphs[0];
+    assert_eq!(cell_text(d), "d");
+    assert_eq!(content.paragraph_index, 4);
+    assert_eq!((content.x, content.y), (left + 5100, top + 1100));
+
+    // Cell paragraphs are not laid out again after the table
+    let page = &layout.pages[0];
+    assert_eq!(page.paragraphs.len(), 2);
+    assert_eq!(page.paragraphs[1].paragraph_index, 5);
+    assert_eq!(page.paragraphs[1].y, top + 2000);
+}
+
+#[test]
+fn test_spans_and_row_heights() {
+    // A merged cell with four lines needs 2600 units, so its last row grows
+    let document = build(|writer| {
+        fill(writer.add_table(2, 2), &[&["a\nb\nc\nd", "x"], &["", "y"]])
+            .merge_cells(0, 0, 2, 1)
+            .finish()
+            .unwrap();
+    });
+    let layout = lay_out(&document);
+    let table = table_on(&layout, 0);
+    assert_eq!(table.row_heights, [1000, 1600]);
+    let merged = cell(table, 0, 0);
+    assert_eq!((merged.row_span, merged.height), (2, 2600));
+    assert_eq!(cell(table, 1, 1).height, 1600);
+
+    let document = build(|writer| {
+        fill(writer.add_table(2, 2), &[&["wide"], &["c", "d"]])
+            .merge_cells(0, 0, 1, 2)
+            .finish()
+            .unwrap();
+    });
+    let layout = lay_out(&document);
+    let table = table_on(&layout, 0);
+    assert_eq!(table.cells.len(), 3);
+    assert_eq!(cell(table, 0, 0).width, 10_000);
+    assert_eq!(cell(table, 1, 1).x - cell(table, 1, 0).x, 5000);
+}
+
+#[test]
+fn test_vertical_alignment() {
+    let mut document = build(|writer| {
+        fill(writer.add_table(1, 3), &[&["a\nb\nc\nd", "top", "center"]])
+            .finish()
+            .unwrap();
+    });
+    document.body_texts[0].sections[0].paragraphs[3].list_header = Some(ListHeader {
+        paragraph_count: 1,
+        properties: 1 << 5,
+        text_width: 0,
+        text_height: 0,
+        padding: [0; 8],
+    });
+
+    let layout = lay_out(&document);
+    let table = table_on(&layout, 0);
+    let (_, top) = origin();
+    assert_eq!(cell(table, 0, 1).paragraphs[0].y, top + 100);
+    // 2600 high with 600 of content and 200 of margins leaves 1800
+    assert_eq!(cell(table, 0, 2).paragraphs[0].y, top + 100 + 900);
+}
+
+#[test]
+fn test_draws_fills_content_and_borders() {
+    let mut document = build(|writer| {
+        fill(writer.add_table(1, 2), &[&["a", "b"]])
+            .set_all_borders(BorderLineStyle::solid(3).with_color(0x0000FF))
+            .finish()
+            .unwrap();
+    });
+    let mut shaded = document.doc_info.border_fills.last().unwrap().clone();
+    shaded.fill_info.fill_type = 1;
+    shaded.fill_info.back_color = 0x00FF00;
+    document.doc_info.border_fills.push(shaded);
+    let shaded_id = document.doc_info.border_fills.len() as u16;
+    document.body_texts[0].sections[0].paragraphs[0]
+        .table_data
+        .as_mut()
+        .unwrap()
+        .cells[0]
+        .border_fill_id = shaded_id;
+
+    let options = RenderOptions {
+        dpi: 72,
+        ..Default::default()
+    };
+    let result = HwpRenderer::new(&document, options).render();
+    let elements = &result.pages[0].elements;
+
+    let (left, top) = origin();
+    let position =
+        |matches: &dyn Fn(&RenderElement) -> bool| elements.iter().position(matches).unwrap();
+    let fill = position(&|element| {
+        matches!(
+            element,
+            RenderElement::Rectangle {
+                fill_color: Some(0x00FF00),
+                ..
+            }
+        )
+    });
+    let RenderElement::Rectangle {
+        x,
+        y,
+        width,
+        height,
+        ..
+    } = &elements[fill]
+    else {
+        unreachable!()
+    };
+    assert_eq!((*x, *y, *width, *height), (left / 100, top / 100, 50, 10));
+
+    let text = position(&|element| matches!(element, RenderElement::Text { .. }));
+    let border = position(&|element| matches!(element, RenderElement::Line { .. }));
+    assert!(fill < text && text < border);
+
+    let borders: Vec<f32> = elements
+        .iter()
+        .filter_map(|element| match element {
+            RenderElement::Line { color, width, .. } => {
+                assert_eq!(*color, 0x0000FF);
+                Some(*width)
+            }
+            _ => None,
+        })
+        .collect();
+    assert_eq!(borders.len(), 8);
+    // Thickness 3 is 0.2mm
+    assert!((borders[0] - 0.2 / 25.4 * 72.0).abs() < 1e-4);
+}
+
+#[test]
+fn test_splits_tables_between_rows() {
+    let rows: Vec<Vec<String>> = (0..8)
+        .map(|row| vec![format!("r{row}"), String::new()])
+        .collect();
+    let rows: Vec<Vec<&str>> = rows
+        .iter()
+        .map(|row| row.iter().map(String::as_str).collect())
+        .collect();
+    let rows: Vec<&[&str]> = rows.iter().map(Vec::as_slice).collect();
+    let short_page = |document: &mut HwpDocument| {
+        let mut page_def = PageDef::new_default();
+        page_def.height = page_def.top_margin + page_def.bottom_margin + 5000;
+        document.body_texts[0].sections[0].page_def = Some(page_def);
+    };
+    let (_, top) = origin();
+
+    let mut document = build(|writer| fill(writer.add_table(8, 2), &rows).finish().unwrap());
+    short_page(&mut document);
+    let layout = lay_out(&document);
+    assert_eq!(layout.page_count(), 2);
+    assert_eq!(table_on(&layout, 0).row_heights.len(), 5);
+    let rest = table_on(&layout, 1);
+    assert_eq!((rest.first_row, rest.row_heights.len()), (5, 3));
+    assert_eq!(cell_text(cell(rest, 5, 0)), "r5");
+    assert_eq!(cell(rest, 5, 0).y, top);
+
+    // The header row repeats at the top of the continued table
+    let mut document = build(|writer| {
+        fill(writer.add_table(8, 2), &rows)
+            .set_header_row(true)
+            .finish()
+            .unwrap()
+    });
+    short_page(&mut document);
+    let layout = lay_out(&document);
+    let rest = table_on(&layout, 1);
+    assert_eq!((rest.first_row, rest.row_heights.len()), (5, 4));
+    assert_eq!(rest.height, 4000);
+    assert_eq!(cell_text(cell(rest, 0, 0)), "r0");
+    assert_eq!(cell(rest, 0, 0).y, top);
+    assert_eq!(cell(rest, 5, 0).y, top + 1000);
+    assert_eq!(cell(rest, 5, 0).paragraphs[0].y, top + 1100);
+
+    // A table that may not split moves whole to the next page
+    let mut document = build(|writer| {
+        writer.add_paragraph("one").unwrap();
+        writer.add_paragraph("two").unwrap();
+        fill(writer.add_table(4, 2), &rows[..4]).finish().unwrap();
+    });
+    short_page(&mut document);
+    assert_eq!(table_on(&lay_out(&document), 0).row_heights.len(), 3);
+    let table_data = document.body_texts[0].sections[0].paragraphs[2]
+        .table_data
+        .as_mut()
+        .unwrap();
+    table_data.properties &= !0x03;
+    let layout = lay_out(&document);
+    assert_eq!(layout.pages[0].paragraphs.len(), 2);
+    assert_eq!(table_on(&layout, 1).row_heights.len(), 4);
+}
+
+#[test]
+fn test_nested_tables() {
+    let mut document = build(|writer| {
+        fill(writer.add_table(1, 1), &[&["outer"]])
+            .finish()
+            .unwrap();
+        fill(writer.add_table(1, 2), &[&["in 1", "in 2"]])
+            .finish()
+            .unwrap();
+        writer.add_paragraph("after").unwrap();
+    });
+    // The inner table becomes the content of the outer cell
+    document.body_texts[0].sections[0].paragraphs.remove(1);
+
+    let layout = lay_out(&document);
+    let page = &layout.pages[0];
+    assert_eq!(page.paragraphs.len(), 2);
+    assert_eq!(page.paragraphs[1].paragraph_index, 4);
+
+    let (left, top) = origin();
+    let outer = table_on(&layout, 0);
+    assert_eq!(outer.row_heights, [1200]);
+    let inner = outer.cells[0].paragraphs[0].table.as_ref().unwrap();
+    assert_eq!((inner.x, inner.y), (left + 100, top + 100));
+    assert_eq!(cell_text(cell(inner, 0, 1)), "in 2");
+    assert_eq!(cell(inner, 0, 1).x, left + 5100);
+
+    let result = HwpRenderer::new(&document, RenderOptions::default()).render();
+    let texts: Vec<&str> = result.pages[0]
+        .elements
+        .iter()
+        .filter_map(|element| match element {
+            RenderElement::Text { text, .. } => Some(text.as_str()),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(texts, ["in 1", "in 2", "after"]);
+}
@@ -0,0 +1,178 @@
+use hwpers::model::{PageDef, TextDirection};
+use hwpers::render::layout::VerticalFrame;
+use hwpers::render::renderer::RenderElement;
+use hwpers::render::{HwpRenderer, PageRect, RenderOptions, TextPosition};
+use hwpers::{HwpDocument, HwpWriter};
+
+mod common;
+
+use common::{lay_out_on_grid, left_aligned};
+
+/// Vertical section of left aligned 600 unit lines
+fn build(add: impl FnOnce(&mut HwpWriter)) -> HwpDocument {
+    let mut document = common::build(|writer| {
+        writer.set_text_direction(TextDirection::Vertical);
+        add(writer);
+    });
+    left_aligned(&mut document, 0);
+    document
+}
+
+fn texts(elements: &[RenderElement]) -> Vec<(String, i32, i32, f32)> {
+    elements
+        .iter()
+        .filter_map(|element| match element {
+            RenderElement::Text {
+                text,
+                x,
+                y,
+                rotation,
+                ..
+            } => Some((text.clone(), *x, *y, *rotation)),
+            _ => None,
+        })
+        .collect()
+}
+
+#[test]
+fn test_section_text_direction() {
+    let document = build(|writer| writer.add_paragraph("세로").unwrap());
+    let section = &document.body_texts[0].sections[0];
+    let section_def = section.section_def.as_ref().unwrap();
+    assert_eq!(section_def.text_direction(), TextDirection::Vertical);
+
+    let mut section_def = section_def.clone();
+    section_def.set_text_direction(TextDirection::Horizontal);
+    assert_eq!(section_def.text_direction(), TextDirection::Horizontal);
+}
+
+#[test]
+fn test_lines_stack_from_the_right() {
+    let document = build(|writer| {
+        writer.add_paragraph("가나").unwrap();
+        writer.add_paragraph("다라").unwrap();
+    });
+    let layout = lay_out_on_grid(&document);
+    let page = &layout.pages[0];
+    let page_def = PageDef::new_default();
+    assert_eq!(page.text_direction, TextDirection::Vertical);
+    let frame = page.frame().unwrap();
+    assert_eq!(
+        frame,
+        VerticalFrame {
+            right: page_def.width as i32,
+            top: 0
+        }
+    );
+
+    // Lines run down from the top margin, the first at the right margin
+    let first = &page.paragraphs[0].lines[0];
+    let second = &page.paragraphs[1].lines[0];
+    assert_eq!(first.runs[0].x, page_def.top_margin as i32);
+    assert_eq!(first.y, page_def.right_margin as i32);
+    assert_eq!(second.y, first.y + 600);
+
+    let (right, top) = frame.to_page(first.runs[0].x, first.y);
+    assert_eq!(right, (page_def.width - page_def.right_margin) as i32);
+    assert_eq!(top, page_def.top_margin as i32);
+    assert_eq!(frame.to_layout(right, top), (first.runs[0].x, first.y));
+}
+
+#[test]
+fn test_latin_is_turned_and_punctuation_takes_vertical_forms() {
+    let document = build(|writer| writer.add_paragraph("한글。abc").unwrap());
+    let layout = lay_out_on_grid(&document);
+    let runs = &layout.pages[0].paragraphs[0].lines[0].runs;
+    let upright: Vec<_> = runs.iter().filter(|run| !run.sideways).collect();
+    let sideways: Vec<_> = runs.iter().filter(|run| run.sideways).collect();
+    assert_eq!(upright[0].text, "한글︒");
+    assert_eq!(sideways[0].text, "abc");
+
+    let result = HwpRenderer::new(&document, RenderOptions::default()).render();
+    let texts = texts(&result.pages[0].elements);
+    // Upright characters are drawn one at a time, down the page
+    let hangul: Vec<_> = texts
+        .iter()
+        .filter(|(text, ..)| text == "한" || text == "글")
+        .collect();
+    assert_eq!(hangul.len(), 2);
+    assert_eq!(hangul[0].3, 0.0);
+    assert_eq!(hangul[0].1, hangul[1].1);
+    assert!(hangul[0].2 < hangul[1].2);
+    assert!(texts.iter().any(|(text, ..)| text == "︒"));
+    let latin = texts.iter().find(|(text, ..)| text == "abc").unwrap();
+    assert_eq!(latin.3, 90.0);
+
+    let svg = result.to_svg(0).unwrap();
+    assert!(svg.contains("<g transform=\"rotate(90 "), "{svg}");
+}
+
+#[test]
+fn test_hit_test_and_caret_in_vertical_text() {
+    let document = build(|writer| writer.add_paragraph("가나다").unwrap());
+    let layout = lay_out_on_grid(&document);
+    let page_def = PageDef::new_default();
+    let right = (page_def.width - page_def.right_margin) as i32;
+    let top = page_def.top_margin as i32;
+
+    // Characters are a thousand units tall; the line is 600 wide
+    assert_eq!(
+        layout.hit_test(0, right - 300, top + 1100),
+        Some(TextPosition::new(0, 0, 1))
+    );
+    assert_eq!(
+        layout.caret_rect(TextPosition::new(0, 0, 2)),
+        Some(PageRect {
+            page: 0,
+            x: right - 600,
+            y: top + 2000,
+            width: 600,
+            height: 0,
+        })
+    );
+    assert_eq!(
+        layout.selection_rects(TextPosition::new(0, 0, 0)..TextPosition::new(0, 0, 2)),
+        [PageRect {
+            page: 0,
+            x: right - 600,
+            y: top,
+            width: 600,
+            height: 2000,
+        }]
+    );
+}
+
+#[test]
+fn test_vertical_text_box() {
+    let mut writer = HwpWriter::new();
+    writer.add_text_box("가나").unwrap();
+    let mut document = writer.document().clone();
+    let paragraph = document.body_texts[0].sections[0]
+        .paragraphs
+        .iter_mut()
+        .find(|paragraph| paragraph.text_box_data.is_some())
+        .unwrap();
+    let text_box = paragraph.text_box_data.as_mut().unwrap();
+    text_box.text_direction = TextDirection::Vertical;
+
+    let layout = lay_out_on_grid(&document);
+    let object = layout.pages[0]
+        .objects
+        .iter()
+        .chain(
+            layout.pages[0]
+                .paragraphs
+                .iter()
+                .flat_map(|paragraph| &paragraph.objects),
+        )
+        .find(|object| !object.paragraphs.is_empty())
+        .unwrap();
+    assert_eq!(object.text_direction, TextDirection::Vertical);
+    let frame = object.frame().unwrap();
+    assert_eq!(frame.right, object.x + object.width);
+    assert_eq!(frame.top, object.y);
+    assert!(object.paragraphs[0].lines[0]
+        .runs
+        .iter()
+        .all(|run| !run.sideways));
+}